@@ -1,17 +1,21 @@
+use std::ops::Range;
 #[cfg(feature = "sys_time")]
 use std::time::Instant;
 
 use crate::{
+    ScaErrorKind, ToErrorKind,
     executor::runtime::LineApplicationLimit,
     ir::tokens::IrToken,
     matcher::{
         choices::Choices,
-        patterns::{check_box::CheckBox, non_bound::NonBound, optional::Optional, selection::Selection, Pattern},
+        gap_buffer::{GapBuffer, SplitSlice},
+        patterns::{check_box::CheckBox, features::FeatureMatrix, non_bound::NonBound, optional::Optional, selection::Selection, Pattern},
         phones::Phones,
-        patterns::{rule::SoundChangeRule, ir_to_patterns::RuleStructureError},
+        patterns::{rule::{MatchState, SoundChangeRule}, ir_to_patterns::RuleStructureError},
     },
-    phones::Phone,
-    tokens::{Direction, ShiftType}
+    phones::{Phone, suprasegmentals::Suprasegmentals},
+    keywords::FEATURES_LINE_START,
+    tokens::{Direction, FeatureSpec, ShiftType}
 };
 
 #[cfg(test)]
@@ -57,148 +61,378 @@ impl LimitCondition {
 }
 
 /// Applies a rule to a list of phones within a time limit
-pub fn apply<'s: 'p, 'p>(rule: &SoundChangeRule<'s>, phones: &mut Vec<Phone<'p>>, limit: Option<LineApplicationLimit>) -> Result<(), ApplicationError<'s>> {
+pub fn apply(rule: &SoundChangeRule, phones: &mut Vec<Phone>, limit: Option<LineApplicationLimit>) -> Result<(), ApplicationError> {
+    let mut syllables = syllabify(rule, phones);
+    // the phones are edited in a gap buffer so that each change only moves the phones between it and the last change
+    let mut buffer = GapBuffer::from(std::mem::take(phones));
+
+    let result = apply_to_buffer(rule, &mut buffer, &mut syllables, limit);
+    *phones = buffer.into();
+
+    result
+}
+
+/// Applies a rule to a list of phones in a gap buffer within a time limit,
+/// where `syllables` are the syllable boundaries of the phones if the rule uses them,
+/// which are kept up to date as the phones change
+fn apply_to_buffer(rule: &SoundChangeRule, phones: &mut GapBuffer<Phone>, syllables: &mut Option<GapBuffer<bool>>, limit: Option<LineApplicationLimit>) -> Result<(), ApplicationError> {
+    // matching state is kept apart from the pattern so that the rule is never changed
+    let mut state = rule.pattern.new_state();
+
+    if rule.kind.kind == ShiftType::Simultaneous {
+        return apply_simultaneous(rule, &mut state, phones, syllables.as_mut(), limit);
+    }
+
     let dir = rule.kind.dir;
-    let mut phone_index = dir.start_index(phones);
+    let mut phone_index = dir.start_index(phones.len());
     let mut limit_condition: Option<LimitCondition> = limit.map(Into::into);
     
     while phone_index < phones.len() {
-        if let Some((replace_len, input_len)) = apply_at(rule, phones, phone_index)? {
-            phone_index = next_position(rule, input_len, replace_len, phone_index, phones);
+        // skips positions the input cannot start matching at
+        phone_index = next_candidate_index(rule, phones.as_split(), phone_index);
+
+        if phone_index >= phones.len() {
+            break;
+        }
+
+        if let Some((replace_len, input_len)) = apply_at(rule, &mut state, phones, syllables.as_mut(), phone_index)? {
+            phone_index = if rule.kind.once {
+                next_word_index(dir, phones.as_split(), input_start(dir, phone_index, input_len), replace_len)
+            } else {
+                next_position(rule, input_len, replace_len, phone_index, phones.len())
+            };
         } else {
             phone_index = dir.change_by_one(phone_index);
         }
 
         // returns an error if the limit is exceeded
         // protects against infinite loops
-        if let Some(limit_condition) = limit_condition.as_mut()
+        if let (Some(limit), Some(limit_condition)) = (limit, limit_condition.as_mut())
             && limit_condition.check()
         {
-            return Err(ApplicationError::ExceededLimit(*limit_condition));
+            return Err(ApplicationError::ExceededLimit(limit));
         }
     }
 
     Ok(())
 }
 
-fn next_position(rule: &SoundChangeRule, input_len: usize, replace_len: usize, phone_index: usize, phones: &[Phone]) -> usize {
+/// Applies a rule to a list of phones within a time limit
+/// by finding every match before replacing any of them
+fn apply_simultaneous(rule: &SoundChangeRule, state: &mut MatchState, phones: &mut GapBuffer<Phone>, mut syllables: Option<&mut GapBuffer<bool>>, limit: Option<LineApplicationLimit>) -> Result<(), ApplicationError> {
+    let dir = rule.kind.dir;
+    let mut phone_index = dir.start_index(phones.len());
+    let mut limit_condition: Option<LimitCondition> = limit.map(Into::into);
+    let mut replacements = Vec::new();
+    // the phones are not changed until every match is found
+    let unchanged_syllables = syllables.as_deref().map(GapBuffer::as_split);
+
+    while phone_index < phones.len() {
+        // skips positions the input cannot start matching at
+        phone_index = next_candidate_index(rule, phones.as_split(), phone_index);
+
+        if phone_index >= phones.len() {
+            break;
+        }
+
+        if let Some((choices, input_len)) = match_at(rule, state, phones.as_split(), unchanged_syllables, phone_index)? {
+            let input_start = input_start(dir, phone_index, input_len);
+            let input_phones = phones.as_split().slice(input_start..input_start + input_len).unwrap_or_default().to_vec();
+            let output_phones = inherit_suprasegmentals(patterns_to_phones(&rule.output, &choices)?, &input_phones, &rule.marked_phones);
+
+            replacements.push((input_start, input_len, output_phones));
+
+            phone_index = if rule.kind.once {
+                next_word_index(dir, phones.as_split(), input_start, input_len)
+            } else {
+                // matches cannot overlap
+                dir.change_by(phone_index, input_len.max(1))
+            };
+        } else {
+            phone_index = dir.change_by_one(phone_index);
+        }
+
+        // returns an error if the limit is exceeded
+        // protects against infinite loops
+        if let (Some(limit), Some(limit_condition)) = (limit, limit_condition.as_mut())
+            && limit_condition.check()
+        {
+            return Err(ApplicationError::ExceededLimit(limit));
+        }
+    }
+
+    // replacing the last match first keeps the indices of the other matches valid
+    // and only moves the gap of the buffer in one direction
+    replacements.sort_by_key(|(input_start, ..)| std::cmp::Reverse(*input_start));
+
+    for (input_start, input_len, output_phones) in replacements {
+        let (_, replaced, inserted_len) = splice_output(phones, input_start, input_len, output_phones);
+
+        if let Some(syllables) = syllables.as_deref_mut() {
+            resyllabify(rule, phones.as_split(), syllables, replaced, inserted_len);
+        }
+    }
+
+    Ok(())
+}
+
+fn next_position(rule: &SoundChangeRule, input_len: usize, replace_len: usize, phone_index: usize, phones_len: usize) -> usize {
     let dir = rule.kind.dir;
     match (dir, rule.kind.kind) {
         // prevents repeat application on zero-sized inputs
         _ if input_len == 0 && replace_len == 0 => dir.change_by_one(phone_index),
         (Direction::Ltr, ShiftType::Move) => dir.change_by(phone_index, replace_len),
         // ensures removing a phone does not take the phone index out of the phone list ending the rule early
-        (Direction::Rtl, _) if phone_index >= phones.len() => phones.len().wrapping_sub(1),
+        (Direction::Rtl, _) if phone_index >= phones_len => phones_len.wrapping_sub(1),
         (Direction::Rtl, ShiftType::Move) => dir.change_by(phone_index, input_len),
         _ => phone_index,
     }
 }
 
-/// Applies a rule to a location in a list of phones if the input and conds match
+/// Gets the index of the bound that ends the word a change was made in,
+/// according to the direction
 /// 
-/// Return: (the length of the output, the length of what it replaced)
-fn apply_at<'s: 'p, 'p>(rule: &SoundChangeRule<'s>, phones: &mut Vec<Phone<'p>>, phone_index: usize) -> Result<Option<(usize, usize)>, ApplicationError<'s>> {
-    let SoundChangeRule {
-        kind,
-        output,
-        pattern,
-    } = rule;
-    
-    pattern.borrow_mut().reset();
+/// If there is no such bound, an index outside of the phones is returned
+fn next_word_index(dir: Direction, phones: SplitSlice<Phone>, change_start: usize, change_len: usize) -> usize {
+    match dir {
+        Direction::Ltr => {
+            let change_end = change_start + change_len;
+            
+            phones.slice(change_end..phones.len())
+                .and_then(|after| after.position(Phone::is_bound))
+                .map_or(phones.len(), |i| change_end + i)
+        },
+        Direction::Rtl => phones.slice(0..change_start)
+            .and_then(|before| before.rposition(Phone::is_bound))
+            .unwrap_or(usize::MAX),
+    }
+}
+
+/// Gets the index of the first phone, starting at `phone_index` and moving according to the direction,
+/// that a match of the rule's input can start with
+/// 
+/// If there is no such phone, an index outside of the phones is returned
+fn next_candidate_index(rule: &SoundChangeRule, phones: SplitSlice<Phone>, phone_index: usize) -> usize {
+    let Some(first_phones) = &rule.first_phones else {
+        return phone_index;
+    };
+
+    let is_candidate = |phone: &Phone| first_phones.iter().any(|first| phone.matches_pattern(first));
+
+    match rule.kind.dir {
+        Direction::Ltr => phones.slice(phone_index..phones.len())
+            .and_then(|after| after.position(is_candidate))
+            .map_or(phones.len(), |i| phone_index + i),
+        Direction::Rtl => phones.slice(0..phone_index + 1)
+            .and_then(|before| before.rposition(is_candidate))
+            .unwrap_or(usize::MAX),
+    }
+}
+
+/// Finds the syllable boundaries of the phones if the rule uses them
+fn syllabify(rule: &SoundChangeRule, phones: &[Phone]) -> Option<GapBuffer<bool>> {
+    rule.pattern.syllabifier().map(|syllabifier| syllabifier.syllabify(phones).into())
+}
+
+/// Finds the syllable boundaries of the words changed by replacing the phones in `replaced` with `inserted_len` phones,
+/// keeping the boundaries of every other word
+fn resyllabify(rule: &SoundChangeRule, phones: SplitSlice<Phone>, syllables: &mut GapBuffer<bool>, replaced: Range<usize>, inserted_len: usize) {
+    let Some(syllabifier) = rule.pattern.syllabifier() else {
+        return;
+    };
+
+    let inserted_end = replaced.start + inserted_len;
 
-    let match_phones = Phones::new(phones, phone_index, kind.dir);
+    let words_start = phones.slice(0..replaced.start)
+        .and_then(|before| before.rposition(Phone::is_bound))
+        .map_or(0, |i| i + 1);
+    let words_end = phones.slice(inserted_end..phones.len())
+        .and_then(|after| after.position(Phone::is_bound))
+        .map_or(phones.len(), |i| inserted_end + i);
+    // the end of the changed words before they were changed
+    let old_words_end = words_end - inserted_len + replaced.len();
+
+    let words = phones.slice(words_start..words_end).unwrap_or_default().to_vec();
+
+    // the boundaries include the gaps at both edges of the words
+    syllables.splice(words_start..old_words_end + 1, &syllabifier.syllabify(&words));
+}
+
+/// Gets the index of the first phone of an input matched at an index
+const fn input_start(dir: Direction, index: usize, input_len: usize) -> usize {
+    match dir {
+        Direction::Ltr => index,
+        Direction::Rtl => index + 1 - input_len,
+    }
+}
+
+/// Finds a match for a rule's input and conditions at a location in a list of phones
+/// using `state` to hold the matching state
+/// and `syllables` as the syllable boundaries of the phones
+/// 
+/// Return: (the choices made to match, the length of the input)
+fn match_at<'p>(rule: &SoundChangeRule, state: &mut MatchState, phones: SplitSlice<Phone>, syllables: Option<SplitSlice<bool>>, phone_index: usize) -> Result<Option<(Choices<'p>, usize)>, ApplicationError> {
+    rule.pattern.reset(state);
+
+    let mut match_phones = Phones::new_split(phones, phone_index, rule.kind.dir);
+
+    if let Some(syllables) = syllables {
+        match_phones = match_phones.with_syllables(syllables);
+    }
 
     let mut choices = Choices::default();
 
-    if let Some(new_choices) = pattern.borrow_mut().next_match(&match_phones)? {
+    if let Some(new_choices) = rule.pattern.next_match(state, &match_phones)? {
         choices.take_owned(new_choices);
     } else {
         return Ok(None);
     }
 
-    let input_len = pattern.borrow().len();
+    let input_len = rule.pattern.len(state);
 
-    replace_input(phones, phone_index, input_len, output, &choices, kind.dir)
+    Ok(Some((choices, input_len)))
 }
 
-/// Replaces the slice `phones[index..input_len]` with the output as phones
+/// Applies a rule to a location in a list of phones if the input and conds match,
+/// updating the syllable boundaries of the phones if they are given
 /// 
 /// Return: (the length of the output, the length of what it replaced)
-fn replace_input<'s: 'p, 'p>(phones: &mut Vec<Phone<'p>>, index: usize, input_len: usize, output: &[Pattern<'s>], choices: &Choices<'_, 'p>, dir: Direction) -> Result<Option<(usize, usize)>, ApplicationError<'s>> {
-    let mut shifted_phones = Vec::new();
+fn apply_at(rule: &SoundChangeRule, state: &mut MatchState, phones: &mut GapBuffer<Phone>, syllables: Option<&mut GapBuffer<bool>>, phone_index: usize) -> Result<Option<(usize, usize)>, ApplicationError> {
+    let Some((choices, input_len)) = match_at(rule, state, phones.as_split(), syllables.as_deref().map(GapBuffer::as_split), phone_index)? else {
+        return Ok(None);
+    };
 
-    let phone_iter = &mut phones.iter();
+    replace_input(phones, syllables, phone_index, input_len, rule, &choices)
+}
 
-    // adds the proceeding phones to the new phones
-    let input_start = match dir {
-        Direction::Ltr => index,
-        Direction::Rtl => index + 1 - input_len,
-    };
+/// Replaces the slice `phones[index..input_len]` with the output as phones
+/// 
+/// Return: (the length of the output, the length of what it replaced)
+fn replace_input(phones: &mut GapBuffer<Phone>, syllables: Option<&mut GapBuffer<bool>>, index: usize, input_len: usize, rule: &SoundChangeRule, choices: &Choices<'_>) -> Result<Option<(usize, usize)>, ApplicationError> {
+    let input_start = input_start(rule.kind.dir, index, input_len);
+    let input_phones = phones.contiguous(input_start..input_start + input_len);
+    let output_phones = inherit_suprasegmentals(patterns_to_phones(&rule.output, choices)?, input_phones, &rule.marked_phones);
 
-    for &phone in phone_iter.take(input_start) {
-        shifted_phones.push(phone);
+    let (output_len, replaced, inserted_len) = splice_output(phones, input_start, input_len, output_phones);
+
+    // syllable boundaries may have moved
+    if let Some(syllables) = syllables {
+        resyllabify(rule, phones.as_split(), syllables, replaced, inserted_len);
     }
 
-    // discards the input
-    _ = phone_iter.take(input_len).count(); // since take is lazy, the count causes it to be used
+    Ok(Some((output_len, input_len)))
+}
 
-    let mut output_phones = Vec::new();
+/// Replaces the slice `phones[input_start..input_start + input_len]` with the output phones
+/// without doubling bounds
+/// 
+/// Return: (the length of the output, the range of phones that were replaced, the number of phones that replaced them)
+fn splice_output(phones: &mut GapBuffer<Phone>, input_start: usize, input_len: usize, mut output: Vec<Phone>) -> (usize, Range<usize>, usize) {
+    // prevents in-output bound doubling
+    output.dedup_by(|phone, last| phone.is_bound() && last.is_bound());
 
-    // adds the output
-    for phone in patterns_to_phones(output, choices)? {
-        // prevents in-output bound doubling
-        if output_phones.last().is_some_and(Phone::is_bound) && phone.is_bound() {
-            continue;
-        }
-        
-        output_phones.push(phone);
-    }
+    let is_bound_at = |phones: &GapBuffer<Phone>, i: usize| phones.get(i).is_some_and(Phone::is_bound);
 
-    let mut output_len = output_phones.len();
+    let input_end = input_start + input_len;
+    let mut replace_start = input_start;
+    let mut output_start = 0;
+    let mut output_end = output.len();
+    let mut output_len = output.len();
 
     // prevents bound doubling at the start of the output
-    while shifted_phones.last().is_none_or(Phone::is_bound) && output_phones.first().is_some_and(Phone::is_bound) {
-        shifted_phones.pop();
+    if output.first().is_some_and(Phone::is_bound) && input_start.checked_sub(1).is_none_or(|i| is_bound_at(phones, i)) {
+        output_start += 1;
         output_len -= 1;
     }
 
-    shifted_phones.append(&mut output_phones);
-    drop(output_phones);
-
-    let mut after_output_phones = Vec::new();
+    // prevents bound doubling at the end of the output,
+    // removing a bound before the input if the output is empty
+    while is_bound_at(phones, input_end) {
+        if output_end > output_start {
+            if !output[output_end - 1].is_bound() {
+                break;
+            }
 
-    // adds the following phones
-    for &phone in phone_iter {
-        after_output_phones.push(phone);
+            output_end -= 1;
+        } else if replace_start > 0 && is_bound_at(phones, replace_start - 1) {
+            replace_start -= 1;
+        } else {
+            break;
+        }
     }
 
-    // prevents bound doubling at the end of the output
-    while shifted_phones.last().is_none_or(Phone::is_bound) && after_output_phones.first().is_some_and(Phone::is_bound) {
-        shifted_phones.pop();
+    phones.splice(replace_start..input_end, &output[output_start..output_end]);
 
-        if shifted_phones.is_empty() {
-            break;
+    (output_len, replace_start..input_end, output_end - output_start)
+}
+
+/// Gives phones written in an output the suprasegmentals of the input phones they replace,
+/// with any suprasegmentals marked in the output taking priority
+/// 
+/// Each phone takes the suprasegmentals of the first unclaimed input phone with the same symbol,
+/// otherwise of the unclaimed input phone in the same position.
+/// Suprasegmentals marked on the phone in the rule's input that matched an input phone are not inherited,
+/// so that the output can remove them
+fn inherit_suprasegmentals(output: Vec<(Phone, bool)>, input: &[Phone], marked_phones: &[Phone]) -> Vec<Phone> {
+    let mut written = vec![Suprasegmentals::default(); input.len()];
+    let mut matched = vec![false; marked_phones.len()];
+
+    // each input phone is matched by the first unmatched marked phone it satisfies
+    for (phone, written) in input.iter().zip(&mut written) {
+        if let Some(i) = (0..marked_phones.len()).find(|&i| !matched[i] && phone.matches_pattern(&marked_phones[i])) {
+            matched[i] = true;
+            *written = marked_phones[i].suprasegmentals();
         }
     }
 
-    shifted_phones.append(&mut after_output_phones);
-    drop(after_output_phones);
+    let mut claimed = vec![false; input.len()];
+    let mut sources = vec![None; output.len()];
 
-    *phones = shifted_phones;
+    // phones with the same symbol are claimed first
+    for ((phone, written), source) in output.iter().zip(&mut sources) {
+        if *written && let Some(i) = (0..input.len()).find(|&i| !claimed[i] && input[i].symbol() == phone.symbol()) {
+            claimed[i] = true;
+            *source = Some(i);
+        }
+    }
 
-    Ok(Some((output_len, input_len)))
+    for (i, ((_, written), source)) in output.iter().zip(&mut sources).enumerate() {
+        if *written && source.is_none() && claimed.get(i) == Some(&false) {
+            claimed[i] = true;
+            *source = Some(i);
+        }
+    }
+
+    output.into_iter()
+        .zip(sources)
+        .map(|((phone, _), source)| match source {
+            Some(i) => {
+                let supras = input[i].suprasegmentals().clear(&written[i]).overlay(phone.suprasegmentals());
+                phone.with_suprasegmentals(supras)
+            },
+            None => phone,
+        })
+        .collect()
 }
 
 /// Converts patterns to the phones that they represent according to choices that have been made
-fn patterns_to_phones<'s: 'p, 'p>(patterns: &[Pattern<'s>], choices: &Choices<'_, 'p>) -> Result<Vec<Phone<'p>>, ApplicationError<'s>> {
+/// along with whether each phone was written directly in the patterns
+fn patterns_to_phones(patterns: &[Pattern], choices: &Choices<'_>) -> Result<Vec<(Phone, bool)>, ApplicationError> {
     let mut phones = Vec::new();
 
     for pattern in patterns {
         match pattern {
-            Pattern::Phone(phone) => phones.push(phone.unit_state),
+            Pattern::Phone(phone) => phones.push((phone.unit_state.clone(), true)),
             Pattern::NonBound(CheckBox { unit_state: NonBound{ id: Some(id) }, .. }) => {
                 if let Some(phone) = choices.any().get(id) {
-                    phones.push(*phone);
+                    phones.push((phone.clone(), false));
+                } else {
+                    return Err(ApplicationError::UnmatchedTokenInOutput(pattern.clone()));
+                }
+            },
+            Pattern::Features(CheckBox { unit_state: matrix @ FeatureMatrix { id: Some(id), .. }, .. }) => {
+                if let Some(phone) = choices.any().get(id) {
+                    phones.push((matrix.rewrite(phone, choices)?, false));
                 } else {
                     return Err(ApplicationError::UnmatchedTokenInOutput(pattern.clone()));
                 }
@@ -206,9 +440,7 @@ fn patterns_to_phones<'s: 'p, 'p>(patterns: &[Pattern<'s>], choices: &Choices<'_
             Pattern::Optional(Optional { id: Some(id), option, .. }) => {
                 if let Some(insert) = choices.optional().get(id) {
                     if *insert {
-                        for phone in patterns_to_phones(option.inner(), choices)? {
-                            phones.push(phone);
-                        }
+                        phones.append(&mut patterns_to_phones(option.inner(), choices)?);
                     }
                 } else {
                     return Err(ApplicationError::UnmatchedTokenInOutput(pattern.clone()));
@@ -217,9 +449,7 @@ fn patterns_to_phones<'s: 'p, 'p>(patterns: &[Pattern<'s>], choices: &Choices<'_
             Pattern::Selection(Selection { id: Some(id), options, .. }) => {
                 if let Some(choice) = choices.selection().get(id) {
                     if let Some(content) = options.get(*choice) {
-                        for phone in patterns_to_phones(content.inner(), choices)? {
-                            phones.push(phone);
-                        }
+                        phones.append(&mut patterns_to_phones(content.inner(), choices)?);
                     } else {
                         return Err(ApplicationError::InvalidSelectionAccess(pattern.clone(), *choice))
                     }
@@ -238,32 +468,50 @@ fn patterns_to_phones<'s: 'p, 'p>(patterns: &[Pattern<'s>], choices: &Choices<'_
 /// Errors that occur when trying to apply a rule
 #[cfg_attr(test, derive(PartialEq))]
 #[derive(Debug)]
-pub enum ApplicationError<'s> {
-    UnmatchedTokenInOutput(Pattern<'s>),
-    InvalidSelectionAccess(Pattern<'s>, usize),
-    ExceededLimit(LimitCondition),
+pub enum ApplicationError {
+    UnmatchedTokenInOutput(Pattern),
+    InvalidSelectionAccess(Pattern, usize),
+    ExceededLimit(LineApplicationLimit),
     RepetitionOutOfCond,
-    PatternCannotBeConvertedToPhones(Pattern<'s>),
+    PatternCannotBeConvertedToPhones(Pattern),
+    NoPhoneWithFeatures(String, Pattern),
+    UnsetAlphaVariable(FeatureSpec),
 }
 
-impl std::error::Error for ApplicationError<'_> {}
+impl std::error::Error for ApplicationError {}
 
-impl std::fmt::Display for ApplicationError<'_> {
+impl std::fmt::Display for ApplicationError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::InvalidSelectionAccess(scope, elem) => {
                 write!(f, "Cannot access element {} in scope: {scope}", elem + 1)
             },
             Self::UnmatchedTokenInOutput(pattern) => {
-                write!(f, "Cannot match the following token in the output to a token in the input: {pattern}\nConsider adding a label '{}' and ensuring it is used in the input or every condition", IrToken::Label("name"))
+                write!(f, "Cannot match the following token in the output to a token in the input: {pattern}\nConsider adding a label '{}' and ensuring it is used in the input or every condition", IrToken::Label("name".into()))
             },
             Self::ExceededLimit(limit) => write!(f, "{}", match limit {
                 #[cfg(feature = "sys_time")]
-                LimitCondition::Time(_) => "Could not apply changes in allotted time",
-                LimitCondition::Count { attempts: _, max: _ } => "Could not apply changes with the allotted application attempts",
+                LineApplicationLimit::Time(_) => "Could not apply changes in allotted time",
+                LineApplicationLimit::Attempts(_) => "Could not apply changes with the allotted application attempts",
             }),
             Self::RepetitionOutOfCond => write!(f, "{}", RuleStructureError::RepetitionOutOfCond),
             Self::PatternCannotBeConvertedToPhones(pattern) => write!(f, "'{pattern}' cannot be converted to a phone or list of phones"),
+            Self::NoPhoneWithFeatures(phone, pattern) => write!(f, "No phone with the features of '{phone}' changed by '{pattern}' has been declared with '{FEATURES_LINE_START}'"),
+            Self::UnsetAlphaVariable(spec) => write!(f, "Cannot use '{spec}' in an output before its alpha variable is set in the input or a condition"),
         }
     }
-}
\ No newline at end of file
+}
+
+impl ToErrorKind for ApplicationError {
+    fn error_kind(&self) -> ScaErrorKind {
+        match self {
+            Self::UnmatchedTokenInOutput(pattern) => ScaErrorKind::UnmatchedTokenInOutput { pattern: pattern.to_string() },
+            Self::InvalidSelectionAccess(scope, element) => ScaErrorKind::InvalidSelectionAccess { scope: scope.to_string(), element: *element },
+            Self::ExceededLimit(limit) => ScaErrorKind::ExceededLimit { limit: *limit },
+            Self::RepetitionOutOfCond => ScaErrorKind::RepetitionOutOfCond,
+            Self::PatternCannotBeConvertedToPhones(pattern) => ScaErrorKind::PatternCannotBeConvertedToPhones { pattern: pattern.to_string() },
+            Self::NoPhoneWithFeatures(phone, pattern) => ScaErrorKind::NoPhoneWithFeatures { phone: phone.clone(), pattern: pattern.to_string() },
+            Self::UnsetAlphaVariable(spec) => ScaErrorKind::UnsetAlphaVariable { spec: spec.to_string() },
+        }
+    }
+}
@@ -1,18 +1,18 @@
-use std::cell::RefCell;
-
 use crate::{executor::runtime::DEFAULT_LINE_APPLICATION_LIMIT, matcher::patterns::{cond::CondPattern, list::PatternList, rule::RulePattern}, tokens::{CondType, LabelType, ScopeId, ScopeType, Shift}};
 use super::*;
 
 #[test]
 fn apply_empty_rule_to_no_phones() {
     let rule = SoundChangeRule {
-        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move },
+        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false },
         output: Vec::new(),
-        pattern: RefCell::new(RulePattern::new(
+        pattern: RulePattern::new(
             PatternList::default(),
             Vec::new(),
             Vec::new(),
-        ).expect("rule structure should be valid")),
+        ).expect("rule structure should be valid"),
+        first_phones: None,
+        marked_phones: Vec::new(),
     };
     
     assert_eq!(Ok(()), apply(&rule, &mut Vec::new(), Some(DEFAULT_LINE_APPLICATION_LIMIT)));
@@ -21,7 +21,7 @@ fn apply_empty_rule_to_no_phones() {
 // #[test]
 // fn apply_empty_rule_to_one_phone() {
 //     let rule = SoundChangeRule {
-//         kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move },
+//         kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false },
 //         input: Vec::new(),
 //         output: Vec::new(),
 //         conds: vec![CondPattern::default()],
@@ -34,117 +34,129 @@ fn apply_empty_rule_to_no_phones() {
 #[test]
 fn one_to_one_shift() {
     let rule = SoundChangeRule {
-        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move },
-        output: vec![Pattern::new_phone(Phone::Symbol("b"))],
-        pattern: RefCell::new(RulePattern::new(
-            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false },
+        output: vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+        pattern: RulePattern::new(
+            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
             Vec::new(),
             Vec::new(),
-        ).expect("rule structure should be valid")),
+        ).expect("rule structure should be valid"),
+        first_phones: None,
+        marked_phones: Vec::new(),
     };
 
-    let mut phones = vec![Phone::Symbol("a"), Phone::Symbol("c"), Phone::Symbol("a")];
+    let mut phones = vec![Phone::Symbol("a".into()), Phone::Symbol("c".into()), Phone::Symbol("a".into())];
     
     assert_eq!(Ok(()), apply(&rule, &mut phones, Some(DEFAULT_LINE_APPLICATION_LIMIT)));
 
-    assert_eq!(vec![Phone::Symbol("b"), Phone::Symbol("c"), Phone::Symbol("b")], phones);
+    assert_eq!(vec![Phone::Symbol("b".into()), Phone::Symbol("c".into()), Phone::Symbol("b".into())], phones);
 }
 
 #[test]
 fn one_to_two_shift() {
     let rule = SoundChangeRule {
-        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move },
+        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false },
         output: vec![
-            Pattern::new_phone(Phone::Symbol("b")),
-            Pattern::new_phone(Phone::Symbol("c")),
+            Pattern::new_phone(Phone::Symbol("b".into())),
+            Pattern::new_phone(Phone::Symbol("c".into())),
         ],
-        pattern: RefCell::new(RulePattern::new(
-            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+        pattern: RulePattern::new(
+            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
             Vec::new(),
             Vec::new(),
-        ).expect("rule structure should be valid")),
+        ).expect("rule structure should be valid"),
+        first_phones: None,
+        marked_phones: Vec::new(),
     };
 
-    let mut phones = vec![Phone::Symbol("a"), Phone::Symbol("d"), Phone::Symbol("a")];
+    let mut phones = vec![Phone::Symbol("a".into()), Phone::Symbol("d".into()), Phone::Symbol("a".into())];
     
     assert_eq!(Ok(()), apply(&rule, &mut phones, Some(DEFAULT_LINE_APPLICATION_LIMIT)));
 
-    assert_eq!(vec![Phone::Symbol("b"), Phone::Symbol("c"), Phone::Symbol("d"), Phone::Symbol("b"), Phone::Symbol("c")], phones);
+    assert_eq!(vec![Phone::Symbol("b".into()), Phone::Symbol("c".into()), Phone::Symbol("d".into()), Phone::Symbol("b".into()), Phone::Symbol("c".into())], phones);
 }
 
 #[test]
 fn two_to_one_shift() {
     let rule = SoundChangeRule {
-        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move },
-        output: vec![Pattern::new_phone(Phone::Symbol("c"))],
-        pattern: RefCell::new(RulePattern::new(
+        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false },
+        output: vec![Pattern::new_phone(Phone::Symbol("c".into()))],
+        pattern: RulePattern::new(
             PatternList::new(vec![
-                Pattern::new_phone(Phone::Symbol("a")),
-                Pattern::new_phone(Phone::Symbol("b")),
+                Pattern::new_phone(Phone::Symbol("a".into())),
+                Pattern::new_phone(Phone::Symbol("b".into())),
             ]),
             Vec::new(),
             Vec::new(),
-        ).expect("rule structure should be valid")),
+        ).expect("rule structure should be valid"),
+        first_phones: None,
+        marked_phones: Vec::new(),
     };
 
-    let mut phones = vec![Phone::Symbol("a"), Phone::Symbol("b"), Phone::Symbol("d"), Phone::Symbol("a"), Phone::Symbol("b")];
+    let mut phones = vec![Phone::Symbol("a".into()), Phone::Symbol("b".into()), Phone::Symbol("d".into()), Phone::Symbol("a".into()), Phone::Symbol("b".into())];
     
     assert_eq!(Ok(()), apply(&rule, &mut phones, Some(DEFAULT_LINE_APPLICATION_LIMIT)));
 
-    assert_eq!(vec![Phone::Symbol("c"), Phone::Symbol("d"), Phone::Symbol("c")], phones);
+    assert_eq!(vec![Phone::Symbol("c".into()), Phone::Symbol("d".into()), Phone::Symbol("c".into())], phones);
 }
 
 #[test]
 fn one_to_none_shift() {
     let rule = SoundChangeRule {
-        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move },
+        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false },
         output: vec![],
-        pattern: RefCell::new(RulePattern::new(
-            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+        pattern: RulePattern::new(
+            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
             Vec::new(),
             Vec::new(),
-        ).expect("rule structure should be valid")),
+        ).expect("rule structure should be valid"),
+        first_phones: None,
+        marked_phones: Vec::new(),
     };
 
-    let mut phones = vec![Phone::Symbol("a"), Phone::Symbol("b"), Phone::Symbol("a")];
+    let mut phones = vec![Phone::Symbol("a".into()), Phone::Symbol("b".into()), Phone::Symbol("a".into())];
 
     assert_eq!(Ok(()), apply(&rule, &mut phones, Some(DEFAULT_LINE_APPLICATION_LIMIT)));
 
-    assert_eq!(vec![Phone::Symbol("b")], phones);
+    assert_eq!(vec![Phone::Symbol("b".into())], phones);
 }
 
 #[test]
 fn remove_word_final_ltr() { 
     let rule = SoundChangeRule {
-        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move },
+        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false },
         output: vec![],
-        pattern: RefCell::new(RulePattern::new(
+        pattern: RulePattern::new(
             PatternList::new(vec![Pattern::new_any(Some(ScopeId::IOUnlabeled { id_num: 0, label_type: LabelType::Any, parent: None }))]),
             vec![CondPattern::new(CondType::Pattern, PatternList::default(), PatternList::new(vec![Pattern::new_phone(Phone::Bound)]))],
             Vec::new()
-        ).expect("rule structure should be valid")),
+        ).expect("rule structure should be valid"),
+        first_phones: None,
+        marked_phones: Vec::new(),
     };
 
-    let mut phones = vec![Phone::Symbol("a"), Phone::Symbol("b"), Phone::Symbol("c"), Phone::Bound, Phone::Symbol("e"), Phone::Bound, Phone::Symbol("f"), Phone::Symbol("g")];
+    let mut phones = vec![Phone::Symbol("a".into()), Phone::Symbol("b".into()), Phone::Symbol("c".into()), Phone::Bound, Phone::Symbol("e".into()), Phone::Bound, Phone::Symbol("f".into()), Phone::Symbol("g".into())];
     
     assert_eq!(Ok(()), apply(&rule, &mut phones, Some(DEFAULT_LINE_APPLICATION_LIMIT)));
 
-    assert_eq!(vec![Phone::Symbol("a"), Phone::Symbol("b"), Phone::Bound, Phone::Symbol("f")], phones);
+    assert_eq!(vec![Phone::Symbol("a".into()), Phone::Symbol("b".into()), Phone::Bound, Phone::Symbol("f".into())], phones);
 }
 
 #[test]
 fn remove_word_final_rtl() {
     let rule = SoundChangeRule {
-        kind: Shift { dir: Direction::Rtl, kind: ShiftType::Move },
+        kind: Shift { dir: Direction::Rtl, kind: ShiftType::Move, once: false },
         output: vec![],
-        pattern: RefCell::new(RulePattern::new(
+        pattern: RulePattern::new(
             PatternList::new(vec![Pattern::new_any(Some(ScopeId::IOUnlabeled { id_num: 0, label_type: LabelType::Any, parent: None }))]),
             vec![CondPattern::new(CondType::Pattern, PatternList::default(), PatternList::new(vec![Pattern::new_phone(Phone::Bound)]))],
             Vec::new()
-        ).expect("rule structure should be valid")),
+        ).expect("rule structure should be valid"),
+        first_phones: None,
+        marked_phones: Vec::new(),
     };
 
-    let mut phones = vec![Phone::Symbol("a"), Phone::Symbol("b"), Phone::Symbol("c"), Phone::Bound, Phone::Symbol("e"), Phone::Bound, Phone::Symbol("f"), Phone::Symbol("g")];
+    let mut phones = vec![Phone::Symbol("a".into()), Phone::Symbol("b".into()), Phone::Symbol("c".into()), Phone::Bound, Phone::Symbol("e".into()), Phone::Bound, Phone::Symbol("f".into()), Phone::Symbol("g".into())];
     
     assert_eq!(Ok(()), apply(&rule, &mut phones, Some(DEFAULT_LINE_APPLICATION_LIMIT)));
     
@@ -156,9 +168,9 @@ fn selection_to_selection() {
     let input = PatternList::new(vec![
         Pattern::new_selection(
             vec![
-                vec![Pattern::new_phone(Phone::Symbol("a"))],
-                vec![Pattern::new_phone(Phone::Symbol("b"))],
-                vec![Pattern::new_phone(Phone::Symbol("c"))],
+                vec![Pattern::new_phone(Phone::Symbol("a".into()))],
+                vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+                vec![Pattern::new_phone(Phone::Symbol("c".into()))],
             ],
             Some(ScopeId::IOUnlabeled {
                 id_num: 0,
@@ -169,13 +181,13 @@ fn selection_to_selection() {
     ]);
 
     let rule = SoundChangeRule {
-        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move },
+        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false },
         output: vec![
             Pattern::new_selection(
                 vec![
-                    vec![Pattern::new_phone(Phone::Symbol("d"))],
-                    vec![Pattern::new_phone(Phone::Symbol("e"))],
-                    vec![Pattern::new_phone(Phone::Symbol("f"))],
+                    vec![Pattern::new_phone(Phone::Symbol("d".into()))],
+                    vec![Pattern::new_phone(Phone::Symbol("e".into()))],
+                    vec![Pattern::new_phone(Phone::Symbol("f".into()))],
                 ],
                 Some(ScopeId::IOUnlabeled {
                     id_num: 0,
@@ -184,93 +196,101 @@ fn selection_to_selection() {
                 }),
             )
         ],
-        pattern: RefCell::new(RulePattern::new(input, Vec::new(), Vec::new()).expect("rule structure should be valid")),
+        pattern: RulePattern::new(input, Vec::new(), Vec::new()).expect("rule structure should be valid"),
+        first_phones: None,
+        marked_phones: Vec::new(),
     };
 
-    let mut phones = vec![Phone::Symbol("a"), Phone::Symbol("b"), Phone::Symbol("c"), Phone::Symbol("d")];
+    let mut phones = vec![Phone::Symbol("a".into()), Phone::Symbol("b".into()), Phone::Symbol("c".into()), Phone::Symbol("d".into())];
 
     assert_eq!(Ok(()), apply(&rule, &mut phones, Some(DEFAULT_LINE_APPLICATION_LIMIT)));
     
-    assert_eq!(vec![Phone::Symbol("d"), Phone::Symbol("e"), Phone::Symbol("f"), Phone::Symbol("d")], phones);
+    assert_eq!(vec![Phone::Symbol("d".into()), Phone::Symbol("e".into()), Phone::Symbol("f".into()), Phone::Symbol("d".into())], phones);
 }
 
 #[test]
 fn option_phone_to_option_phone() { 
     let input = PatternList::new(vec![
             Pattern::new_optional(
-                vec![Pattern::new_phone(Phone::Symbol("a"))],
+                vec![Pattern::new_phone(Phone::Symbol("a".into()))],
                 Some(ScopeId::IOUnlabeled {
                     id_num: 0,
                     label_type: LabelType::Scope(ScopeType::Optional),
                     parent: None
                 })
             ),
-            Pattern::new_phone(Phone::Symbol("b")),
+            Pattern::new_phone(Phone::Symbol("b".into())),
         ]);
 
     let rule = SoundChangeRule {
-        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move },
+        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false },
         output: vec![
             Pattern::new_optional(
-                vec![Pattern::new_phone(Phone::Symbol("c"))],
+                vec![Pattern::new_phone(Phone::Symbol("c".into()))],
                 Some(ScopeId::IOUnlabeled {
                     id_num: 0,
                     label_type: LabelType::Scope(ScopeType::Optional),
                     parent: None
                 }),
             ),
-            Pattern::new_phone(Phone::Symbol("d")),
+            Pattern::new_phone(Phone::Symbol("d".into())),
         ],
-        pattern: RefCell::new(RulePattern::new(
+        pattern: RulePattern::new(
             input,
             Vec::new(),
             Vec::new(),
-        ).expect("rule structure should be valid")),
+        ).expect("rule structure should be valid"),
+        first_phones: None,
+        marked_phones: Vec::new(),
     };
 
-    let mut phones = vec![Phone::Symbol("a"), Phone::Symbol("b"), Phone::Symbol("e"), Phone::Symbol("b"), Phone::Symbol("e")];
+    let mut phones = vec![Phone::Symbol("a".into()), Phone::Symbol("b".into()), Phone::Symbol("e".into()), Phone::Symbol("b".into()), Phone::Symbol("e".into())];
     
     assert_eq!(Ok(()), apply(&rule, &mut phones, Some(DEFAULT_LINE_APPLICATION_LIMIT)));
   
-    assert_eq!(vec![Phone::Symbol("c"), Phone::Symbol("d"), Phone::Symbol("e"), Phone::Symbol("d"), Phone::Symbol("e")], phones);
+    assert_eq!(vec![Phone::Symbol("c".into()), Phone::Symbol("d".into()), Phone::Symbol("e".into()), Phone::Symbol("d".into()), Phone::Symbol("e".into())], phones);
 }
 
 #[test]
 fn phone_to_phone_word_final_ltr() { 
     let rule = SoundChangeRule {
-        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move },
-        output: vec![Pattern::new_phone(Phone::Symbol("b"))],
-        pattern: RefCell::new(RulePattern::new(
-            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false },
+        output: vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+        pattern: RulePattern::new(
+            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
             vec![CondPattern::new(CondType::Pattern, PatternList::default(), PatternList::new(vec![Pattern::new_phone(Phone::Bound)]))],
             Vec::new(),
-        ).expect("rule structure should be valid")),
+        ).expect("rule structure should be valid"),
+        first_phones: None,
+        marked_phones: Vec::new(),
     };
 
-    let mut phones = vec![Phone::Symbol("a"), Phone::Symbol("c"), Phone::Symbol("a")];
+    let mut phones = vec![Phone::Symbol("a".into()), Phone::Symbol("c".into()), Phone::Symbol("a".into())];
     
     assert_eq!(Ok(()), apply(&rule, &mut phones, Some(DEFAULT_LINE_APPLICATION_LIMIT)));
     
-    assert_eq!(vec![Phone::Symbol("a"), Phone::Symbol("c"), Phone::Symbol("b")], phones);
+    assert_eq!(vec![Phone::Symbol("a".into()), Phone::Symbol("c".into()), Phone::Symbol("b".into())], phones);
 }
 
 #[test]
 fn phone_to_phone_word_final_rtl() { 
     let rule = SoundChangeRule {
-        kind: Shift { dir: Direction::Rtl, kind: ShiftType::Move },
-        output: vec![Pattern::new_phone(Phone::Symbol("b"))],
-        pattern: RefCell::new(RulePattern::new(
-            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+        kind: Shift { dir: Direction::Rtl, kind: ShiftType::Move, once: false },
+        output: vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+        pattern: RulePattern::new(
+            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
             vec![CondPattern::new(CondType::Pattern, PatternList::default(), PatternList::new(vec![Pattern::new_phone(Phone::Bound)]))],
             Vec::new(),
-        ).expect("rule structure should be valid")),
+        ).expect("rule structure should be valid"),
+        first_phones: None,
+        marked_phones: Vec::new(),
     };
 
-    let mut phones = vec![Phone::Symbol("a"), Phone::Symbol("c"), Phone::Symbol("a")];
+    let mut phones = vec![Phone::Symbol("a".into()), Phone::Symbol("c".into()), Phone::Symbol("a".into())];
 
     assert_eq!(Ok(()), apply(&rule, &mut phones, Some(DEFAULT_LINE_APPLICATION_LIMIT)));
     
-    assert_eq!(vec![Phone::Symbol("a"), Phone::Symbol("c"), Phone::Symbol("b")], phones);
+    assert_eq!(vec![Phone::Symbol("a".into()), Phone::Symbol("c".into()), Phone::Symbol("b".into())], phones);
 }
 
 #[test]
@@ -278,95 +298,318 @@ fn quadruple_agreement() {
     let input = PatternList::new(vec![
         Pattern::new_selection(
             vec![
-                vec![Pattern::new_phone(Phone::Symbol("a"))],
-                vec![Pattern::new_phone(Phone::Symbol("b"))],
-                vec![Pattern::new_phone(Phone::Symbol("c"))],
+                vec![Pattern::new_phone(Phone::Symbol("a".into()))],
+                vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+                vec![Pattern::new_phone(Phone::Symbol("c".into()))],
             ],
-            Some(ScopeId::Name("label")),
+            Some(ScopeId::Name("label".into())),
     )]);
 
     let conds = vec![CondPattern::new(CondType::Pattern, PatternList::default(), PatternList::new(vec![
         Pattern::new_selection(
             vec![
-                vec![Pattern::new_phone(Phone::Symbol("g"))],
-                vec![Pattern::new_phone(Phone::Symbol("h"))],
-                vec![Pattern::new_phone(Phone::Symbol("i"))],
+                vec![Pattern::new_phone(Phone::Symbol("g".into()))],
+                vec![Pattern::new_phone(Phone::Symbol("h".into()))],
+                vec![Pattern::new_phone(Phone::Symbol("i".into()))],
             ],
-            Some(ScopeId::Name("label")),
+            Some(ScopeId::Name("label".into())),
         )
     ]))];
 
     let anti_conds = vec![CondPattern::new(CondType::Pattern, PatternList::new(vec![
         Pattern::new_selection(
             vec![
-                vec![Pattern::new_phone(Phone::Symbol("j"))],
-                vec![Pattern::new_phone(Phone::Symbol("k"))],
-                vec![Pattern::new_phone(Phone::Symbol("l"))],
+                vec![Pattern::new_phone(Phone::Symbol("j".into()))],
+                vec![Pattern::new_phone(Phone::Symbol("k".into()))],
+                vec![Pattern::new_phone(Phone::Symbol("l".into()))],
             ],
-            Some(ScopeId::Name("label")),
+            Some(ScopeId::Name("label".into())),
         )
     ]), PatternList::default() )];
 
     let rule = SoundChangeRule {
-        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move },
+        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false },
         output: vec![
             Pattern::new_selection(
                 vec![
-                    vec![Pattern::new_phone(Phone::Symbol("d"))],
-                    vec![Pattern::new_phone(Phone::Symbol("e"))],
-                    vec![Pattern::new_phone(Phone::Symbol("f"))],
+                    vec![Pattern::new_phone(Phone::Symbol("d".into()))],
+                    vec![Pattern::new_phone(Phone::Symbol("e".into()))],
+                    vec![Pattern::new_phone(Phone::Symbol("f".into()))],
                 ],
-                Some(ScopeId::Name("label")),
+                Some(ScopeId::Name("label".into())),
             )
         ],
-        pattern: RefCell::new(RulePattern::new(input, conds, anti_conds).expect("rule structure should be valid")),
+        pattern: RulePattern::new(input, conds, anti_conds).expect("rule structure should be valid"),
+        first_phones: None,
+        marked_phones: Vec::new(),
     };
 
     let mut phones = vec![
-        Phone::Symbol("a"),
-        Phone::Symbol("g"),
-        Phone::Symbol("a"),
-        Phone::Symbol("h"),
-        Phone::Symbol("b"),
-        Phone::Symbol("h"),
-        Phone::Symbol("j"),
-        Phone::Symbol("c"),
-        Phone::Symbol("i"),
-        Phone::Symbol("l"),
-        Phone::Symbol("c"),
-        Phone::Symbol("i"),
+        Phone::Symbol("a".into()),
+        Phone::Symbol("g".into()),
+        Phone::Symbol("a".into()),
+        Phone::Symbol("h".into()),
+        Phone::Symbol("b".into()),
+        Phone::Symbol("h".into()),
+        Phone::Symbol("j".into()),
+        Phone::Symbol("c".into()),
+        Phone::Symbol("i".into()),
+        Phone::Symbol("l".into()),
+        Phone::Symbol("c".into()),
+        Phone::Symbol("i".into()),
     ];
     
     assert_eq!(Ok(()), apply(&rule, &mut phones, Some(DEFAULT_LINE_APPLICATION_LIMIT)));
     
     assert_eq!(vec![
-        Phone::Symbol("d"),
-        Phone::Symbol("g"),
-        Phone::Symbol("a"),
-        Phone::Symbol("h"),
-        Phone::Symbol("e"),
-        Phone::Symbol("h"),
-        Phone::Symbol("j"),
-        Phone::Symbol("f"),
-        Phone::Symbol("i"),
-        Phone::Symbol("l"),
-        Phone::Symbol("c"),
-        Phone::Symbol("i")
+        Phone::Symbol("d".into()),
+        Phone::Symbol("g".into()),
+        Phone::Symbol("a".into()),
+        Phone::Symbol("h".into()),
+        Phone::Symbol("e".into()),
+        Phone::Symbol("h".into()),
+        Phone::Symbol("j".into()),
+        Phone::Symbol("f".into()),
+        Phone::Symbol("i".into()),
+        Phone::Symbol("l".into()),
+        Phone::Symbol("c".into()),
+        Phone::Symbol("i".into())
     ], phones);
 }
 
 #[test]
 fn count_limit() {
     let rule = SoundChangeRule {
-        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move },
-        output: vec![Pattern::new_phone(Phone::Symbol("b"))],
-        pattern: RefCell::new(RulePattern::new(
-            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+        kind: Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false },
+        output: vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+        pattern: RulePattern::new(
+            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
             Vec::new(),
         Vec::new()
-        ).expect("rule structure should be valid")),
+        ).expect("rule structure should be valid"),
+        first_phones: None,
+        marked_phones: Vec::new(),
     };
 
-    assert!(apply(&rule, &mut vec![Phone::Symbol("a")], Some(LineApplicationLimit::Attempts(1))).is_ok());
-    assert!(apply(&rule, &mut vec![Phone::Symbol("a"), Phone::Symbol("b")], Some(LineApplicationLimit::Attempts(1))).is_err());
-}
\ No newline at end of file
+    assert!(apply(&rule, &mut vec![Phone::Symbol("a".into())], Some(LineApplicationLimit::Attempts(1))).is_ok());
+    assert!(apply(&rule, &mut vec![Phone::Symbol("a".into()), Phone::Symbol("b".into())], Some(LineApplicationLimit::Attempts(1))).is_err());
+}
+
+#[test]
+fn first_phones_skip_only_positions_that_cannot_match() {
+    for dir in [Direction::Ltr, Direction::Rtl] {
+        // {a, b} c >> d
+        let unfiltered = SoundChangeRule {
+            kind: Shift { dir, kind: ShiftType::Move, once: false },
+            output: vec![Pattern::new_phone(Phone::Symbol("d".into()))],
+            pattern: RulePattern::new(
+                PatternList::new(vec![
+                    Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("a".into()))], vec![Pattern::new_phone(Phone::Symbol("b".into()))]], None),
+                    Pattern::new_phone(Phone::Symbol("c".into())),
+                ]),
+                Vec::new(),
+                Vec::new(),
+            ).expect("rule structure should be valid"),
+            first_phones: None,
+            marked_phones: Vec::new(),
+        };
+
+        let first_phones = match dir {
+            Direction::Ltr => vec![Phone::Symbol("a".into()), Phone::Symbol("b".into())],
+            Direction::Rtl => vec![Phone::Symbol("c".into())],
+        };
+
+        let filtered = SoundChangeRule { first_phones: Some(first_phones), ..unfiltered.clone() };
+
+        let phones = crate::phones::build_phone_list(crate::escaped_strings::EscapedString::from("cac xbc ac bcac ca").as_escaped_str());
+        let mut unfiltered_phones = phones.clone();
+        let mut filtered_phones = phones;
+
+        assert_eq!(Ok(()), apply(&unfiltered, &mut unfiltered_phones, Some(DEFAULT_LINE_APPLICATION_LIMIT)));
+        assert_eq!(Ok(()), apply(&filtered, &mut filtered_phones, Some(DEFAULT_LINE_APPLICATION_LIMIT)));
+        assert_eq!(filtered_phones, unfiltered_phones);
+        assert_eq!(crate::phones::phone_list_to_string(&filtered_phones), "cd xd d dd ca");
+    }
+}
+
+/// The previous implementation of `splice_output`, which rebuilds the whole phone list
+fn rebuilding_splice_output(phones: &mut Vec<Phone>, input_start: usize, input_len: usize, output_phones: Vec<Phone>) -> usize {
+    let mut shifted_phones = phones[..input_start].to_vec();
+    let mut output = Vec::new();
+
+    for phone in output_phones {
+        if output.last().is_some_and(Phone::is_bound) && phone.is_bound() {
+            continue;
+        }
+
+        output.push(phone);
+    }
+
+    let mut output_len = output.len();
+
+    while shifted_phones.last().is_none_or(Phone::is_bound) && output.first().is_some_and(Phone::is_bound) {
+        shifted_phones.pop();
+        output_len -= 1;
+    }
+
+    shifted_phones.append(&mut output);
+
+    let mut after_output_phones = phones[input_start + input_len..].to_vec();
+
+    while shifted_phones.last().is_none_or(Phone::is_bound) && after_output_phones.first().is_some_and(Phone::is_bound) {
+        shifted_phones.pop();
+
+        if shifted_phones.is_empty() {
+            break;
+        }
+    }
+
+    shifted_phones.append(&mut after_output_phones);
+    *phones = shifted_phones;
+
+    output_len
+}
+
+/// Applies `splice_output` to phones in a `Vec`
+/// 
+/// Return: the length of the output that was inserted
+fn splice_output_vec(phones: &mut Vec<Phone>, input_start: usize, input_len: usize, output_phones: Vec<Phone>) -> usize {
+    let old_phones = phones.clone();
+    let mut buffer = GapBuffer::from(std::mem::take(phones));
+    let (output_len, replaced, inserted_len) = splice_output(&mut buffer, input_start, input_len, output_phones);
+    *phones = buffer.into();
+
+    // only the phones in the replaced range are changed
+    assert_eq!(phones[..replaced.start], old_phones[..replaced.start]);
+    assert_eq!(phones[replaced.start + inserted_len..], old_phones[replaced.end..]);
+
+    output_len
+}
+
+#[test]
+fn apply_moves_each_phone_a_bounded_number_of_times() {
+    // 10000 phones in 2000 words
+    let text = "pata ".repeat(2000);
+    let phones = crate::phones::build_phone_list(crate::escaped_strings::EscapedString::from(text.as_str()).as_escaped_str());
+    assert_eq!(phones.len(), 10000);
+
+    let syllabify = "SYLLABIFY {p, t, d, z}, {a, o}, ({p, t, d, z})\n";
+
+    for (rules, expected) in [
+        ("t >> d z", "padza "),
+        ("t >> d", "pada "),
+        ("t >>", "paa "),
+        ("t << d z", "padza "),
+        ("a t >>! o", "poa "),
+        ("a >> o / _ . t", "pota "),
+        ("a > o / _ #", "pato "),
+        ("t a >> d / . _", "pad "),
+        ("a >> o z / _ .", "poztoz "),
+    ] {
+        let rules = format!("{syllabify}{rules}");
+        let mut sir = crate::lexer::Lexer::lex(&rules);
+        let mut tokenization_data = crate::ir::tokenization_data::TokenizationData::new();
+        let mut rule = None;
+
+        while !sir.is_empty() {
+            if let Some(crate::matcher::patterns::ir_to_patterns::RuleLine::Rule { rule: built, .. }) = crate::executor::build_line_without_io(&mut sir, &mut tokenization_data) {
+                rule = Some(built);
+            }
+        }
+
+        let rule = rule.expect("rules should build");
+        let mut buffer = GapBuffer::from(phones.clone());
+        let mut syllables = super::syllabify(&rule, &phones);
+
+        assert_eq!(Ok(()), apply_to_buffer(&rule, &mut buffer, &mut syllables, Some(DEFAULT_LINE_APPLICATION_LIMIT)), "{rules}");
+
+        let moved = (buffer.moved(), syllables.as_ref().map_or(0, GapBuffer::moved));
+        let phones = Vec::from(buffer);
+
+        // rebuilding or splicing into a `Vec` for each match moves phones quadratically many times
+        assert!(moved.0 <= 3 * phones.len() && moved.1 <= 3 * phones.len(), "{rules} moved {moved:?}");
+        assert_eq!(crate::phones::phone_list_to_string(&phones), expected.repeat(2000), "{rules}");
+        // only the syllables of changed words are found again
+        assert_eq!(syllables.map(Vec::from), super::syllabify(&rule, &phones).map(Vec::from), "{rules}");
+    }
+}
+
+#[test]
+fn splice_output_matches_rebuilding() {
+    let alphabet = &[Phone::Symbol("a".into()), Phone::Symbol("b".into()), Phone::Bound];
+
+    // every list of up to `max_len` phones from the alphabet
+    let lists = |max_len: u32| (0..=max_len).flat_map(move |len| (0..alphabet.len().pow(len)).map(move |mut n| {
+        (0..len).map(|_| {
+            let phone = alphabet[n % alphabet.len()].clone();
+            n /= alphabet.len();
+            phone
+        }).collect::<Vec<_>>()
+    }));
+
+    // phones never contain doubled bounds
+    for phones in lists(5).filter(|phones| !phones.windows(2).any(|w| w[0].is_bound() && w[1].is_bound())) {
+        for input_start in 0..=phones.len() {
+            for input_len in 0..=phones.len() - input_start {
+                for output in lists(3) {
+                    // the previous implementation panics on an output starting with a bound with only bounds before it
+                    if output.first().is_some_and(Phone::is_bound) && phones[..input_start].iter().all(Phone::is_bound) {
+                        continue;
+                    }
+
+                    let mut spliced = phones.clone();
+                    let mut rebuilt = phones.clone();
+
+                    assert_eq!(
+                        splice_output_vec(&mut spliced, input_start, input_len, output.clone()),
+                        rebuilding_splice_output(&mut rebuilt, input_start, input_len, output.clone()),
+                        "{phones:?}[{input_start}..{}] = {output:?}", input_start + input_len
+                    );
+                    assert_eq!(spliced, rebuilt, "{phones:?}[{input_start}..{}] = {output:?}", input_start + input_len);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn splice_bound_at_start_of_phones() {
+    let mut phones = vec![Phone::Symbol("a".into())];
+
+    assert_eq!(splice_output_vec(&mut phones, 0, 1, vec![Phone::Bound, Phone::Symbol("b".into())]), 1);
+    assert_eq!(phones, [Phone::Symbol("b".into())]);
+
+    let mut phones = vec![Phone::Bound, Phone::Symbol("a".into())];
+
+    assert_eq!(splice_output_vec(&mut phones, 1, 1, vec![Phone::Bound, Phone::Symbol("b".into())]), 1);
+    assert_eq!(phones, [Phone::Bound, Phone::Symbol("b".into())]);
+}
+
+#[test]
+fn gap_buffer_splice_matches_vec() {
+    let mut vec = (0..10).collect::<Vec<usize>>();
+    let mut buffer = GapBuffer::from(vec.clone());
+
+    for (range, elements) in [(2..4, vec![20, 21, 22]), (8..8, vec![80]), (0..1, Vec::new()), (5..11, vec![50; 12]), (0..0, vec![1, 2])] {
+        vec.splice(range.clone(), elements.iter().copied());
+        buffer.splice(range, &elements);
+
+        assert_eq!(buffer.len(), vec.len());
+        assert_eq!(buffer.as_split().to_vec(), vec);
+    }
+
+    assert_eq!(Vec::from(buffer), vec);
+}
+
+#[test]
+fn split_slice_ranges() {
+    let split = SplitSlice::new(&[0, 1, 2][..], &[3, 4][..]);
+
+    assert_eq!(split.get(3), Some(&3));
+    assert_eq!(split.get(5), None);
+    assert_eq!(split.slice(1..4).map(SplitSlice::to_vec), Some(vec![1, 2, 3]));
+    assert_eq!(split.slice(3..5).map(SplitSlice::to_vec), Some(vec![3, 4]));
+    assert_eq!(split.slice(4..6), None);
+    assert_eq!(split.position(|&n| n > 1), Some(2));
+    assert_eq!(split.rposition(|&n| n < 4), Some(3));
+}
@@ -1,19 +1,28 @@
 const USE_TEMPLATE_FLAGS: [&str; 2] = ["-t", "--template"];
 const CHAIN_FLAGS: [&str; 2] = ["-c", "--chain"];
 const READ_FLAGS: [&str; 2] = ["-r", "--read"];
+const JOBS_FLAGS: [&str; 2] = ["-j", "--jobs"];
 const WRITE_FLAGS: [&str; 2] = ["-w", "--write"];
 const QUIET_FLAGS: [&str; 2] = ["-q", "--quiet"];
+const TRACE_FLAGS: [&str; 2] = ["-t", "--trace"];
+const STATS_FLAGS: [&str; 2] = ["-S", "--stats"];
 const MAP_OUTPUT_FLAGS: [&str; 2] = ["-o", "--map_outputs"];
 const MAP_LOGS_FLAGS: [&str; 2] = ["-p", "--map_prints"];
 const MAP_ALL_FLAGS: [&str; 2] = ["-m", "--map_all"];
 const REDUCE_OUTPUT_FLAGS: [&str; 2] = ["-x", "--reduce"];
 const MAP_SEPARATOR_FLAGS: [&str; 2] = ["-s", "--separator"];
+const LEXICON_FLAGS: [&str; 2] = ["-l", "--lexicon"];
+const FMT_CHECK_FLAGS: [&str; 2] = ["-c", "--check"];
+const LATEX_FLAGS: [&str; 2] = ["-L", "--latex"];
+const HTML_FLAGS: [&str; 2] = ["-H", "--html"];
 
 const DEFAULT_MAP_SPACER: &str = "->";
 
-use std::env;
+use std::{env, num::NonZero};
 
-use crate::{cli_tools::ansi::{BOLD, RESET}, APPLY_CMD, CHAR_HELP_CMD, HELP_CMD, NEW_CMD};
+use cscsca::Notation;
+
+use crate::{cli_tools::ansi::{BOLD, RESET}, APPLY_CMD, CHAR_HELP_CMD, CHECK_CMD, EXPAND_CMD, FMT_CMD, HELP_CMD, LINT_CMD, LSP_CMD, NEW_CMD, RENDER_CMD, TEST_CMD, VERIFY_CMD};
 
 /// Parsed CLI input
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -22,13 +31,31 @@ pub enum CliCommand {
         paths: Vec<String>,
         output_data: OutputData,
         input: InputType,
+        jobs: NonZero<usize>,
     },
     Chars { words: Vec<String> },
+    Check { path: String },
+    Expand { path: String },
+    Fmt {
+        check: bool,
+        path: String,
+    },
     Help { extra_args: bool },
+    Lint { path: String },
+    Lsp,
     New {
         use_template: bool,
         path: String,
     },
+    Render {
+        notation: Notation,
+        path: String,
+    },
+    Test { path: String },
+    Verify {
+        path: String,
+        lexicon: String,
+    },
     None,
 }
 
@@ -50,6 +77,10 @@ pub struct OutputData {
     map: Option<MapData>,
     /// If intermediate stages should be printed during runtime
     quiet: bool,
+    /// If every change made by a rule should be printed during runtime
+    trace: bool,
+    /// If how often each rule changes an input should be printed after all inputs are changed
+    stats: bool,
 }
 
 impl OutputData {
@@ -67,6 +98,16 @@ impl OutputData {
     pub const fn quiet(&self) -> bool {
         self.quiet
     }
+
+    /// Gets if the trace flag is set
+    pub const fn trace(&self) -> bool {
+        self.trace
+    }
+
+    /// Gets if the stats flag is set
+    pub const fn stats(&self) -> bool {
+        self.stats
+    }
 }
 
 /// Which outputs are displayed in the final output
@@ -131,6 +172,49 @@ impl CliCommand {
 
                     Ok(Self::New { use_template, path })
                 },
+                RENDER_CMD => {
+                    let notation = if args.next_if(|s| LATEX_FLAGS.contains(&s.as_str())).is_some() {
+                        Notation::Latex
+                    } else if args.next_if(|s| HTML_FLAGS.contains(&s.as_str())).is_some() {
+                        Notation::Html
+                    } else {
+                        Notation::Unicode
+                    };
+
+                    Ok(Self::Render { notation, path: parse_single_path(&mut args)? })
+                },
+                TEST_CMD => Ok(Self::Test { path: parse_single_path(&mut args)? }),
+                CHECK_CMD => Ok(Self::Check { path: parse_single_path(&mut args)? }),
+                EXPAND_CMD => Ok(Self::Expand { path: parse_single_path(&mut args)? }),
+                FMT_CMD => {
+                    let check = args.next_if(|s| FMT_CHECK_FLAGS.contains(&s.as_str())).is_some();
+
+                    Ok(Self::Fmt { check, path: parse_single_path(&mut args)? })
+                },
+                LINT_CMD => Ok(Self::Lint { path: parse_single_path(&mut args)? }),
+                LSP_CMD => match args.next() {
+                    Some(cmd) => Err(ArgumentParseError::UnexpectedCommand(cmd)),
+                    None => Ok(Self::Lsp),
+                },
+                VERIFY_CMD => {
+                    let Some(path) = args.next() else {
+                        return Err(ArgumentParseError::ExpectedFileName);
+                    };
+
+                    if args.next_if(|s| LEXICON_FLAGS.contains(&s.as_str())).is_none() {
+                        return Err(ArgumentParseError::ExpectedLexicon);
+                    }
+
+                    let Some(lexicon) = args.next() else {
+                        return Err(ArgumentParseError::ExpectedFileName);
+                    };
+
+                    if let Some(cmd) = args.next() {
+                        return Err(ArgumentParseError::UnexpectedCommand(cmd));
+                    }
+
+                    Ok(Self::Verify { path, lexicon })
+                },
                 HELP_CMD => Ok(Self::Help { extra_args: args.next().is_some() }),
                 _ => Err(ArgumentParseError::UnexpectedCommand(cmd)),
             }
@@ -139,6 +223,19 @@ impl CliCommand {
     }
 }
 
+/// Parses the arguments of a command that only takes a file path
+fn parse_single_path(args: &mut std::iter::Peekable<env::Args>) -> Result<String, ArgumentParseError> {
+    let Some(path) = args.next() else {
+        return Err(ArgumentParseError::ExpectedFileName);
+    };
+
+    if let Some(cmd) = args.next() {
+        return Err(ArgumentParseError::UnexpectedCommand(cmd));
+    }
+
+    Ok(path)
+}
+
 /// Parses the application command's arguments
 fn parse_sca(args: &mut std::iter::Peekable<env::Args>) -> Result<CliCommand, ArgumentParseError> {
     let mut paths = Vec::new();
@@ -190,9 +287,15 @@ fn parse_sca(args: &mut std::iter::Peekable<env::Args>) -> Result<CliCommand, Ar
         None
     };
 
-    // sets the quite flag
+    // sets the quiet flag
     let quiet = args.next_if(|s| QUIET_FLAGS.contains(&s.as_str())).is_some();
 
+    // sets the trace flag
+    let trace = args.next_if(|s| TRACE_FLAGS.contains(&s.as_str())).is_some();
+
+    // sets the stats flag
+    let stats = args.next_if(|s| STATS_FLAGS.contains(&s.as_str())).is_some();
+
     // sets the write files
     let write = if args.next_if(|s| WRITE_FLAGS.contains(&s.as_str())).is_some() {
         match args.next() {
@@ -213,13 +316,23 @@ fn parse_sca(args: &mut std::iter::Peekable<env::Args>) -> Result<CliCommand, Ar
         InputType::Raw(args.collect::<Vec<_>>().join(" "))
     };
 
+    // sets the number of worker threads
+    let jobs = if args.next_if(|s| JOBS_FLAGS.contains(&s.as_str())).is_some() {
+        match args.next().and_then(|jobs| jobs.parse().ok()) {
+            Some(jobs) => jobs,
+            None => return Err(ArgumentParseError::ExpectedJobCount),
+        }
+    } else {
+        NonZero::<usize>::MIN
+    };
+
     // returns an error for extra arguments
     if let Some(cmd) = args.next() {
         return Err(ArgumentParseError::UnexpectedCommand(cmd));
     }
 
     // constructs the apply command
-    Ok(CliCommand::Apply { paths, output_data: OutputData { write, map, quiet }, input })
+    Ok(CliCommand::Apply { paths, output_data: OutputData { write, map, quiet, trace, stats }, input, jobs })
 }
 
 /// An error caused by invalid cli input
@@ -228,6 +341,8 @@ pub enum ArgumentParseError {
     UnexpectedCommand(String),
     ExpectedFileName,
     ExpectedSeparator,
+    ExpectedLexicon,
+    ExpectedJobCount,
 }
 
 impl std::error::Error for ArgumentParseError {}
@@ -238,6 +353,8 @@ impl std::fmt::Display for ArgumentParseError {
             Self::UnexpectedCommand(cmd) => writeln!(f, "Unexpected command '{cmd}'")?,
             Self::ExpectedFileName => writeln!(f, "Input ended unexpectedly, expected a file name")?,
             Self::ExpectedSeparator => writeln!(f, "Expected a seperator after flag {}", MAP_SEPARATOR_FLAGS.join(" or "))?,
+            Self::ExpectedLexicon => writeln!(f, "Expected {} followed by a file name", LEXICON_FLAGS.join(" or "))?,
+            Self::ExpectedJobCount => writeln!(f, "Expected a positive number of jobs after flag {}", JOBS_FLAGS.join(" or "))?,
         }
 
         write!(f, "Run '{BOLD}cscsca help{RESET}' for more information")
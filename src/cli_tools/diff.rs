@@ -0,0 +1,46 @@
+/// A part of a difference between two lists of phones
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PhoneDiff<'a> {
+    /// A phone in both lists
+    Same(&'a str),
+    /// A phone only in the expected list
+    Missing(&'a str),
+    /// A phone only in the found list
+    Extra(&'a str),
+}
+
+/// Finds the difference between the expected and found phones
+/// using their longest common subsequence
+pub fn diff_phones<'a>(expected: &'a [String], found: &'a [String]) -> Vec<PhoneDiff<'a>> {
+    // lcs_lens[i][j] is the length of the longest common subsequence of expected[i..] and found[j..]
+    let mut lcs_lens = vec![vec![0usize; found.len() + 1]; expected.len() + 1];
+
+    for i in (0..expected.len()).rev() {
+        for j in (0..found.len()).rev() {
+            lcs_lens[i][j] = if expected[i] == found[j] {
+                lcs_lens[i + 1][j + 1] + 1
+            } else {
+                lcs_lens[i + 1][j].max(lcs_lens[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::new();
+
+    while i < expected.len() || j < found.len() {
+        if i < expected.len() && j < found.len() && expected[i] == found[j] {
+            diff.push(PhoneDiff::Same(&expected[i]));
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == found.len() || lcs_lens[i + 1][j] >= lcs_lens[i][j + 1]) {
+            diff.push(PhoneDiff::Missing(&expected[i]));
+            i += 1;
+        } else {
+            diff.push(PhoneDiff::Extra(&found[j]));
+            j += 1;
+        }
+    }
+
+    diff
+}
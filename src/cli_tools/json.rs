@@ -0,0 +1,321 @@
+use std::{fmt::Write as _, iter::Peekable, str::Chars};
+
+/// A JSON value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    /// A number, stored as written so that it is not rounded
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Parses a JSON value, returns `None` if the source is not valid JSON
+    pub fn parse(source: &str) -> Option<Self> {
+        let mut chars = source.chars().peekable();
+        let json = parse_value(&mut chars)?;
+
+        skip_whitespace(&mut chars);
+        chars.peek().is_none().then_some(json)
+    }
+
+    /// Creates an object from key-value pairs
+    pub fn object<const N: usize>(fields: [(&str, Self); N]) -> Self {
+        Self::Object(fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+    }
+
+    /// Gets the value of a key if the value is an object
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        match self {
+            Self::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Gets the string if the value is a string
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Gets the number if the value is a non-negative integer
+    pub fn as_usize(&self) -> Option<usize> {
+        match self {
+            Self::Number(n) => n.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Gets the values if the value is an array
+    pub fn as_array(&self) -> Option<&[Self]> {
+        match self {
+            Self::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(value: usize) -> Self {
+        Self::Number(value.to_string())
+    }
+}
+
+impl From<Vec<Json>> for Json {
+    fn from(value: Vec<Json>) -> Self {
+        Self::Array(value)
+    }
+}
+
+impl std::fmt::Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Number(n) => write!(f, "{n}"),
+            Self::String(s) => write_string(f, s),
+            Self::Array(values) => {
+                write!(f, "[")?;
+
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+
+                    write!(f, "{value}")?;
+                }
+
+                write!(f, "]")
+            },
+            Self::Object(fields) => {
+                write!(f, "{{")?;
+
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+
+                write!(f, "}}")
+            },
+        }
+    }
+}
+
+/// Writes a string as a quoted and escaped JSON string
+fn write_string(f: &mut std::fmt::Formatter<'_>, s: &str) -> std::fmt::Result {
+    f.write_char('"')?;
+
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            _ if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            _ => f.write_char(c)?,
+        }
+    }
+
+    f.write_char('"')
+}
+
+/// Skips any whitespace at the start of the source
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(char::is_ascii_whitespace).is_some() {}
+}
+
+/// Parses the next value in the source
+fn parse_value(chars: &mut Peekable<Chars>) -> Option<Json> {
+    skip_whitespace(chars);
+
+    match chars.peek()? {
+        'n' => parse_literal(chars, "null", Json::Null),
+        't' => parse_literal(chars, "true", Json::Bool(true)),
+        'f' => parse_literal(chars, "false", Json::Bool(false)),
+        '"' => parse_string(chars).map(Json::String),
+        '[' => {
+            chars.next();
+            let mut values = Vec::new();
+
+            skip_whitespace(chars);
+            if chars.next_if_eq(&']').is_some() {
+                return Some(Json::Array(values));
+            }
+
+            loop {
+                values.push(parse_value(chars)?);
+                skip_whitespace(chars);
+
+                match chars.next()? {
+                    ',' => (),
+                    ']' => return Some(Json::Array(values)),
+                    _ => return None,
+                }
+            }
+        },
+        '{' => {
+            chars.next();
+            let mut fields = Vec::new();
+
+            skip_whitespace(chars);
+            if chars.next_if_eq(&'}').is_some() {
+                return Some(Json::Object(fields));
+            }
+
+            loop {
+                skip_whitespace(chars);
+                let key = parse_string(chars)?;
+
+                skip_whitespace(chars);
+                chars.next_if_eq(&':')?;
+
+                fields.push((key, parse_value(chars)?));
+                skip_whitespace(chars);
+
+                match chars.next()? {
+                    ',' => (),
+                    '}' => return Some(Json::Object(fields)),
+                    _ => return None,
+                }
+            }
+        },
+        _ => {
+            let mut number = String::new();
+
+            while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+                number.push(c);
+            }
+
+            is_number(&number).then_some(Json::Number(number))
+        },
+    }
+}
+
+/// Checks if a number is written as JSON allows,
+/// which is stricter than what `f64` parsing allows
+/// 
+/// Numbers have an optional minus sign, an integer part without leading zeros,
+/// and optional fraction and exponent parts
+fn is_number(number: &str) -> bool {
+    let mut chars = number.chars().peekable();
+
+    chars.next_if_eq(&'-');
+
+    if chars.next_if_eq(&'0').is_none() && !skip_digits(&mut chars) {
+        return false;
+    }
+
+    if chars.next_if_eq(&'.').is_some() && !skip_digits(&mut chars) {
+        return false;
+    }
+
+    if chars.next_if(|c| matches!(c, 'e' | 'E')).is_some() {
+        chars.next_if(|c| matches!(c, '+' | '-'));
+
+        if !skip_digits(&mut chars) {
+            return false;
+        }
+    }
+
+    chars.peek().is_none()
+}
+
+/// Skips the digits at the start of the source, returns whether there were any
+fn skip_digits(chars: &mut Peekable<Chars>) -> bool {
+    let mut any = false;
+
+    while chars.next_if(char::is_ascii_digit).is_some() {
+        any = true;
+    }
+
+    any
+}
+
+/// Parses a keyword literal
+fn parse_literal(chars: &mut Peekable<Chars>, literal: &str, value: Json) -> Option<Json> {
+    for expected in literal.chars() {
+        chars.next_if_eq(&expected)?;
+    }
+
+    Some(value)
+}
+
+/// Parses a quoted string
+fn parse_string(chars: &mut Peekable<Chars>) -> Option<String> {
+    chars.next_if_eq(&'"')?;
+    let mut s = String::new();
+
+    loop {
+        match chars.next()? {
+            '"' => return Some(s),
+            '\\' => match chars.next()? {
+                'n' => s.push('\n'),
+                'r' => s.push('\r'),
+                't' => s.push('\t'),
+                'b' => s.push('\u{8}'),
+                'f' => s.push('\u{c}'),
+                'u' => {
+                    let high = parse_hex(chars)?;
+
+                    // characters outside of the basic multilingual plane are escaped as surrogate pairs
+                    let code = if (0xD800..0xDC00).contains(&high) {
+                        chars.next_if_eq(&'\\')?;
+                        chars.next_if_eq(&'u')?;
+                        let low = parse_hex(chars)?;
+
+                        if !(0xDC00..0xE000).contains(&low) {
+                            return None;
+                        }
+
+                        0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                    } else {
+                        high
+                    };
+
+                    s.push(char::from_u32(code)?);
+                },
+                c => s.push(c),
+            },
+            c => s.push(c),
+        }
+    }
+}
+
+/// Parses four hexadecimal digits
+fn parse_hex(chars: &mut Peekable<Chars>) -> Option<u32> {
+    let mut code = 0;
+
+    for _ in 0..4 {
+        code = code * 16 + chars.next()?.to_digit(16)?;
+    }
+
+    Some(code)
+}
@@ -0,0 +1,369 @@
+use std::{collections::HashMap, io::{self, BufRead, Write}};
+
+use cscsca::{HighlightKind, ScaError};
+
+use super::json::Json;
+
+/// The semantic token types the server reports, indexed by `token_type`
+const TOKEN_TYPES: [&str; 8] = ["keyword", "comment", "macro", "variable", "parameter", "string", "operator", "enumMember"];
+
+/// The LSP error code for an unknown request method
+const METHOD_NOT_FOUND: &str = "-32601";
+
+/// The LSP diagnostic severity of an error
+const ERROR_SEVERITY: usize = 1;
+/// The LSP diagnostic severity of a warning
+const WARNING_SEVERITY: usize = 2;
+
+/// A language server that communicates over standard input and output
+#[derive(Debug, Default)]
+pub struct LanguageServer {
+    /// The text of each open document by URI
+    documents: HashMap<String, String>,
+    shutdown: bool,
+}
+
+impl LanguageServer {
+    /// Handles messages until the client sends `exit` or closes standard input
+    ///
+    /// Returns whether the client requested a shutdown before exiting
+    pub fn run(mut self) -> io::Result<bool> {
+        let mut stdin = io::stdin().lock();
+        let mut stdout = io::stdout().lock();
+
+        while let Some(message) = read_message(&mut stdin)? {
+            if message.get("method").and_then(Json::as_str) == Some("exit") {
+                break;
+            }
+
+            self.handle_message(&message, &mut stdout)?;
+        }
+
+        Ok(self.shutdown)
+    }
+
+    /// Responds to a request or acts on a notification, writing any messages to the client to the output
+    pub(super) fn handle_message(&mut self, message: &Json, output: &mut impl Write) -> io::Result<()> {
+        let Some(method) = message.get("method").and_then(Json::as_str) else {
+            return Ok(());
+        };
+
+        let params = message.get("params").unwrap_or(&Json::Null);
+
+        let result = match method {
+            "initialize" => Some(initialize_result()),
+            "shutdown" => {
+                self.shutdown = true;
+                Some(Json::Null)
+            },
+            "textDocument/didOpen" => {
+                let document = params.get("textDocument");
+
+                if let (Some(uri), Some(text)) = (
+                    document.and_then(|d| d.get("uri")).and_then(Json::as_str),
+                    document.and_then(|d| d.get("text")).and_then(Json::as_str),
+                ) {
+                    self.documents.insert(uri.to_string(), text.to_string());
+                    publish_diagnostics(output, uri, text)?;
+                }
+
+                None
+            },
+            "textDocument/didChange" => {
+                // the server uses full document sync, so the last change is the whole document
+                let text = params.get("contentChanges")
+                    .and_then(Json::as_array)
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change.get("text"))
+                    .and_then(Json::as_str);
+
+                if let (Some(uri), Some(text)) = (document_uri(params), text) {
+                    self.documents.insert(uri.to_string(), text.to_string());
+                    publish_diagnostics(output, uri, text)?;
+                }
+
+                None
+            },
+            "textDocument/didClose" => {
+                if let Some(uri) = document_uri(params) {
+                    self.documents.remove(uri);
+                    publish_diagnostics(output, uri, "")?;
+                }
+
+                None
+            },
+            "textDocument/semanticTokens/full" => Some(self.document(params)
+                .map_or(Json::Null, |(_, text)| Json::object([("data", semantic_tokens(text))]))),
+            "textDocument/hover" => Some(self.document_index(params)
+                .and_then(|(text, index)| cscsca::expand_definition_at(text, index))
+                .map_or(Json::Null, |contents| Json::object([
+                    ("contents", Json::object([
+                        ("kind", "markdown".into()),
+                        ("value", format!("```\n{contents}\n```").into()),
+                    ])),
+                ]))),
+            "textDocument/definition" => Some(self.document_index(params)
+                .and_then(|(text, index)| Some((text, cscsca::find_declaration(text, index)?)))
+                .and_then(|(text, bytes)| Some(Json::object([
+                    ("uri", document_uri(params)?.into()),
+                    ("range", range(text, bytes.start, bytes.end)),
+                ])))
+                .unwrap_or(Json::Null)),
+            _ => {
+                // unknown requests must be answered, unknown notifications are ignored
+                if let Some(id) = message.get("id") {
+                    send_message(output, &Json::object([
+                        ("jsonrpc", "2.0".into()),
+                        ("id", id.clone()),
+                        ("error", Json::object([
+                            ("code", Json::Number(METHOD_NOT_FOUND.to_string())),
+                            ("message", format!("Unknown method: {method}").into()),
+                        ])),
+                    ]))?;
+                }
+
+                None
+            },
+        };
+
+        if let (Some(result), Some(id)) = (result, message.get("id")) {
+            send_message(output, &Json::object([
+                ("jsonrpc", "2.0".into()),
+                ("id", id.clone()),
+                ("result", result),
+            ]))?;
+        }
+
+        Ok(())
+    }
+
+    /// Gets the URI and text of the document a request is about
+    fn document<'a>(&'a self, params: &'a Json) -> Option<(&'a str, &'a str)> {
+        let uri = document_uri(params)?;
+        Some((uri, self.documents.get(uri)?))
+    }
+
+    /// Gets the text of the document a request is about and the byte index of the request's position
+    fn document_index<'a>(&'a self, params: &Json) -> Option<(&'a str, usize)> {
+        let text = self.documents.get(document_uri(params)?)?;
+        let position = params.get("position")?;
+
+        let line = position.get("line").and_then(Json::as_usize)?;
+        let character = position.get("character").and_then(Json::as_usize)?;
+
+        Some((text, position_to_index(text, line, character)))
+    }
+}
+
+/// Reads a message with a `Content-Length` header,
+/// returns `None` once the input is closed
+pub(super) fn read_message(input: &mut impl BufRead) -> io::Result<Option<Json>> {
+    loop {
+        let mut len = None;
+
+        // reads headers until the empty line that ends them
+        loop {
+            let mut header = String::new();
+
+            if input.read_line(&mut header)? == 0 {
+                return Ok(None);
+            }
+
+            let header = header.trim_end();
+
+            if header.is_empty() {
+                break;
+            }
+
+            if let Some((name, value)) = header.split_once(':') && name.eq_ignore_ascii_case("Content-Length") {
+                len = value.trim().parse::<usize>().ok();
+            }
+        }
+
+        let Some(len) = len else {
+            continue;
+        };
+
+        let mut content = vec![0; len];
+        input.read_exact(&mut content)?;
+
+        if let Some(message) = String::from_utf8(content).ok().and_then(|content| Json::parse(&content)) {
+            return Ok(Some(message));
+        }
+    }
+}
+
+/// Writes a message to the output with a `Content-Length` header
+pub(super) fn send_message(output: &mut impl Write, message: &Json) -> io::Result<()> {
+    let content = message.to_string();
+
+    write!(output, "Content-Length: {}\r\n\r\n{content}", content.len())?;
+    output.flush()
+}
+
+/// Gets the URI of the document in a request's parameters
+fn document_uri(params: &Json) -> Option<&str> {
+    params.get("textDocument")?.get("uri")?.as_str()
+}
+
+/// Lists the features the server supports
+fn initialize_result() -> Json {
+    Json::object([
+        ("capabilities", Json::object([
+            ("textDocumentSync", 1.into()),
+            ("hoverProvider", true.into()),
+            ("definitionProvider", true.into()),
+            ("semanticTokensProvider", Json::object([
+                ("legend", Json::object([
+                    ("tokenTypes", TOKEN_TYPES.iter().map(|&token_type| token_type.into()).collect::<Vec<_>>().into()),
+                    ("tokenModifiers", Vec::new().into()),
+                ])),
+                ("full", true.into()),
+            ])),
+        ])),
+        ("serverInfo", Json::object([
+            ("name", "cscsca".into()),
+            ("version", env!("CARGO_PKG_VERSION").into()),
+        ])),
+    ])
+}
+
+/// Checks and lints a document and sends every error and warning to the client
+fn publish_diagnostics(output: &mut impl Write, uri: &str, text: &str) -> io::Result<()> {
+    let mut diagnostics = Vec::new();
+
+    // variables are empty so that checking does not prompt for input
+    for error in &cscsca::check_rules(text) {
+        let message = format!("{} Error: {}", error.error_type(), error.error_message());
+        diagnostics.push(diagnostic(error_range(text, error), ERROR_SEVERITY, message));
+    }
+
+    for warning in cscsca::lint_rules(text) {
+        let range = line_range(text, warning.line_number().get() - 1, warning.rule().lines().count());
+        diagnostics.push(diagnostic(range, WARNING_SEVERITY, warning.kind().to_string()));
+    }
+
+    send_message(output, &Json::object([
+        ("jsonrpc", "2.0".into()),
+        ("method", "textDocument/publishDiagnostics".into()),
+        ("params", Json::object([
+            ("uri", uri.into()),
+            ("diagnostics", diagnostics.into()),
+        ])),
+    ]))
+}
+
+/// Creates an LSP diagnostic
+fn diagnostic(range: Json, severity: usize, message: String) -> Json {
+    Json::object([
+        ("range", range),
+        ("severity", severity.into()),
+        ("source", "cscsca".into()),
+        ("message", message.into()),
+    ])
+}
+
+/// Gets the range of the token that caused an error,
+/// or of the lines it occured on if it was not caused by a single token
+fn error_range(text: &str, error: &ScaError) -> Json {
+    if let Some(span) = error.span() {
+        let bytes = span.bytes();
+        range(text, bytes.start, bytes.end)
+    } else {
+        line_range(text, error.line_number().get() - 1, error.line_count().get())
+    }
+}
+
+/// Encodes the highlights of a document as LSP semantic tokens
+///
+/// Each token is its line and start relative to the previous token, its length, and its type
+fn semantic_tokens(text: &str) -> Json {
+    let mut data = Vec::new();
+    let (mut last_line, mut last_start) = (0, 0);
+
+    for highlight in cscsca::highlight_rules(text) {
+        let token_type = match highlight.kind() {
+            HighlightKind::Keyword => 0,
+            HighlightKind::Comment => 1,
+            HighlightKind::Definition => 2,
+            HighlightKind::Variable => 3,
+            HighlightKind::Label => 4,
+            HighlightKind::Message => 5,
+            HighlightKind::Operator => 6,
+            HighlightKind::Phone => 7,
+            _ => continue,
+        };
+
+        let bytes = highlight.bytes();
+        let (line, start) = index_to_position(text, bytes.start);
+
+        // tokens may not span multiple lines
+        let len = text[bytes].split('\n').next().unwrap_or_default().encode_utf16().count();
+
+        if len == 0 {
+            continue;
+        }
+
+        let relative_start = if line == last_line { start - last_start } else { start };
+
+        data.extend([line - last_line, relative_start, len, token_type, 0].map(Json::from));
+        (last_line, last_start) = (line, start);
+    }
+
+    data.into()
+}
+
+/// Creates an LSP range from a start and end byte index
+fn range(text: &str, start: usize, end: usize) -> Json {
+    Json::object([
+        ("start", position(index_to_position(text, start))),
+        ("end", position(index_to_position(text, end))),
+    ])
+}
+
+/// Creates an LSP range covering `count` whole lines starting at the zero indexed `line`
+fn line_range(text: &str, line: usize, count: usize) -> Json {
+    let last_line = line + count.max(1) - 1;
+    let end = text.split('\n').nth(last_line).map_or(0, |s| s.trim_end_matches('\r').encode_utf16().count());
+
+    Json::object([
+        ("start", position((line, 0))),
+        ("end", position((last_line, end))),
+    ])
+}
+
+/// Creates an LSP position from a line and character
+fn position((line, character): (usize, usize)) -> Json {
+    Json::object([
+        ("line", line.into()),
+        ("character", character.into()),
+    ])
+}
+
+/// Converts a byte index into a zero indexed line and UTF-16 character offset
+fn index_to_position(text: &str, index: usize) -> (usize, usize) {
+    let before = &text[..index.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+    (before.matches('\n').count(), before[line_start..].encode_utf16().count())
+}
+
+/// Converts a zero indexed line and UTF-16 character offset into a byte index
+fn position_to_index(text: &str, line: usize, character: usize) -> usize {
+    let line_start = text.split_inclusive('\n')
+        .take(line)
+        .map(str::len)
+        .sum::<usize>();
+
+    let mut utf16_len = 0;
+
+    for (i, c) in text[line_start..].char_indices() {
+        if utf16_len >= character || c == '\n' {
+            return line_start + i;
+        }
+
+        utf16_len += c.len_utf16();
+    }
+
+    text.len()
+}
@@ -1,3 +1,9 @@
 pub mod cli_parser;
 pub mod cli_io;
-pub mod ansi;
\ No newline at end of file
+pub mod ansi;
+pub mod diff;
+pub mod json;
+pub mod lsp;
+
+#[cfg(test)]
+mod tests;
\ No newline at end of file
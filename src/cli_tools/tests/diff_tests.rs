@@ -0,0 +1,54 @@
+use crate::cli_tools::diff::{PhoneDiff, diff_phones};
+
+/// Converts a list of string slices to owned phones
+fn phones(phones: &[&str]) -> Vec<String> {
+    phones.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn identical() {
+    let expected = phones(&["p", "a", "t"]);
+
+    assert_eq!(diff_phones(&expected, &expected.clone()), [PhoneDiff::Same("p"), PhoneDiff::Same("a"), PhoneDiff::Same("t")]);
+}
+
+#[test]
+fn empty() {
+    let some = phones(&["a", "b"]);
+
+    assert_eq!(diff_phones(&[], &[]), []);
+    assert_eq!(diff_phones(&some, &[]), [PhoneDiff::Missing("a"), PhoneDiff::Missing("b")]);
+    assert_eq!(diff_phones(&[], &some), [PhoneDiff::Extra("a"), PhoneDiff::Extra("b")]);
+}
+
+#[test]
+fn insertion() {
+    let expected = phones(&["p", "t"]);
+    let found = phones(&["p", "a", "t"]);
+
+    assert_eq!(diff_phones(&expected, &found), [PhoneDiff::Same("p"), PhoneDiff::Extra("a"), PhoneDiff::Same("t")]);
+}
+
+#[test]
+fn deletion() {
+    let expected = phones(&["p", "a", "t", "a"]);
+    let found = phones(&["p", "t", "a"]);
+
+    assert_eq!(diff_phones(&expected, &found), [PhoneDiff::Same("p"), PhoneDiff::Missing("a"), PhoneDiff::Same("t"), PhoneDiff::Same("a")]);
+}
+
+#[test]
+fn substitution() {
+    let expected = phones(&["p", "ˈaː", "t", " ", "a"]);
+    let found = phones(&["p", "ˈa", "d", " ", "a"]);
+
+    assert_eq!(diff_phones(&expected, &found), [
+        PhoneDiff::Same("p"),
+        PhoneDiff::Missing("ˈaː"),
+        PhoneDiff::Missing("t"),
+        PhoneDiff::Extra("ˈa"),
+        PhoneDiff::Extra("d"),
+        PhoneDiff::Same(" "),
+        PhoneDiff::Same("a"),
+    ]);
+}
@@ -0,0 +1,49 @@
+use crate::cli_tools::json::Json;
+
+#[test]
+fn surrogate_pairs() {
+    assert_eq!(Json::parse(r#""\ud83d\ude00""#), Some("😀".into()));
+    assert_eq!(Json::parse(r#""a\u00e9\u4e2d""#), Some("aé中".into()));
+}
+
+#[test]
+fn lone_surrogates() {
+    assert_eq!(Json::parse(r#""\udc00""#), None);
+    assert_eq!(Json::parse(r#""\ud83d""#), None);
+    assert_eq!(Json::parse(r#""\ud83da""#), None);
+    assert_eq!(Json::parse(r#""\ud83d\u0041""#), None);
+    assert_eq!(Json::parse(r#""\ud83d\ue000""#), None);
+}
+
+#[test]
+fn numbers() {
+    for number in ["0", "-1", "12", "1.5", "-0.25", "1e5", "1E-5", "2.5e+3"] {
+        assert_eq!(Json::parse(number), Some(Json::Number(number.to_string())), "{number} should be a number");
+    }
+
+    for not_number in ["-", "1e", "+1", "01", ".5", "1.", "1e+", "--1", "1.2.3"] {
+        assert_eq!(Json::parse(not_number), None, "{not_number} should not be a number");
+    }
+}
+
+#[test]
+fn trailing_garbage() {
+    assert_eq!(Json::parse("{} x"), None);
+    assert_eq!(Json::parse("1 2"), None);
+    assert_eq!(Json::parse("truex"), None);
+    assert_eq!(Json::parse("[1]]"), None);
+    assert_eq!(Json::parse(" [1] \n"), Some(vec![1.into()].into()));
+}
+
+#[test]
+fn display_round_trip() {
+    let json = Json::object([
+        ("null", Json::Null),
+        ("bool", true.into()),
+        ("number", Json::Number("-1.5e3".to_string())),
+        ("string", "quote \" backslash \\ newline \n tab \t control \u{1} emoji 😀".into()),
+        ("array", vec![Json::Null, 0.into(), Vec::new().into(), Json::object([])].into()),
+    ]);
+
+    assert_eq!(Json::parse(&json.to_string()), Some(json));
+}
@@ -0,0 +1,146 @@
+use crate::cli_tools::{json::Json, lsp::{LanguageServer, read_message, send_message}};
+
+/// Sends messages to a server, returns every message it sends back
+fn respond(server: &mut LanguageServer, messages: &[Json]) -> Vec<Json> {
+    let mut output = Vec::new();
+
+    for message in messages {
+        server.handle_message(message, &mut output).expect("writing to a buffer should not fail");
+    }
+
+    read_all(&output)
+}
+
+/// Reads every message in the input
+fn read_all(mut input: &[u8]) -> Vec<Json> {
+    let mut messages = Vec::new();
+
+    while let Some(message) = read_message(&mut input).expect("reading from a buffer should not fail") {
+        messages.push(message);
+    }
+
+    messages
+}
+
+/// Creates a request
+fn request(id: usize, method: &str, params: Json) -> Json {
+    Json::object([
+        ("jsonrpc", "2.0".into()),
+        ("id", id.into()),
+        ("method", method.into()),
+        ("params", params),
+    ])
+}
+
+/// Creates a notification
+fn notification(method: &str, params: Json) -> Json {
+    Json::object([
+        ("jsonrpc", "2.0".into()),
+        ("method", method.into()),
+        ("params", params),
+    ])
+}
+
+/// Creates the parameters of a `didOpen` notification
+fn open_params(text: &str) -> Json {
+    Json::object([
+        ("textDocument", Json::object([
+            ("uri", "file:///rules.sca".into()),
+            ("text", text.into()),
+        ])),
+    ])
+}
+
+/// Creates the parameters of a request about a position in a document
+fn position_params(line: usize, character: usize) -> Json {
+    Json::object([
+        ("textDocument", Json::object([("uri", "file:///rules.sca".into())])),
+        ("position", Json::object([("line", line.into()), ("character", character.into())])),
+    ])
+}
+
+/// Gets the start lines of the diagnostics in a `publishDiagnostics` notification
+fn diagnostic_lines(message: &Json) -> Vec<usize> {
+    assert_eq!(message.get("method").and_then(Json::as_str), Some("textDocument/publishDiagnostics"));
+
+    message.get("params")
+        .and_then(|params| params.get("diagnostics"))
+        .and_then(Json::as_array)
+        .expect("diagnostics should be an array")
+        .iter()
+        .filter_map(|diagnostic| diagnostic.get("range")?.get("start")?.get("line")?.as_usize())
+        .collect()
+}
+
+#[test]
+fn framing() {
+    let mut output = Vec::new();
+    send_message(&mut output, &Json::object([("text", "é".into())])).expect("writing to a buffer should not fail");
+
+    // the length is in bytes rather than characters
+    assert_eq!(String::from_utf8(output.clone()), Ok("Content-Length: 13\r\n\r\n{\"text\":\"é\"}".to_string()));
+    assert_eq!(read_all(&output), [Json::object([("text", "é".into())])]);
+
+    let input = "content-length: 8\r\nContent-Type: application/vscode-jsonrpc; charset=utf-8\r\n\r\n{\"id\":1}\
+        Content-Length: 3\r\n\r\nabc\
+        Content-Length: 2\r\n\r\n[]";
+
+    // header names are case insensitive, other headers are ignored, and invalid content is skipped
+    assert_eq!(read_all(input.as_bytes()), [Json::object([("id", 1.into())]), Vec::new().into()]);
+
+    let mut truncated = "Content-Length: 10\r\n\r\n{}".as_bytes();
+    assert!(read_message(&mut truncated).is_err());
+}
+
+#[test]
+fn requests() {
+    let mut server = LanguageServer::default();
+
+    let responses = respond(&mut server, &[
+        request(1, "initialize", Json::object([])),
+        request(2, "unknown", Json::Null),
+        notification("$/unknown", Json::Null),
+        request(3, "shutdown", Json::Null),
+    ]);
+
+    assert_eq!(responses.len(), 3);
+
+    assert_eq!(responses[0].get("id"), Some(&1.into()));
+    assert_eq!(responses[0].get("result").and_then(|result| result.get("capabilities")?.get("hoverProvider")), Some(&true.into()));
+
+    assert_eq!(responses[1].get("id"), Some(&2.into()));
+    assert_eq!(responses[1].get("error").and_then(|error| error.get("code")), Some(&Json::Number("-32601".to_string())));
+
+    assert_eq!(responses[2].get("id"), Some(&3.into()));
+    assert_eq!(responses[2].get("result"), Some(&Json::Null));
+}
+
+#[test]
+fn documents() {
+    let mut server = LanguageServer::default();
+    let text = "GET_AS_CODE y Enter:\na %y\nDEFINE V {a, e}\n@V >> i\n@C >> i";
+
+    let responses = respond(&mut server, &[
+        notification("textDocument/didOpen", open_params(text)),
+        request(1, "textDocument/hover", position_params(3, 0)),
+        request(2, "textDocument/definition", position_params(3, 1)),
+        notification("textDocument/didClose", open_params("")),
+        request(3, "textDocument/hover", position_params(3, 0)),
+    ]);
+
+    assert_eq!(responses.len(), 5);
+
+    // the line using the variable fetched with `GET_AS_CODE` is not reported
+    assert_eq!(diagnostic_lines(&responses[0]), [4]);
+
+    let hover = responses[1].get("result")
+        .and_then(|result| result.get("contents")?.get("value")?.as_str());
+    assert!(hover.is_some_and(|hover| hover.contains("{ a, e }")), "hover should show the definition, found {hover:?}");
+
+    let definition = responses[2].get("result")
+        .and_then(|result| result.get("range")?.get("start")?.get("line")?.as_usize());
+    assert_eq!(definition, Some(2));
+
+    assert_eq!(diagnostic_lines(&responses[3]), []);
+    assert_eq!(responses[4].get("result"), Some(&Json::Null));
+}
@@ -0,0 +1,3 @@
+mod diff_tests;
+mod json_tests;
+mod lsp_tests;
\ No newline at end of file
@@ -5,17 +5,10 @@ use crate::{keywords::{is_isolated_char, is_special_char, ESCAPE_CHAR}};
 pub struct EscapedString(String);
 
 impl EscapedString {
-    /// Shrinks the internal `String` to have no extra capacity 
-    pub fn shrink_to_fit(&mut self) {
-        self.0.shrink_to_fit();
-    }
-
-    /// Leaks into an aribitrary-lifetime `EscapedStr`
-    /// 
-    /// # Warning
-    /// If the `EscapedStr` is dropped a memory leak may occur
-    pub fn leak<'a>(self) -> EscapedStr<'a> {
-        EscapedStr(self.0.leak())
+    /// Replaces the contents with an escaped copy of `input`, reusing the allocation
+    pub fn replace(&mut self, input: &str) {
+        self.0.clear();
+        escape_input_into(input, &mut self.0);
     }
 
     /// Returns a reference the internal `String` as an `EscapedStr`
@@ -44,7 +37,12 @@ impl<'a> EscapedStr<'a> {
 /// Escapes special chars and isolated special strings in input
 fn escape_input(input: &str) -> String {
     let mut escaped = String::new();
+    escape_input_into(input, &mut escaped);
+    escaped
+}
 
+/// Pushes `input` to `escaped`, escaping special chars and isolated special strings
+fn escape_input_into(input: &str, escaped: &mut String) {
     for c in input.chars() {
         if is_special_char(c) || is_isolated_char(c) {
             escaped.push(ESCAPE_CHAR);
@@ -52,6 +50,4 @@ fn escape_input(input: &str) -> String {
 
         escaped.push(c);
     }
-
-    escaped
 }
\ No newline at end of file
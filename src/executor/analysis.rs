@@ -0,0 +1,248 @@
+use std::ops::Range;
+
+use crate::{
+    executor::{build_line_without_io, format::ir_token_to_string},
+    ir::{tokenization_data::TokenizationData, tokens::IrToken},
+    lexer::{Lexer, sir::SirToken, token_types::Span},
+    shared_str::SharedStr,
+};
+
+/// Splits rules into highlighted ranges for editors
+///
+/// Whitespace and line ends are not highlighted
+#[must_use]
+pub fn highlight_rules(rules: &str) -> Vec<Highlight> {
+    let mut highlights = Vec::new();
+
+    for expr in expressions(rules) {
+        let declared = declared_name(&expr).map(|(_, token)| token.span().index());
+
+        for token in &expr {
+            let kind = match token {
+                SirToken::Phone(name) if Some(name.span().index()) == declared => match expr[0] {
+                    SirToken::GetCommand(_) | SirToken::GetAsCodeCommand(_) => HighlightKind::Variable,
+                    _ => HighlightKind::Definition,
+                },
+                SirToken::Phone(_) => HighlightKind::Phone,
+                SirToken::Definition(_) => HighlightKind::Definition,
+                SirToken::Label(_) => HighlightKind::Label,
+                SirToken::Variable(_) => HighlightKind::Variable,
+                SirToken::InvalidPrefix(..) | SirToken::InvalidPhone(_) => HighlightKind::Invalid,
+                SirToken::NonPhoneEscape(..) | SirToken::Break(..)
+                | SirToken::CondFocus(..) | SirToken::ScopeStart(..)
+                | SirToken::ScopeEnd(..) | SirToken::Any(_)
+                | SirToken::ArgSep(_) | SirToken::Bound(_)
+                | SirToken::SyllableBound(_) | SirToken::Negative(_) => HighlightKind::Operator,
+                SirToken::DefinitionDeclaration(_) | SirToken::LazyDefinitionDeclaration(_)
+                | SirToken::FeaturesDeclaration(_) | SirToken::SyllabifyDeclaration(_)
+                | SirToken::GetCommand(_) | SirToken::GetAsCodeCommand(_)
+                | SirToken::PrintCommand(_) | SirToken::TestCommand(_) => HighlightKind::Keyword,
+                SirToken::Comment(_) => HighlightKind::Comment,
+                SirToken::Message(..) => HighlightKind::Message,
+                SirToken::Whitespace(_) | SirToken::EndOfExpr(_) => continue,
+            };
+
+            let span = token.span();
+
+            if span.len() > 0 {
+                highlights.push(Highlight { bytes: span.index()..span.index() + span.len(), kind });
+            }
+        }
+    }
+
+    highlights
+}
+
+/// Finds where the definition or variable at byte `index` of the rules is declared
+///
+/// The latest declaration before the name is used,
+/// if there is none the first declaration after it is used instead
+///
+/// Returns the range of bytes of the declared name
+#[must_use]
+pub fn find_declaration(rules: &str, index: usize) -> Option<Range<usize>> {
+    let names = names(rules);
+    let target = names.iter().find(|name| name.contains(index))?;
+
+    let mut declarations = names.iter()
+        .filter(|name| name.declaration && name.kind == target.kind && name.text == target.text);
+
+    let declaration = if target.declaration {
+        target
+    } else {
+        declarations.clone()
+            .rfind(|name| name.expr < target.expr)
+            .or_else(|| declarations.find(|name| name.expr > target.expr))?
+    };
+
+    Some(declaration.bytes())
+}
+
+/// Gets the contents of the definition at byte `index` of the rules
+/// with every definition in it expanded
+///
+/// Lines before the definition are built to find its contents
+/// with variables fetched with `GET` or `GET_AS_CODE` treated as empty
+#[must_use]
+pub fn expand_definition_at(rules: &str, index: usize) -> Option<String> {
+    let target = names(rules).into_iter()
+        .find(|name| name.kind == NameKind::Definition && name.contains(index))?;
+
+    // a declared name is expanded after its declaration, any other after the lines before it
+    let last_expr = if target.declaration { target.expr + 1 } else { target.expr };
+
+    let mut sir = Lexer::lex(rules);
+    let mut tokenization_data = TokenizationData::new();
+
+    for _ in 0..last_expr {
+        build_line_without_io(&mut sir, &mut tokenization_data);
+    }
+
+    let mut tokens = Vec::new();
+    tokenization_data.get_definition(&target.text, &mut tokens, &mut Vec::new())
+        .ok()
+        .map(|()| ir_tokens_to_string(&tokens))
+}
+
+/// A highlighted range of rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Highlight {
+    bytes: Range<usize>,
+    kind: HighlightKind,
+}
+
+impl Highlight {
+    /// Gets the range of bytes that is highlighted
+    #[must_use]
+    #[inline]
+    pub fn bytes(&self) -> Range<usize> {
+        self.bytes.clone()
+    }
+
+    /// Gets what the highlighted range is
+    #[must_use]
+    #[inline]
+    pub const fn kind(&self) -> HighlightKind {
+        self.kind
+    }
+}
+
+/// What a `Highlight` is
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HighlightKind {
+    /// A statement keyword such as `DEFINE` or `PRINT`
+    Keyword,
+    /// A comment
+    Comment,
+    /// A definition's name
+    Definition,
+    /// A variable's name
+    Variable,
+    /// A label
+    Label,
+    /// A phone
+    Phone,
+    /// A token with special meaning such as `>` or `{`
+    Operator,
+    /// A message of a `PRINT`, `GET`, or `TEST` statement
+    Message,
+    /// A token that cannot be used where it is
+    Invalid,
+}
+
+/// Whether a name belongs to a definition or variable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NameKind {
+    Definition,
+    Variable,
+}
+
+/// A definition or variable name that is declared or used
+#[derive(Debug, Clone)]
+struct Name {
+    text: SharedStr,
+    kind: NameKind,
+    span: Span,
+    /// The index of the expression the name is in
+    expr: usize,
+    declaration: bool,
+}
+
+impl Name {
+    /// Gets the range of bytes the name occupies
+    const fn bytes(&self) -> Range<usize> {
+        self.span.index()..self.span.index() + self.span.len()
+    }
+
+    /// Determines if the byte `index` is in or at the end of the name
+    const fn contains(&self, index: usize) -> bool {
+        self.span.index() <= index && index <= self.span.index() + self.span.len()
+    }
+}
+
+/// Splits rules into expressions of SIR tokens
+fn expressions(rules: &str) -> Vec<Vec<SirToken>> {
+    let mut expressions = vec![Vec::new()];
+
+    for token in Lexer::lex(rules) {
+        let end = matches!(token, SirToken::EndOfExpr(_));
+
+        expressions.last_mut().expect("there should always be an expression").push(token);
+
+        if end {
+            expressions.push(Vec::new());
+        }
+    }
+
+    expressions
+}
+
+/// Gets the kind and token of the name declared by an expression, if any
+fn declared_name(expr: &[SirToken]) -> Option<(NameKind, &SirToken)> {
+    let mut tokens = expr.iter().filter(|token| !matches!(token, SirToken::Whitespace(_)));
+
+    match (tokens.next()?, tokens.next()?) {
+        (SirToken::DefinitionDeclaration(_) | SirToken::LazyDefinitionDeclaration(_), name @ SirToken::Phone(_)) => Some((NameKind::Definition, name)),
+        (SirToken::GetCommand(_) | SirToken::GetAsCodeCommand(_), name @ SirToken::Phone(_)) => Some((NameKind::Variable, name)),
+        _ => None,
+    }
+}
+
+/// Finds every definition and variable name that is declared or used in rules
+fn names(rules: &str) -> Vec<Name> {
+    let mut names = Vec::new();
+
+    for (i, expr) in expressions(rules).iter().enumerate() {
+        if let Some((kind, SirToken::Phone(name))) = declared_name(expr) {
+            names.push(Name { text: name.str().clone(), kind, span: *name.span(), expr: i, declaration: true });
+        }
+
+        for token in expr {
+            let (kind, name) = match token {
+                SirToken::Definition(name) => (NameKind::Definition, name),
+                SirToken::Variable(name) => (NameKind::Variable, name),
+                _ => continue,
+            };
+
+            names.push(Name { text: name.str().clone(), kind, span: *name.span(), expr: i, declaration: false });
+        }
+    }
+
+    names
+}
+
+/// Formats a list of ir tokens as they would be written in rules
+fn ir_tokens_to_string(tokens: &[IrToken]) -> String {
+    let mut s = String::new();
+
+    for token in tokens {
+        if !s.is_empty() && *token != IrToken::ArgSep {
+            s.push(' ');
+        }
+
+        s += &ir_token_to_string(token);
+    }
+
+    s
+}
@@ -5,8 +5,8 @@ use std::num::NonZero;
 
 use crate::{
     ONE, ScaError, await_io, escaped_strings::EscapedString, executor::{
-        build_line, getter::{ContextIoGetter, IoGetter}, runtime::{ContextRuntime, Runtime, RuntimeApplier}
-    }, io_fn, ir::tokenization_data::TokenizationData, lexer::Lexer, matcher::patterns::ir_to_patterns::RuleLine, phones::{build_phone_list, phone_list_to_string}
+        build_line, derivation::{Derivation, DerivationRuntime}, expansion::ExpandedRule, io_events::RuntimeIoEvent, rule_tests::RuleTestResult, statistics::{RuleStatistics, StatisticsRuntime}, getter::{ContextIoGetter, IoGetter}, runtime::{ContextRuntime, DEFAULT_LINE_APPLICATION_LIMIT, LineApplicationLimit, Runtime, RuntimeApplier}
+    }, io_fn, ir::tokenization_data::TokenizationData, lexer::Lexer, matcher::patterns::{ir_to_patterns::RuleLine, rule::SoundChangeRule}, phones::{Phone, build_phone_list, phone_list_to_string, rebuild_phone_list}, shared_str::SharedStr
 };
 
 /// Builds all rules to a form that may be applied more easily within a given context
@@ -14,10 +14,11 @@ use crate::{
 /// # Errors
 /// Errors on invalid rules or failed io
 #[io_fn]
-pub fn build_rules_with_context<'s, G: ContextIoGetter>(rules: &'s str, getter: &mut G, ctx: G::InputContext) -> Result<AppliableRules<'s>, ScaError> {
+pub fn build_rules_with_context<G: ContextIoGetter>(rules: &str, getter: &mut G, ctx: G::InputContext) -> Result<AppliableRules, ScaError> {
     let tokenization_data = TokenizationData::new();
     
-    await_io! { build_rules_with_tokenization_data_and_context(rules, tokenization_data, getter, ctx) }
+    await_io! { build_rules_with_tokenization_data_and_context(rules, tokenization_data, getter, ctx, false) }
+        .map_err(first_error)
 }
 
 /// Builds all rules to a form that may be applied more easily
@@ -26,18 +27,54 @@ pub fn build_rules_with_context<'s, G: ContextIoGetter>(rules: &'s str, getter:
 /// Errors on invalid rules or failed io
 #[io_fn]
 #[inline]
-pub fn build_rules<'s, G: IoGetter>(rules: &'s str, getter: &mut G) -> Result<AppliableRules<'s>, ScaError> {
+pub fn build_rules<G: IoGetter>(rules: &str, getter: &mut G) -> Result<AppliableRules, ScaError> {
     await_io! { build_rules_with_context(rules, getter, ()) }
 }
 
+/// Builds all rules to a form that may be applied more easily within a given context,
+/// skipping lines that cannot be built so that every error is found
+/// 
+/// # Errors
+/// Errors with every error found on invalid rules or failed io
+#[io_fn]
+pub fn build_rules_recovering_with_context<G: ContextIoGetter>(rules: &str, getter: &mut G, ctx: G::InputContext) -> Result<AppliableRules, Vec<ScaError>> {
+    let tokenization_data = TokenizationData::new();
+    
+    await_io! { build_rules_with_tokenization_data_and_context(rules, tokenization_data, getter, ctx, true) }
+}
+
+/// Builds all rules to a form that may be applied more easily,
+/// skipping lines that cannot be built so that every error is found
+/// 
+/// # Errors
+/// Errors with every error found on invalid rules or failed io
+#[io_fn]
+#[inline]
+pub fn build_rules_recovering<G: IoGetter>(rules: &str, getter: &mut G) -> Result<AppliableRules, Vec<ScaError>> {
+    await_io! { build_rules_recovering_with_context(rules, getter, ()) }
+}
+
+/// Gets the first error from a list of errors
+/// 
+/// The list should never be empty
+fn first_error(errors: Vec<ScaError>) -> ScaError {
+    errors.into_iter()
+        .next()
+        .expect("a failed build should have at least one error")
+}
+
 /// Builds an `AppliableRules` struct from rules, pre-built tokenization data,
-/// the number of lines that proceed the first line, and a `ContextIoGetter`
+/// and a `ContextIoGetter`
+/// 
+/// If `recover` is `true`, lines that cannot be built are skipped
+/// and building continues to find further errors
 /// 
 /// # Errors
 /// Errors on invalid rules or failed io
 #[io_fn]
-fn build_rules_with_tokenization_data_and_context<'s, G: ContextIoGetter>(rules: &'s str, mut tokenization_data: TokenizationData<'s>, getter: &mut G, mut ctx: G::InputContext) -> Result<AppliableRules<'s>, ScaError> {
+fn build_rules_with_tokenization_data_and_context<G: ContextIoGetter>(rules: &str, mut tokenization_data: TokenizationData, getter: &mut G, mut ctx: G::InputContext, recover: bool) -> Result<AppliableRules, Vec<ScaError>> {
     let mut rule_lines = Vec::new();
+    let mut errors = Vec::new();
     let mut sir = Lexer::lex(rules);
 
     // prepares the getter to start fetching a new set of input
@@ -45,34 +82,38 @@ fn build_rules_with_tokenization_data_and_context<'s, G: ContextIoGetter>(rules:
 
     // builds each line
     while !sir.is_empty() {
-        // builds the line and returns any errors
-        let (rule_line, c) = match await_io! {
+        // builds the line and records any errors
+        match await_io! {
             build_line(&mut sir, &mut tokenization_data, getter, ctx)
         } {
-            Ok(rule_line) => rule_line,
-            Err(e) => {
-                // signals to the getter that the rules are done being built
-                getter.on_end();
-
-                drop(rule_lines);
-                // Safety: Since the output is a `ScaError`,
-                // which owns all of its values, and `rule_lines` is dropped,
-                // no references remain to the sources buffer in `tokenization_data`
-                unsafe { tokenization_data.free_sources() };
-
-                
-                return Err(e.into_sca_error(rules.lines()));
+            Ok((rule_line, c)) => {
+                ctx = c;
+                rule_lines.push(rule_line);
+            },
+            Err((e, c)) => {
+                errors.push(e.into_sca_error(rules.lines()));
+
+                // building cannot continue without the context
+                if let Some(c) = c && recover {
+                    ctx = c;
+                } else {
+                    break;
+                }
             }
-        };
-        ctx = c;
-        rule_lines.push(rule_line);
+        }
     }
 
     // signals to the getter that the rules are done being built
     getter.on_end();
 
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let source = SharedStr::from(rules);
+
     Ok(AppliableRules {
-        lines: rules.lines().collect(),
+        lines: source.lines().map(|line| source.share(line)).collect(),
         rules: rule_lines,
         tokenization_data,
     })
@@ -80,17 +121,20 @@ fn build_rules_with_tokenization_data_and_context<'s, G: ContextIoGetter>(rules:
 
 /// A set of rules reduced to an easily appliable form
 /// that may be applied any number of times
-#[derive(Debug)]
-pub struct AppliableRules<'s> {
-    /// References to each line of the input text (for error messages)
-    lines: Vec<&'s str>,
+/// 
+/// The rules own their source and any fetched io,
+/// so they may be cloned and stored without borrowing the source
+#[derive(Debug, Clone)]
+pub struct AppliableRules {
+    /// Each line of the input text (for error messages)
+    lines: Vec<SharedStr>,
     /// The built rules
-    rules: Vec<RuleLine<'s>>,
-    /// Data required to build rules, including raw pointers to input strings
-    tokenization_data: TokenizationData<'s>,
+    rules: Vec<RuleLine>,
+    /// Data required to build rules
+    tokenization_data: TokenizationData,
 }
 
-impl<'s> AppliableRules<'s> {
+impl AppliableRules {
     /// Applies all rules to the input using a runtime, errors are formatted as a string within a given context
     #[inline]
     #[io_fn]
@@ -114,34 +158,50 @@ impl<'s> AppliableRules<'s> {
     /// # Errors
     /// Errors on invalid rules, application that takes too long, and failed io
     #[io_fn]
-    pub fn apply_fallible_with_context<R: ContextRuntime>(&self, input: &str, runtime: &mut R, mut ctx: R::OutputContext) -> Result<String, ScaError> {
+    #[inline]
+    pub fn apply_fallible_with_context<R: ContextRuntime>(&self, input: &str, runtime: &mut R, ctx: R::OutputContext) -> Result<String, ScaError> {
+        await_io! {
+            self.apply_first_lines(input, runtime, ctx, self.rules.len())
+        }
+    }
+
+    /// Applies the first `count` rule lines to the input using a runtime within a given context
+    /// 
+    /// # Errors
+    /// Errors on invalid rules, application that takes too long, and failed io
+    #[io_fn]
+    fn apply_first_lines<R: ContextRuntime>(&self, input: &str, runtime: &mut R, ctx: R::OutputContext, count: usize) -> Result<String, ScaError> {
         let escaped_input = EscapedString::from(input);
         let mut phones = build_phone_list(escaped_input.as_escaped_str());
 
-        let mut line_num = ONE;
-
         // prepares the runtime for a new set of applications
         runtime.on_start();
 
+        let result = await_io! { self.apply_first_lines_to_phones(&mut phones, runtime, ctx, count) };
+
+        // signals to the runtime that execution is complete
+        runtime.on_end();
+
+        result.map(|_| phone_list_to_string(&phones))
+    }
+
+    /// Applies the first `count` rule lines to a list of phones using a runtime within a given context
+    /// 
+    /// # Errors
+    /// Errors on invalid rules, application that takes too long, and failed io
+    #[io_fn]
+    fn apply_first_lines_to_phones<R: ContextRuntime>(&self, phones: &mut Vec<Phone>, runtime: &mut R, mut ctx: R::OutputContext, count: usize) -> Result<R::OutputContext, ScaError> {
+        let mut line_num = ONE;
+
         // applies rules
-        for rule_line in &self.rules {
-            match await_io! { runtime.apply_line(ctx, rule_line, &mut phones, line_num) } {
-                Ok(c) => ctx = c,
-                Err(e) => {
-                    // signals to the runtime that execution is complete
-                    runtime.on_end();
-
-                    return Err(e.into_sca_error(self.lines.iter().copied()));
-                }
-            }
+        for rule_line in &self.rules[..count] {
+            ctx = await_io! { runtime.apply_line(ctx, rule_line, phones, line_num, &self.lines) }
+                .map_err(|e| e.into_sca_error(self.lines.iter().map(SharedStr::as_str)))?;
 
             line_num = line_num.saturating_add(rule_line.lines().get());
         }
 
-        // signals to the runtime that execution is complete
-        runtime.on_end();
-
-        Ok(phone_list_to_string(&phones))
+        Ok(ctx)
     }
 
     /// Applies all rules to the input using a runtime
@@ -156,6 +216,106 @@ impl<'s> AppliableRules<'s> {
         }
     }
 
+    /// Applies all rules to each input using a runtime,
+    /// returning the result for each input in the same order
+    /// 
+    /// The runtime is started once before the first input and ended once after the last,
+    /// and the buffers used to apply the rules are reused between inputs.
+    /// An input that fails to apply, such as one that takes too long,
+    /// does not stop the rules from being applied to the remaining inputs
+    #[io_fn]
+    pub fn apply_batch<R: Runtime, S: AsRef<str>>(&self, inputs: &[S], runtime: &mut R) -> Vec<Result<String, ScaError>> {
+        let mut outputs = Vec::with_capacity(inputs.len());
+        let mut escaped_input = EscapedString::default();
+        let mut phones = Vec::new();
+
+        // prepares the runtime for a new set of applications
+        runtime.on_start();
+
+        for input in inputs {
+            escaped_input.replace(input.as_ref());
+
+            rebuild_phone_list(escaped_input.as_escaped_str(), &mut phones);
+
+            let result = await_io! { self.apply_first_lines_to_phones(&mut phones, runtime, (), self.rules.len()) };
+            outputs.push(result.map(|()| phone_list_to_string(&phones)));
+        }
+
+        // signals to the runtime that execution is complete
+        runtime.on_end();
+
+        outputs
+    }
+
+    /// Applies all rules to the input using a runtime within a given context,
+    /// recording which rules change the input in `statistics`
+    /// 
+    /// `statistics` should be created from `self`
+    /// 
+    /// # Errors
+    /// Errors on invalid rules, application that takes too long, and failed io
+    #[io_fn]
+    pub fn apply_with_statistics_and_context<R: ContextRuntime>(&self, input: &str, runtime: &mut R, ctx: R::OutputContext, statistics: &mut RuleStatistics) -> Result<String, ScaError> {
+        let mut runtime = StatisticsRuntime::new(runtime, statistics, input);
+
+        await_io! {
+            self.apply_fallible_with_context(input, &mut runtime, ctx)
+        }
+    }
+
+    /// Applies all rules to the input using a runtime,
+    /// recording which rules change the input in `statistics`
+    /// 
+    /// `statistics` should be created from `self`
+    /// 
+    /// # Errors
+    /// Errors on invalid rules, application that takes too long, and failed io
+    #[io_fn]
+    #[inline]
+    pub fn apply_with_statistics<R: Runtime>(&self, input: &str, runtime: &mut R, statistics: &mut RuleStatistics) -> Result<String, ScaError> {
+        await_io! {
+            self.apply_with_statistics_and_context(input, runtime, (), statistics)
+        }
+    }
+
+    /// Runs every `TEST` statement, applying the rules before each test to its input
+    /// and comparing the output to the test's expected output
+    #[io_fn]
+    pub fn run_tests<R: Runtime>(&self, runtime: &mut R) -> Vec<RuleTestResult> {
+        let mut results = Vec::new();
+        let mut line_num = ONE;
+
+        for (i, rule_line) in self.rules.iter().enumerate() {
+            if let RuleLine::IoEvent(RuntimeIoEvent::Test { input, expected }) = rule_line {
+                let output = await_io! {
+                    self.apply_first_lines(input, runtime, (), i)
+                };
+
+                results.push(RuleTestResult::new(line_num, input, expected, output));
+            }
+
+            line_num = line_num.saturating_add(rule_line.lines().get());
+        }
+
+        results
+    }
+
+    /// Applies all rules to the input, recording every change made by a rule and every `PRINT` statement
+    /// 
+    /// The default limit is used if `line_application_limit` is `None`
+    /// 
+    /// # Errors
+    /// Errors on application that takes longer than the limit
+    #[io_fn]
+    pub fn derive(&self, input: &str, line_application_limit: Option<LineApplicationLimit>) -> Result<Derivation, ScaError> {
+        let mut runtime = DerivationRuntime::new(line_application_limit.unwrap_or(DEFAULT_LINE_APPLICATION_LIMIT));
+
+        let output = await_io! {
+            self.apply_fallible(input, &mut runtime)
+        }?;
+
+        Ok(runtime.into_derivation(input.to_string(), output))
+    }
 
     /// Extends appliable rules with new rules source within a given context
     /// 
@@ -164,26 +324,27 @@ impl<'s> AppliableRules<'s> {
     /// 
     /// leaves `self` unchanged on error
     #[io_fn]
-    pub fn extend_with_context<G: ContextIoGetter>(&mut self, next_rules: &'s str, getter: &mut G, ctx: G::InputContext) -> Result<(), ScaError> {
-        let tokenization_data = self.tokenization_data.with_inserts();
+    pub fn extend_with_context<G: ContextIoGetter>(&mut self, next_rules: &str, getter: &mut G, ctx: G::InputContext) -> Result<(), ScaError> {
+        let tokenization_data = self.tokenization_data.clone();
 
         let num_lines_pre_extension = self.lines.len();
+        // the sources are joined with newlines, so the new source starts after every line and its newline
+        let num_bytes_pre_extension = self.lines.iter().map(|line| line.len() + 1).sum::<usize>();
         
         let mut new_appliable = await_io! {
-            build_rules_with_tokenization_data_and_context(next_rules, tokenization_data, getter, ctx)
-        }.map_err(|mut e| {
+            build_rules_with_tokenization_data_and_context(next_rules, tokenization_data, getter, ctx, false)
+        }.map_err(first_error).map_err(|mut e| {
             e.line_num = unsafe { NonZero::new_unchecked(e.line_num.get() + num_lines_pre_extension) };
+            if let Some(span) = &mut e.span {
+                span.line_num = unsafe { NonZero::new_unchecked(span.line_num.get() + num_lines_pre_extension) };
+                span.bytes = span.bytes.start + num_bytes_pre_extension..span.bytes.end + num_bytes_pre_extension;
+            }
             e
         })?;
 
         self.lines.append(&mut new_appliable.lines);
         self.rules.append(&mut new_appliable.rules);
-        std::mem::swap(&mut self.tokenization_data, &mut new_appliable.tokenization_data);
-
-        // Saftey: it is safe to drop `new_appliable`
-        // because all of its sources have been moved to `self`
-        // `new_appliable` is dropped before `self`
-        unsafe { self.tokenization_data.take_sources_from(&mut new_appliable.tokenization_data) };
+        self.tokenization_data = new_appliable.tokenization_data;
 
         Ok(())
     }
@@ -196,27 +357,54 @@ impl<'s> AppliableRules<'s> {
     /// leaves `self` unchanged on error
     #[io_fn]
     #[inline]
-    pub fn extend<G: IoGetter>(&mut self, next_rules: &'s str, getter: &mut G) -> Result<(), ScaError> {
+    pub fn extend<G: IoGetter>(&mut self, next_rules: &str, getter: &mut G) -> Result<(), ScaError> {
         await_io! {
             self.extend_with_context(next_rules, getter, ())
         }
     }
 
+    /// Returns an iterator over the line number and source code of every sound change rule
+    pub(crate) fn rule_sources(&self) -> impl Iterator<Item = (NonZero<usize>, String)> {
+        self.sound_change_rules().map(|(line_num, rule, _)| (line_num, rule))
+    }
+
+    /// Returns an iterator over the line number, source code, and built form of every sound change rule
+    fn sound_change_rules(&self) -> impl Iterator<Item = (NonZero<usize>, String, &SoundChangeRule)> {
+        let mut line_num = ONE;
+
+        self.rules.iter().filter_map(move |rule_line| {
+            let start = line_num;
+            line_num = line_num.saturating_add(rule_line.lines().get());
+
+            let RuleLine::Rule { rule, lines } = rule_line else {
+                return None;
+            };
+
+            let source = self.lines.iter()
+                .skip(start.get() - 1)
+                .take(lines.get())
+                .map(SharedStr::as_str)
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            Some((start, source, rule))
+        })
+    }
+
+    /// Gets every sound change rule with all definitions and variables substituted
+    /// 
+    /// Definitions are substituted with their contents when the rules are built,
+    /// so the expanded rules are the built rules written as rules
+    #[must_use]
+    pub fn expanded_rules(&self) -> Vec<ExpandedRule> {
+        self.sound_change_rules()
+            .map(|(line_num, rule, built)| ExpandedRule::new(line_num, rule, built.to_string()))
+            .collect()
+    }
+
     /// Returns a copy of the source rules
     #[must_use]
     pub fn get_rules(&self) -> String {
         self.lines.join("\n")
     }
 }
-
-impl Drop for AppliableRules<'_> {
-    fn drop(&mut self) {
-        for source in self.tokenization_data.sources() {
-            // Safety: `AppliableRules` should never be cloned
-            // or leak references to the IO sources
-            // ! this must be invarient and maintained within the `AppliableRules` API
-            let ptr = source.cast_mut();
-            unsafe { drop(Box::from_raw(ptr)); }
-        }
-    }
-}
@@ -1,5 +1,5 @@
 use super::*;
-use crate::{io_macros::{await_io, io_test}, tests::{NoGet, NoLog}, ONE};
+use crate::{io_macros::{await_io, io_fn, io_test}, tests::{NoGet, NoLog}, ONE, ScaErrorKind};
 
 #[io_test(pollster::block_on)]
 fn appliable_rules() {
@@ -80,6 +80,22 @@ fn extend_rules_with_error() {
     assert_eq!(res.unwrap_err().line_num.get(), 2);
 }
 
+#[io_test(pollster::block_on)]
+fn extend_rules_with_error_span() {
+    let rules_1 = "a >> bc\nb >> c";
+    let rules_2 = "c >> @a";
+
+    let mut rules = await_io! { build_rules(rules_1, &mut NoGet) }.expect("Rules should be valid");
+
+    let error = await_io! { rules.extend(rules_2, &mut NoGet) }.expect_err("'@a' is not defined");
+    let span = error.span().expect("the error should have a span");
+    let combined = format!("{rules_1}\n{rules_2}");
+
+    assert_eq!(span.line_number().get(), 3);
+    assert_eq!(&combined[span.bytes()], "@a");
+    assert_eq!(&rules_2[span.columns()], "@a");
+}
+
 #[io_test(pollster::block_on)]
 fn extend_rules_with_definition() {
     let rules_1 = "DEFINE a bc >> d\na >> bc";
@@ -92,4 +108,270 @@ fn extend_rules_with_definition() {
     let output = await_io! { rules.apply_fallible("a bc", &mut NoLog::default()) }.expect("Rules should be valid");
 
     assert_eq!(&output, "d bc");
-}
\ No newline at end of file
+}
+
+struct SingleGet(&'static str);
+
+impl IoGetter for SingleGet {
+    #[io_fn(impl)]
+    fn get_io(&mut self, _: &str) -> Result<String, String> {
+        Ok(self.0.to_string())
+    }
+}
+
+/// Builds rules from a copy of the source that is dropped before the rules are returned
+#[io_fn]
+fn build_owned_rules(rules: &str) -> AppliableRules {
+    let rules = rules.to_string();
+    await_io! { build_rules(&rules, &mut SingleGet("x")) }.expect("Rules should be valid")
+}
+
+#[io_test(pollster::block_on)]
+fn owned_rules() {
+    // the source and fetched input are dropped before the rules are applied
+    let rules = await_io! { build_owned_rules("GET var :\nDEFINE V {a, e}\n@V >> %var") };
+    let mut extended = rules.clone();
+
+    let next_rules = String::from("x >> y");
+    await_io! { extended.extend(&next_rules, &mut NoGet) }.expect("Rules should be valid");
+    drop(next_rules);
+    drop(rules.clone());
+
+    assert_eq!(await_io! { rules.apply_fallible("bae", &mut NoLog::default()) }, Ok("bxx".to_string()));
+    assert_eq!(await_io! { extended.apply_fallible("bae", &mut NoLog::default()) }, Ok("byy".to_string()));
+
+    drop(rules);
+
+    assert_eq!(await_io! { extended.apply_fallible("ea", &mut NoLog::default()) }, Ok("yy".to_string()));
+    assert_eq!(extended.get_rules(), "GET var :\nDEFINE V {a, e}\n@V >> %var\nx >> y");
+}
+
+#[io_test(pollster::block_on)]
+fn apply_batch() {
+    let rules = "t >> d / _ i\na > b a";
+    let inputs = ["tip ti", "tap", "", "ti (> *", "tit tit tit tit"];
+
+    let appliable_rules = await_io! { build_rules(rules, &mut NoGet) }
+        .expect("rules should compile");
+
+    let outputs = await_io! { appliable_rules.apply_batch(&inputs, &mut NoLog::default()) };
+
+    assert_eq!(outputs.len(), inputs.len());
+    assert_eq!(outputs[0], Ok("dip di".to_string()));
+    assert!(outputs[1].as_ref().is_err_and(|e| e.line_num.get() == 2));
+
+    for (input, output) in inputs.iter().zip(outputs) {
+        assert_eq!(output, await_io! { appliable_rules.apply_fallible(input, &mut NoLog::default()) });
+    }
+}
+
+#[io_test(pollster::block_on)]
+fn derivation() {
+    use crate::executor::derivation::DerivationStep;
+
+    let rules = "a >> b\nPRINT first:\nx >> y\nc >> e e";
+
+    let appliable_rules = await_io! { build_rules(rules, &mut NoGet) }
+        .expect("rules should compile");
+
+    let derivation = await_io! { appliable_rules.derive("abc", None) }
+        .expect("rules should apply");
+
+    assert_eq!(derivation.input(), "abc");
+    assert_eq!(derivation.output(), "bbee");
+    assert_eq!(derivation.steps().len(), 3);
+    assert_eq!(derivation.steps()[1], DerivationStep::Print { msg: "first:".to_string(), phones: "bbc".to_string() });
+
+    let changes = derivation.changes().collect::<Vec<_>>();
+
+    assert_eq!(changes.len(), 2);
+    assert_eq!((changes[0].line_number().get(), changes[0].rule(), changes[0].before(), changes[0].after(), changes[0].span()), (1, "a >> b", "abc", "bbc", 0..1));
+    assert_eq!((changes[1].line_number().get(), changes[1].rule(), changes[1].before(), changes[1].after(), changes[1].span()), (4, "c >> e e", "bbc", "bbee", 2..4));
+}
+
+#[io_test(pollster::block_on)]
+fn cyclic_derivation() {
+    let appliable_rules = await_io! { build_rules("a > b a", &mut NoGet) }
+        .expect("rules should compile");
+
+    let error = await_io! { appliable_rules.derive("a", None) }
+        .expect_err("a cyclic rule should exceed the default limit");
+
+    assert_eq!(error.kind(), &ScaErrorKind::ExceededLimit { limit: DEFAULT_LINE_APPLICATION_LIMIT });
+}
+
+#[io_test(pollster::block_on)]
+fn rule_statistics() {
+    use crate::executor::statistics::RuleStatistics;
+
+    let rules = "DEFINE V {a, i}\nt >> d / @V _ @V\nPRINT x:\nx >> h\na >> a\ni >> e / _ #";
+
+    let appliable_rules = await_io! { build_rules(rules, &mut NoGet) }
+        .expect("rules should compile");
+
+    let mut statistics = RuleStatistics::new(&appliable_rules);
+
+    for input in ["ata", "iti", "ta"] {
+        await_io! { appliable_rules.apply_with_statistics(input, &mut NoLog::default(), &mut statistics) }
+            .expect("rules should apply");
+    }
+
+    assert_eq!(statistics.inputs(), 3);
+    assert_eq!(
+        statistics.rules().iter().map(|rule| (rule.line_number().get(), rule.rule(), rule.times_fired())).collect::<Vec<_>>(),
+        [(2, "t >> d / @V _ @V", 2), (4, "x >> h", 0), (5, "a >> a", 0), (6, "i >> e / _ #", 1)]
+    );
+    assert_eq!(statistics.rules()[0].words(), ["ata", "iti"]);
+    assert_eq!(statistics.rules()[3].words(), ["iti"]);
+    assert_eq!(statistics.unused_rules().map(|rule| rule.line_number().get()).collect::<Vec<_>>(), [4, 5]);
+}
+
+#[io_test(pollster::block_on)]
+fn merged_rule_statistics() {
+    use crate::executor::statistics::RuleStatistics;
+
+    let rules = "t >> d / _ {a, i}\ni >> e / _ #";
+
+    let appliable_rules = await_io! { build_rules(rules, &mut NoGet) }
+        .expect("rules should compile");
+
+    let mut all = RuleStatistics::new(&appliable_rules);
+    let mut first = RuleStatistics::new(&appliable_rules);
+    let mut second = RuleStatistics::new(&appliable_rules);
+
+    for (input, in_first) in [("ata", true), ("iti", true), ("ati", false)] {
+        let statistics = if in_first { &mut first } else { &mut second };
+
+        await_io! { appliable_rules.apply_with_statistics(input, &mut NoLog::default(), statistics) }
+            .expect("rules should apply");
+        await_io! { appliable_rules.apply_with_statistics(input, &mut NoLog::default(), &mut all) }
+            .expect("rules should apply");
+    }
+
+    first.merge(second);
+
+    assert_eq!(first, all);
+    assert_eq!(first.rules()[0].words(), ["ata", "iti", "ati"]);
+}
+
+#[io_test(pollster::block_on)]
+fn sampled_rule_statistics() {
+    use crate::executor::statistics::{RuleStatistics, RuleStats};
+
+    let appliable_rules = await_io! { build_rules("t >> d", &mut NoGet) }
+        .expect("rules should compile");
+
+    let inputs = (0..RuleStats::MAX_SAMPLE_WORDS + 5).map(|i| format!("t{i}")).collect::<Vec<_>>();
+    let mut first = RuleStatistics::new(&appliable_rules);
+    let mut second = RuleStatistics::new(&appliable_rules);
+
+    for (i, input) in inputs.iter().enumerate() {
+        let statistics = if i < 3 { &mut first } else { &mut second };
+
+        await_io! { appliable_rules.apply_with_statistics(input, &mut NoLog::default(), statistics) }
+            .expect("rules should apply");
+    }
+
+    assert_eq!(second.rules()[0].times_fired(), RuleStats::MAX_SAMPLE_WORDS + 2);
+    assert_eq!(second.rules()[0].words(), &inputs[3..RuleStats::MAX_SAMPLE_WORDS + 3]);
+
+    first.merge(second);
+
+    assert_eq!(first.rules()[0].times_fired(), inputs.len());
+    assert_eq!(first.rules()[0].words(), &inputs[..RuleStats::MAX_SAMPLE_WORDS]);
+}
+
+#[io_test(pollster::block_on)]
+fn rule_tests() {
+    let rules = "TEST pata => pata\nt >> d / a _ a\nTEST pata => pada\nTEST tata => dada\na >> e / _ #\nTEST pata => pade\nTEST  ˈata  =>  ˈadaː ";
+
+    let appliable_rules = await_io! { build_rules(rules, &mut NoGet) }
+        .expect("rules should compile");
+
+    assert_eq!(await_io! { appliable_rules.apply_fallible("pata", &mut NoLog::default()) }, Ok("pade".to_string()));
+
+    let results = await_io! { appliable_rules.run_tests(&mut NoLog::default()) };
+
+    assert_eq!(
+        results.iter().map(|result| (result.line_number().get(), result.input(), result.expected(), result.output().ok(), result.passed())).collect::<Vec<_>>(),
+        [
+            (1, "pata", "pata", Some("pata"), true),
+            (3, "pata", "pada", Some("pada"), true),
+            (4, "tata", "dada", Some("tada"), false),
+            (6, "pata", "pade", Some("pade"), true),
+            (7, "ˈata", "ˈadaː", Some("ˈade"), false),
+        ]
+    );
+}
+
+#[io_test(pollster::block_on)]
+fn recovering_build() {
+    let rules = "a >> b > c\nDEFINE V {a, e}\n@V >> $ / _ #\nb >> c\n@C >> d\n%x >> a";
+
+    let errors = await_io! { build_rules_recovering(rules, &mut NoGet) }
+        .expect_err("rules should not compile");
+
+    assert_eq!(errors.iter().map(|e| e.line_num.get()).collect::<Vec<_>>(), [1, 3, 5, 6]);
+    assert_eq!(errors[2].rule, "@C >> d");
+
+    assert!(await_io! { build_rules_recovering("DEFINE V {a, e}\n@V >> o", &mut NoGet) }.is_ok());
+}
+
+#[io_test(pollster::block_on)]
+fn error_spans() {
+    let rules = "a >> b\nŋ >> @C / _ #\nc >> d / _ $x";
+
+    let errors = await_io! { build_rules_recovering(rules, &mut NoGet) }
+        .expect_err("rules should not compile");
+
+    let spans = errors.iter()
+        .map(|e| e.span().map(|span| (span.line_number().get(), span.bytes(), span.columns())))
+        .collect::<Vec<_>>();
+
+    assert_eq!(spans, [Some((2, 13..15, 5..7)), Some((3, 33..35, 11..13))]);
+
+    let error = await_io! { build_rules("ŋ >> \\\n {a, b", &mut NoGet) }
+        .expect_err("rules should not compile");
+
+    assert_eq!(error.span().map(|span| (span.line_number().get(), span.columns())), Some((2, 1..2)));
+}
+
+#[io_test(pollster::block_on)]
+fn extended_error_spans() {
+    let mut rules = await_io! { build_rules("a >> b\nb >> c\nc >> d", &mut NoGet) }.expect("Rules should be valid");
+
+    let error = await_io! { rules.extend("x >> @V", &mut NoGet) }
+        .expect_err("rules should not compile");
+
+    assert_eq!(error.line_number().get(), 4);
+    assert_eq!(error.span().map(|span| (span.line_number().get(), span.columns())), Some((4, 5..7)));
+}
+
+#[io_test(pollster::block_on)]
+fn rules_are_thread_safe() {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<AppliableRules>();
+
+    let inputs = ["pata", "tipa", "atipat", "apapa"];
+    let rules = "SYLLABIFY ({p, t}), {a, i}, ({p, t})\nDEFINE V {a, i}\n{p, t} >> {b, d} / @V _ @V\na >> e / _ . t // [* ! i] _ #";
+
+    let rules = await_io! { build_rules(rules, &mut NoGet) }.expect("Rules should be valid");
+
+    let mut expected = Vec::new();
+
+    for input in inputs {
+        expected.push(await_io! { rules.apply_fallible(input, &mut NoLog::default()) });
+    }
+
+    // every thread applies the same rules
+    let outputs = std::thread::scope(|scope| {
+        inputs.map(|input| {
+            let rules = &rules;
+            scope.spawn(move || pollster::block_on(async move {
+                await_io! { rules.apply_fallible(input, &mut NoLog::default()) }
+            }))
+        }).map(|handle| handle.join().expect("application should not panic"))
+    });
+
+    assert_eq!(Vec::from(outputs), expected);
+}
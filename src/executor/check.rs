@@ -0,0 +1,88 @@
+use std::collections::HashSet;
+
+use crate::{
+    ScaError, await_io, io_fn,
+    executor::{appliable_rules::build_rules_recovering, getter::EmptyGetter},
+    lexer::{Lexer, sir::SirToken},
+    shared_str::SharedStr,
+};
+
+/// Finds every error in rules without fetching any input
+///
+/// Variables fetched with `GET` or `GET_AS_CODE` are treated as empty.
+/// The code fetched by `GET_AS_CODE` could change how a line is built,
+/// so errors on lines that use such a variable, directly or through a definition, are not reported
+#[io_fn]
+pub fn check_rules(rules: &str) -> Vec<ScaError> {
+    let built = await_io! { build_rules_recovering(rules, &mut EmptyGetter) };
+
+    let Err(errors) = built else {
+        return Vec::new();
+    };
+
+    let code_lines = lines_using_code_variables(rules);
+
+    errors.into_iter()
+        .filter(|e| !code_lines.contains(&e.line_number().get()))
+        .collect()
+}
+
+/// Finds the first line of each expression that uses a variable fetched with `GET_AS_CODE`,
+/// directly or through a definition
+fn lines_using_code_variables(rules: &str) -> HashSet<usize> {
+    let mut sir = Lexer::lex(rules);
+    let mut code_names = HashSet::new();
+    let mut lines = HashSet::new();
+
+    while !sir.is_empty() {
+        let line_num = sir.line() + 1;
+        let expr = sir.by_ref()
+            .take_while(|token| !matches!(token, SirToken::EndOfExpr(_)))
+            .filter(|token| !matches!(token, SirToken::Whitespace(_)))
+            .collect::<Vec<_>>();
+
+        let uses_code = expr.iter().any(|token| match token {
+            SirToken::Variable(name) | SirToken::Definition(name) => code_names.contains(&CodeName::from(token, name.str())),
+            _ => false,
+        });
+
+        match expr.as_slice() {
+            [SirToken::GetAsCodeCommand(_), SirToken::Phone(name), ..] => {
+                code_names.insert(CodeName::Variable(name.str().clone()));
+            },
+            [SirToken::GetCommand(_), SirToken::Phone(name), ..] => {
+                code_names.remove(&CodeName::Variable(name.str().clone()));
+            },
+            [SirToken::DefinitionDeclaration(_) | SirToken::LazyDefinitionDeclaration(_), SirToken::Phone(name), ..] => if uses_code {
+                code_names.insert(CodeName::Definition(name.str().clone()));
+            } else {
+                code_names.remove(&CodeName::Definition(name.str().clone()));
+            },
+            _ => (),
+        }
+
+        if uses_code {
+            lines.insert(line_num);
+        }
+    }
+
+    lines
+}
+
+/// The name of a variable fetched with `GET_AS_CODE` or of a definition that uses one
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum CodeName {
+    Variable(SharedStr),
+    Definition(SharedStr),
+}
+
+impl CodeName {
+    /// Creates the name used by a variable or definition token
+    fn from(token: &SirToken, name: &SharedStr) -> Self {
+        if let SirToken::Variable(_) = token {
+            Self::Variable(name.clone())
+        } else {
+            Self::Definition(name.clone())
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use std::{num::NonZero, ops::Range};
+
+use crate::{executor::runtime::{LineApplicationLimit, Runtime}, io_fn};
+
+/// A record of every change made to an input by a set of rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Derivation {
+    input: String,
+    output: String,
+    steps: Vec<DerivationStep>,
+}
+
+impl Derivation {
+    /// Gets the input the rules were applied to
+    #[must_use]
+    #[inline]
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Gets the output of the rules
+    #[must_use]
+    #[inline]
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Gets every change and `PRINT` snapshot in the order they occured
+    #[must_use]
+    #[inline]
+    pub fn steps(&self) -> &[DerivationStep] {
+        &self.steps
+    }
+
+    /// Gets an iterator over every change made by a rule
+    #[inline]
+    pub fn changes(&self) -> impl Iterator<Item = &RuleChange> {
+        self.steps.iter().filter_map(|step| match step {
+            DerivationStep::Change(change) => Some(change),
+            DerivationStep::Print { .. } => None,
+        })
+    }
+}
+
+/// A step in a `Derivation`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DerivationStep {
+    /// A rule changed the phones
+    Change(RuleChange),
+    /// A `PRINT` statement was executed
+    Print {
+        /// The printed message
+        msg: String,
+        /// The phones when the message was printed
+        phones: String,
+    },
+}
+
+/// A change made by a single rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleChange {
+    line_num: NonZero<usize>,
+    rule: String,
+    before: String,
+    after: String,
+    span: Range<usize>,
+}
+
+impl RuleChange {
+    /// Gets the number of the line the rule starts on
+    #[must_use]
+    #[inline]
+    pub const fn line_number(&self) -> NonZero<usize> {
+        self.line_num
+    }
+
+    /// Gets the source code of the rule
+    #[must_use]
+    #[inline]
+    pub fn rule(&self) -> &str {
+        &self.rule
+    }
+
+    /// Gets the phones before the rule was applied
+    #[must_use]
+    #[inline]
+    pub fn before(&self) -> &str {
+        &self.before
+    }
+
+    /// Gets the phones after the rule was applied
+    #[must_use]
+    #[inline]
+    pub fn after(&self) -> &str {
+        &self.after
+    }
+
+    /// Gets the byte range of the phones after the rule was applied
+    /// that differ from the phones before it was applied
+    #[must_use]
+    #[inline]
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+}
+
+/// A `Runtime` that records the steps of a `Derivation`
+#[derive(Debug)]
+pub(super) struct DerivationRuntime {
+    steps: Vec<DerivationStep>,
+    line_application_limit: LineApplicationLimit,
+}
+
+impl DerivationRuntime {
+    /// Creates a new `DerivationRuntime`
+    pub(super) const fn new(line_application_limit: LineApplicationLimit) -> Self {
+        Self {
+            steps: Vec::new(),
+            line_application_limit,
+        }
+    }
+
+    /// Creates a `Derivation` from the recorded steps
+    pub(super) fn into_derivation(self, input: String, output: String) -> Derivation {
+        Derivation {
+            input,
+            output,
+            steps: self.steps,
+        }
+    }
+}
+
+impl Runtime for DerivationRuntime {
+    #[io_fn(impl)]
+    fn put_io(&mut self, msg: &str, phones: String) -> Result<(), String> {
+        self.steps.push(DerivationStep::Print { msg: msg.to_string(), phones });
+        Ok(())
+    }
+
+    #[inline]
+    fn on_rule_applied(&mut self, line_num: NonZero<usize>, rule_source: &str, before: &str, after: &str, span: Range<usize>) {
+        self.steps.push(DerivationStep::Change(RuleChange {
+            line_num,
+            rule: rule_source.to_string(),
+            before: before.to_string(),
+            after: after.to_string(),
+            span,
+        }));
+    }
+
+    #[inline]
+    fn wants_rule_applied(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn line_application_limit(&self) -> Option<LineApplicationLimit> {
+        Some(self.line_application_limit)
+    }
+}
@@ -0,0 +1,43 @@
+use std::num::NonZero;
+
+/// A sound change rule with all definitions and variables substituted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpandedRule {
+    line_num: NonZero<usize>,
+    rule: String,
+    expanded: String,
+}
+
+impl ExpandedRule {
+    /// Creates a new `ExpandedRule`
+    pub(super) const fn new(line_num: NonZero<usize>, rule: String, expanded: String) -> Self {
+        Self { line_num, rule, expanded }
+    }
+
+    /// Gets the number of the line the rule starts on
+    #[must_use]
+    #[inline]
+    pub const fn line_number(&self) -> NonZero<usize> {
+        self.line_num
+    }
+
+    /// Gets the source code of the rule
+    #[must_use]
+    #[inline]
+    pub fn rule(&self) -> &str {
+        &self.rule
+    }
+
+    /// Gets the rule with all definitions and variables substituted
+    #[must_use]
+    #[inline]
+    pub fn expanded(&self) -> &str {
+        &self.expanded
+    }
+}
+
+impl std::fmt::Display for ExpandedRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.expanded)
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{
+    ir::tokens::IrToken,
+    keywords::{
+        BOUND_CHAR, COMMENT_LINE_START, DEFINITION_LINE_START, FEATURES_LINE_START, GET_LINE_START,
+        PRINT_LINE_START, SYLLABIFY_LINE_START, TEST_LINE_START,
+    },
+    lexer::{Lexer, sir::SirToken},
+    phones::Phone,
+};
+
+/// The indentation of a line continued from the previous line
+const CONTINUATION_INDENT: &str = "    ";
+
+/// The starts of statements and comments, which are only found at the start of a line
+/// 
+/// `DEFINE_LAZY` and `GET_AS_CODE` are covered by `DEFINE` and `GET`
+const LINE_STARTS: [&str; 7] = [
+    DEFINITION_LINE_START, FEATURES_LINE_START, SYLLABIFY_LINE_START,
+    GET_LINE_START, PRINT_LINE_START, TEST_LINE_START, COMMENT_LINE_START,
+];
+
+/// Re-emits rules with normalized spacing
+///
+/// Tokens are seperated by a single space,
+/// except for after the start of a scope, before the end of a scope, before a `,`,
+/// and between a label and its scope.
+/// Comments, messages, and line continuations are kept,
+/// and continued lines are indented
+#[must_use]
+pub fn format_rules(rules: &str) -> String {
+    let newline = if rules.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut formatted = String::with_capacity(rules.len());
+    let mut prev = None;
+    let mut continued = false;
+    let mut whitespace = false;
+
+    for token in Lexer::lex(rules) {
+        match token {
+            SirToken::Whitespace(_) => {
+                whitespace = true;
+                continue;
+            },
+            SirToken::EndOfExpr(_) => {
+                formatted += newline;
+                (prev, continued) = (None, false);
+                continue;
+            },
+            SirToken::NonPhoneEscape('\n', _) => {
+                if prev.is_some() {
+                    formatted.push(' ');
+                }
+
+                formatted.push('\\');
+                formatted += newline;
+                (prev, continued) = (None, true);
+                continue;
+            },
+            SirToken::Message(msg, _) if msg.trim_end().is_empty() => continue,
+            _ => (),
+        }
+
+        let text = token_text(rules, &token);
+
+        match prev {
+            // invalid tokens keep their spacing so that they are not split or joined differently
+            Some(prev) if is_invalid(&prev) || is_invalid(&token) => if whitespace {
+                formatted.push(' ');
+            },
+            Some(prev) => if spaced(&prev, &token) {
+                formatted.push(' ');
+            },
+            // statements are only found at the start of a line
+            None if is_line_start(&token) => (),
+            None if continued => formatted += CONTINUATION_INDENT,
+            // keeps text that is not a statement from becoming one
+            None => if LINE_STARTS.iter().any(|start| text.starts_with(start)) {
+                formatted.push(' ');
+            },
+        }
+
+        formatted += &text;
+        prev = Some(token);
+        whitespace = false;
+    }
+
+    formatted
+}
+
+/// Formats an ir token as it would be written in rules
+pub(super) fn ir_token_to_string(token: &IrToken) -> String {
+    match token {
+        IrToken::Phone(Phone::Bound) => BOUND_CHAR.to_string(),
+        _ => token.to_string(),
+    }
+}
+
+/// Gets the formatted text of a token
+fn token_text(rules: &str, token: &SirToken) -> String {
+    let ir = match token {
+        SirToken::Any(_) => IrToken::Any,
+        SirToken::ArgSep(_) => IrToken::ArgSep,
+        SirToken::Bound(_) => IrToken::Phone(Phone::Bound),
+        SirToken::SyllableBound(_) => IrToken::SyllableBound,
+        SirToken::Break(r#break, _) => IrToken::Break(*r#break),
+        SirToken::CondFocus(focus, _) => IrToken::CondType(*focus),
+        SirToken::ScopeStart(kind, _) => IrToken::ScopeStart(*kind),
+        SirToken::ScopeEnd(kind, _) => IrToken::ScopeEnd(*kind),
+        SirToken::Negative(_) => IrToken::Negative,
+        SirToken::Message(msg, _) => return msg.trim_end().to_string(),
+        SirToken::Comment(span) => return rules[span.index()..span.index() + span.len()].trim_end().to_string(),
+        // names, phones, and escapes are kept as written so that their escapes are kept
+        _ => {
+            let span = token.span();
+            return rules[span.index()..span.index() + span.len()].to_string();
+        },
+    };
+
+    ir_token_to_string(&ir)
+}
+
+/// Determines if a token is only found at the start of a line
+const fn is_line_start(token: &SirToken) -> bool {
+    matches!(token,
+        SirToken::Comment(_)
+        | SirToken::DefinitionDeclaration(_) | SirToken::LazyDefinitionDeclaration(_)
+        | SirToken::FeaturesDeclaration(_) | SirToken::SyllabifyDeclaration(_)
+        | SirToken::GetCommand(_) | SirToken::GetAsCodeCommand(_)
+        | SirToken::PrintCommand(_) | SirToken::TestCommand(_)
+    )
+}
+
+/// Determines if a token is not valid where it is
+const fn is_invalid(token: &SirToken) -> bool {
+    matches!(token, SirToken::InvalidPhone(_) | SirToken::InvalidPrefix(..) | SirToken::NonPhoneEscape(..))
+}
+
+/// Determines if two consecutive tokens should be seperated by a space
+const fn spaced(prev: &SirToken, token: &SirToken) -> bool {
+    !matches!(
+        (prev, token),
+        (SirToken::ScopeStart(..), _)
+        | (_, SirToken::ScopeEnd(..) | SirToken::ArgSep(_))
+        | (SirToken::Label(_), SirToken::ScopeStart(..))
+    )
+}
@@ -80,6 +80,18 @@ pub trait IoGetter {
     fn on_end(&mut self) {}
 }
 
+/// An `IoGetter` that gives empty input without prompting,
+/// used when rules are built but not applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct EmptyGetter;
+
+impl IoGetter for EmptyGetter {
+    #[io_fn(impl)]
+    fn get_io(&mut self, _: &str) -> Result<String, String> {
+        Ok(String::new())
+    }
+}
+
 /// An internal secondary trait that controls specifically how build time commands are executed
 /// 
 /// Is implemented on all implementers of `IoGetter`
@@ -89,7 +101,7 @@ pub trait IoGetter {
 pub(super) trait ComptimeCommandExecutor: ContextIoGetter {
     /// Runs a command at build time
     #[io_fn]
-    fn run_build_time_command<'s>(&mut self, ctx: Self::InputContext, cmd: &TokenizerIoEvent<'s>, tokenization_data: &mut TokenizationData<'s>, line_num: NonZero<usize>) -> Result<Self::InputContext, RulelessScaError> {
+    fn run_build_time_command(&mut self, ctx: Self::InputContext, cmd: &TokenizerIoEvent, tokenization_data: &mut TokenizationData, line_num: NonZero<usize>) -> Result<Self::InputContext, RulelessScaError> {
         match cmd {
             TokenizerIoEvent::Get { get_type, var, msg } => {
                 let (input, c) = await_io! {
@@ -97,8 +109,8 @@ pub(super) trait ComptimeCommandExecutor: ContextIoGetter {
                 }.map_err(|e| RulelessScaError::from_error_message(e, ScaErrorType::Input, line_num, ONE))?;
 
                 match get_type {
-                    GetType::Phones => tokenization_data.set_variable(var, &input),
-                    GetType::Code => tokenization_data.set_variable_as_ir(var, input)
+                    GetType::Phones => tokenization_data.set_variable(var.clone(), &input),
+                    GetType::Code => tokenization_data.set_variable_as_ir(var.clone(), &input)
                         .map_err(|e| RulelessScaError::from_error(&e, ScaErrorType::Input, line_num, ONE))?,
                 }
 
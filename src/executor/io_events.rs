@@ -1,25 +1,31 @@
-use crate::keywords::{GET_LINE_START, GET_AS_CODE_LINE_START};
+use crate::{keywords::{GET_LINE_START, GET_AS_CODE_LINE_START}, shared_str::SharedStr};
 
 /// Events that require IO executed by the `IoGetter` or `Runtime`
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum IoEvent<'s> {
-    Runtime(RuntimeIoEvent<'s>),
-    Tokenizer(TokenizerIoEvent<'s>),
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IoEvent {
+    Runtime(RuntimeIoEvent),
+    Tokenizer(TokenizerIoEvent),
 }
 
 /// IO event that is executed by the `Runtime` during execution
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum RuntimeIoEvent<'s> {
-    Print { msg: &'s str },
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuntimeIoEvent {
+    Print { msg: SharedStr },
+    /// Does nothing when applying rules,
+    /// the expected output of applying every proceeding rule to the input
+    Test {
+        input: SharedStr,
+        expected: SharedStr,
+    },
 }
 
 /// IO event that is executed by the `IoGetter` when building rules
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum TokenizerIoEvent<'s> {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenizerIoEvent {
     Get {
         get_type: GetType,
-        var: &'s str,
-        msg: &'s str,
+        var: SharedStr,
+        msg: SharedStr,
     },
 }
 
@@ -0,0 +1,324 @@
+use std::{collections::HashMap, num::NonZero};
+
+use crate::{
+    executor::build_line_without_io,
+    ir::tokenization_data::TokenizationData,
+    keywords::{DEFINITION_PREFIX, LABEL_PREFIX, VARIABLE_PREFIX},
+    lexer::{Lexer, sir::SirToken},
+    matcher::patterns::{
+        Pattern, check_box::CheckBox, features::FeatureMatrix, non_bound::NonBound,
+        ir_to_patterns::RuleLine, optional::Optional, repetition::Repetition, rule::SoundChangeRule,
+        selection::Selection,
+    },
+    phones::{Phone, suprasegmentals::is_detached_mark},
+    shared_str::SharedStr,
+    tokens::ScopeId,
+};
+
+/// Finds rules that are valid but are likely mistakes
+///
+/// Rules are built without fetching any input, so lines that cannot be built are skipped,
+/// and variables fetched with `GET` or `GET_AS_CODE` are treated as empty
+#[must_use]
+pub fn lint_rules(rules: &str) -> Vec<LintWarning> {
+    let mut linter = Linter::new(rules);
+    let mut sir = Lexer::lex(rules);
+    let mut tokenization_data = TokenizationData::new();
+
+    while !sir.is_empty() {
+        let line_num = unsafe { NonZero::new_unchecked(sir.line() + 1) };
+
+        // the names used by the expression are checked before it is built
+        let expr = sir.clone()
+            .take_while(|token| !matches!(token, SirToken::EndOfExpr(_)))
+            .collect::<Vec<_>>();
+        linter.check_names(&expr, line_num);
+
+        if let Some(RuleLine::Rule { rule, lines }) = build_line_without_io(&mut sir, &mut tokenization_data) {
+            // an empty variable could make a rule look like it does not change its input
+            let has_variables = expr.iter().any(|token| matches!(token, SirToken::Variable(_)));
+            linter.check_rule(&rule, line_num, lines, !has_variables);
+        }
+    }
+
+    linter.finish()
+}
+
+/// A warning about rules that are valid but are likely a mistake
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintWarning {
+    line_num: NonZero<usize>,
+    rule: String,
+    kind: LintKind,
+}
+
+impl LintWarning {
+    /// Gets the number of the line the warning is about
+    #[must_use]
+    #[inline]
+    pub const fn line_number(&self) -> NonZero<usize> {
+        self.line_num
+    }
+
+    /// Gets the source code of the line the warning is about
+    #[must_use]
+    #[inline]
+    pub fn rule(&self) -> &str {
+        &self.rule
+    }
+
+    /// Gets the cause of the warning
+    #[must_use]
+    #[inline]
+    pub const fn kind(&self) -> &LintKind {
+        &self.kind
+    }
+}
+
+impl std::fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Warning: {}\nLine {}: {}", self.kind, self.line_num, self.rule)
+    }
+}
+
+/// The cause of a `LintWarning`
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintKind {
+    /// A definition is never used
+    UnusedDefinition {
+        /// The definition's name
+        name: String,
+    },
+    /// A definition is defined again before it is used
+    RedefinedDefinition {
+        /// The definition's name
+        name: String,
+    },
+    /// A rule's output is the same as its input
+    IdentityRule,
+    /// A label is only used once in a rule, so it links nothing
+    SingleUseLabel {
+        /// The label's name
+        label: String,
+    },
+    /// A variable is never used
+    UnusedVariable {
+        /// The variable's name
+        name: String,
+    },
+    /// A repetition excludes everything it includes, so it can only match nothing
+    ImpossibleRepetition,
+    /// A suprasegmental mark is written as its own phone,
+    /// but marks in inputs are part of the phone they are written next to
+    DetachedMark {
+        /// The mark
+        mark: String,
+    },
+}
+
+impl std::fmt::Display for LintKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnusedDefinition { name } => write!(f, "Definition '{DEFINITION_PREFIX}{name}' is never used"),
+            Self::RedefinedDefinition { name } => write!(f, "Definition '{DEFINITION_PREFIX}{name}' is redefined before it is used"),
+            Self::IdentityRule => write!(f, "Rule does not change its input"),
+            Self::SingleUseLabel { label } => write!(f, "Label '{LABEL_PREFIX}{label}' is only used once"),
+            Self::UnusedVariable { name } => write!(f, "Variable '{VARIABLE_PREFIX}{name}' is never used"),
+            Self::ImpossibleRepetition => write!(f, "Repetition excludes everything it includes, so it can only match nothing"),
+            Self::DetachedMark { mark } => write!(f, "'{mark}' is written as its own phone, but marks in inputs are part of the phone they are written next to"),
+        }
+    }
+}
+
+/// A declared definition or variable and whether it has been used
+#[derive(Debug, Clone, Copy)]
+struct Declaration {
+    line_num: NonZero<usize>,
+    lines: NonZero<usize>,
+    used: bool,
+}
+
+/// Collects warnings while rules are built
+struct Linter<'s> {
+    source: Vec<&'s str>,
+    warnings: Vec<LintWarning>,
+    definitions: HashMap<SharedStr, Declaration>,
+    variables: HashMap<SharedStr, Declaration>,
+}
+
+impl<'s> Linter<'s> {
+    fn new(rules: &'s str) -> Self {
+        Self {
+            source: rules.lines().collect(),
+            warnings: Vec::new(),
+            definitions: HashMap::new(),
+            variables: HashMap::new(),
+        }
+    }
+
+    /// Adds a warning about the lines starting at `line_num`
+    fn warn(&mut self, kind: LintKind, line_num: NonZero<usize>, lines: NonZero<usize>) {
+        let rule = self.source.iter()
+            .skip(line_num.get() - 1)
+            .take(lines.get())
+            .copied()
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        self.warnings.push(LintWarning { line_num, rule, kind });
+    }
+
+    /// Records the definitions and variables declared and used by an expression
+    fn check_names(&mut self, expr: &[SirToken], line_num: NonZero<usize>) {
+        let lines = unsafe { NonZero::new_unchecked(1 + expr.iter().filter(|token| matches!(token, SirToken::NonPhoneEscape('\n', _))).count()) };
+
+        // names used in a declaration are used before the declared name is set
+        for token in expr {
+            match token {
+                SirToken::Definition(name) => if let Some(def) = self.definitions.get_mut(name.str()) {
+                    def.used = true;
+                },
+                SirToken::Variable(name) => if let Some(var) = self.variables.get_mut(name.str()) {
+                    var.used = true;
+                },
+                _ => (),
+            }
+        }
+
+        let mut tokens = expr.iter().filter(|token| !matches!(token, SirToken::Whitespace(_)));
+        let declaration = Declaration { line_num, lines, used: false };
+
+        match (tokens.next(), tokens.next()) {
+            (Some(SirToken::DefinitionDeclaration(_) | SirToken::LazyDefinitionDeclaration(_)), Some(SirToken::Phone(name))) => {
+                if let Some(old) = self.definitions.insert(name.str().clone(), declaration) && !old.used {
+                    self.warn(LintKind::RedefinedDefinition { name: name.str().to_string() }, line_num, lines);
+                }
+            },
+            (Some(SirToken::GetCommand(_) | SirToken::GetAsCodeCommand(_)), Some(SirToken::Phone(name))) => {
+                if let Some(old) = self.variables.insert(name.str().clone(), declaration) && !old.used {
+                    self.warn(LintKind::UnusedVariable { name: name.str().to_string() }, old.line_num, old.lines);
+                }
+            },
+            _ => (),
+        }
+    }
+
+    /// Checks the labels, repetitions, and marks of a built rule,
+    /// and its output if `check_output` is true
+    fn check_rule(&mut self, rule: &SoundChangeRule, line_num: NonZero<usize>, lines: NonZero<usize>, check_output: bool) {
+        let input = rule.pattern.input().inner();
+        let mut patterns = Vec::new();
+
+        collect_patterns(input, &mut patterns);
+        collect_patterns(&rule.output, &mut patterns);
+
+        for cond in rule.pattern.conds().iter().chain(rule.pattern.anti_conds()) {
+            for side in cond.sides() {
+                collect_patterns(side.inner(), &mut patterns);
+            }
+        }
+
+        let mut label_uses = HashMap::new();
+
+        for label in patterns.iter().filter_map(|pattern| label(pattern)) {
+            *label_uses.entry(label).or_insert(0) += 1;
+        }
+
+        let mut single_use_labels = label_uses.into_iter()
+            .filter_map(|(label, uses)| (uses == 1).then_some(label))
+            .collect::<Vec<_>>();
+        single_use_labels.sort_unstable();
+
+        for label in single_use_labels {
+            self.warn(LintKind::SingleUseLabel { label: label.to_string() }, line_num, lines);
+        }
+
+        for pattern in &patterns {
+            if is_impossible_repetition(pattern) {
+                self.warn(LintKind::ImpossibleRepetition, line_num, lines);
+            }
+        }
+
+        for pattern in &patterns {
+            if let Pattern::Phone(CheckBox { unit_state: Phone::Symbol(symbol), .. }) = pattern && is_detached_mark(symbol) {
+                self.warn(LintKind::DetachedMark { mark: (*symbol).to_string() }, line_num, lines);
+            }
+        }
+
+        if check_output && rule.output.as_slice() == input {
+            self.warn(LintKind::IdentityRule, line_num, lines);
+        }
+    }
+
+    /// Adds warnings for unused declarations and returns every warning in line order
+    fn finish(mut self) -> Vec<LintWarning> {
+        let mut unused = self.definitions.drain()
+            .filter(|(_, def)| !def.used)
+            .map(|(name, def)| (LintKind::UnusedDefinition { name: name.to_string() }, def))
+            .chain(self.variables.drain()
+                .filter(|(_, var)| !var.used)
+                .map(|(name, var)| (LintKind::UnusedVariable { name: name.to_string() }, var))
+            )
+            .collect::<Vec<_>>();
+        unused.sort_unstable_by_key(|(_, declaration)| declaration.line_num);
+
+        for (kind, declaration) in unused {
+            self.warn(kind, declaration.line_num, declaration.lines);
+        }
+
+        self.warnings.sort_by_key(LintWarning::line_number);
+        self.warnings
+    }
+}
+
+/// Adds every pattern in a list and every pattern nested in them to `patterns`,
+/// each pattern before the patterns nested in it
+fn collect_patterns<'a>(list: &'a [Pattern], patterns: &mut Vec<&'a Pattern>) {
+    for pattern in list {
+        patterns.push(pattern);
+
+        match pattern {
+            Pattern::Repetition(Repetition { inclusive, exclusive, .. }) => {
+                collect_patterns(inclusive.inner(), patterns);
+
+                if let Some(exclusive) = exclusive {
+                    collect_patterns(exclusive.inner(), patterns);
+                }
+            },
+            Pattern::Optional(Optional { option, .. }) => collect_patterns(option.inner(), patterns),
+            Pattern::Selection(Selection { options, .. }) => for option in options {
+                collect_patterns(option.inner(), patterns);
+            },
+            Pattern::List(list) => collect_patterns(list.inner(), patterns),
+            Pattern::Phone(_) | Pattern::NonBound(_) | Pattern::Features(_) | Pattern::SyllableBound(_) => (),
+        }
+    }
+}
+
+/// Gets the name of the label on a pattern, if it has one
+fn label(pattern: &Pattern) -> Option<&str> {
+    let id = match pattern {
+        Pattern::Repetition(Repetition { id, .. }) => return id.as_deref(),
+        Pattern::NonBound(CheckBox { unit_state: NonBound { id }, .. })
+        | Pattern::Features(CheckBox { unit_state: FeatureMatrix { id, .. }, .. })
+        | Pattern::Optional(Optional { id, .. })
+        | Pattern::Selection(Selection { id, .. }) => id.as_ref(),
+        Pattern::Phone(_) | Pattern::SyllableBound(_) | Pattern::List(_) => None,
+    };
+
+    match id {
+        Some(ScopeId::Name(name)) => Some(name.as_str()),
+        _ => None,
+    }
+}
+
+/// Determines if a pattern is a repetition (`[a ! b]`) that excludes everything it includes
+fn is_impossible_repetition(pattern: &Pattern) -> bool {
+    let Pattern::Repetition(Repetition { inclusive, exclusive: Some(exclusive), .. }) = pattern else {
+        return false;
+    };
+
+    // unlabeled scopes are numbered differently in each side, so the sides are compared as they are written
+    matches!(exclusive.inner(), [Pattern::NonBound(_)]) || exclusive.to_string() == inclusive.to_string()
+}
\ No newline at end of file
@@ -1,6 +1,15 @@
 pub mod runtime;
 pub mod getter;
 pub mod appliable_rules;
+pub mod derivation;
+pub mod expansion;
+pub mod statistics;
+pub mod rule_tests;
+pub mod check;
+pub mod lint;
+pub mod analysis;
+pub mod format;
+pub mod notation;
 pub(crate) mod io_events;
 
 #[cfg(test)]
@@ -12,11 +21,11 @@ use crate::{
     ONE, RulelessScaError, ScaError, ScaErrorType, await_io, escaped_strings::EscapedString,
     executor::{getter::ContextIoGetter, runtime::ContextRuntime}, io_fn,
     ir::{IrLine, ir_line_from_sir, tokenization_data::TokenizationData},
-    lexer::{Sir, Lexer}, matcher::patterns::ir_to_patterns::{RuleLine, build_rule},
+    lexer::{Sir, Lexer}, matcher::patterns::ir_to_patterns::{RuleLine, build_rule, build_syllabifier},
     phones::{build_phone_list, phone_list_to_string}
 };
 
-use io_events::IoEvent;
+use io_events::{IoEvent, TokenizerIoEvent};
 use runtime::{Runtime, RuntimeApplier};
 use getter::{IoGetter, ComptimeCommandExecutor};
 
@@ -107,11 +116,13 @@ impl<R: ContextRuntime, G: ContextIoGetter> LineByLineExecutor<R, G> {
     /// Errors on invalid rules, application that takes too long, and failed io
     #[io_fn]
     pub fn apply_fallible_with_contexts(&mut self, input: &str, rules: &str, mut octx: R::OutputContext, mut ictx: G::InputContext) -> Result<String, ScaError> {
+        let mut tokenization_data = TokenizationData::new();
+
         let escaped = EscapedString::from(input);
         let mut phones = build_phone_list(escaped.as_escaped_str());
 
         let mut sir = Lexer::lex(rules);
-        let mut tokenization_data = TokenizationData::new();
+        let source = rules.lines().collect::<Vec<_>>();
 
         // prepares the runtime and getter for a new set of applications
         self.getter.on_start();
@@ -124,12 +135,12 @@ impl<R: ContextRuntime, G: ContextIoGetter> LineByLineExecutor<R, G> {
             // builds and attempts to apply the rules
             let application_result = match await_io! {
                 build_line(&mut sir, &mut tokenization_data, &mut self.getter, ictx)
-            } {
+            }.map_err(|(e, _)| e) {
                 Ok((rule_line, ic)) => {
                     ictx = ic;
 
                     await_io! {
-                        self.runtime.apply_line(octx, &rule_line, &mut phones, line_num)
+                        self.runtime.apply_line(octx, &rule_line, &mut phones, line_num, &source)
                     }
                 },
                 Err(e) => {
@@ -137,12 +148,6 @@ impl<R: ContextRuntime, G: ContextIoGetter> LineByLineExecutor<R, G> {
                     self.getter.on_end();
                     self.runtime.on_end();
 
-                    drop(phones);
-                    // Safety: Since the output is a `ScaError`,
-                    // which owns all of its values, and `phones` is dropped,
-                    // no references remain to the sources buffer in `tokenization_data`
-                    unsafe { tokenization_data.free_sources() };
-
                     return Err(e.into_sca_error(rules.lines()));
                 },
             };
@@ -154,12 +159,6 @@ impl<R: ContextRuntime, G: ContextIoGetter> LineByLineExecutor<R, G> {
                     self.getter.on_end();
                     self.runtime.on_end();
 
-                    drop(phones);
-                    // Safety: Since the output is a `ScaError`,
-                    // which owns all of its values, and `phones` is dropped,
-                    // no references remain to the sources buffer in `tokenization_data`
-                    unsafe { tokenization_data.free_sources() };
-
                     return Err(e.into_sca_error(rules.lines()));
                 }
             }
@@ -169,36 +168,64 @@ impl<R: ContextRuntime, G: ContextIoGetter> LineByLineExecutor<R, G> {
         self.getter.on_end();
         self.runtime.on_end();
 
-        let output = phone_list_to_string(&phones);
-
-        drop(phones);
-        // Safety: Since the output is a `String`,
-        // which owns all of its values, and `phones` is dropped,
-        // no references remain to the sources buffer in `tokenization_data`
-        unsafe { tokenization_data.free_sources() };
-
-        Ok(output)
+        Ok(phone_list_to_string(&phones))
     }
 }
 
+/// The result of building a line, containing the context if it was not lost
+type BuildResult<C> = Result<(RuleLine, C), (RulelessScaError, Option<C>)>;
+
 /// Builds a line from a string to a `RuleLine`
+/// 
+/// The context is returned with errors that do not occur during io
 #[io_fn]
-fn build_line<'s, G: ContextIoGetter>(sir: &mut Sir<'s>, tokenization_data: &mut TokenizationData<'s>, getter: &mut G, ctx: G::InputContext) -> Result<(RuleLine<'s>, G::InputContext), RulelessScaError> {
+fn build_line<G: ContextIoGetter>(sir: &mut Sir, tokenization_data: &mut TokenizationData, getter: &mut G, ctx: G::InputContext) -> BuildResult<G::InputContext> {
     let line_num = unsafe { NonZero::new_unchecked(sir.line() + 1) };
 
 
-    let ir_line = ir_line_from_sir(sir, tokenization_data, &mut Vec::new())
-        .map_err(|(e, line_count)| RulelessScaError::from_error(&e, ScaErrorType::Parse, line_num, line_count))?;
+    let (ir_line, spans) = match ir_line_from_sir(sir, tokenization_data, &mut Vec::new()) {
+        Ok(spanned) => spanned,
+        Err((e, span, line_count)) => return Err((RulelessScaError::from_error(&e, ScaErrorType::Parse, line_num, line_count).with_span(Some(span)), Some(ctx))),
+    };
 
     match ir_line {
         IrLine::IoEvent(IoEvent::Tokenizer(cmd)) => {
-            let c = await_io! { getter.run_build_time_command(ctx, &cmd, tokenization_data, line_num) }?;
+            let c = await_io! { getter.run_build_time_command(ctx, &cmd, tokenization_data, line_num) }
+                .map_err(|e| (e, None))?;
             Ok((RuleLine::Empty { lines: ONE }, c))
         },
+        IrLine::Syllabify { tokens, lines } => match build_syllabifier(&tokens, &spans, tokenization_data) {
+            Ok(syllabifier) => {
+                tokenization_data.set_syllabifier(syllabifier);
+                Ok((RuleLine::Empty { lines }, ctx))
+            },
+            Err((e, span)) => Err((RulelessScaError::from_error(&e, ScaErrorType::Parse, line_num, lines).with_span(span), Some(ctx))),
+        },
         // builds a rule from ir
-        ir_line =>
-            build_rule(ir_line)
-                .map(|r| (r, ctx))
-                .map_err(|(e, line_count)| RulelessScaError::from_error(&e, ScaErrorType::Parse, line_num, line_count)),
+        ir_line => match build_rule(ir_line, &spans, tokenization_data) {
+            Ok(rule) => Ok((rule, ctx)),
+            Err((e, span, line_count)) => Err((RulelessScaError::from_error(&e, ScaErrorType::Parse, line_num, line_count).with_span(span), Some(ctx))),
+        },
+    }
+}
+
+/// Builds a line from a string to a `RuleLine` without fetching any input,
+/// returning `None` if the line cannot be built
+/// 
+/// Variables fetched with `GET` or `GET_AS_CODE` are treated as empty
+pub(crate) fn build_line_without_io(sir: &mut Sir, tokenization_data: &mut TokenizationData) -> Option<RuleLine> {
+    let (ir_line, spans) = ir_line_from_sir(sir, tokenization_data, &mut Vec::new()).ok()?;
+
+    match ir_line {
+        IrLine::IoEvent(IoEvent::Tokenizer(TokenizerIoEvent::Get { var, .. })) => {
+            tokenization_data.set_variable(var, "");
+            Some(RuleLine::Empty { lines: ONE })
+        },
+        IrLine::Syllabify { tokens, lines } => {
+            let syllabifier = build_syllabifier(&tokens, &spans, tokenization_data).ok()?;
+            tokenization_data.set_syllabifier(syllabifier);
+            Some(RuleLine::Empty { lines })
+        },
+        ir_line => build_rule(ir_line, &spans, tokenization_data).ok(),
     }
 }
\ No newline at end of file
@@ -0,0 +1,808 @@
+use std::{fmt::Write as _, num::NonZero};
+
+use crate::{
+    executor::build_line_without_io,
+    ir::{tokenization_data::TokenizationData, tokens::Break},
+    keywords::{ESCAPE_CHAR, FEATURE_MINUS_CHAR},
+    lexer::{Lexer, sir::SirToken},
+    matcher::patterns::ir_to_patterns::RuleLine,
+    tokens::{AndType, CondType, Direction, FeatureSpec, ScopeType, Shift, ShiftType},
+};
+
+/// Renders every rule in standard linguistic notation,
+/// such as `a b >> c / @V _ @V` as `ab → c / V_V`
+///
+/// Definitions and variables are written by name,
+/// shifts that are not `>>` are noted after the rule,
+/// labels and statements are not written,
+/// and lines that cannot be built are skipped.
+/// Variables fetched with `GET` or `GET_AS_CODE` are treated as empty
+#[must_use]
+pub fn render_rules(rules: &str, notation: Notation) -> Vec<RenderedRule> {
+    let source = rules.lines().collect::<Vec<_>>();
+    let mut rendered_rules = Vec::new();
+    let mut sir = Lexer::lex(rules);
+    let mut tokenization_data = TokenizationData::new();
+
+    while !sir.is_empty() {
+        let line_num = unsafe { NonZero::new_unchecked(sir.line() + 1) };
+
+        let expr = sir.clone()
+            .take_while(|token| !matches!(token, SirToken::EndOfExpr(_)))
+            .filter(|token| !matches!(token, SirToken::Whitespace(_) | SirToken::Comment(_) | SirToken::NonPhoneEscape('\n', _)))
+            .collect::<Vec<_>>();
+
+        if let Some(RuleLine::Rule { lines, .. }) = build_line_without_io(&mut sir, &mut tokenization_data)
+            && let Some(rule) = NotationParser::new(&expr).rule()
+        {
+            rendered_rules.push(RenderedRule {
+                line_num,
+                rule: source.iter()
+                    .skip(line_num.get() - 1)
+                    .take(lines.get())
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                rendered: notation.rule(&rule),
+            });
+        }
+    }
+
+    rendered_rules
+}
+
+/// A format for writing rules in standard linguistic notation
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Notation {
+    /// Plain text using Unicode symbols such as `→` and `∅`
+    #[default]
+    Unicode,
+    /// LaTeX, with selections and feature matrices stacked
+    ///
+    /// IPA symbols are written with the macros of the `tipa` package
+    /// and alpha variables as Greek letters in math mode, so pdfLaTeX may be used
+    Latex,
+    /// HTML using `<sub>` for repetitions,
+    /// with selections and feature matrices stacked
+    Html,
+}
+
+/// A rule rendered in a `Notation`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedRule {
+    line_num: NonZero<usize>,
+    rule: String,
+    rendered: String,
+}
+
+impl RenderedRule {
+    /// Gets the number of the line the rule starts on
+    #[must_use]
+    #[inline]
+    pub const fn line_number(&self) -> NonZero<usize> {
+        self.line_num
+    }
+
+    /// Gets the source code of the rule
+    #[must_use]
+    #[inline]
+    pub fn rule(&self) -> &str {
+        &self.rule
+    }
+
+    /// Gets the rule in its notation
+    #[must_use]
+    #[inline]
+    pub fn rendered(&self) -> &str {
+        &self.rendered
+    }
+}
+
+impl std::fmt::Display for RenderedRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.rendered)
+    }
+}
+
+/// A part of a rule as it is written in notation
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    /// A phone or the name of a definition or variable
+    Text(String),
+    Any,
+    Bound,
+    SyllableBound,
+    Optional(Vec<Node>),
+    Selection(Vec<Vec<Node>>),
+    Repetition(Vec<Node>, Option<Vec<Node>>),
+    Features(Vec<String>),
+}
+
+/// One side of a condition and what it is joined to the previous side with
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CondClause {
+    and: Option<AndType>,
+    left: Vec<Node>,
+    focus: Option<CondType>,
+    right: Vec<Node>,
+}
+
+/// A rule as it is written in notation
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+struct NotationRule {
+    shift: Option<Shift>,
+    input: Vec<Node>,
+    outputs: Vec<Vec<Node>>,
+    conds: Vec<Vec<CondClause>>,
+    anti_conds: Vec<Vec<CondClause>>,
+}
+
+/// Converts the SIR of a valid rule into a `NotationRule`
+struct NotationParser<'a> {
+    tokens: &'a [SirToken],
+    index: usize,
+}
+
+impl<'a> NotationParser<'a> {
+    /// Creates a parser from SIR without whitespace, comments, or line continuations
+    const fn new(tokens: &'a [SirToken]) -> Self {
+        Self { tokens, index: 0 }
+    }
+
+    /// Gets the next token without advancing
+    fn peek(&self) -> Option<&'a SirToken> {
+        self.tokens.get(self.index)
+    }
+
+    /// Advances past the next token if it matches `is_expected`
+    fn eat(&mut self, is_expected: impl Fn(&SirToken) -> bool) -> Option<&'a SirToken> {
+        let token = self.peek().filter(|token| is_expected(token))?;
+        self.index += 1;
+        Some(token)
+    }
+
+    /// Parses a whole rule
+    fn rule(mut self) -> Option<NotationRule> {
+        let mut rule = NotationRule { input: self.sequence()?, ..Default::default() };
+
+        // every shift in a chain shift is the same
+        while let Some(SirToken::Break(Break::Shift(shift), _)) = self.eat(|token| matches!(token, SirToken::Break(Break::Shift(_), _))) {
+            rule.shift = Some(*shift);
+            rule.outputs.push(self.sequence()?);
+        }
+
+        while let Some(SirToken::Break(r#break, _)) = self.eat(|token| matches!(token, SirToken::Break(Break::Cond | Break::AntiCond, _))) {
+            let mut clauses = vec![self.cond_clause(None)?];
+
+            while let Some(SirToken::Break(Break::And(and), _)) = self.eat(|token| matches!(token, SirToken::Break(Break::And(_), _))) {
+                clauses.push(self.cond_clause(Some(*and))?);
+            }
+
+            if *r#break == Break::Cond {
+                rule.conds.push(clauses);
+            } else {
+                rule.anti_conds.push(clauses);
+            }
+        }
+
+        self.peek().is_none().then_some(rule)
+    }
+
+    /// Parses one side of a condition
+    fn cond_clause(&mut self, and: Option<AndType>) -> Option<CondClause> {
+        let left = self.sequence()?;
+
+        let focus = self.eat(|token| matches!(token, SirToken::CondFocus(..)))
+            .map(|token| if let SirToken::CondFocus(focus, _) = token { *focus } else { CondType::Pattern });
+
+        let right = self.sequence()?;
+
+        Some(CondClause { and, left, focus, right })
+    }
+
+    /// Parses nodes until a token that ends a sequence
+    fn sequence(&mut self) -> Option<Vec<Node>> {
+        let mut nodes = Vec::new();
+
+        while let Some(token) = self.peek() {
+            let node = match token {
+                SirToken::Phone(phone) => Node::Text(unescape(phone.str())),
+                SirToken::Definition(name) | SirToken::Variable(name) => Node::Text(name.str().to_string()),
+                SirToken::Any(_) => Node::Any,
+                SirToken::Bound(_) => Node::Bound,
+                SirToken::SyllableBound(_) => Node::SyllableBound,
+                // labels only link scopes so they are not written
+                SirToken::Label(_) => {
+                    self.index += 1;
+                    continue;
+                },
+                SirToken::ScopeStart(kind, _) => {
+                    self.index += 1;
+                    nodes.push(self.scope(*kind)?);
+                    continue;
+                },
+                _ => break,
+            };
+
+            self.index += 1;
+            nodes.push(node);
+        }
+
+        Some(nodes)
+    }
+
+    /// Parses the contents and end of a scope
+    fn scope(&mut self, kind: ScopeType) -> Option<Node> {
+        let node = match kind {
+            ScopeType::Optional => Node::Optional(self.sequence()?),
+            ScopeType::Selection => {
+                let mut options = vec![self.sequence()?];
+
+                while self.eat(|token| matches!(token, SirToken::ArgSep(_))).is_some() {
+                    options.push(self.sequence()?);
+                }
+
+                Node::Selection(options)
+            },
+            ScopeType::Repetition | ScopeType::Features => if let Some(specs) = self.feature_matrix() {
+                Node::Features(specs)
+            } else {
+                let inclusive = self.sequence()?;
+                let exclusive = match self.eat(|token| matches!(token, SirToken::Negative(_))) {
+                    Some(_) => Some(self.sequence()?),
+                    None => None,
+                };
+
+                Node::Repetition(inclusive, exclusive)
+            },
+        };
+
+        self.eat(|token| matches!(token, SirToken::ScopeEnd(..)))?;
+
+        Some(node)
+    }
+
+    /// Parses the specifications of a feature matrix, leaving its end,
+    /// returns `None` and does not advance if the scope is not a feature matrix
+    fn feature_matrix(&mut self) -> Option<Vec<String>> {
+        let specs = self.tokens[self.index..].iter()
+            .map_while(|token| match token {
+                SirToken::Phone(phone) => FeatureSpec::parse(phone.str()).map(|spec| spec.to_string()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let is_matrix = !specs.is_empty()
+            && matches!(self.tokens.get(self.index + specs.len()), Some(SirToken::ScopeEnd(ScopeType::Repetition, _)));
+
+        if is_matrix {
+            self.index += specs.len();
+            Some(specs)
+        } else {
+            None
+        }
+    }
+}
+
+impl Notation {
+    /// Renders a whole rule
+    fn rule(self, rule: &NotationRule) -> String {
+        let mut rendered = self.sequence(&rule.input);
+
+        for output in &rule.outputs {
+            _ = write!(rendered, " {} {}", self.arrow(), self.sequence(output));
+        }
+
+        let conds = |conds: &[Vec<CondClause>]| conds.iter()
+            .map(|clauses| self.cond(clauses))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        if !rule.conds.is_empty() {
+            _ = write!(rendered, " / {}", conds(&rule.conds));
+        }
+
+        if !rule.anti_conds.is_empty() {
+            _ = write!(rendered, " // {}", conds(&rule.anti_conds));
+        }
+
+        if let Some(shift) = rule.shift {
+            let notes = shift_notes(shift);
+
+            if !notes.is_empty() {
+                _ = write!(rendered, " {}", self.note(&notes.join(", ")));
+            }
+        }
+
+        rendered
+    }
+
+    /// Renders a note about how a rule is applied
+    fn note(self, note: &str) -> String {
+        match self {
+            Self::Unicode => format!("({note})"),
+            Self::Latex => format!("\\quad ({note})"),
+            Self::Html => format!("<i>({note})</i>"),
+        }
+    }
+
+    /// Renders a condition and its and-clauses
+    fn cond(self, clauses: &[CondClause]) -> String {
+        let mut rendered = String::new();
+
+        for clause in clauses {
+            match clause.and {
+                Some(AndType::And) => _ = write!(rendered, " {} ", self.escape("&")),
+                Some(AndType::AndNot) => _ = write!(rendered, " {} {}", self.escape("&"), self.not()),
+                None => (),
+            }
+
+            // an environment is written without spaces around its focus, an equality with spaces
+            match clause.focus {
+                Some(CondType::Pattern) => _ = write!(rendered, "{}{}{}", self.nodes(&clause.left), self.focus(), self.nodes(&clause.right)),
+                Some(CondType::Match) => _ = write!(rendered, "{} = {}", self.sequence(&clause.left), self.sequence(&clause.right)),
+                None => rendered += &self.nodes(&clause.left),
+            }
+        }
+
+        rendered
+    }
+
+    /// Renders a sequence of nodes, using the empty set symbol if it is empty
+    fn sequence(self, nodes: &[Node]) -> String {
+        if nodes.is_empty() {
+            self.empty().to_string()
+        } else {
+            self.nodes(nodes)
+        }
+    }
+
+    /// Renders a sequence of nodes without seperators
+    fn nodes(self, nodes: &[Node]) -> String {
+        nodes.iter().map(|node| self.node(node)).collect()
+    }
+
+    /// Renders a single node
+    fn node(self, node: &Node) -> String {
+        match node {
+            Node::Text(text) => self.escape(text),
+            Node::Any => "X".to_string(),
+            Node::Bound => self.escape("#"),
+            Node::SyllableBound => ".".to_string(),
+            Node::Optional(nodes) => format!("({})", self.sequence(nodes)),
+            Node::Selection(options) => {
+                let options = options.iter().map(|option| self.sequence(option)).collect::<Vec<_>>();
+
+                match self {
+                    Self::Latex => format!("$\\left\\{{\\begin{{tabular}}{{@{{}}c@{{}}}}{}\\end{{tabular}}\\right\\}}$", options.join("\\\\")),
+                    Self::Html => html_stack('{', '}', "center", &options),
+                    Self::Unicode => format!("{{{}}}", options.join(", ")),
+                }
+            },
+            Node::Features(specs) => {
+                let specs = specs.iter().map(|spec| self.feature(spec)).collect::<Vec<_>>();
+
+                match self {
+                    Self::Latex => format!("$\\left[\\begin{{tabular}}{{@{{}}l@{{}}}}{}\\end{{tabular}}\\right]$", specs.join("\\\\")),
+                    Self::Html => html_stack('[', ']', "left", &specs),
+                    Self::Unicode => format!("[{}]", specs.join(" ")),
+                }
+            },
+            Node::Repetition(inclusive, exclusive) => {
+                let mut repeated = self.sequence(inclusive);
+
+                if let Some(exclusive) = exclusive {
+                    repeated = format!("{repeated}{}{}", self.except(), self.sequence(exclusive));
+                }
+
+                if inclusive.len() > 1 || exclusive.is_some() {
+                    repeated = format!("({repeated})");
+                }
+
+                repeated + self.zero_or_more()
+            },
+        }
+    }
+
+    /// Renders a feature specification with a minus sign instead of a hyphen,
+    /// writing alpha variables as math in LaTeX
+    fn feature(self, spec: &str) -> String {
+        let (negated, spec) = match spec.strip_prefix(FEATURE_MINUS_CHAR) {
+            Some(rest) => (true, rest),
+            None => (false, spec),
+        };
+
+        let mut chars = spec.chars();
+
+        match (self, chars.next().and_then(greek_letter)) {
+            (Self::Latex, Some(alpha)) => format!("${}{alpha}${}", if negated { "-" } else { "" }, self.escape(chars.as_str())),
+            _ if negated => format!("{}{}", self.minus(), self.escape(spec)),
+            _ => self.escape(spec),
+        }
+    }
+
+    /// Escapes text so that it is not read as markup,
+    /// writing IPA with `tipa` macros in LaTeX
+    fn escape(self, text: &str) -> String {
+        if self == Self::Latex {
+            return latex_text(text);
+        }
+
+        let mut escaped = String::with_capacity(text.len());
+
+        for c in text.chars() {
+            match (self, c) {
+                (Self::Html, '&') => escaped += "&amp;",
+                (Self::Html, '<') => escaped += "&lt;",
+                (Self::Html, '>') => escaped += "&gt;",
+                (Self::Html, '"') => escaped += "&quot;",
+                _ => escaped.push(c),
+            }
+        }
+
+        escaped
+    }
+
+    /// The arrow between an input and output
+    const fn arrow(self) -> &'static str {
+        match self {
+            Self::Unicode | Self::Html => "→",
+            Self::Latex => "$\\rightarrow$",
+        }
+    }
+
+    /// The symbol for an empty input or output
+    const fn empty(self) -> &'static str {
+        match self {
+            Self::Unicode | Self::Html => "∅",
+            Self::Latex => "$\\emptyset$",
+        }
+    }
+
+    /// The position of the input in an environment
+    const fn focus(self) -> &'static str {
+        match self {
+            Self::Unicode | Self::Html => "_",
+            Self::Latex => "\\_\\_",
+        }
+    }
+
+    /// The symbol for negating an and-clause
+    const fn not(self) -> &'static str {
+        match self {
+            Self::Unicode | Self::Html => "¬",
+            Self::Latex => "$\\neg$",
+        }
+    }
+
+    /// The symbol for excluding part of a repetition
+    const fn except(self) -> &'static str {
+        match self {
+            Self::Unicode | Self::Html => "∖",
+            Self::Latex => "$\\setminus$",
+        }
+    }
+
+    /// The minus sign of a feature value
+    const fn minus(self) -> &'static str {
+        match self {
+            Self::Unicode | Self::Html => "−",
+            Self::Latex => "$-$",
+        }
+    }
+
+    /// The subscript that marks a repetition
+    const fn zero_or_more(self) -> &'static str {
+        match self {
+            Self::Unicode => "₀",
+            Self::Latex => "$_0$",
+            Self::Html => "<sub>0</sub>",
+        }
+    }
+}
+
+/// Gets the notes written after a rule for every way its shift differs from `>>`
+fn shift_notes(shift: Shift) -> Vec<&'static str> {
+    let mut notes = Vec::new();
+
+    if shift.dir == Direction::Rtl {
+        notes.push("right to left");
+    }
+
+    match shift.kind {
+        ShiftType::Move => (),
+        ShiftType::Stay => notes.push("reapplied to its output"),
+        ShiftType::Simultaneous => notes.push("simultaneous"),
+    }
+
+    if shift.once {
+        notes.push("once per word");
+    }
+
+    notes
+}
+
+/// Renders rows stacked between two brackets in HTML
+fn html_stack(open: char, close: char, align: &str, rows: &[String]) -> String {
+    // the brackets are stretched to the height of the rows
+    let bracket = |c| format!("<span style=\"display:inline-block;transform:scaleY({})\">{c}</span>", rows.len());
+    let rows = rows.iter().fold(String::new(), |mut rows, row| {
+        _ = write!(rows, "<span>{row}</span>");
+        rows
+    });
+
+    format!(
+        "<span style=\"display:inline-flex;align-items:center\">{}<span style=\"display:inline-flex;flex-direction:column;text-align:{align}\">{rows}</span>{}</span>",
+        bracket(open),
+        bracket(close),
+    )
+}
+
+/// Writes text in LaTeX for pdfLaTeX, escaping special characters
+/// and writing IPA symbols, diacritics, and tone letters with `tipa` macros
+fn latex_text(text: &str) -> String {
+    // each unit is a symbol with its diacritics, so that a diacritic can be put on the symbol before it
+    let mut units: Vec<String> = Vec::new();
+    let mut tied = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == TIE_CHAR {
+            tied = true;
+            continue;
+        }
+
+        if let Some(diacritic) = tipa_diacritic(c) {
+            let base = units.pop().unwrap_or_default();
+            units.push(format!("{diacritic}{{{base}}}"));
+            continue;
+        }
+
+        let unit = if let Some(level) = tone_level(c) {
+            // a contour is written as one tone
+            let mut levels = String::from(level);
+
+            while let Some(level) = chars.peek().copied().and_then(tone_level) {
+                levels.push(level);
+                chars.next();
+            }
+
+            format!("\\tone{{{levels}}}")
+        } else {
+            latex_char(c)
+        };
+
+        match units.pop() {
+            Some(first) if tied => units.push(format!("\\t{{{first}{unit}}}")),
+            Some(last) => units.extend([last, unit]),
+            None => units.push(unit),
+        }
+
+        tied = false;
+    }
+
+    units.concat()
+}
+
+/// Writes a character that is not a diacritic or tone letter in LaTeX
+fn latex_char(c: char) -> String {
+    match c {
+        '#' | '$' | '%' | '&' | '_' | '{' | '}' => format!("\\{c}"),
+        '\\' => "\\textbackslash{}".to_string(),
+        '~' => "\\textasciitilde{}".to_string(),
+        '^' => "\\textasciicircum{}".to_string(),
+        _ => tipa_symbol(c).map_or_else(|| c.to_string(), str::to_string),
+    }
+}
+
+/// The combining tie bar that joins two symbols
+const TIE_CHAR: char = '\u{361}';
+
+/// IPA symbols and the `tipa` macros they are written with
+const TIPA_SYMBOLS: [(char, &str); 105] = [
+    ('ɐ', "\\textturna{}"),
+    ('ɑ', "\\textscripta{}"),
+    ('ɒ', "\\textturnscripta{}"),
+    ('æ', "\\ae{}"),
+    ('ʌ', "\\textturnv{}"),
+    ('ɔ', "\\textopeno{}"),
+    ('ə', "\\textschwa{}"),
+    ('ɘ', "\\textreve{}"),
+    ('ɚ', "\\textrhookschwa{}"),
+    ('ɛ', "\\textepsilon{}"),
+    ('ɜ', "\\textrevepsilon{}"),
+    ('ɝ', "\\textrhookrevepsilon{}"),
+    ('ɞ', "\\textcloserevepsilon{}"),
+    ('ɤ', "\\textramshorns{}"),
+    ('ɨ', "\\textbari{}"),
+    ('ɪ', "\\textsci{}"),
+    ('ɯ', "\\textturnm{}"),
+    ('ɵ', "\\textbaro{}"),
+    ('ø', "\\o{}"),
+    ('œ', "\\oe{}"),
+    ('ɶ', "\\textscoelig{}"),
+    ('ʉ', "\\textbaru{}"),
+    ('ʊ', "\\textupsilon{}"),
+    ('ʏ', "\\textscy{}"),
+    ('β', "\\textbeta{}"),
+    ('ɓ', "\\texthtb{}"),
+    ('ʙ', "\\textscb{}"),
+    ('ç', "\\c{c}"),
+    ('ɕ', "\\textctc{}"),
+    ('ð', "\\textipa{D}"),
+    ('ɗ', "\\texthtd{}"),
+    ('ɖ', "\\textrtaild{}"),
+    ('ɟ', "\\textbardotlessj{}"),
+    ('ʝ', "\\textctj{}"),
+    ('ʄ', "\\texthtbardotlessj{}"),
+    ('ɠ', "\\texthtg{}"),
+    ('ɢ', "\\textscg{}"),
+    ('ɡ', "\\textscriptg{}"),
+    ('ʛ', "\\texthtscg{}"),
+    ('ɣ', "\\textgamma{}"),
+    ('ɦ', "\\texthth{}"),
+    ('ħ', "\\textcrh{}"),
+    ('ɧ', "\\texththeng{}"),
+    ('ɥ', "\\textturnh{}"),
+    ('ʜ', "\\textsch{}"),
+    ('ʎ', "\\textturny{}"),
+    ('ɭ', "\\textrtaill{}"),
+    ('ɬ', "\\textbeltl{}"),
+    ('ɫ', "\\textltilde{}"),
+    ('ɮ', "\\textlyoghlig{}"),
+    ('ʟ', "\\textscl{}"),
+    ('ɱ', "\\textltailm{}"),
+    ('ŋ', "\\textipa{N}"),
+    ('ɲ', "\\textltailn{}"),
+    ('ɳ', "\\textrtailn{}"),
+    ('ɴ', "\\textscn{}"),
+    ('ɸ', "\\textphi{}"),
+    ('θ', "\\texttheta{}"),
+    ('ɹ', "\\textturnr{}"),
+    ('ɺ', "\\textturnlonglegr{}"),
+    ('ɻ', "\\textturnrrtail{}"),
+    ('ɽ', "\\textrtailr{}"),
+    ('ɾ', "\\textfishhookr{}"),
+    ('ʀ', "\\textscr{}"),
+    ('ʁ', "\\textinvscr{}"),
+    ('ʂ', "\\textrtails{}"),
+    ('ʃ', "\\textesh{}"),
+    ('ʈ', "\\textrtailt{}"),
+    ('ʋ', "\\textscriptv{}"),
+    ('ɰ', "\\textturnmrleg{}"),
+    ('ʍ', "\\textturnw{}"),
+    ('χ', "\\textchi{}"),
+    ('ʒ', "\\textyogh{}"),
+    ('ʐ', "\\textrtailz{}"),
+    ('ʑ', "\\textctz{}"),
+    ('ʔ', "\\textglotstop{}"),
+    ('ʕ', "\\textrevglotstop{}"),
+    ('ʡ', "\\textbarglotstop{}"),
+    ('ʢ', "\\textbarrevglotstop{}"),
+    ('ʘ', "\\textbullseye{}"),
+    ('ǀ', "\\textpipe{}"),
+    ('ǁ', "\\textdoublepipe{}"),
+    ('ǂ', "\\textdoublebarpipe{}"),
+    ('\u{31A}', "\\textcorner{}"),
+    ('ˈ', "\\textprimstress{}"),
+    ('ˌ', "\\textsecstress{}"),
+    ('ː', "\\textlengthmark{}"),
+    ('ˑ', "\\texthalflength{}"),
+    ('ʰ', "\\textsuperscript{h}"),
+    ('ʷ', "\\textsuperscript{w}"),
+    ('ʲ', "\\textsuperscript{j}"),
+    ('ⁿ', "\\textsuperscript{n}"),
+    ('ˡ', "\\textsuperscript{l}"),
+    ('ˠ', "\\textsuperscript{\\textgamma{}}"),
+    ('ˤ', "\\textsuperscript{\\textrevglotstop{}}"),
+    ('¹', "\\textsuperscript{1}"),
+    ('²', "\\textsuperscript{2}"),
+    ('³', "\\textsuperscript{3}"),
+    ('⁴', "\\textsuperscript{4}"),
+    ('⁵', "\\textsuperscript{5}"),
+    ('⁶', "\\textsuperscript{6}"),
+    ('⁷', "\\textsuperscript{7}"),
+    ('⁸', "\\textsuperscript{8}"),
+    ('⁹', "\\textsuperscript{9}"),
+    ('⁰', "\\textsuperscript{0}"),
+];
+
+/// Gets the `tipa` macro for an IPA symbol
+fn tipa_symbol(c: char) -> Option<&'static str> {
+    TIPA_SYMBOLS.iter()
+        .find(|(symbol, _)| *symbol == c)
+        .map(|(_, tipa)| *tipa)
+}
+
+/// Gets the `tipa` macro for a combining diacritic, which takes the symbol it is on as an argument
+fn tipa_diacritic(c: char) -> Option<&'static str> {
+    Some(match c {
+        '\u{300}' => "\\`",
+        '\u{301}' => "\\'",
+        '\u{302}' => "\\^",
+        '\u{303}' => "\\~",
+        '\u{304}' => "\\=",
+        '\u{306}' => "\\u",
+        '\u{308}' => "\\\"",
+        '\u{30C}' => "\\v",
+        '\u{318}' => "\\textadvancing",
+        '\u{319}' => "\\textretracting",
+        '\u{31D}' => "\\textraising",
+        '\u{31E}' => "\\textlowering",
+        '\u{31F}' => "\\textsubplus",
+        '\u{320}' => "\\textsubminus",
+        '\u{324}' => "\\textsubumlaut",
+        '\u{325}' => "\\textsubring",
+        '\u{329}' => "\\textsyllabic",
+        '\u{32A}' => "\\textsubbridge",
+        '\u{32C}' => "\\textsubwedge",
+        '\u{32F}' => "\\textsubarch",
+        '\u{330}' => "\\textsubtilde",
+        '\u{33A}' => "\\textinvsubbridge",
+        '\u{33B}' => "\\textsubsquare",
+        '\u{33C}' => "\\textseagull",
+        _ => return None,
+    })
+}
+
+/// Gets the `tipa` level of a tone letter
+const fn tone_level(c: char) -> Option<char> {
+    match c {
+        '˥' => Some('5'),
+        '˦' => Some('4'),
+        '˧' => Some('3'),
+        '˨' => Some('2'),
+        '˩' => Some('1'),
+        _ => None,
+    }
+}
+
+/// Gets the LaTeX math for an alpha variable
+const fn greek_letter(c: char) -> Option<&'static str> {
+    Some(match c {
+        'α' => "\\alpha",
+        'β' => "\\beta",
+        'γ' => "\\gamma",
+        'δ' => "\\delta",
+        'ε' => "\\epsilon",
+        'ζ' => "\\zeta",
+        'η' => "\\eta",
+        'θ' => "\\theta",
+        'ι' => "\\iota",
+        'κ' => "\\kappa",
+        'λ' => "\\lambda",
+        'μ' => "\\mu",
+        'ν' => "\\nu",
+        'ξ' => "\\xi",
+        'ο' => "o",
+        'π' => "\\pi",
+        'ρ' => "\\rho",
+        'ς' => "\\varsigma",
+        'σ' => "\\sigma",
+        'τ' => "\\tau",
+        'υ' => "\\upsilon",
+        'φ' => "\\phi",
+        'χ' => "\\chi",
+        'ψ' => "\\psi",
+        'ω' => "\\omega",
+        _ => return None,
+    })
+}
+
+/// Removes the escape characters from a phone
+fn unescape(phone: &str) -> String {
+    let mut unescaped = String::with_capacity(phone.len());
+    let mut chars = phone.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ESCAPE_CHAR => if let Some(escaped) = chars.next() {
+                unescaped.push(escaped);
+            },
+            _ => unescaped.push(c),
+        }
+    }
+
+    unescaped
+}
@@ -0,0 +1,64 @@
+use std::num::NonZero;
+
+use crate::{ScaError, escaped_strings::EscapedString, phones::{build_phone_list, phone_list_to_string}};
+
+/// The result of a `TEST` statement
+#[derive(Debug)]
+pub struct RuleTestResult {
+    line_num: NonZero<usize>,
+    input: String,
+    expected: String,
+    output: Result<String, ScaError>,
+}
+
+impl RuleTestResult {
+    /// Creates a new `RuleTestResult`, formatting `expected` the same way as the output
+    pub(super) fn new(line_num: NonZero<usize>, input: &str, expected: &str, output: Result<String, ScaError>) -> Self {
+        let escaped_expected = EscapedString::from(expected);
+        let expected = phone_list_to_string(&build_phone_list(escaped_expected.as_escaped_str()));
+
+        Self {
+            line_num,
+            input: input.to_string(),
+            expected,
+            output,
+        }
+    }
+
+    /// Gets the number of the line the test is on
+    #[must_use]
+    #[inline]
+    pub const fn line_number(&self) -> NonZero<usize> {
+        self.line_num
+    }
+
+    /// Gets the input of the test
+    #[must_use]
+    #[inline]
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Gets the expected output of the test
+    #[must_use]
+    #[inline]
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+
+    /// Gets the output of applying the rules before the test to its input
+    /// 
+    /// # Errors
+    /// Errors if the rules could not be applied
+    #[inline]
+    pub fn output(&self) -> Result<&str, &ScaError> {
+        self.output.as_deref()
+    }
+
+    /// Checks if the output matches the expected output
+    #[must_use]
+    #[inline]
+    pub fn passed(&self) -> bool {
+        self.output.as_ref().is_ok_and(|output| *output == self.expected)
+    }
+}
@@ -1,4 +1,4 @@
-use std::num::NonZero;
+use std::{num::NonZero, ops::Range};
 #[cfg(feature = "sys_time")]
 use std::time::Duration;
 
@@ -18,6 +18,9 @@ pub enum LineApplicationLimit {
     /// Maximum time allotted for line application
     Time(Duration),
     /// Maximum times an application attempt may be made by a line
+    /// 
+    /// Positions skipped because no match of a rule's input can start with their phone
+    /// are not attempted, so they are not counted
     Attempts(usize),
 }
 
@@ -54,6 +57,27 @@ pub trait ContextRuntime {
     #[inline]
     fn on_end(&mut self) {}
 
+    /// Called whenever a rule changes the phones it is applied to
+    /// 
+    /// `rule_source` is the source code of the rule starting on line `line_num`,
+    /// `span` is the byte range of `after` that differs from `before`
+    /// 
+    /// Is only called if `wants_rule_applied` returns `true`,
+    /// does nothing by default
+    #[inline]
+    fn on_rule_applied(&mut self, line_num: NonZero<usize>, rule_source: &str, before: &str, after: &str, span: Range<usize>) {
+        _ = (line_num, rule_source, before, after, span);
+    }
+
+    /// Whether `on_rule_applied` should be called,
+    /// so the phones before each rule are not kept when it does nothing
+    /// 
+    /// Returns `false` by default
+    #[inline]
+    fn wants_rule_applied(&self) -> bool {
+        false
+    }
+
     /// The maximum limit for applying changes to a line
     #[inline]
     fn line_application_limit(&self) -> Option<LineApplicationLimit> {
@@ -80,6 +104,16 @@ impl<T: Runtime> ContextRuntime for T {
         Runtime::on_end(self);
     }
 
+    #[inline]
+    fn on_rule_applied(&mut self, line_num: NonZero<usize>, rule_source: &str, before: &str, after: &str, span: Range<usize>) {
+        Runtime::on_rule_applied(self, line_num, rule_source, before, after, span);
+    }
+
+    #[inline]
+    fn wants_rule_applied(&self) -> bool {
+        Runtime::wants_rule_applied(self)
+    }
+
     #[inline]
     fn line_application_limit(&self) -> Option<LineApplicationLimit> {
         Runtime::line_application_limit(self)
@@ -112,6 +146,27 @@ pub trait Runtime {
     #[inline]
     fn on_end(&mut self) {}
 
+    /// Called whenever a rule changes the phones it is applied to
+    /// 
+    /// `rule_source` is the source code of the rule starting on line `line_num`,
+    /// `span` is the byte range of `after` that differs from `before`
+    /// 
+    /// Is only called if `wants_rule_applied` returns `true`,
+    /// does nothing by default
+    #[inline]
+    fn on_rule_applied(&mut self, line_num: NonZero<usize>, rule_source: &str, before: &str, after: &str, span: Range<usize>) {
+        _ = (line_num, rule_source, before, after, span);
+    }
+
+    /// Whether `on_rule_applied` should be called,
+    /// so the phones before each rule are not kept when it does nothing
+    /// 
+    /// Returns `false` by default
+    #[inline]
+    fn wants_rule_applied(&self) -> bool {
+        false
+    }
+
     /// The maximum limit for applying changes to a line
     #[inline]
     fn line_application_limit(&self) -> Option<LineApplicationLimit> {
@@ -127,34 +182,73 @@ pub trait Runtime {
 /// Default methods should not be overridden
 pub(super) trait RuntimeApplier: ContextRuntime {
     /// Applies changes for a single `RuleLine`
+    /// 
+    /// `source` should contain every line of the rules' source code
     #[io_fn]
-    fn apply_line<'s: 'p, 'p>(&mut self, ctx: Self::OutputContext, rule_line: &RuleLine<'s>, phones: &mut Vec<Phone<'p>>, line_num: NonZero<usize>) -> Result<Self::OutputContext, RulelessScaError> {
+    fn apply_line(&mut self, ctx: Self::OutputContext, rule_line: &RuleLine, phones: &mut Vec<Phone>, line_num: NonZero<usize>, source: &[impl AsRef<str>]) -> Result<Self::OutputContext, RulelessScaError> {
         match rule_line {
             RuleLine::Empty { lines: _ } => Ok(ctx),
             RuleLine::IoEvent(cmd) => await_io! {
                 self.execute_runtime_command(ctx, cmd, phones, line_num)
             },
-            RuleLine::Rule { rule, lines } => apply(rule, phones, self.line_application_limit())
-                .map(|()| ctx)
-                .map_err(|e| RulelessScaError::from_error(&e, ScaErrorType::Application, line_num, *lines))
+            RuleLine::Rule { rule, lines } => {
+                let before = self.wants_rule_applied().then(|| phones.clone());
+
+                apply(rule, phones, self.line_application_limit())
+                    .map_err(|e| RulelessScaError::from_error(&e, ScaErrorType::Application, line_num, *lines))?;
+
+                if let Some(before) = before && before != *phones {
+                    let rule_source = source.iter()
+                        .skip(line_num.get() - 1)
+                        .take(lines.get())
+                        .map(AsRef::as_ref)
+                        .collect::<Vec<&str>>()
+                        .join("\n");
+                    let before = phone_list_to_string(&before);
+                    let after = phone_list_to_string(phones);
+                    let span = changed_span(&before, &after);
+
+                    self.on_rule_applied(line_num, &rule_source, &before, &after, span);
+                }
+
+                Ok(ctx)
+            },
         }
     }
 
     /// Executes a command at runtime
     #[io_fn]
-    fn execute_runtime_command(&mut self, ctx: Self::OutputContext, cmd: &RuntimeIoEvent<'_>, phones: &[Phone<'_>], line_num: NonZero<usize>) -> Result<Self::OutputContext, RulelessScaError> {
+    fn execute_runtime_command(&mut self, ctx: Self::OutputContext, cmd: &RuntimeIoEvent, phones: &[Phone], line_num: NonZero<usize>) -> Result<Self::OutputContext, RulelessScaError> {
         match cmd {
             RuntimeIoEvent::Print { msg } => {
                 await_io! {
                     self.put_io(ctx, msg, phone_list_to_string(phones))
                 }.map_err(|e| RulelessScaError::from_error_message(e, ScaErrorType::Output, line_num, ONE))
-            }
+            },
+            RuntimeIoEvent::Test { .. } => Ok(ctx),
         }
     }
 }
 
 impl<T: ContextRuntime> RuntimeApplier for T {}
 
+/// Finds the byte range of `after` that differs from `before`
+fn changed_span(before: &str, after: &str) -> Range<usize> {
+    let prefix_len = before.char_indices()
+        .zip(after.chars())
+        .find(|((_, b), a)| b != a)
+        .map_or_else(|| before.len().min(after.len()), |((i, _), _)| i);
+
+    // the suffix cannot overlap the prefix
+    let suffix_len = before[prefix_len..].chars().rev()
+        .zip(after[prefix_len..].chars().rev())
+        .take_while(|(b, a)| b == a)
+        .map(|(c, _)| c.len_utf8())
+        .sum::<usize>();
+
+    prefix_len..after.len() - suffix_len
+}
+
 /// A basic `Runtime` that logs outputs to itself
 /// 
 /// Clears its logs before starting to apply a new set of rules
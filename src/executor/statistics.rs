@@ -0,0 +1,169 @@
+use std::{num::NonZero, ops::Range};
+
+use crate::{await_io, executor::{appliable_rules::AppliableRules, runtime::{ContextRuntime, LineApplicationLimit}}, io_fn};
+
+/// Counts how many inputs each rule in a set of `AppliableRules` changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleStatistics {
+    rules: Vec<RuleStats>,
+    inputs: usize,
+}
+
+impl RuleStatistics {
+    /// Creates new empty statistics for every rule in a set of `AppliableRules`
+    #[must_use]
+    pub fn new(rules: &AppliableRules) -> Self {
+        let rules = rules.rule_sources()
+            .map(|(line_num, rule)| RuleStats {
+                line_num,
+                rule,
+                times_fired: 0,
+                words: Vec::new(),
+            })
+            .collect();
+
+        Self { rules, inputs: 0 }
+    }
+
+    /// Gets the statistics of each rule in the order they are applied
+    #[must_use]
+    #[inline]
+    pub fn rules(&self) -> &[RuleStats] {
+        &self.rules
+    }
+
+    /// Gets the number of inputs the rules have been applied to
+    #[must_use]
+    #[inline]
+    pub const fn inputs(&self) -> usize {
+        self.inputs
+    }
+
+    /// Gets an iterator over the rules that have not changed any input
+    #[inline]
+    pub fn unused_rules(&self) -> impl Iterator<Item = &RuleStats> {
+        self.rules.iter().filter(|rule| rule.times_fired() == 0)
+    }
+
+    /// Adds the statistics collected by another `RuleStatistics` for the same rules,
+    /// as if its inputs were applied after this one's
+    pub fn merge(&mut self, other: Self) {
+        for (rule, other) in self.rules.iter_mut().zip(other.rules) {
+            let sample_space = RuleStats::MAX_SAMPLE_WORDS - rule.words.len();
+
+            rule.times_fired += other.times_fired;
+            rule.words.extend(other.words.into_iter().take(sample_space));
+        }
+
+        self.inputs += other.inputs;
+    }
+
+    /// Records that the rule starting on `line_num` changed an input
+    fn record(&mut self, line_num: NonZero<usize>, input: &str) {
+        if let Ok(i) = self.rules.binary_search_by_key(&line_num, |rule| rule.line_num) {
+            let rule = &mut self.rules[i];
+            rule.times_fired += 1;
+
+            if rule.words.len() < RuleStats::MAX_SAMPLE_WORDS {
+                rule.words.push(input.to_string());
+            }
+        }
+    }
+}
+
+/// The statistics of a single rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleStats {
+    line_num: NonZero<usize>,
+    rule: String,
+    times_fired: usize,
+    /// The first inputs the rule changed
+    words: Vec<String>,
+}
+
+impl RuleStats {
+    /// The maximum number of inputs kept as a sample of the inputs a rule changed
+    pub const MAX_SAMPLE_WORDS: usize = 10;
+
+    /// Gets the number of the line the rule starts on
+    #[must_use]
+    #[inline]
+    pub const fn line_number(&self) -> NonZero<usize> {
+        self.line_num
+    }
+
+    /// Gets the source code of the rule
+    #[must_use]
+    #[inline]
+    pub fn rule(&self) -> &str {
+        &self.rule
+    }
+
+    /// Gets the number of inputs the rule has changed
+    #[must_use]
+    #[inline]
+    pub const fn times_fired(&self) -> usize {
+        self.times_fired
+    }
+
+    /// Gets the first inputs the rule has changed,
+    /// at most `MAX_SAMPLE_WORDS` of them
+    #[must_use]
+    #[inline]
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+}
+
+/// A `ContextRuntime` that records rule statistics
+/// and passes everything else to another runtime
+pub(super) struct StatisticsRuntime<'a, R: ContextRuntime> {
+    runtime: &'a mut R,
+    statistics: &'a mut RuleStatistics,
+    input: &'a str,
+}
+
+impl<'a, R: ContextRuntime> StatisticsRuntime<'a, R> {
+    /// Creates a new `StatisticsRuntime` for applying rules to a single input
+    pub(super) fn new(runtime: &'a mut R, statistics: &'a mut RuleStatistics, input: &'a str) -> Self {
+        statistics.inputs += 1;
+
+        Self { runtime, statistics, input }
+    }
+}
+
+impl<R: ContextRuntime> ContextRuntime for StatisticsRuntime<'_, R> {
+    type OutputContext = R::OutputContext;
+
+    #[io_fn(impl)]
+    #[inline]
+    fn put_io(&mut self, context: Self::OutputContext, msg: &str, phones: String) -> Result<Self::OutputContext, String> {
+        await_io! { self.runtime.put_io(context, msg, phones) }
+    }
+
+    #[inline]
+    fn on_start(&mut self) {
+        self.runtime.on_start();
+    }
+
+    #[inline]
+    fn on_end(&mut self) {
+        self.runtime.on_end();
+    }
+
+    #[inline]
+    fn on_rule_applied(&mut self, line_num: NonZero<usize>, rule_source: &str, before: &str, after: &str, span: Range<usize>) {
+        self.statistics.record(line_num, self.input);
+        self.runtime.on_rule_applied(line_num, rule_source, before, after, span);
+    }
+
+    #[inline]
+    fn wants_rule_applied(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn line_application_limit(&self) -> Option<LineApplicationLimit> {
+        self.runtime.line_application_limit()
+    }
+}
@@ -1,12 +1,17 @@
-use std::{cell::RefCell, marker::PhantomData, rc::Rc};
+use std::{cell::RefCell, marker::PhantomData, num::NonZero, ops::Range, rc::Rc};
 
 use super::{
+    analysis::{HighlightKind, expand_definition_at, find_declaration, highlight_rules},
+    format::format_rules,
+    notation::{Notation, render_rules},
+    check::check_rules,
+    lint::{LintKind, lint_rules},
     runtime::LogRuntime,
     getter::IoGetter,
     LineByLineExecutor,
 };
 
-use crate::{ContextIoGetter, ContextRuntime, build_rules, executor::appliable_rules::build_rules_with_context, io_macros::{await_io, io_fn, io_test}, tests::{NoGet, NoLog}};
+use crate::{ContextIoGetter, ContextRuntime, Runtime, build_rules, executor::appliable_rules::build_rules_with_context, io_macros::{await_io, io_fn, io_test}, tests::{NoGet, NoLog}};
 
 struct SingleGetter(&'static str);
 
@@ -53,6 +58,65 @@ fn line_by_line_log_runtime() {
     );
 }
 
+#[derive(Default)]
+struct TraceRuntime(Vec<(usize, String, String, String, Range<usize>)>);
+
+impl Runtime for TraceRuntime {
+    #[io_fn(impl)]
+    fn put_io(&mut self, _: &str, _: String) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn on_rule_applied(&mut self, line_num: NonZero<usize>, rule_source: &str, before: &str, after: &str, span: Range<usize>) {
+        self.0.push((line_num.get(), rule_source.to_string(), before.to_string(), after.to_string(), span));
+    }
+
+    fn wants_rule_applied(&self) -> bool {
+        true
+    }
+}
+
+#[io_test(pollster::block_on)]
+fn rule_application_trace() {
+    let rules = "a >> b\nx >> y\nPRINT:\nc >> c\nd >> e e";
+    let expected = [
+        (1, "a >> b".to_string(), "abcd".to_string(), "bbcd".to_string(), 0..1),
+        (5, "d >> e e".to_string(), "bbcd".to_string(), "bbcee".to_string(), 3..5),
+    ];
+
+    let mut executor = LineByLineExecutor::new(TraceRuntime::default(), NoGet);
+    assert_eq!(await_io! { executor.apply_fallible("abcd", rules) }, Ok("bbcee".to_string()));
+    assert_eq!(executor.runtime().0, expected);
+
+    let appliable_rules = await_io! { build_rules(rules, &mut NoGet) }.expect("should build");
+    let mut runtime = TraceRuntime::default();
+    assert_eq!(await_io! { appliable_rules.apply_fallible("abcd", &mut runtime) }, Ok("bbcee".to_string()));
+    assert_eq!(runtime.0, expected);
+}
+
+/// Counts calls to `on_rule_applied` without asking for them
+#[derive(Default)]
+struct UnwantedTraceRuntime(usize);
+
+impl Runtime for UnwantedTraceRuntime {
+    #[io_fn(impl)]
+    fn put_io(&mut self, _: &str, _: String) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn on_rule_applied(&mut self, _: NonZero<usize>, _: &str, _: &str, _: &str, _: Range<usize>) {
+        self.0 += 1;
+    }
+}
+
+#[io_test(pollster::block_on)]
+fn unwanted_rule_application_trace() {
+    let appliable_rules = await_io! { build_rules("a >> b\nd >> e e", &mut NoGet) }.expect("should build");
+    let mut runtime = UnwantedTraceRuntime::default();
+    assert_eq!(await_io! { appliable_rules.apply_fallible("abcd", &mut runtime) }, Ok("bbcee".to_string()));
+    assert_eq!(runtime.0, 0);
+}
+
 struct RefContextLogger<'a>(PhantomData<&'a ()>);
 
 impl<'a> ContextRuntime for RefContextLogger<'a> {
@@ -180,4 +244,241 @@ fn context_getters() {
     let res = await_io! { appliable_rules.apply("a", &mut NoLog::default()) };
 
     assert_eq!(res, "in");
-}
\ No newline at end of file
+}
+
+#[io_test(pollster::block_on)]
+fn check_code_variables() {
+    let rules = "GET_AS_CODE y Enter:\na %y\nDEFINE C %y\n@C\nGET y Enter:\nb %y\nDEFINE C c\nd @C\n@V >> a";
+
+    let errors = await_io! { check_rules(rules) }
+        .into_iter()
+        .map(|e| e.line_number().get())
+        .collect::<Vec<_>>();
+
+    assert_eq!(errors, [6, 8, 9]);
+}
+
+#[test]
+fn lint() {
+    let rules = "DEFINE V {a, e}\nDEFINE C {p, t}\nDEFINE C {p, t, k}\nGET x word:\nGET y word:\n@C >> %y\na >> a / _ #\n$l{a, e} >> o / _ [* ! *]\n@V >> [* ! @V] / _ #\n@V >> i / _ [@V ! @V]";
+
+    let warnings = lint_rules(rules)
+        .into_iter()
+        .map(|warning| (warning.line_number().get(), warning.kind().clone()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(warnings, [
+        (3, LintKind::RedefinedDefinition { name: "C".to_string() }),
+        (4, LintKind::UnusedVariable { name: "x".to_string() }),
+        (7, LintKind::IdentityRule),
+        (8, LintKind::SingleUseLabel { label: "l".to_string() }),
+        (8, LintKind::ImpossibleRepetition),
+        (10, LintKind::ImpossibleRepetition),
+    ]);
+}
+
+#[test]
+fn lint_detached_marks() {
+    let warnings = lint_rules("a ː >> o
+aː >> o
+ˈ a >> a
+a ː >> aː")
+        .into_iter()
+        .map(|warning| (warning.line_number().get(), warning.kind().clone()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(warnings, [
+        (1, LintKind::DetachedMark { mark: "ː".to_string() }),
+        (3, LintKind::DetachedMark { mark: "ˈ".to_string() }),
+        (4, LintKind::DetachedMark { mark: "ː".to_string() }),
+    ]);
+}
+
+#[test]
+fn lint_fetched_variables() {
+    // variables are empty while linting, so a rule using one could look like it changes nothing
+    let warnings = lint_rules("GET y word:\na %y >> a")
+        .into_iter()
+        .map(|warning| (warning.line_number().get(), warning.kind().clone()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(warnings, []);
+}
+
+#[test]
+fn highlights() {
+    let rules = "DEFINE V {a, e}\n## comment\n@V > $x / _ %y";
+
+    let highlights = highlight_rules(rules)
+        .into_iter()
+        .map(|highlight| (&rules[highlight.bytes()], highlight.kind()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(highlights, [
+        ("DEFINE", HighlightKind::Keyword),
+        ("V", HighlightKind::Definition),
+        ("{", HighlightKind::Operator),
+        ("a", HighlightKind::Phone),
+        (",", HighlightKind::Operator),
+        ("e", HighlightKind::Phone),
+        ("}", HighlightKind::Operator),
+        ("## comment", HighlightKind::Comment),
+        ("@V", HighlightKind::Definition),
+        (">", HighlightKind::Operator),
+        ("$x", HighlightKind::Label),
+        ("/", HighlightKind::Operator),
+        ("_", HighlightKind::Operator),
+        ("%y", HighlightKind::Variable),
+    ]);
+}
+
+#[test]
+fn declarations() {
+    let rules = "DEFINE V {a, e}\nGET x word:\n@V > %x\nDEFINE V {@V, i}\n@V > o";
+
+    let declaration = |name: &str, occurrence: usize| {
+        let index = rules.match_indices(name).nth(occurrence).expect("name should be in rules").0;
+        find_declaration(rules, index).map(|bytes| rules[..bytes.start].lines().count())
+    };
+
+    assert_eq!(declaration("@V", 0), Some(1));
+    assert_eq!(declaration("%x", 0), Some(2));
+    assert_eq!(declaration("@V", 1), Some(1));
+    assert_eq!(declaration("@V", 2), Some(4));
+    assert_eq!(declaration("V {@", 0), Some(4));
+    assert_eq!(declaration("word", 0), None);
+}
+
+#[test]
+fn expanded_definitions() {
+    let rules = "DEFINE V {a, e}\nDEFINE_LAZY W [@V]\n@W > o\nDEFINE V {@V, i}\n@V > o";
+
+    let expansion = |name: &str, occurrence: usize| {
+        let index = rules.match_indices(name).nth(occurrence).expect("name should be in rules").0;
+        expand_definition_at(rules, index)
+    };
+
+    assert_eq!(expansion("@V", 0), Some("{ a, e }".to_string()));
+    assert_eq!(expansion("@W", 0), Some("[ { a, e } ]".to_string()));
+    assert_eq!(expansion("@V", 2), Some("{ { a, e }, i }".to_string()));
+    assert_eq!(expansion("V {@", 0), Some("{ { a, e }, i }".to_string()));
+    assert_eq!(expansion("o", 0), None);
+}
+
+#[io_test(pollster::block_on)]
+fn formatting() {
+    let rules = "## vowels  \nDEFINE   V {a,e ,  i}\nPRINT  hello  world  \n  a>>b/#_ {c,@V}&!  _ e\n$l{ a , b }>>$l{c,d}  //  _ #\nDEFINE C {p, \\\nt,\\\n        k}\n @C > o / _ [* ! @V]";
+    let expected = "## vowels\nDEFINE V {a, e, i}\nPRINT hello  world\na >> b / # _ {c, @V} &! _ e\n$l{a, b} >> $l{c, d} // _ #\nDEFINE C {p, \\\n    t, \\\n    k}\n@C > o / _ [* ! @V]";
+
+    let formatted = format_rules(rules);
+    assert_eq!(formatted, expected);
+    assert_eq!(format_rules(&formatted), formatted);
+
+    let original_rules = await_io! { build_rules(rules, &mut NoGet) }.expect("should build");
+    let formatted_rules = await_io! { build_rules(&formatted, &mut NoGet) }.expect("should build");
+
+    for input in ["abtae", "ka aki", "bap"] {
+        assert_eq!(
+            await_io! { original_rules.apply_fallible(input, &mut NoLog::default()) },
+            await_io! { formatted_rules.apply_fallible(input, &mut NoLog::default()) },
+        );
+    }
+}
+
+#[test]
+fn formatting_keeps_statements_and_continuations() {
+    assert_eq!(format_rules(" DEFINE > x\r\nGET_AS_CODE  x  code:\r\n"), " DEFINE > x\r\nGET_AS_CODE x code:\r\n");
+    assert_eq!(format_rules("a >> \\\n## comment\nb"), "a >> \\\n## comment\nb");
+}
+
+#[test]
+fn notation() {
+    let rules = "DEFINE V {a, e}\nFEATURES p t [-voice]\nFEATURES b d [+voice]\n[-voice] >> [+voice] / @V _ @V\nh >> / # _\nPRINT not a rule\n>> ə / p _ {p, t}\nu >> y / i [* ! w] _ // _ #\na >> b >> c / _ & _ t";
+
+    let render = |notation| render_rules(rules, notation)
+        .into_iter()
+        .map(|rule| (rule.line_number().get(), rule.rendered().to_string()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(render(Notation::Unicode), [
+        (4, "[−voice] → [+voice] / V_V".to_string()),
+        (5, "h → ∅ / #_".to_string()),
+        (7, "∅ → ə / p_{p, t}".to_string()),
+        (8, "u → y / i(X∖w)₀_ // _#".to_string()),
+        (9, "a → b → c / _ & _t".to_string()),
+    ]);
+
+    let latex = render(Notation::Latex);
+    assert_eq!(latex[1].1, "h $\\rightarrow$ $\\emptyset$ / \\#\\_\\_");
+    assert_eq!(latex[2].1, "$\\emptyset$ $\\rightarrow$ \\textschwa{} / p\\_\\_$\\left\\{\\begin{tabular}{@{}c@{}}p\\\\t\\end{tabular}\\right\\}$");
+
+    let html = render(Notation::Html);
+    assert_eq!(html[3].1, "u → y / i(X∖w)<sub>0</sub>_ // _#");
+    assert_eq!(html[4].1, "a → b → c / _ &amp; _t");
+}
+
+#[test]
+fn notation_shift_notes() {
+    let rules = "a <<! o\na >>> o >>> e\na > o / _ #\na << o";
+
+    let render = |notation| render_rules(rules, notation)
+        .into_iter()
+        .map(|rule| rule.rendered().to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(render(Notation::Unicode), [
+        "a → o (right to left, once per word)",
+        "a → o → e (simultaneous)",
+        "a → o / _# (reapplied to its output)",
+        "a → o (right to left)",
+    ]);
+    assert_eq!(render(Notation::Latex)[1], "a $\\rightarrow$ o $\\rightarrow$ e \\quad (simultaneous)");
+    assert_eq!(render(Notation::Html)[0], "a → o <i>(right to left, once per word)</i>");
+}
+
+#[test]
+fn notation_latex_ipa() {
+    let rules = "FEATURES m [+nasal +lab]\nFEATURES n [+nasal -lab]\nFEATURES p [-nasal +lab]\nFEATURES t [-nasal -lab]\nʃ >> t͡s / _ ə\naː˥˩ >> a\u{303}\n[+nasal] >> [-αlab] / _ [αlab]";
+
+    let latex = render_rules(rules, Notation::Latex)
+        .into_iter()
+        .map(|rule| rule.rendered().to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(latex, [
+        "\\textesh{} $\\rightarrow$ \\t{ts} / \\_\\_\\textschwa{}",
+        "a\\textlengthmark{}\\tone{51} $\\rightarrow$ \\~{a}",
+        "$\\left[\\begin{tabular}{@{}l@{}}+nasal\\end{tabular}\\right]$ $\\rightarrow$ $\\left[\\begin{tabular}{@{}l@{}}$-\\alpha$lab\\end{tabular}\\right]$ / \\_\\_$\\left[\\begin{tabular}{@{}l@{}}$\\alpha$lab\\end{tabular}\\right]$",
+    ]);
+}
+
+#[test]
+fn notation_html_stacks() {
+    let html = render_rules("a >> o / _ {i, e}", Notation::Html);
+
+    assert_eq!(
+        html[0].rendered(),
+        "a → o / _<span style=\"display:inline-flex;align-items:center\"><span style=\"display:inline-block;transform:scaleY(2)\">{</span><span style=\"display:inline-flex;flex-direction:column;text-align:center\"><span>i</span><span>e</span></span><span style=\"display:inline-block;transform:scaleY(2)\">}</span></span>"
+    );
+}
+
+#[io_test(pollster::block_on)]
+fn expanded_rules() {
+    let rules = "DEFINE V {a, i}\nDEFINE_LAZY intervocalic @V _ @V\nDEFINE C {p, t}\n@C >> {b, d} / @intervocalic\nPRINT done\nGET var :\n%var >> / _ #\nx (y) >> \\\n z // [* ! q] _";
+
+    let appliable_rules = await_io! { build_rules(rules, &mut SingleGetter("h")) }
+        .expect("rules should compile");
+
+    let expanded = appliable_rules.expanded_rules();
+
+    assert_eq!(
+        expanded.iter().map(|rule| (rule.line_number().get(), rule.rule(), rule.expanded())).collect::<Vec<_>>(),
+        vec![
+            (4, "@C >> {b, d} / @intervocalic", "{ p, t } >> { b, d } / { a, i } _ { a, i }"),
+            (7, "%var >> / _ #", "h >> / _ #"),
+            (8, "x (y) >> \\\n z // [* ! q] _", "x ( y ) >> z // [ * ! q ] _"),
+        ]
+    );
+
+    assert_eq!(expanded[1].to_string(), "h >> / _ #");
+}
@@ -1,12 +1,14 @@
 use std::num::NonZero;
 
 use crate::{
-    ONE,
+    ONE, ScaErrorKind, ToErrorKind,
     executor::io_events::{GetType, IoEvent, RuntimeIoEvent, TokenizerIoEvent},
     ir::tokenization_data::TokenizationData,
-    keywords::{DEFINITION_LINE_START, DEFINITION_PREFIX, ESCAPE_CHAR, VARIABLE_PREFIX},
-    lexer::{Sir, sir::SirToken},
-    phones::Phone,
+    keywords::{DEFINITION_LINE_START, DEFINITION_PREFIX, ESCAPE_CHAR, FEATURES_LINE_START, TEST_LINE_START, TEST_SEP_STR, VARIABLE_PREFIX},
+    lexer::{Sir, sir::SirToken, token_types::Span},
+    phones::{Phone, suprasegmentals::is_suprasegmental_feature},
+    shared_str::SharedStr,
+    tokens::{FeatureSpec, FeatureValue, ScopeType},
 };
 
 use tokens::IrToken;
@@ -19,17 +21,19 @@ pub mod tokenization_data;
 #[cfg(test)]
 mod tests;
 
-/// Takes an expression or statement from a SIR iterator and creates an `IrLine`
-/// or produces an error with the number of lines the line takes up
-pub fn ir_line_from_sir<'s>(sir: &mut Sir<'s>, tokenization_data: &mut TokenizationData<'s>, lazy_expansions: &mut Vec<&'s str>) -> Result<IrLine<'s>, (IrError<'s>, NonZero<usize>)> {
+/// Takes an expression or statement from a SIR iterator and creates an `IrLine` with the span of each of its tokens
+/// or produces an error with the span of the token that caused it and the number of lines the line takes up
+pub fn ir_line_from_sir(sir: &mut Sir, tokenization_data: &mut TokenizationData, lazy_expansions: &mut Vec<SharedStr>) -> Result<SpannedIrLine, (IrError, Span, NonZero<usize>)> {
     let mut lines = ONE;
     let line = get_expr(sir);
 
     // handles empty lines
     if line.is_empty() {
-        return Ok(IrLine::Empty { lines });
+        return Ok((IrLine::Empty { lines }, Vec::new()));
     }
 
+    let statement_span = *line[0].span();
+
     // handles statements
     match &line[0] {
         SirToken::DefinitionDeclaration(_) => {
@@ -37,9 +41,9 @@ pub fn ir_line_from_sir<'s>(sir: &mut Sir<'s>, tokenization_data: &mut Tokenizat
                 .skip_while(|t| matches!(t, SirToken::Whitespace(_)));
         
             if let Some(name) = sir_iter.next() && let SirToken::Phone(name) = name {
-                let name = name.str();
+                let name = name.str().clone();
 
-                let sir_tokens = sir_iter.copied().collect::<Vec<_>>();
+                let sir_tokens = sir_iter.cloned().collect::<Vec<_>>();
                 for t in &sir_tokens {
                     if let SirToken::NonPhoneEscape('\n', _) | SirToken::EndOfExpr(_) = t {
                         lines = unsafe { NonZero::new_unchecked(lines.get() + 1) }
@@ -47,18 +51,18 @@ pub fn ir_line_from_sir<'s>(sir: &mut Sir<'s>, tokenization_data: &mut Tokenizat
                 }
                 let ir_line = ir_line_from_sir(&mut Sir::new(sir_tokens), tokenization_data, lazy_expansions);
 
-                let content = match ir_line? {
+                let content = match ir_line?.0 {
                         IrLine::Empty { .. }  => Vec::new(),
-                        IrLine::IoEvent(_) => return Err((IrError::StatementParseError, lines)),
+                        IrLine::IoEvent(_) | IrLine::Syllabify { .. } => return Err((IrError::StatementParseError, statement_span, lines)),
                         IrLine::Ir { tokens, .. } => tokens,
             
                 };
 
                 tokenization_data.set_definition(name, content);
 
-                Ok(IrLine::Empty { lines })
+                Ok((IrLine::Empty { lines }, Vec::new()))
             } else {
-                Err((IrError::UnnamedDefinition, lines))
+                Err((IrError::UnnamedDefinition, statement_span, lines))
             }
         }
         SirToken::LazyDefinitionDeclaration(_) => {
@@ -66,8 +70,8 @@ pub fn ir_line_from_sir<'s>(sir: &mut Sir<'s>, tokenization_data: &mut Tokenizat
                 .skip_while(|t| matches!(t, SirToken::Whitespace(_)));
         
             if let Some(name) = sir_iter.next() && let SirToken::Phone(name) = name {
-                let name = name.str();
-                let sir_tokens = sir_iter.copied().collect::<Vec<_>>();
+                let name = name.str().clone();
+                let sir_tokens = sir_iter.cloned().collect::<Vec<_>>();
                 for t in &sir_tokens {
                     if let SirToken::NonPhoneEscape('\n', _) | SirToken::EndOfExpr(_) = t {
                         lines = unsafe { NonZero::new_unchecked(lines.get() + 1) }
@@ -75,127 +79,245 @@ pub fn ir_line_from_sir<'s>(sir: &mut Sir<'s>, tokenization_data: &mut Tokenizat
                 }
                 tokenization_data.set_lazy_definition(name, Sir::new(sir_tokens));
 
-                Ok(IrLine::Empty { lines })
+                Ok((IrLine::Empty { lines }, Vec::new()))
             } else {
-                Err((IrError::UnnamedDefinition, lines))
+                Err((IrError::UnnamedDefinition, statement_span, lines))
             }
         }
-        SirToken::GetAsCodeCommand(_) => {
-            let mut sir_iter = line[1..].iter()
-                .skip_while(|t| matches!(t, SirToken::Whitespace(_)));
+        SirToken::FeaturesDeclaration(_) => {
+            let (ir_res, lines) = sir_expr_to_ir_line(line[1..].to_vec(), tokenization_data, lazy_expansions);
 
-            if let Some(SirToken::Phone(var)) = sir_iter.next() {
-                Ok(IrLine::IoEvent(IoEvent::Tokenizer(TokenizerIoEvent::Get {
-                    get_type: GetType::Code,
-                    var: var.str(),
-                    msg: sir_iter.next()
-                        .map(|t| if let SirToken::Message(msg, _) = t { msg } else { "" })
-                        .unwrap_or_default()
-                })))
-            } else {
-                Err((IrError::InvalidGetFormat(GetType::Code), lines))
-            }
-        },
-        SirToken::GetCommand(_) => {
-            let mut sir_iter = line[1..].iter()
-                .skip_while(|t| matches!(t, SirToken::Whitespace(_)));
+            let (ir, _) = ir_res.map_err(|(e, span)| (e, span, lines))?;
 
-            if let Some(SirToken::Phone(var)) = sir_iter.next() {
-                Ok(IrLine::IoEvent(IoEvent::Tokenizer(TokenizerIoEvent::Get {
-                    get_type: GetType::Phones,
-                    var: var.str(),
-                    msg: sir_iter.next()
-                        .map(|t| if let SirToken::Message(msg, _) = t { msg } else { "" })
-                        .unwrap_or_default()
-                })))
-            } else {
-                Err((IrError::InvalidGetFormat(GetType::Phones), lines))
-            }
-        },
-        SirToken::PrintCommand(_) => {
-            let mut sir_iter = line[1..].iter()
-                .skip_while(|t| matches!(t, SirToken::Whitespace(_)));
+            declare_features(&ir, tokenization_data)
+                .map_err(|e| (e, statement_span, lines))?;
 
-            let msg = sir_iter.next()
-                .map(|t| if let SirToken::Message(msg, _) = t { msg } else { "" })
-                .unwrap_or_default();
+            Ok((IrLine::Empty { lines }, Vec::new()))
+        },
+        SirToken::SyllabifyDeclaration(_) => {
+            let (ir_res, lines) = sir_expr_to_ir_line(line[1..].to_vec(), tokenization_data, lazy_expansions);
 
-            Ok(IrLine::IoEvent(IoEvent::Runtime(RuntimeIoEvent::Print { msg })))
+            ir_res
+                .map(|(tokens, spans)| (IrLine::Syllabify { tokens, lines }, spans))
+                .map_err(|(e, span)| (e, span, lines))
         },
+        SirToken::GetAsCodeCommand(_) => get_statement(&line[1..], GetType::Code)
+            .map(|line| (line, Vec::new()))
+            .map_err(|e| (e, statement_span, lines)),
+        SirToken::GetCommand(_) => get_statement(&line[1..], GetType::Phones)
+            .map(|line| (line, Vec::new()))
+            .map_err(|e| (e, statement_span, lines)),
+        SirToken::PrintCommand(_) => Ok((IrLine::IoEvent(IoEvent::Runtime(RuntimeIoEvent::Print { msg: statement_message(&line[1..]) })), Vec::new())),
+        SirToken::TestCommand(_) => test_statement(&statement_message(&line[1..]))
+            .map(|line| (line, Vec::new()))
+            .map_err(|e| (e, *line[line.len() - 1].span(), lines)),
         // handles non-statement lines
         _ => {
             let (ir_res, lines) = sir_expr_to_ir_line(line, tokenization_data, lazy_expansions);
-            let ir = match ir_res {
-                Err(e) => return Err((e, lines)),
+            let (tokens, spans) = match ir_res {
+                Err((e, span)) => return Err((e, span, lines)),
                 Ok(ir) => ir,
             };
 
-            if ir.is_empty() {
-                Ok(IrLine::Empty { lines })
+            if tokens.is_empty() {
+                Ok((IrLine::Empty { lines }, Vec::new()))
             } else {
-                Ok(IrLine::Ir { tokens: ir, lines })
+                Ok((IrLine::Ir { tokens, lines }, spans))
             }
         }
     }
 }
 
-/// Converts a HIR expression to an `IrLine` or `IrError` with the number of lines the rule occurs on
-pub fn sir_expr_to_ir_line<'s>(sir: Vec<SirToken<'s>>, tokenization_data: &mut TokenizationData<'s>, lazy_expansions: &mut Vec<&'s str>) -> (Result<Vec<IrToken<'s>>, IrError<'s>>, NonZero<usize>) {
+/// Gets the message that follows a statement
+fn statement_message(sir: &[SirToken]) -> SharedStr {
+    sir.iter()
+        .find(|t| !matches!(t, SirToken::Whitespace(_)))
+        .map(|t| if let SirToken::Message(msg, _) = t { msg.clone() } else { SharedStr::default() })
+        .unwrap_or_default()
+}
+
+/// Gets the variable and message of a `GET` or `GET_AS_CODE` statement
+fn get_statement(sir: &[SirToken], get_type: GetType) -> Result<IrLine, IrError> {
+    let mut sir_iter = sir.iter()
+        .skip_while(|t| matches!(t, SirToken::Whitespace(_)));
+
+    if let Some(SirToken::Phone(var)) = sir_iter.next() {
+        Ok(IrLine::IoEvent(IoEvent::Tokenizer(TokenizerIoEvent::Get {
+            get_type,
+            var: var.str().clone(),
+            msg: sir_iter.next()
+                .map(|t| if let SirToken::Message(msg, _) = t { msg.clone() } else { SharedStr::default() })
+                .unwrap_or_default()
+        })))
+    } else {
+        Err(IrError::InvalidGetFormat(get_type))
+    }
+}
+
+/// Splits the message of a test statement into its input and expected output
+fn test_statement(msg: &SharedStr) -> Result<IrLine, IrError> {
+    let (input, expected) = msg.split_once(TEST_SEP_STR).ok_or(IrError::InvalidTestFormat)?;
+
+    Ok(IrLine::IoEvent(IoEvent::Runtime(RuntimeIoEvent::Test {
+        input: msg.share(input.trim()),
+        expected: msg.share(expected.trim()),
+    })))
+}
+
+/// Converts a HIR expression to a list of `IrTokens` and their spans
+/// or an `IrError` and its span with the number of lines the rule occurs on
+/// 
+/// Tokens that come from expanding a definition or variable take the span of its name
+pub fn sir_expr_to_ir_line(sir: Vec<SirToken>, tokenization_data: &mut TokenizationData, lazy_expansions: &mut Vec<SharedStr>) -> (Result<SpannedIr, (IrError, Span)>, NonZero<usize>) {
     let mut ir = Vec::new();
+    let mut spans = Vec::new();
     let mut lines = ONE;
+    let mut sir = sir.into_iter();
+    let mut last_span = None;
+
+    while let Some(token) = sir.next() {
+        // gives every token added since the last sir token that token's span
+        if let Some(span) = last_span {
+            spans.resize(ir.len(), span);
+        }
+
+        let span = *token.span();
+        last_span = Some(span);
 
-    for token in sir {
         ir.push(match token {
             SirToken::Any(_) => IrToken::Any,
             SirToken::ArgSep(_) => IrToken::ArgSep,
             SirToken::Bound(_) => IrToken::Phone(Phone::Bound),
+            SirToken::SyllableBound(_) => IrToken::SyllableBound,
             SirToken::Break(b, _) => IrToken::Break(b),
             SirToken::Comment(_) | SirToken::Whitespace(_) => continue,
             SirToken::CondFocus(ct, _) => IrToken::CondType(ct),
             SirToken::Definition(def) => {
                 if let Err(e) = tokenization_data.get_definition(def.str(), &mut ir, lazy_expansions) {
-                    return (Err(e), lines);
+                    return (Err((e, span)), lines);
                 }
 
                 continue;
             }
-            SirToken::InvalidPrefix(prefix, _) => return (Err(IrError::EmptyPrefix(prefix)), lines),
-            SirToken::InvalidPhone(phone) => return (Err(IrError::InvalidPhone(phone.str())), lines),
-            SirToken::Label(label) => IrToken::Label(label.str()),
+            SirToken::InvalidPrefix(prefix, _) => return (Err((IrError::EmptyPrefix(prefix), span)), lines),
+            SirToken::InvalidPhone(phone) => return (Err((IrError::InvalidPhone(phone.str().clone()), span)), lines),
+            SirToken::Label(label) => IrToken::Label(label.str().clone()),
             SirToken::Negative(_) => IrToken::Negative,
             SirToken::EndOfExpr(_) | SirToken::NonPhoneEscape('\n', _) => {
                 lines = unsafe { NonZero::new_unchecked(lines.get() + 1) };
                 continue;
             }
-            SirToken::NonPhoneEscape(c, _) => return (Err(IrError::BadEscape(Some(c))), lines),
-            SirToken::Phone(symbol) => IrToken::Phone(Phone::Symbol(symbol.str())),
+            SirToken::NonPhoneEscape(c, _) => return (Err((IrError::BadEscape(Some(c)), span)), lines),
+            SirToken::Phone(symbol) => IrToken::Phone(Phone::from_marked(symbol.str())),
             SirToken::ScopeEnd(st, _) => IrToken::ScopeEnd(st),
+            SirToken::ScopeStart(ScopeType::Repetition, _) => {
+                // checks if the scope is a feature matrix instead of a repetition
+                let mut matrix_sir = sir.clone();
+
+                if let Some(specs) = feature_matrix(&mut matrix_sir) {
+                    sir = matrix_sir;
+
+                    ir.push(IrToken::ScopeStart(ScopeType::Features));
+                    ir.extend(specs.into_iter().map(IrToken::Feature));
+                    ir.push(IrToken::ScopeEnd(ScopeType::Features));
+
+                    continue;
+                }
+
+                IrToken::ScopeStart(ScopeType::Repetition)
+            },
             SirToken::ScopeStart(st, _) => IrToken::ScopeStart(st),
             SirToken::Variable(var) => {
                 let content = tokenization_data.get_variable(var.str());
 
                 let tokens = match content {
-                    Err(e) => return (Err(e), lines),
+                    Err(e) => return (Err((e, span)), lines),
                     Ok(tokens) => tokens,
                 };
 
                 for token in tokens {
-                    ir.push(*token);
+                    ir.push(token.clone());
                 }
 
                 continue;
             },
             SirToken::DefinitionDeclaration(_) | SirToken::LazyDefinitionDeclaration(_)
-            | SirToken::GetCommand(_) | SirToken::GetAsCodeCommand(_)
-            | SirToken::PrintCommand(_) | SirToken::Message(_, _) => return (Err(IrError::StatementParseError), lines)
+            | SirToken::FeaturesDeclaration(_) | SirToken::SyllabifyDeclaration(_) | SirToken::GetCommand(_) | SirToken::GetAsCodeCommand(_)
+            | SirToken::PrintCommand(_) | SirToken::TestCommand(_) | SirToken::Message(_, _) => return (Err((IrError::StatementParseError, span)), lines)
         });
     }
 
-    (Ok(ir), lines)
+    if let Some(span) = last_span {
+        spans.resize(ir.len(), span);
+    }
+
+    (Ok((ir, spans)), lines)
+}
+
+/// Takes the specifications and closing bracket of a feature matrix (`[+voice -cont]`) from SIR
+/// 
+/// Returns `None` if the tokens are not a feature matrix
+fn feature_matrix(sir: &mut impl Iterator<Item = SirToken>) -> Option<Vec<FeatureSpec>> {
+    let mut specs = Vec::new();
+
+    for token in sir {
+        match token {
+            SirToken::Whitespace(_) => (),
+            SirToken::Phone(phone) => specs.push(FeatureSpec::parse(phone.str())?),
+            SirToken::ScopeEnd(ScopeType::Repetition, _) if !specs.is_empty() => return Some(specs),
+            _ => return None,
+        }
+    }
+
+    None
+}
+
+/// Adds the features in a feature declaration to the tokenization data
+/// 
+/// The declaration should be a list of phones followed by a single feature matrix
+fn declare_features(ir: &[IrToken], tokenization_data: &mut TokenizationData) -> Result<(), IrError> {
+    let Some((IrToken::ScopeEnd(ScopeType::Features), rest)) = ir.split_last() else {
+        return Err(IrError::InvalidFeaturesFormat);
+    };
+
+    let matrix_start = rest.iter()
+        .rposition(|token| token == &IrToken::ScopeStart(ScopeType::Features))
+        .ok_or(IrError::InvalidFeaturesFormat)?;
+
+    let mut symbols = Vec::new();
+
+    // selection scopes and argument seperators are allowed so definitions may be used
+    for token in &rest[..matrix_start] {
+        match token {
+            IrToken::Phone(Phone::Symbol(symbol)) => symbols.push(symbol.clone()),
+            IrToken::ArgSep
+            | IrToken::ScopeStart(ScopeType::Selection)
+            | IrToken::ScopeEnd(ScopeType::Selection) => (),
+            _ => return Err(IrError::InvalidFeaturesFormat),
+        }
+    }
+
+    // alpha variables have no meaning in a declaration
+    let values = rest[matrix_start + 1..].iter()
+        .map(|token| match token {
+            IrToken::Feature(FeatureSpec { name, .. }) if is_suprasegmental_feature(name) => Err(IrError::ReservedFeature(name.clone())),
+            IrToken::Feature(FeatureSpec { value: FeatureValue::Plus, name }) => Ok((name.clone(), true)),
+            IrToken::Feature(FeatureSpec { value: FeatureValue::Minus, name }) => Ok((name.clone(), false)),
+            _ => Err(IrError::InvalidFeaturesFormat),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if symbols.is_empty() {
+        return Err(IrError::InvalidFeaturesFormat);
+    }
+
+    tokenization_data.declare_features(&symbols, &values);
+
+    Ok(())
 }
 
 /// Gets the tokens for an expression or statement from a HIR iterator
-fn get_expr<'s>(sir: &mut Sir<'s>) -> Vec<SirToken<'s>> {
+fn get_expr(sir: &mut Sir) -> Vec<SirToken> {
     let mut line = Vec::new();
 
     for token in sir.by_ref() {
@@ -209,23 +331,34 @@ fn get_expr<'s>(sir: &mut Sir<'s>) -> Vec<SirToken<'s>> {
     line
 }
 
+/// A list of `IrTokens` with the span of the source code each was created from
+pub type SpannedIr = (Vec<IrToken>, Vec<Span>);
+
+/// An `IrLine` with the span of each of its tokens
+pub type SpannedIrLine = (IrLine, Vec<Span>);
+
 /// A list of `IrTokens`, a command, or nothing representing a line of source code
 #[cfg_attr(test, derive(PartialEq))]
 #[derive(Debug, Clone)]
-pub enum IrLine<'s> {
+pub enum IrLine {
     Ir {
-        tokens: Vec<IrToken<'s>>,
+        tokens: Vec<IrToken>,
+        lines: NonZero<usize>,
+    },
+    IoEvent(IoEvent),
+    /// The onset, nucleus, and coda patterns used to find syllable boundaries
+    Syllabify {
+        tokens: Vec<IrToken>,
         lines: NonZero<usize>,
     },
-    IoEvent(IoEvent<'s>),
     Empty { lines: NonZero<usize> },
 }
 
-impl IrLine<'_> {
+impl IrLine {
     /// Gets the number of lines an `IrLine` takes up
     pub const fn lines(&self) -> NonZero<usize> {
         match self {
-            Self::Ir {lines, .. } => *lines,
+            Self::Ir {lines, .. } | Self::Syllabify { lines, .. } => *lines,
             _ => ONE,
         }
     }
@@ -234,21 +367,24 @@ impl IrLine<'_> {
 /// Errors that occur when parsing raw text to tokens
 #[cfg_attr(test, derive(PartialEq))]
 #[derive(Debug)]
-pub enum IrError<'s> {
-    RecursiveLazyDefiniton(&'s str),
+pub enum IrError {
+    RecursiveLazyDefiniton(SharedStr),
     EmptyPrefix(Prefix),
-    UndefinedDefinition(&'s str),
-    UndefinedVariable(&'s str),
+    UndefinedDefinition(SharedStr),
+    UndefinedVariable(SharedStr),
     UnnamedDefinition,
     BadEscape(Option<char>),
     InvalidGetFormat(GetType),
-    InvalidPhone(&'s str),
+    InvalidPhone(SharedStr),
+    InvalidFeaturesFormat,
+    ReservedFeature(SharedStr),
+    InvalidTestFormat,
     StatementParseError,
 }
 
-impl std::error::Error for IrError<'_> {}
+impl std::error::Error for IrError {}
 
-impl std::fmt::Display for IrError<'_> {
+impl std::fmt::Display for IrError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::RecursiveLazyDefiniton(name) => write!(f, "Lazy definition '{DEFINITION_PREFIX}{name}' is recursive"),
@@ -260,8 +396,29 @@ impl std::fmt::Display for IrError<'_> {
             Self::BadEscape(Some(c)) => write!(f, "Escaped normal character '{}' ({ESCAPE_CHAR}{c})", c.escape_debug()),
             Self::InvalidGetFormat(get_type) => write!(f, "Invalid format after '{get_type}', expected variable name and message"),
             Self::InvalidPhone(s) => write!(f, "'{s}' is not a valid phone, label, or name"),
+            Self::InvalidFeaturesFormat => write!(f, "Invalid format after '{FEATURES_LINE_START}', expected phones followed by a feature matrix such as '[+voice -cont]'"),
+            Self::ReservedFeature(name) => write!(f, "Feature '{name}' is built-in and cannot be declared with '{FEATURES_LINE_START}'"),
+            Self::InvalidTestFormat => write!(f, "Invalid format after '{TEST_LINE_START}', expected an input and an expected output seperated by '{TEST_SEP_STR}'"),
             Self::StatementParseError => write!(f, "Found invalid statement"),
         }
     }
 }
- 
\ No newline at end of file
+
+impl ToErrorKind for IrError {
+    fn error_kind(&self) -> ScaErrorKind {
+        match self {
+            Self::RecursiveLazyDefiniton(name) => ScaErrorKind::RecursiveLazyDefinition { name: name.to_string() },
+            Self::EmptyPrefix(prefix) => ScaErrorKind::EmptyPrefix { prefix: prefix.char() },
+            Self::UndefinedDefinition(name) => ScaErrorKind::UndefinedDefinition { name: name.to_string() },
+            Self::UndefinedVariable(name) => ScaErrorKind::UndefinedVariable { name: name.to_string() },
+            Self::UnnamedDefinition => ScaErrorKind::UnnamedDefinition,
+            Self::BadEscape(escaped) => ScaErrorKind::BadEscape { escaped: *escaped },
+            Self::InvalidGetFormat(get_type) => ScaErrorKind::InvalidGetFormat { command: get_type.to_string() },
+            Self::InvalidPhone(phone) => ScaErrorKind::InvalidPhone { phone: phone.to_string() },
+            Self::InvalidFeaturesFormat => ScaErrorKind::InvalidFeaturesFormat,
+            Self::ReservedFeature(name) => ScaErrorKind::ReservedFeature { name: name.to_string() },
+            Self::InvalidTestFormat => ScaErrorKind::InvalidTestFormat,
+            Self::StatementParseError => ScaErrorKind::InvalidStatement,
+        }
+    }
+}
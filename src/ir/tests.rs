@@ -5,11 +5,17 @@ use crate::{
 
 use super::*;
 
-/// Tokenizes rules, returns errors with the line they occur on
-fn tokenize<'s>(rules: &'s str) -> Result<Vec<IrLine<'s>>, (IrError<'s>, usize)> {
+/// Tokenizes rules without the spans of tokens, returns errors with the line they occur on
+fn tokenize(rules: &str) -> Result<Vec<IrLine>, (IrError, usize)> {
+    tokenize_with_spans(rules)
+        .map(|ir| ir.into_iter().map(|(line, _)| line).collect())
+        .map_err(|(e, _, line)| (e, line))
+}
+
+/// Tokenizes rules, returns errors with the span and line they occur on
+fn tokenize_with_spans(rules: &str) -> Result<Vec<SpannedIrLine>, (IrError, Span, usize)> {
     let mut ir = Vec::new();
 
-    // Note: no IO can be preformed so no memory leaks occur if sources are not dropped
     let mut tokenization_data = TokenizationData::new();
 
     let mut sir = Lexer::lex(rules);
@@ -18,7 +24,7 @@ fn tokenize<'s>(rules: &'s str) -> Result<Vec<IrLine<'s>>, (IrError<'s>, usize)>
 
     while !sir.is_empty() {
         let ir_line = ir_line_from_sir(&mut sir, &mut tokenization_data, &mut Vec::new())
-            .map_err(|(e, lines)| (e, last_line + lines.get()))?;
+            .map_err(|(e, span, lines)| (e, span, last_line + lines.get()))?;
         ir.push(ir_line);
         last_line = sir.line();
     }
@@ -33,49 +39,49 @@ fn tokenize_nothing() {
 
 #[test]
 fn tokenize_phone() {
-    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a"))], lines: ONE}]), tokenize("a"));
+    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a".into()))], lines: ONE}]), tokenize("a"));
 }
 
 #[test]
 fn tokenize_long_phone() {
-    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("abcdefg"))], lines: ONE}]), tokenize("abcdefg"));
+    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("abcdefg".into()))], lines: ONE}]), tokenize("abcdefg"));
 }
 
 #[test]
 fn tokenize_phones() {
-    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a")), IrToken::Phone(Phone::Symbol("bc")), IrToken::Phone(Phone::Symbol("def"))], lines: ONE}]), tokenize("a bc def"));
+    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a".into())), IrToken::Phone(Phone::Symbol("bc".into())), IrToken::Phone(Phone::Symbol("def".into()))], lines: ONE}]), tokenize("a bc def"));
 }
 
 #[test]
 fn tokenize_lines_of_phones() {
     assert_eq!(Ok(vec![
-        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a")), IrToken::Phone(Phone::Symbol("bc")), IrToken::Phone(Phone::Symbol("def"))], lines: ONE},
-        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("fed")), IrToken::Phone(Phone::Symbol("cb")), IrToken::Phone(Phone::Symbol("a"))], lines: ONE},
+        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a".into())), IrToken::Phone(Phone::Symbol("bc".into())), IrToken::Phone(Phone::Symbol("def".into()))], lines: ONE},
+        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("fed".into())), IrToken::Phone(Phone::Symbol("cb".into())), IrToken::Phone(Phone::Symbol("a".into()))], lines: ONE},
     ]), tokenize("a bc def\nfed cb a"));
 }
 
 #[test]
 fn tokenize_lines_of_phones_and_nothing() {
     assert_eq!(Ok(vec![
-        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a")), IrToken::Phone(Phone::Symbol("bc")), IrToken::Phone(Phone::Symbol("def"))], lines: ONE},
+        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a".into())), IrToken::Phone(Phone::Symbol("bc".into())), IrToken::Phone(Phone::Symbol("def".into()))], lines: ONE},
         IrLine::Empty { lines: ONE },
-        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("fed")), IrToken::Phone(Phone::Symbol("cb")), IrToken::Phone(Phone::Symbol("a"))], lines: ONE},
+        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("fed".into())), IrToken::Phone(Phone::Symbol("cb".into())), IrToken::Phone(Phone::Symbol("a".into()))], lines: ONE},
     ]), tokenize("a bc def\n\nfed cb a"));
 }
 
 #[test]
 fn tokenize_lines_of_phones_and_comment() {
     assert_eq!(Ok(vec![
-        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a")), IrToken::Phone(Phone::Symbol("bc")), IrToken::Phone(Phone::Symbol("def"))], lines: ONE},
+        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a".into())), IrToken::Phone(Phone::Symbol("bc".into())), IrToken::Phone(Phone::Symbol("def".into()))], lines: ONE},
         IrLine::Empty { lines: ONE },
-        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("fed")), IrToken::Phone(Phone::Symbol("cb")), IrToken::Phone(Phone::Symbol("a"))], lines: ONE},
+        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("fed".into())), IrToken::Phone(Phone::Symbol("cb".into())), IrToken::Phone(Phone::Symbol("a".into()))], lines: ONE},
     ]), tokenize("a bc def\n## this is a comment\nfed cb a"));
 }
 
 #[test]
 fn tokenize_with_def() {
     assert_eq!(
-        Ok(vec![IrLine::Empty { lines: ONE }, IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("b")), IrToken::Phone(Phone::Symbol("cd")), IrToken::Phone(Phone::Symbol("e"))], lines: ONE,}]),
+        Ok(vec![IrLine::Empty { lines: ONE }, IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("b".into())), IrToken::Phone(Phone::Symbol("cd".into())), IrToken::Phone(Phone::Symbol("e".into()))], lines: ONE,}]),
         tokenize("DEFINE a b cd e\n@a")
     );
 }
@@ -84,9 +90,9 @@ fn tokenize_with_def() {
 fn tokenize_with_redef() {
     assert_eq!(Ok(vec![
         IrLine::Empty { lines: ONE },
-        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("b")), IrToken::Phone(Phone::Symbol("cd")), IrToken::Phone(Phone::Symbol("e"))], lines: ONE},
+        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("b".into())), IrToken::Phone(Phone::Symbol("cd".into())), IrToken::Phone(Phone::Symbol("e".into()))], lines: ONE},
         IrLine::Empty { lines: ONE },
-        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("new")), IrToken::Phone(Phone::Symbol("content"))], lines: ONE},
+        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("new".into())), IrToken::Phone(Phone::Symbol("content".into()))], lines: ONE},
     ]), tokenize("DEFINE a b cd e\n@a\nDEFINE a new content\n@a"));
 }
 
@@ -98,24 +104,24 @@ fn tokenize_empty_def() {
 #[test]
 fn tokenize_late_def() {
     assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![
-        IrToken::Phone(Phone::Symbol("a")),
-        IrToken::Phone(Phone::Symbol("DEFINE")),
-        IrToken::Phone(Phone::Symbol("a")),
-        IrToken::Phone(Phone::Symbol("b")),
-        IrToken::Phone(Phone::Symbol("c")),
+        IrToken::Phone(Phone::Symbol("a".into())),
+        IrToken::Phone(Phone::Symbol("DEFINE".into())),
+        IrToken::Phone(Phone::Symbol("a".into())),
+        IrToken::Phone(Phone::Symbol("b".into())),
+        IrToken::Phone(Phone::Symbol("c".into())),
     ], lines: ONE}]), tokenize("a DEFINE a b c"));
 }
 
 #[test]
 fn tokenize_undef() {
-    assert_eq!(Err((IrError::UndefinedDefinition("a"), 1)), tokenize("@a\nDEFINE a a b c"));
+    assert_eq!(Err((IrError::UndefinedDefinition("a".into()), 1)), tokenize("@a\nDEFINE a a b c"));
 }
 
 #[test]
 fn tokenize_lazy_def() {
     assert_eq!(Ok(vec![
         IrLine::Empty { lines: ONE },
-        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("b")), IrToken::Phone(Phone::Symbol("c"))], lines: ONE }
+        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("b".into())), IrToken::Phone(Phone::Symbol("c".into()))], lines: ONE }
     ]), tokenize("DEFINE_LAZY a b c\n@a"));
 }
 
@@ -124,7 +130,7 @@ fn tokenize_lazy_def_before_content_def() {
     assert_eq!(Ok(vec![
         IrLine::Empty { lines: ONE },
         IrLine::Empty { lines: ONE },
-        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("c"))], lines: ONE }
+        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("c".into()))], lines: ONE }
     ]), tokenize("DEFINE_LAZY a @b\nDEFINE b c\n@a"));
 }
 
@@ -132,7 +138,7 @@ fn tokenize_lazy_def_before_content_def() {
 
 #[test]
 fn tokenize_recursive_lazy_def() {
-    assert_eq!(Err((IrError::RecursiveLazyDefiniton("a"), 3)), tokenize("DEFINE_LAZY a @b\nDEFINE_LAZY b @a\n@a"));
+    assert_eq!(Err((IrError::RecursiveLazyDefiniton("a".into()), 3)), tokenize("DEFINE_LAZY a @b\nDEFINE_LAZY b @a\n@a"));
 }
 
 #[test]
@@ -141,66 +147,94 @@ fn tokenize_lazy_def_redef() {
         IrLine::Empty { lines: ONE },
         IrLine::Empty { lines: ONE },
         IrLine::Empty { lines: ONE },
-        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("c"))], lines: ONE }
+        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("c".into()))], lines: ONE }
     ]), tokenize("DEFINE b z\nDEFINE_LAZY a @b\nDEFINE b c\n@a"));
 }
 
 #[test]
 fn tokenize_label() {
-    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Label("label")], lines: ONE}]), tokenize("$label"));
+    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Label("label".into())], lines: ONE}]), tokenize("$label"));
 }
 
 #[test]
 fn tokenize_phones_and_labels() {
     assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![
-        IrToken::Phone(Phone::Symbol("a")),
-        IrToken::Label("label"),
-        IrToken::Phone(Phone::Symbol("phone")),
-        IrToken::Label("label_two"),
-        IrToken::Phone(Phone::Symbol("b"))
+        IrToken::Phone(Phone::Symbol("a".into())),
+        IrToken::Label("label".into()),
+        IrToken::Phone(Phone::Symbol("phone".into())),
+        IrToken::Label("label_two".into()),
+        IrToken::Phone(Phone::Symbol("b".into()))
     ], lines: ONE}]), tokenize("a $label phone$label_two b"));
 }
 
 #[test]
 fn tokenize_ltr() {
     assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Break(
-        Break::Shift(Shift { dir: Direction::Ltr, kind: ShiftType::Stay })
+        Break::Shift(Shift { dir: Direction::Ltr, kind: ShiftType::Stay, once: false })
     )], lines: ONE}]), tokenize(">"));
 }
 
 #[test]
 fn tokenize_double_ltr() {
     assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Break(
-        Break::Shift(Shift { dir: Direction::Ltr, kind: ShiftType::Move })
+        Break::Shift(Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false })
     )], lines: ONE}]), tokenize(">>"));
 }
 
 #[test]
 fn tokenize_double_ltr_with_suroundings() {
-    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a")), IrToken::Phone(Phone::Symbol("bc")), IrToken::Break(
-        Break::Shift(Shift { dir: Direction::Ltr, kind: ShiftType::Move })
-    ), IrToken::Phone(Phone::Symbol("de")), IrToken::Phone(Phone::Symbol("f"))], lines: ONE}]), tokenize("a bc>>de f"));
+    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a".into())), IrToken::Phone(Phone::Symbol("bc".into())), IrToken::Break(
+        Break::Shift(Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false })
+    ), IrToken::Phone(Phone::Symbol("de".into())), IrToken::Phone(Phone::Symbol("f".into()))], lines: ONE}]), tokenize("a bc>>de f"));
+}
+
+#[test]
+fn tokenize_triple_ltr() {
+    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Break(
+        Break::Shift(Shift { dir: Direction::Ltr, kind: ShiftType::Simultaneous, once: false })
+    )], lines: ONE}]), tokenize(">>>"));
+}
+
+#[test]
+fn tokenize_once_ltr() {
+    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Break(
+        Break::Shift(Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: true })
+    )], lines: ONE}]), tokenize(">>!"));
 }
 
 #[test]
 fn tokenize_rtl() {
     assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Break(
-        Break::Shift(Shift { dir: Direction::Rtl, kind: ShiftType::Stay })
+        Break::Shift(Shift { dir: Direction::Rtl, kind: ShiftType::Stay, once: false })
     )], lines: ONE}]), tokenize("<"));
 }
 
 #[test]
 fn tokenize_double_rtl() {
     assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Break(
-        Break::Shift(Shift { dir: Direction::Rtl, kind: ShiftType::Move })
+        Break::Shift(Shift { dir: Direction::Rtl, kind: ShiftType::Move, once: false })
     )], lines: ONE}]), tokenize("<<"));
 }
 
 #[test]
 fn tokenize_double_rtl_with_suroundings() {
-    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a")), IrToken::Phone(Phone::Symbol("bc")), IrToken::Break(
-        Break::Shift(Shift { dir: Direction::Rtl, kind: ShiftType::Move })
-    ), IrToken::Phone(Phone::Symbol("de")), IrToken::Phone(Phone::Symbol("f"))], lines: ONE}]), tokenize("a bc<<de f"));
+    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a".into())), IrToken::Phone(Phone::Symbol("bc".into())), IrToken::Break(
+        Break::Shift(Shift { dir: Direction::Rtl, kind: ShiftType::Move, once: false })
+    ), IrToken::Phone(Phone::Symbol("de".into())), IrToken::Phone(Phone::Symbol("f".into()))], lines: ONE}]), tokenize("a bc<<de f"));
+}
+
+#[test]
+fn tokenize_triple_rtl() {
+    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Break(
+        Break::Shift(Shift { dir: Direction::Rtl, kind: ShiftType::Simultaneous, once: false })
+    )], lines: ONE}]), tokenize("<<<"));
+}
+
+#[test]
+fn tokenize_once_rtl() {
+    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Break(
+        Break::Shift(Shift { dir: Direction::Rtl, kind: ShiftType::Stay, once: true })
+    )], lines: ONE}]), tokenize("<!"));
 }
 
 #[test]
@@ -216,22 +250,22 @@ fn tokenize_anti_cond() {
 #[test]
 fn tokenize_cond_with_suroundings() {
     assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![
-        IrToken::Phone(Phone::Symbol("a")),
-        IrToken::Phone(Phone::Symbol("bc")),
+        IrToken::Phone(Phone::Symbol("a".into())),
+        IrToken::Phone(Phone::Symbol("bc".into())),
         IrToken::Break(Break::Cond),
-        IrToken::Phone(Phone::Symbol("de")),
-        IrToken::Phone(Phone::Symbol("f"))
+        IrToken::Phone(Phone::Symbol("de".into())),
+        IrToken::Phone(Phone::Symbol("f".into()))
     ], lines: ONE}]), tokenize("a bc/de f"));
 }
 
 #[test]
 fn tokenize_anti_cond_with_suroundings() {
     assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![
-        IrToken::Phone(Phone::Symbol("a")),
-        IrToken::Phone(Phone::Symbol("bc")),
+        IrToken::Phone(Phone::Symbol("a".into())),
+        IrToken::Phone(Phone::Symbol("bc".into())),
         IrToken::Break(Break::AntiCond),
-        IrToken::Phone(Phone::Symbol("de")),
-        IrToken::Phone(Phone::Symbol("f"))
+        IrToken::Phone(Phone::Symbol("de".into())),
+        IrToken::Phone(Phone::Symbol("f".into()))
     ], lines: ONE}]), tokenize("a bc//de f"));
 }
 
@@ -257,19 +291,19 @@ fn tokenize_scope_bounds() {
 #[test]
 fn tokenize_scope_bounds_with_suroundings() {
     assert_eq!(Ok(vec![
-        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a")), IrToken::ScopeStart(ScopeType::Optional), IrToken::Phone(Phone::Symbol("b"))], lines: ONE},
+        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a".into())), IrToken::ScopeStart(ScopeType::Optional), IrToken::Phone(Phone::Symbol("b".into()))], lines: ONE},
     ]), tokenize("a(b"));
 
     assert_eq!(Ok(vec![
-        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a")), IrToken::ScopeEnd(ScopeType::Optional), IrToken::Phone(Phone::Symbol("b"))], lines: ONE},
+        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a".into())), IrToken::ScopeEnd(ScopeType::Optional), IrToken::Phone(Phone::Symbol("b".into()))], lines: ONE},
     ]), tokenize("a)b"));
 
     assert_eq!(Ok(vec![
-        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a")), IrToken::ScopeStart(ScopeType::Selection), IrToken::Phone(Phone::Symbol("b"))], lines: ONE}
+        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a".into())), IrToken::ScopeStart(ScopeType::Selection), IrToken::Phone(Phone::Symbol("b".into()))], lines: ONE}
     ]), tokenize("a{b"));
 
     assert_eq!(Ok(vec![
-        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a")), IrToken::ScopeEnd(ScopeType::Selection), IrToken::Phone(Phone::Symbol("b"))], lines: ONE},
+        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a".into())), IrToken::ScopeEnd(ScopeType::Selection), IrToken::Phone(Phone::Symbol("b".into()))], lines: ONE},
     ]), tokenize("a}b"));
 }
 
@@ -287,7 +321,7 @@ fn tokenize_repetition() {
         IrToken::ScopeStart(ScopeType::Repetition),
         IrToken::Any,
         IrToken::Negative,
-        IrToken::Phone(Phone::Symbol("w")),
+        IrToken::Phone(Phone::Symbol("w".into())),
         IrToken::ScopeEnd(ScopeType::Repetition),
     ], lines: ONE}]), tokenize("[* ! w]"));
 }
@@ -295,11 +329,11 @@ fn tokenize_repetition() {
 #[test]
 fn tokenize_repetition_with_suroundings() {
     assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![
-        IrToken::Phone(Phone::Symbol("a")),
+        IrToken::Phone(Phone::Symbol("a".into())),
         IrToken::ScopeStart(ScopeType::Repetition),
         IrToken::Any,
         IrToken::ScopeEnd(ScopeType::Repetition),
-        IrToken::Phone(Phone::Symbol("b")),
+        IrToken::Phone(Phone::Symbol("b".into())),
     ], lines: ONE}]), tokenize("a [*] b"));
 }
 
@@ -311,7 +345,7 @@ fn tokenize_any() {
 #[test]
 fn tokenize_any_with_suroundings() {
     assert_eq!(Ok(vec![
-        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a")), IrToken::Any, IrToken::Phone(Phone::Symbol("b"))], lines: ONE},
+        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a".into())), IrToken::Any, IrToken::Phone(Phone::Symbol("b".into()))], lines: ONE},
     ]), tokenize("a*b"));
 }
 
@@ -323,7 +357,7 @@ fn tokenize_sep() {
 #[test]
 fn tokenize_sep_with_suroundings() {
     assert_eq!(Ok(vec![
-        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a")), IrToken::ArgSep, IrToken::Phone(Phone::Symbol("b"))], lines: ONE},
+        IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a".into())), IrToken::ArgSep, IrToken::Phone(Phone::Symbol("b".into()))], lines: ONE},
     ]), tokenize("a,b"));
 }
 
@@ -334,17 +368,17 @@ fn tokenize_input() {
 
 #[test]
 fn tokenize_input_with_suroundings() {
-    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a")), IrToken::CondType(CondType::Pattern), IrToken::Phone(Phone::Symbol("b"))], lines: ONE}]), tokenize("a _ b"))
+    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a".into())), IrToken::CondType(CondType::Pattern), IrToken::Phone(Phone::Symbol("b".into()))], lines: ONE}]), tokenize("a _ b"))
 }
 
 #[test]
 fn tokenize_input_with_contacting() {
-    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a_b"))], lines: ONE}]), tokenize("a_b"));
+    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("a_b".into()))], lines: ONE}]), tokenize("a_b"));
 }
 
 #[test]
 fn print_statement() {
-    assert_eq!(Ok(vec![IrLine::IoEvent(IoEvent::Runtime(RuntimeIoEvent::Print { msg: "test message" }))]), tokenize("PRINT test message"));
+    assert_eq!(Ok(vec![IrLine::IoEvent(IoEvent::Runtime(RuntimeIoEvent::Print { msg: "test message".into() }))]), tokenize("PRINT test message"));
 }
 
 #[test]
@@ -357,13 +391,13 @@ fn escape() {
 
 #[test]
 fn escape_definition_call() {
-    let shift_token = IrToken::Break(Break::Shift(Shift { dir: Direction::Ltr, kind: ShiftType::Move }));
-    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("\\@a")), shift_token], lines: ONE}]), tokenize("\\@a >>"));
+    let shift_token = IrToken::Break(Break::Shift(Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false }));
+    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![IrToken::Phone(Phone::Symbol("\\@a".into())), shift_token], lines: ONE}]), tokenize("\\@a >>"));
 }
 
 #[test]
 fn escape_escape() {
-    assert_eq!(Err((IrError::UndefinedDefinition("a"), 1)), tokenize("\\\\@a >>"));
+    assert_eq!(Err((IrError::UndefinedDefinition("a".into()), 1)), tokenize("\\\\@a >>"));
 }
 
 #[test]
@@ -371,8 +405,8 @@ fn tokenize_multi_line() {
     assert_eq!(Ok(vec![
         IrLine::Ir {
             tokens: vec![
-                IrToken::Phone(Phone::Symbol("h")),
-                IrToken::Break(Break::Shift(Shift { dir: Direction::Ltr, kind: ShiftType::Move })),
+                IrToken::Phone(Phone::Symbol("h".into())),
+                IrToken::Break(Break::Shift(Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false })),
                 IrToken::Break(Break::Cond),
                 IrToken::CondType(CondType::Pattern),
                 IrToken::Phone(Phone::Bound),
@@ -384,8 +418,8 @@ fn tokenize_multi_line() {
 
 #[test]
 fn error_in_multi_line() {
-    assert_eq!(Err((IrError::UndefinedDefinition("a"), 2)), tokenize("h >> \\\n @a"));
-    assert_eq!(Err((IrError::UndefinedDefinition("a"), 3)), tokenize("h >> \\\n \n @a"));
+    assert_eq!(Err((IrError::UndefinedDefinition("a".into()), 2)), tokenize("h >> \\\n @a"));
+    assert_eq!(Err((IrError::UndefinedDefinition("a".into()), 3)), tokenize("h >> \\\n \n @a"));
 }
 
 #[test]
@@ -397,66 +431,180 @@ fn tokenize_simple() {
         IrLine::Empty { lines: ONE },
         IrLine::Empty { lines: ONE },
         IrLine::Ir { tokens: vec![
-            IrToken::Label("stops"),
+            IrToken::Label("stops".into()),
             IrToken::ScopeStart(ScopeType::Selection),
-            IrToken::Phone(Phone::Symbol("p")),
+            IrToken::Phone(Phone::Symbol("p".into())),
             IrToken::ArgSep,
-            IrToken::Phone(Phone::Symbol("t")),
+            IrToken::Phone(Phone::Symbol("t".into())),
             IrToken::ArgSep,
-            IrToken::Phone(Phone::Symbol("k")),
+            IrToken::Phone(Phone::Symbol("k".into())),
             IrToken::ScopeEnd(ScopeType::Selection),
 
-            IrToken::Break(Break::Shift(Shift { dir: Direction::Ltr, kind: ShiftType::Move })),
+            IrToken::Break(Break::Shift(Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false })),
 
-            IrToken::Label("stops"),
+            IrToken::Label("stops".into()),
             IrToken::ScopeStart(ScopeType::Selection),
-            IrToken::Phone(Phone::Symbol("b")),
+            IrToken::Phone(Phone::Symbol("b".into())),
             IrToken::ArgSep,
-            IrToken::Phone(Phone::Symbol("d")),
+            IrToken::Phone(Phone::Symbol("d".into())),
             IrToken::ArgSep,
-            IrToken::Phone(Phone::Symbol("g")),
+            IrToken::Phone(Phone::Symbol("g".into())),
             IrToken::ScopeEnd(ScopeType::Selection),
 
             IrToken::Break(Break::Cond),
 
             IrToken::ScopeStart(ScopeType::Selection),
-            IrToken::Phone(Phone::Symbol("i")),
+            IrToken::Phone(Phone::Symbol("i".into())),
             IrToken::ArgSep,
-            IrToken::Phone(Phone::Symbol("e")),
+            IrToken::Phone(Phone::Symbol("e".into())),
             IrToken::ArgSep,
-            IrToken::Phone(Phone::Symbol("a")),
+            IrToken::Phone(Phone::Symbol("a".into())),
             IrToken::ArgSep,
-            IrToken::Phone(Phone::Symbol("u")),
+            IrToken::Phone(Phone::Symbol("u".into())),
             IrToken::ArgSep,
-            IrToken::Phone(Phone::Symbol("o")),
+            IrToken::Phone(Phone::Symbol("o".into())),
             IrToken::ScopeEnd(ScopeType::Selection),
 
             IrToken::CondType(CondType::Pattern),
 
             IrToken::ScopeStart(ScopeType::Selection),
-            IrToken::Phone(Phone::Symbol("i")),
+            IrToken::Phone(Phone::Symbol("i".into())),
             IrToken::ArgSep,
-            IrToken::Phone(Phone::Symbol("e")),
+            IrToken::Phone(Phone::Symbol("e".into())),
             IrToken::ArgSep,
-            IrToken::Phone(Phone::Symbol("a")),
+            IrToken::Phone(Phone::Symbol("a".into())),
             IrToken::ArgSep,
-            IrToken::Phone(Phone::Symbol("u")),
+            IrToken::Phone(Phone::Symbol("u".into())),
             IrToken::ArgSep,
-            IrToken::Phone(Phone::Symbol("o")),
+            IrToken::Phone(Phone::Symbol("o".into())),
             IrToken::ScopeEnd(ScopeType::Selection),
 
             IrToken::Break(Break::Cond),
             IrToken::CondType(CondType::Pattern),
 
             IrToken::ScopeStart(ScopeType::Selection),
-            IrToken::Phone(Phone::Symbol("l")),
+            IrToken::Phone(Phone::Symbol("l".into())),
             IrToken::ArgSep,
-            IrToken::Phone(Phone::Symbol("r")),
+            IrToken::Phone(Phone::Symbol("r".into())),
             IrToken::ScopeEnd(ScopeType::Selection),
 
             IrToken::Break(Break::AntiCond),
-            IrToken::Phone(Phone::Symbol("h")),
+            IrToken::Phone(Phone::Symbol("h".into())),
             IrToken::CondType(CondType::Pattern),
         ], lines: ONE},
     ]), tokens);
-}
\ No newline at end of file
+}
+
+#[test]
+fn feature_matrix() {
+    use crate::tokens::{FeatureSpec, FeatureValue};
+
+    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![
+        IrToken::ScopeStart(ScopeType::Features),
+        IrToken::Feature(FeatureSpec { value: FeatureValue::Plus, name: "voice".into() }),
+        IrToken::Feature(FeatureSpec { value: FeatureValue::Minus, name: "cont".into() }),
+        IrToken::ScopeEnd(ScopeType::Features),
+    ], lines: ONE }]), tokenize("[+voice -cont]"));
+
+    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![
+        IrToken::ScopeStart(ScopeType::Features),
+        IrToken::Feature(FeatureSpec { value: FeatureValue::Alpha('α'), name: "place".into() }),
+        IrToken::Feature(FeatureSpec { value: FeatureValue::NegatedAlpha('β'), name: "voice".into() }),
+        IrToken::ScopeEnd(ScopeType::Features),
+    ], lines: ONE }]), tokenize("[αplace -βvoice]"));
+
+    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![
+        IrToken::ScopeStart(ScopeType::Repetition),
+        IrToken::Phone(Phone::Symbol("+voice".into())),
+        IrToken::Phone(Phone::Symbol("a".into())),
+        IrToken::ScopeEnd(ScopeType::Repetition),
+    ], lines: ONE }]), tokenize("[+voice a]"));
+
+    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![
+        IrToken::ScopeStart(ScopeType::Repetition),
+        IrToken::Phone(Phone::Symbol("-".into())),
+        IrToken::ScopeEnd(ScopeType::Repetition),
+    ], lines: ONE }]), tokenize("[-]"));
+}
+
+#[test]
+fn feature_declaration() {
+    assert_eq!(Ok(vec![IrLine::Empty { lines: ONE }]), tokenize("FEATURES p t k [-voice]"));
+    assert_eq!(Err((IrError::InvalidFeaturesFormat, 1)), tokenize("FEATURES p t k"));
+    assert_eq!(Err((IrError::InvalidFeaturesFormat, 1)), tokenize("FEATURES [+voice]"));
+}
+
+#[test]
+fn syllabify_declaration() {
+    assert_eq!(Ok(vec![IrLine::Syllabify {
+        tokens: vec![
+            IrToken::ScopeStart(ScopeType::Optional),
+            IrToken::Phone(Phone::Symbol("p".into())),
+            IrToken::ScopeEnd(ScopeType::Optional),
+            IrToken::ArgSep,
+            IrToken::Phone(Phone::Symbol("a".into())),
+            IrToken::ArgSep,
+        ],
+        lines: ONE,
+    }]), tokenize("SYLLABIFY (p), a,"));
+    assert_eq!(Ok(vec![IrLine::Syllabify { tokens: Vec::new(), lines: ONE }]), tokenize("SYLLABIFY"));
+}
+
+#[test]
+fn syllable_bound() {
+    assert_eq!(Ok(vec![IrLine::Ir {
+        tokens: vec![
+            IrToken::Phone(Phone::Symbol("a".into())),
+            IrToken::Break(Break::Cond),
+            IrToken::CondType(CondType::Pattern),
+            IrToken::SyllableBound,
+        ],
+        lines: ONE,
+    }]), tokenize("a / _."));
+}
+
+#[test]
+fn test_statement() {
+    assert_eq!(Ok(vec![IrLine::IoEvent(IoEvent::Runtime(RuntimeIoEvent::Test { input: "a b".into(), expected: "c".into() }))]), tokenize("TEST a b => c"));
+    assert!(tokenize("TEST a b c").is_err());
+}
+
+#[test]
+fn token_spans() {
+    let ir = tokenize_with_spans("DEFINE V {a, e}\nb >> @V / _ [+voice]").expect("rules should tokenize");
+
+    let spans = match &ir[1] {
+        (IrLine::Ir { tokens, .. }, spans) if tokens.len() == spans.len() => spans,
+        line => unreachable!("second line should be a rule with a span for each token, found {line:?}"),
+    };
+
+    assert_eq!(spans.iter().map(|span| (span.line(), span.char(), span.len())).collect::<Vec<_>>(), [
+        (1, 0, 1),
+        (1, 2, 2),
+        // tokens from the definition take the span of its name
+        (1, 5, 2), (1, 5, 2), (1, 5, 2), (1, 5, 2), (1, 5, 2),
+        (1, 8, 1),
+        (1, 10, 1),
+        // tokens from the feature matrix take the span of its opening bracket
+        (1, 12, 1), (1, 12, 1), (1, 12, 1),
+    ]);
+
+    assert!(matches!(tokenize_with_spans("a >> @X"), Err((IrError::UndefinedDefinition(name), span, 1)) if name == "X" && span.index() == 5 && span.len() == 2));
+}
+
+#[test]
+fn feature_matrix_in_repetition_position() {
+    use crate::tokens::{FeatureSpec, FeatureValue};
+
+    assert_eq!(Ok(vec![IrLine::Ir { tokens: vec![
+        IrToken::ScopeStart(ScopeType::Features),
+        IrToken::Feature(FeatureSpec { value: FeatureValue::Plus, name: "x".into() }),
+        IrToken::ScopeEnd(ScopeType::Features),
+    ], lines: ONE }]), tokenize("[+x]"));
+
+    assert_eq!(Ok(vec![IrLine::Empty { lines: ONE }, IrLine::Ir { tokens: vec![
+        IrToken::ScopeStart(ScopeType::Repetition),
+        IrToken::Phone(Phone::Symbol("+x".into())),
+        IrToken::ScopeEnd(ScopeType::Repetition),
+    ], lines: ONE }]), tokenize("DEFINE px +x\n[@px]"));
+}
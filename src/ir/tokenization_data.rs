@@ -1,7 +1,7 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use crate::{
-    escaped_strings::{EscapedStr, EscapedString}, ir::{IrLine, sir_expr_to_ir_line, ir_line_from_sir}, lexer::{Sir, Lexer, sir::SirToken}, phones::build_phone_list
+    escaped_strings::EscapedString, ir::{IrLine, sir_expr_to_ir_line, ir_line_from_sir}, lexer::{Sir, Lexer, sir::SirToken}, matcher::syllables::Syllabifier, phones::{build_phone_list, features::FeatureTable}, shared_str::SharedStr
 };
 
 use super::{tokens::IrToken, IrError};
@@ -12,25 +12,25 @@ use super::{tokens::IrToken, IrError};
 /// Includes:
 /// - definitions
 /// - variables
-/// 
-/// # Warning
-/// If variable io is used and `free_sources` is never called on this struct,
-/// a memory leak will occur
-#[derive(Debug, Default, PartialEq, Eq)]
-pub struct TokenizationData<'s> {
-    definitions: HashMap<&'s str, Definition<'s>>,
-    variables: HashMap<&'s str, Vec<IrToken<'s>>>,
-    /// A list of pointers to all strs leaked
-    sources: Vec<*const str>,
+/// - distinctive features
+/// - syllabification patterns
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenizationData {
+    definitions: HashMap<SharedStr, Definition>,
+    variables: HashMap<SharedStr, Vec<IrToken>>,
+    /// Shared with the patterns built while it is current
+    features: Arc<FeatureTable>,
+    /// Shared with the patterns built while it is current
+    syllabifier: Option<Arc<Syllabifier>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum Definition<'s> {
-    Lazy(Sir<'s>),
-    Eager(Vec<IrToken<'s>>),
+enum Definition {
+    Lazy(Sir),
+    Eager(Vec<IrToken>),
 }
 
-impl<'s> TokenizationData<'s> {
+impl TokenizationData {
     #[inline]
     pub fn new() -> Self {
         Self::default()
@@ -39,67 +39,88 @@ impl<'s> TokenizationData<'s> {
     /// Fetches the tokens associated with a definition's name and appends them to a given lsit
     /// 
     /// Returns an error if there is no definition of the given name
-    pub fn get_definition(&mut self, name: &'s str, tokens: &mut Vec<IrToken<'s>>, lazy_expansions: &mut Vec<&'s str>) -> Result<(), IrError<'s>> {
+    pub fn get_definition(&mut self, name: &SharedStr, tokens: &mut Vec<IrToken>, lazy_expansions: &mut Vec<SharedStr>) -> Result<(), IrError> {
         match self.definitions.get(name) {
             Some(Definition::Eager(def_tokens)) => for token in def_tokens {
-                tokens.push(*token);
+                tokens.push(token.clone());
             },
             Some(Definition::Lazy(definition)) => {
-                if lazy_expansions.contains(&name) {
-                    return Err(IrError::RecursiveLazyDefiniton(name));
+                if lazy_expansions.contains(name) {
+                    return Err(IrError::RecursiveLazyDefiniton(name.clone()));
                 }
 
-                lazy_expansions.push(name);
+                lazy_expansions.push(name.clone());
 
                 match ir_line_from_sir(&mut definition.clone(), self, lazy_expansions) {
-                    Ok(IrLine::Empty { .. }) => (),
-                    Ok(IrLine::IoEvent(_)) => return Err(IrError::StatementParseError),
-                    Ok(IrLine::Ir { tokens: new_tokens, .. }) => for token in new_tokens {
+                    Ok((IrLine::Empty { .. }, _)) => (),
+                    Ok((IrLine::IoEvent(_) | IrLine::Syllabify { .. }, _)) => return Err(IrError::StatementParseError),
+                    Ok((IrLine::Ir { tokens: new_tokens, .. }, _)) => for token in new_tokens {
                         tokens.push(token);
                     },
-                    Err((e, _)) => return Err(e),
+                    Err((e, ..)) => return Err(e),
                 }
 
                 lazy_expansions.pop();
             }
-            None => return Err(IrError::UndefinedDefinition(name)),
+            None => return Err(IrError::UndefinedDefinition(name.clone())),
         }
 
         Ok(())
     }
 
     /// Sets a definition
-    pub fn set_definition(&mut self, name: &'s str, content: Vec<IrToken<'s>>) {
+    pub fn set_definition(&mut self, name: SharedStr, content: Vec<IrToken>) {
         self.definitions.insert(name, Definition::Eager(content));
     }
 
     /// Sets a lazy definition
-    pub fn set_lazy_definition(&mut self, name: &'s str, content: Sir<'s>) {
+    pub fn set_lazy_definition(&mut self, name: SharedStr, content: Sir) {
         self.definitions.insert(name, Definition::Lazy(content));
     }
 
+    /// Gets the declared distinctive features
+    pub const fn features(&self) -> &Arc<FeatureTable> {
+        &self.features
+    }
+
+    /// Sets the values of features for each phone in a list
+    pub fn declare_features(&mut self, symbols: &[SharedStr], values: &[(SharedStr, bool)]) {
+        let features = Arc::make_mut(&mut self.features);
+
+        for symbol in symbols {
+            for (name, value) in values {
+                features.declare(symbol, name, *value);
+            }
+        }
+    }
+
+    /// Gets the current syllabifier
+    pub const fn syllabifier(&self) -> Option<&Arc<Syllabifier>> {
+        self.syllabifier.as_ref()
+    }
+
+    /// Sets the syllabifier used by syllable boundaries built after this call
+    pub fn set_syllabifier(&mut self, syllabifier: Option<Syllabifier>) {
+        self.syllabifier = syllabifier.map(Arc::new);
+    }
+
     /// Fetches the tokens associated with a variable's name
     /// 
     /// Returns an error if there is no variable of the given name
-    pub fn get_variable<'a>(&self, name: &'a str) -> Result<&Vec<IrToken<'s>>, IrError<'a>> {
+    pub fn get_variable(&self, name: &SharedStr) -> Result<&Vec<IrToken>, IrError> {
         self.variables
             .get(name)
-            .ok_or(IrError::UndefinedVariable(name))
+            .ok_or_else(|| IrError::UndefinedVariable(name.clone()))
     }
 
-    /// Tokenizes the source and leaks it to the static scope,
-    /// then assigns the tokens to the given name
-    /// 
-    /// # Warning
-    /// If `free_sources` is never called on this struct, the input will be leaked forever
-    pub fn set_variable_as_ir(&mut self, name: &'s str, source: String) -> Result<(), IrError<'s>> {
-        let source = self.add_source_string(source);
-
+    /// Tokenizes the source and assigns the tokens to the given name
+    pub fn set_variable_as_ir(&mut self, name: SharedStr, source: &str) -> Result<(), IrError> {
         let sir: Vec<SirToken> = Lexer::lex(source).collect();
 
         let escaped_end = sir.last().is_some_and(|token| matches!(token, SirToken::NonPhoneEscape('\n', _)));
 
-        let tokens = sir_expr_to_ir_line(sir, self, &mut Vec::new()).0?;
+        let (tokens, _) = sir_expr_to_ir_line(sir, self, &mut Vec::new()).0
+            .map_err(|(e, _)| e)?;
 
         if escaped_end {
             return Err(IrError::BadEscape(None));
@@ -109,98 +130,15 @@ impl<'s> TokenizationData<'s> {
         Ok(())
     }
 
-    /// Escapes the source then leaks it to the static scope,
-    /// then assigns the it as a list of phones to the name
-    /// 
-    /// # Warning
-    /// If `free_sources` is never called on this struct, the input will be leaked forever
-    pub fn set_variable(&mut self, name: &'s str, source: &str) {
-        let source = self.add_source_escaped(EscapedString::from(source));
+    /// Escapes the source and assigns it as a list of phones to the name
+    pub fn set_variable(&mut self, name: SharedStr, source: &str) {
+        let source = EscapedString::from(source);
 
         self.variables.insert(
             name,
-            build_phone_list(source).into_iter().map(IrToken::Phone).collect()
+            build_phone_list(source.as_escaped_str()).into_iter().map(IrToken::Phone).collect()
         );
     }
-
-    /// Leaks a source and adds it to the sources buffer
-    /// 
-    /// # Warning
-    /// If `free_sources` is never called on this struct, the source will be leaked forever
-    fn add_source_string<'a>(&mut self, mut source: String) -> &'a str {
-        // leaking and moving the source to the sources buffer allows variable to be redefined
-        // and prevents self reference, however, it may also cause memory leaks
-        source.shrink_to_fit();
-        let source = source.leak();
-        self.add_source(source);
-            
-        source
-    }
-
-    /// Leaks a source and adds it to the sources buffer
-    /// 
-    /// # Warning
-    /// If `free_sources` is never called on this struct, the source will be leaked forever
-    fn add_source_escaped<'a>(&mut self, mut source: EscapedString) -> EscapedStr<'a> {
-        // leaking and moving the source to the sources buffer allows variable to be redefined
-        // and prevents self reference, however, it may also cause memory leaks
-        source.shrink_to_fit();
-        let source = source.leak();
-        self.add_source(source.inner());
-            
-        source
-    }
-
-    /// Frees all variable sources and consumes the struct
-    /// 
-    /// # Safety
-    /// There should be no references remaining to any string in the sources buffer
-    pub unsafe fn free_sources(self) {
-        for source in self.sources {
-            let ptr = source.cast_mut();
-            unsafe { drop(Box::from_raw(ptr)); }
-        }
-    }
-
-    /// Adds a source to the sources buffer so it can be freed later
-    fn add_source(&mut self, source: &'s str) {
-        self.sources.push(std::ptr::from_ref(source));
-    }
-
-    /// Returns a reference to the sources buffer
-    /// 
-    /// # Warning
-    /// The internal `HashMap`s may contain references to data in the sources buffer
-    /// 
-    /// Do not free the sources until this struct is dropped
-    pub fn sources(&self) -> &[*const str] {
-        &self.sources
-    }
-
-    /// Takes the sources from another `TokenizationData`
-    /// 
-    /// # Safety
-    /// There may still be data referencing taken sources in `other`
-    /// 
-    /// `other` must be dropped before the sources are freed
-    pub unsafe fn take_sources_from(&mut self, other: &mut Self) {
-        self.sources.append(&mut other.sources);
-    }
-
-    /// Creates a new `TokenizationData` by cloning the definitions and variables,
-    /// but without copying or moving the source pointers
-    /// 
-    /// # Warning
-    /// The cloned `HashMap`s may contain references to data in the origional sources buffer
-    /// 
-    /// The new `TokenizationData` must be dropped before the origional sources can be freed
-    pub fn with_inserts(&self) -> Self {
-        Self {
-            definitions: self.definitions.clone(),
-            variables: self.variables.clone(),
-            sources: Vec::new(),
-        }
-    }
 }
 
 #[cfg(test)]
@@ -209,28 +147,26 @@ fn test_get_variable_to_multiple_phones() {
     use crate::{keywords::{BOUND_CHAR, ESCAPE_CHAR}, phones::Phone};
 
     let mut tokenization_data = TokenizationData::new();
-    tokenization_data.set_variable("name", &format!("ab cd e {BOUND_CHAR} fg\t\t{BOUND_CHAR}h"));
+    tokenization_data.set_variable("name".into(), &format!("ab cd e {BOUND_CHAR} fg\t\t{BOUND_CHAR}h"));
     
     assert_eq!(
-        tokenization_data.get_variable("name"),
+        tokenization_data.get_variable(&"name".into()),
         Ok(&vec![
-            IrToken::Phone(Phone::Symbol("a")),
-            IrToken::Phone(Phone::Symbol("b")),
+            IrToken::Phone(Phone::Symbol("a".into())),
+            IrToken::Phone(Phone::Symbol("b".into())),
             IrToken::Phone(Phone::Bound),
-            IrToken::Phone(Phone::Symbol("c")),
-            IrToken::Phone(Phone::Symbol("d")),
+            IrToken::Phone(Phone::Symbol("c".into())),
+            IrToken::Phone(Phone::Symbol("d".into())),
             IrToken::Phone(Phone::Bound),
-            IrToken::Phone(Phone::Symbol("e")),
+            IrToken::Phone(Phone::Symbol("e".into())),
             IrToken::Phone(Phone::Bound),
-            IrToken::Phone(Phone::Symbol(&format!("{ESCAPE_CHAR}{BOUND_CHAR}"))),
+            IrToken::Phone(Phone::Symbol(format!("{ESCAPE_CHAR}{BOUND_CHAR}").into())),
             IrToken::Phone(Phone::Bound),
-            IrToken::Phone(Phone::Symbol("f")),
-            IrToken::Phone(Phone::Symbol("g")),
+            IrToken::Phone(Phone::Symbol("f".into())),
+            IrToken::Phone(Phone::Symbol("g".into())),
             IrToken::Phone(Phone::Bound),
-            IrToken::Phone(Phone::Symbol(&format!("{ESCAPE_CHAR}{BOUND_CHAR}"))),
-            IrToken::Phone(Phone::Symbol("h")),
+            IrToken::Phone(Phone::Symbol(format!("{ESCAPE_CHAR}{BOUND_CHAR}").into())),
+            IrToken::Phone(Phone::Symbol("h".into())),
         ])
     );
-
-    unsafe { tokenization_data.free_sources() };
 }
\ No newline at end of file
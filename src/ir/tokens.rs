@@ -1,22 +1,25 @@
 use std::fmt::Display;
 
 use crate::{
-    keywords::{ANY_CHAR, ARG_SEP_CHAR, COND_CHAR, LABEL_PREFIX, NOT_CHAR},
+    keywords::{ANY_CHAR, ARG_SEP_CHAR, COND_CHAR, LABEL_PREFIX, NOT_CHAR, SYLLABLE_BOUND_CHAR},
     phones::Phone,
-    tokens::{AndType, CondType, ScopeType, Shift}
+    shared_str::SharedStr,
+    tokens::{AndType, CondType, FeatureSpec, ScopeType, Shift}
 };
 
 /// Tokens that make up the intermediate representation of sound shifts
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum IrToken<'s> {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IrToken {
     /// A phone literal
-    Phone(Phone<'s>),
+    Phone(Phone),
     /// The identifier of a scope's selection
-    Label(&'s str),
+    Label(SharedStr),
     /// A break between phone lists
     Break(Break),
     /// Any non bound phone
     Any,
+    /// A syllable boundary
+    SyllableBound,
     /// An item seperator for selection scopes
     ArgSep,
     /// The main focus and type of a condition or anti-condition
@@ -27,12 +30,15 @@ pub enum IrToken<'s> {
     ScopeEnd(ScopeType),
     /// Repetition negator
     Negative,
+    /// A distinctive feature specification in a feature matrix
+    Feature(FeatureSpec),
 }
 
-impl Display for IrToken<'_> {
+impl Display for IrToken {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Any => write!(f, "{ANY_CHAR}"),
+            Self::SyllableBound => write!(f, "{SYLLABLE_BOUND_CHAR}"),
             Self::ArgSep => write!(f, "{ARG_SEP_CHAR}"),
             Self::Break(r#break) => write!(f, "{break}"),
             Self::CondType(focus) => write!(f, "{focus}"),
@@ -41,6 +47,7 @@ impl Display for IrToken<'_> {
             Self::ScopeStart(kind) => write!(f, "{}", kind.start_char()),
             Self::Label(name) => write!(f, "{LABEL_PREFIX}{name}"),
             Self::Negative => write!(f, "{NOT_CHAR}"),
+            Self::Feature(spec) => write!(f, "{spec}"),
         }
     }
 }
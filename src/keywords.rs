@@ -52,15 +52,15 @@ const_list! {
     VARIABLE_PREFIX = '%';
 
     // Break charactes
-    /// The character for a left-to-right shift, may be duplicated
+    /// The character for a left-to-right shift, may be repeated up to three times
     LTR_CHAR = '>';
-    /// The character for a right-to-left shift, may be duplicated
+    /// The character for a right-to-left shift, may be repeated up to three times
     RTL_CHAR = '<';
     /// The character for a condition, may be duplicated for an anti-condition
     COND_CHAR = '/';
     /// The character for an and-clause in a condition
     AND_CHAR = '&';
-    /// The character for negating conditions and and-clauses
+    /// The character for negating conditions and and-clauses and for making a shift apply once per word
     NOT_CHAR = '!';
 
     // Scope Bounds
@@ -90,6 +90,8 @@ const_list! {
     ESCAPE_CHAR = '\\';
     /// A word boundary
     BOUND_CHAR = '#';
+    /// A syllable boundary
+    SYLLABLE_BOUND_CHAR = '.';
 }
 
 const_list! {
@@ -139,8 +141,33 @@ pub const LAZY_DEFINITION_LINE_START: &str = "DEFINE_LAZY";
 pub const PRINT_LINE_START: &str = "PRINT";
 pub const GET_LINE_START: &str = "GET";
 pub const GET_AS_CODE_LINE_START: &str = "GET_AS_CODE";
+pub const FEATURES_LINE_START: &str = "FEATURES";
+pub const SYLLABIFY_LINE_START: &str = "SYLLABIFY";
+pub const TEST_LINE_START: &str = "TEST";
 pub const COMMENT_LINE_START: &str = "##";
 
+// Strings that are only special in test statements
+/// Seperates the input and expected output of a test
+pub const TEST_SEP_STR: &str = "=>";
+
+// Characters that are only special at the start of a feature specification
+/// Marks a feature as present
+pub const FEATURE_PLUS_CHAR: char = '+';
+/// Marks a feature as absent
+pub const FEATURE_MINUS_CHAR: char = '-';
+
+// Suprasegmental marks on phones
+/// Marks the following phone as having primary stress
+pub const PRIMARY_STRESS_CHAR: char = 'ˈ';
+/// Marks the following phone as having secondary stress
+pub const SECONDARY_STRESS_CHAR: char = 'ˌ';
+/// Marks the preceding phone as long
+pub const LONG_CHAR: char = 'ː';
+/// Marks the preceding phone as half-long
+pub const HALF_LONG_CHAR: char = 'ˑ';
+/// Tone letters and numbers that make up the tone of the preceding phone
+pub const TONE_CHARS: [char; 15] = ['˥', '˦', '˧', '˨', '˩', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹', '⁰'];
+
 /// Converts a `&char` to `&str`
 pub const fn char_to_str(c: &char) -> &str {
     let ptr = std::ptr::from_ref(c).cast::<u8>();
@@ -1,9 +1,10 @@
 use crate::{
     ir::{prefix::Prefix, tokens::Break},
     keywords::{
-        AND_CHAR, ANY_CHAR, ARG_SEP_CHAR, BOUND_CHAR, COMMENT_LINE_START, COND_CHAR, DEFINITION_LINE_START, DEFINITION_PREFIX, ESCAPE_CHAR, GET_AS_CODE_LINE_START, GET_LINE_START, INPUT_PATTERN_STR, LABEL_PREFIX, LAZY_DEFINITION_LINE_START, LTR_CHAR, MATCH_CHAR, NOT_CHAR, OPTIONAL_END_CHAR, OPTIONAL_START_CHAR, PRINT_LINE_START, REPETITION_END_CHAR, REPETITION_START_CHAR, RTL_CHAR, SELECTION_END_CHAR, SELECTION_START_CHAR, VARIABLE_PREFIX, is_isolated_char, is_isolation_bound, is_special_char, is_special_str
+        AND_CHAR, ANY_CHAR, ARG_SEP_CHAR, BOUND_CHAR, COMMENT_LINE_START, COND_CHAR, DEFINITION_LINE_START, DEFINITION_PREFIX, ESCAPE_CHAR, FEATURES_LINE_START, GET_AS_CODE_LINE_START, GET_LINE_START, INPUT_PATTERN_STR, LABEL_PREFIX, LAZY_DEFINITION_LINE_START, LTR_CHAR, MATCH_CHAR, NOT_CHAR, OPTIONAL_END_CHAR, OPTIONAL_START_CHAR, PRINT_LINE_START, REPETITION_END_CHAR, REPETITION_START_CHAR, RTL_CHAR, SELECTION_END_CHAR, SELECTION_START_CHAR, SYLLABIFY_LINE_START, SYLLABLE_BOUND_CHAR, TEST_LINE_START, VARIABLE_PREFIX, is_isolated_char, is_isolation_bound, is_special_char, is_special_str
     },
     lexer::{sir::SirToken, substring::Substring, token_types::{PhoneValidStr, Span}},
+    shared_str::SharedStr,
     tokens::{AndType, CondType, Direction, ScopeType, Shift, ShiftType}
 };
 
@@ -15,15 +16,15 @@ mod tests;
 
 /// An `Iterator` over the Scoped Intermediate Representation of the source code
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Sir<'s> {
+pub struct Sir {
     line: usize,
-    tokens: Vec<SirToken<'s>>,
+    tokens: Vec<SirToken>,
 }
 
-impl<'s> Sir<'s> {
+impl Sir {
     /// Creates a new SIR iterator from a list of `SirTokens`
     #[must_use]
-    pub fn new(mut sir: Vec<SirToken<'s>>) -> Self {
+    pub fn new(mut sir: Vec<SirToken>) -> Self {
         sir.reverse();
 
         Self {
@@ -52,8 +53,8 @@ impl<'s> Sir<'s> {
     }
 }
 
-impl<'s> Iterator for Sir<'s> {
-    type Item = SirToken<'s>;
+impl Iterator for Sir {
+    type Item = SirToken;
 
     fn next(&mut self) -> Option<Self::Item> {
         let token = self.tokens.pop();
@@ -69,9 +70,10 @@ impl<'s> Iterator for Sir<'s> {
 /// A struct from parsing rules into SIR
 #[derive(Debug)]
 pub struct Lexer<'s> {
+    source: &'s SharedStr,
     accumulator: Substring<'s>,
     prefix: Option<Prefix>,
-    tokens: Vec<SirToken<'s>>,
+    tokens: Vec<SirToken>,
 }
 
 /// A helper macro for pushing single-character tokens
@@ -88,14 +90,15 @@ macro_rules! push_char_token {
 
 impl<'s> Lexer<'s> {
     /// Creates a new `Lexer` from rules
-    fn new(rules: &'s str) -> Self {
-        Self { accumulator: Substring::new(rules), prefix: None, tokens: Vec::new() }
+    fn new(source: &'s SharedStr) -> Self {
+        Self { source, accumulator: Substring::new(source), prefix: None, tokens: Vec::new() }
     }
 
     /// Converts rules into SIR
     #[must_use]
-    pub fn lex(rules: &'s str) -> Sir<'s> {
-        let mut lexer = Self::new(rules);
+    pub fn lex(rules: &str) -> Sir {
+        let source = SharedStr::from(rules);
+        let mut lexer = Lexer::new(&source);
 
         while !lexer.accumulator.is_exhausted() {
             lexer.partial_parse_token();
@@ -134,6 +137,7 @@ impl<'s> Lexer<'s> {
                 ANY_CHAR => push_char_token!(self <- Any),
                 ARG_SEP_CHAR => push_char_token!(self <- ArgSep),
                 BOUND_CHAR => push_char_token!(self <- Bound),
+                SYLLABLE_BOUND_CHAR => push_char_token!(self <- SyllableBound),
                 MATCH_CHAR => push_char_token!(self <- CondFocus(CondType::Match)),
                 // handles compoundable characters
                 NOT_CHAR if empty_acc => match self.tokens.last_mut() {
@@ -147,33 +151,42 @@ impl<'s> Lexer<'s> {
                         span.lengthen(c);
                         self.accumulator.skip_char();
                     },
+                    Some(SirToken::Break(Break::Shift(Shift { once: once @ false, .. }), span)) => {
+                        *once = true;
+                        span.lengthen(c);
+                        self.accumulator.skip_char();
+                    },
                     _ => push_char_token!(self <- Negative),
                 },
                 NOT_CHAR => push_char_token!(self <- Negative),
                 LTR_CHAR => if empty_acc && let Some(SirToken::Break(Break::Shift(Shift {
                     dir: Direction::Ltr,
-                    kind: kind @ ShiftType::Stay
+                    kind: kind @ (ShiftType::Stay | ShiftType::Move),
+                    once: false,
                 }), span)) = self.tokens.last_mut() {
-                    *kind = ShiftType::Move;
+                    *kind = if *kind == ShiftType::Stay { ShiftType::Move } else { ShiftType::Simultaneous };
                     span.lengthen(c);
                     self.accumulator.skip_char();
                 } else {
                     push_char_token!(self <- Break(Break::Shift(Shift {
                         dir: Direction::Ltr,
-                        kind: ShiftType::Stay
+                        kind: ShiftType::Stay,
+                        once: false,
                     })));
                 },
                 RTL_CHAR => if empty_acc && let Some(SirToken::Break(Break::Shift(Shift {
                     dir: Direction::Rtl,
-                    kind: kind @ ShiftType::Stay
+                    kind: kind @ (ShiftType::Stay | ShiftType::Move),
+                    once: false,
                 }), span)) = self.tokens.last_mut() {
-                    *kind = ShiftType::Move;
+                    *kind = if *kind == ShiftType::Stay { ShiftType::Move } else { ShiftType::Simultaneous };
                     span.lengthen(c);
                     self.accumulator.skip_char();
                 } else {
                     push_char_token!(self <- Break(Break::Shift(Shift {
                         dir: Direction::Rtl,
-                        kind: ShiftType::Stay
+                        kind: ShiftType::Stay,
+                        once: false,
                     })));
                 },
                 COND_CHAR => if empty_acc && let Some(SirToken::Break(b @ Break::Cond, span)) = self.tokens.last_mut() {
@@ -223,6 +236,24 @@ impl<'s> Lexer<'s> {
             return true;
         }
         
+        // handles feature declarations
+        if rest.starts_with(FEATURES_LINE_START) {
+            self.accumulator.grow_by(FEATURES_LINE_START.len());
+            self.tokens.push(SirToken::FeaturesDeclaration(self.accumulator.span()));
+            _ = self.accumulator.pass();
+
+            return true;
+        }
+        
+        // handles syllabification declarations
+        if rest.starts_with(SYLLABIFY_LINE_START) {
+            self.accumulator.grow_by(SYLLABIFY_LINE_START.len());
+            self.tokens.push(SirToken::SyllabifyDeclaration(self.accumulator.span()));
+            _ = self.accumulator.pass();
+
+            return true;
+        }
+        
         // handles logging statements
         if rest.starts_with(PRINT_LINE_START) {
             self.accumulator.grow_by(PRINT_LINE_START.len());
@@ -230,7 +261,19 @@ impl<'s> Lexer<'s> {
             _ = self.accumulator.pass();
 
             let (msg, span) = self.rest_of_line_as_str();
-            self.tokens.push(SirToken::Message(msg.trim_start(), span));
+            self.tokens.push(SirToken::Message(self.source.share(msg.trim_start()), span));
+
+            return true;
+        }
+        
+        // handles test statements
+        if rest.starts_with(TEST_LINE_START) {
+            self.accumulator.grow_by(TEST_LINE_START.len());
+            self.tokens.push(SirToken::TestCommand(self.accumulator.span()));
+            _ = self.accumulator.pass();
+
+            let (msg, span) = self.rest_of_line_as_str();
+            self.tokens.push(SirToken::Message(self.source.share(msg.trim_start()), span));
 
             return true;
         }
@@ -243,7 +286,7 @@ impl<'s> Lexer<'s> {
 
             if self.parse_phone() {
                 let (msg, span) = self.rest_of_line_as_str();
-                self.tokens.push(SirToken::Message(msg.trim_start(), span));
+                self.tokens.push(SirToken::Message(self.source.share(msg.trim_start()), span));
             }
 
             return true
@@ -257,7 +300,7 @@ impl<'s> Lexer<'s> {
 
             if self.parse_phone() {
                 let (msg, span) = self.rest_of_line_as_str();
-                self.tokens.push(SirToken::Message(msg.trim_start(), span));
+                self.tokens.push(SirToken::Message(self.source.share(msg.trim_start()), span));
             }
 
             return true;
@@ -361,7 +404,7 @@ impl<'s> Lexer<'s> {
         } else {
             self.accumulator = forward_acc;
             self.tokens.push(SirToken::Phone(PhoneValidStr::new(
-                s,
+                self.source.share(s),
                 self.accumulator.line(),
                 self.accumulator.char(),
                 self.accumulator.start_index()
@@ -401,7 +444,7 @@ impl<'s> Lexer<'s> {
                 self.tokens.push(SirToken::InvalidPrefix(prefix, Span::new(line, char, index, len)));
             }
         } else {
-            let fvs = PhoneValidStr::new_with_len(s, line, char, index, len);
+            let fvs = PhoneValidStr::new_with_len(self.source.share(s), line, char, index, len);
 
             let token = match self.prefix {
                 Some(Prefix::Definition) => SirToken::Definition(fvs),
@@ -1,24 +1,25 @@
 use crate::{
     ir::{prefix::Prefix, tokens::Break},
     lexer::token_types::{PhoneValidStr, Span},
+    shared_str::SharedStr,
     tokens::{CondType, ScopeType}
 };
 
 /// A Scoped Intermediate Representation token
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum SirToken<'s> {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SirToken {
     /// A phone
-    Phone(PhoneValidStr<'s>),
+    Phone(PhoneValidStr),
     /// A definition insertion
-    Definition(PhoneValidStr<'s>),
+    Definition(PhoneValidStr),
     /// A label
-    Label(PhoneValidStr<'s>),
+    Label(PhoneValidStr),
     /// A variable insertion
-    Variable(PhoneValidStr<'s>),
+    Variable(PhoneValidStr),
     /// A prefix that does not start a name
     InvalidPrefix(Prefix, Span),
     /// A phone that isn't valid as a phone
-    InvalidPhone(PhoneValidStr<'s>),
+    InvalidPhone(PhoneValidStr),
     /// An escaped character that does not result in part of a phone or name
     NonPhoneEscape(char, Span),
     /// A shift or condition segmenting token
@@ -35,30 +36,37 @@ pub enum SirToken<'s> {
     ArgSep(Span),
     /// A word boundary character
     Bound(Span),
+    /// A syllable boundary character
+    SyllableBound(Span),
     /// The start of a repetition exclusion
     Negative(Span),
     /// The start of a definition declaration
     DefinitionDeclaration(Span),
     /// The start of a lazy definition declaration
     LazyDefinitionDeclaration(Span),
+    /// The start of a feature declaration
+    FeaturesDeclaration(Span),
+    /// The start of a syllabification declaration
+    SyllabifyDeclaration(Span),
     /// The start of a get statement
     GetCommand(Span),
     /// The start of a get as code statement
     GetAsCodeCommand(Span),
     /// The start of a print statement
     PrintCommand(Span),
+    /// The start of a test statement
+    TestCommand(Span),
     /// A comment
     Comment(Span),
     /// A printable message
-    Message(&'s str, Span),
+    Message(SharedStr, Span),
     /// Whitespace
     Whitespace(Span),
     /// The end of an unescaped line
     EndOfExpr(Span),
 }
 
-#[cfg(feature = "debug_tokens")]
-impl SirToken<'_> {
+impl SirToken {
     /// Gets the location data for a token
     #[must_use]
     pub fn span(&self) -> &Span {
@@ -70,10 +78,11 @@ impl SirToken<'_> {
             | Self::Break(_, s) | Self::CondFocus(_, s)
             | Self::ScopeStart(_, s) | Self::ScopeEnd(_, s)
             | Self::Negative(s) | Self::Any(s)
-            | Self::ArgSep(s) | Self::Bound(s)
+            | Self::ArgSep(s) | Self::Bound(s) | Self::SyllableBound(s)
             | Self::DefinitionDeclaration(s) | Self::LazyDefinitionDeclaration(s)
+            | Self::FeaturesDeclaration(s) | Self::SyllabifyDeclaration(s)
             | Self::GetCommand(s) | Self::GetAsCodeCommand(s)
-            | Self::PrintCommand(s) | Self::Comment(s)
+            | Self::PrintCommand(s) | Self::TestCommand(s) | Self::Comment(s)
             | Self::Whitespace(s) | Self::EndOfExpr(s)
             | Self::Message(_, s) => s
         }
@@ -1,46 +1,25 @@
+use crate::shared_str::SharedStr;
+
 /// A struct that contains the information for where in a string a token occurs
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Span {
-    #[cfg(feature = "debug_tokens")]
     index: usize,
-    #[cfg(feature = "debug_tokens")]
     line: usize,
-    #[cfg(feature = "debug_tokens")]
     char: usize,
-    #[cfg(feature = "debug_tokens")]
     len: usize,
 }
 
 impl Span {
-    #[cfg(feature = "debug_tokens")]
     /// Creates a new `Span`
     pub(super) const fn new(line: usize, char: usize, index: usize, len: usize) -> Self {
         Self { index, line, char, len }
-        
     }
 
-    #[cfg(not(feature = "debug_tokens"))]
-    /// Creates a new `Span`
-    #[inline]
-    pub(super) const fn new(_: usize, _: usize, _: usize, _: usize) -> Self {
-        Self { }
-    }
-
-    #[cfg(feature = "debug_tokens")]
     /// Extends the span's length by one
     pub(super) const fn lengthen(&mut self, c: char) {
         self.len += c.len_utf8();
     }
 
-    #[cfg(not(feature = "debug_tokens"))]
-    #[allow(clippy::unused_self)]
-    /// Extends the span's length by one
-    #[inline]
-    pub(super) const fn lengthen(&mut self, _: char) { }
-}
-
-#[cfg(feature = "debug_tokens")]
-impl Span {
     /// Gets the line the span starts on (zero indexed)
     #[must_use]
     pub const fn line(&self) -> usize { self.line }
@@ -59,18 +38,19 @@ impl Span {
 }
 
 /// A scoped string that may be a phone or prefixed name
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct PhoneValidStr<'s> {
-    str: &'s str,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhoneValidStr {
+    str: SharedStr,
     span: Span
 }
 
-impl<'s> PhoneValidStr<'s> {
-    pub(super) const fn new(s: &'s str, line: usize, char: usize, index: usize) -> Self {
-        Self::new_with_len(s, line, char, index, s.len())
+impl PhoneValidStr {
+    pub(super) fn new(s: SharedStr, line: usize, char: usize, index: usize) -> Self {
+        let len = s.len();
+        Self::new_with_len(s, line, char, index, len)
     }
 
-    pub(super) const fn new_with_len(s: &'s str, line: usize, char: usize, index: usize, len: usize) -> Self {
+    pub(super) const fn new_with_len(s: SharedStr, line: usize, char: usize, index: usize, len: usize) -> Self {
         Self {
             str: s,
             span: Span::new(line, char, index, len),
@@ -79,9 +59,8 @@ impl<'s> PhoneValidStr<'s> {
     
     /// Gets the string
     #[must_use]
-    pub const fn str(&self) -> &'s str { self.str }
+    pub const fn str(&self) -> &SharedStr { &self.str }
 
-    #[cfg(feature = "debug_tokens")]
     /// Gets the span of the phone or name
     #[must_use]
     pub const fn span(&self) -> &Span { &self.span }
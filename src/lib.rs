@@ -1,9 +1,11 @@
 #![doc = include_str!("../README.md")]
 
-use std::{error::Error, num::NonZero};
+use std::{error::Error, num::NonZero, ops::Range};
 
 #[cfg(feature = "debug_tokens")]
-pub use lexer::{Lexer, Sir, sir::SirToken, token_types::{PhoneValidStr, Span}};
+pub use {lexer::{Lexer, Sir, sir::SirToken, token_types::{PhoneValidStr, Span}}, shared_str::SharedStr};
+#[cfg(not(feature = "debug_tokens"))]
+use lexer::token_types::Span;
 
 mod lexer;
 mod ir;
@@ -15,6 +17,7 @@ mod tokens;
 mod applier;
 mod matcher;
 mod escaped_strings;
+mod shared_str;
 mod executor;
 mod keywords;
 mod io_macros;
@@ -24,8 +27,39 @@ pub use crate::{
         LineByLineExecutor,
         appliable_rules::{
             build_rules,
+            build_rules_recovering,
             AppliableRules,
         },
+        derivation::{
+            Derivation,
+            DerivationStep,
+            RuleChange,
+        },
+        expansion::ExpandedRule,
+        check::check_rules,
+        lint::{
+            lint_rules,
+            LintKind,
+            LintWarning,
+        },
+        analysis::{
+            highlight_rules,
+            find_declaration,
+            expand_definition_at,
+            Highlight,
+            HighlightKind,
+        },
+        format::format_rules,
+        notation::{
+            render_rules,
+            Notation,
+            RenderedRule,
+        },
+        rule_tests::RuleTestResult,
+        statistics::{
+            RuleStatistics,
+            RuleStats,
+        },
         runtime::{
             Runtime,
             ContextRuntime,
@@ -37,6 +71,7 @@ pub use crate::{
             ContextIoGetter,
         },
     },
+    phones::split_phones,
 };
 
 use io_macros::{
@@ -81,16 +116,196 @@ impl std::fmt::Display for ScaErrorType {
     }
 }
 
+/// The specific cause of an error, with any details of the cause as data
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScaErrorKind {
+    /// A lazy definition is used within itself
+    RecursiveLazyDefinition {
+        /// The definition's name
+        name: String,
+    },
+    /// A prefix is not followed by a name
+    EmptyPrefix {
+        /// The prefix's character
+        prefix: char,
+    },
+    /// A definition is used before it is defined
+    UndefinedDefinition {
+        /// The definition's name
+        name: String,
+    },
+    /// A variable is used before it is set
+    UndefinedVariable {
+        /// The variable's name
+        name: String,
+    },
+    /// A definition statement does not have a name
+    UnnamedDefinition,
+    /// An escape is followed by a character that does not need to be escaped or by nothing
+    BadEscape {
+        /// The escaped character
+        escaped: Option<char>,
+    },
+    /// A `GET` or `GET_AS_CODE` statement is not followed by a variable name
+    InvalidGetFormat {
+        /// The statement's keyword
+        command: String,
+    },
+    /// A string cannot be a phone, label, or name
+    InvalidPhone {
+        /// The invalid string
+        phone: String,
+    },
+    /// A `FEATURES` statement is not phones followed by a feature matrix
+    InvalidFeaturesFormat,
+    /// A built-in feature is declared
+    ReservedFeature {
+        /// The feature's name
+        name: String,
+    },
+    /// A `TEST` statement is not an input and expected output
+    InvalidTestFormat,
+    /// A statement appears where it cannot be used
+    InvalidStatement,
+    /// An argument seperator is outside of a selection scope
+    ArgSepOutOfSelection,
+    /// A condition, anti-condition, or and occurs before a shift
+    BreakWithoutShift {
+        /// The break
+        token: String,
+    },
+    /// A label is not followed by a scope
+    LabelNotFollowedByScope {
+        /// The label's name
+        label: String,
+    },
+    /// A rule does not contain a shift
+    NoShift,
+    /// A scope is never closed
+    UnclosedScope {
+        /// The character that opens the scope
+        start: char,
+    },
+    /// A scope is closed without being opened
+    UnopenedScope {
+        /// The character that closes the scope
+        end: char,
+    },
+    /// A scope is closed by the end of a different kind of scope
+    MismatchedScopeBounds {
+        /// The character that opens the scope
+        start: char,
+        /// The character that closes the scope
+        end: char,
+    },
+    /// A token appears where it cannot be used
+    UnexpectedToken {
+        /// The token
+        token: String,
+    },
+    /// A condition does not contain an input pattern or equality
+    NoConditionFocus,
+    /// An and is not part of a condition or anti-condition
+    AndDoesNotFollowCond {
+        /// The and
+        token: String,
+    },
+    /// A rule contains a second shift that cannot form a chain shift
+    SecondShift {
+        /// The second shift
+        shift: String,
+    },
+    /// An input pattern or equality is outside of a condition or is repeated
+    UnexpectedCondType {
+        /// The input pattern or equality
+        token: String,
+    },
+    /// A repetition is outside of a condition or anti-condition
+    RepetitionOutOfCond,
+    /// A repetition does not contain an inclusive pattern
+    EmptyRepetition,
+    /// A repetition exclusion does not contain a pattern
+    EmptyExclusion,
+    /// A feature is used without being declared
+    UndefinedFeature {
+        /// The feature's name
+        name: String,
+    },
+    /// A syllable boundary is used before syllables are declared
+    NoSyllabifier,
+    /// A syllable boundary is in an output
+    SyllableBoundInOutput,
+    /// A `SYLLABIFY` statement is not onset, nucleus, and coda patterns
+    InvalidSyllabifyFormat,
+    /// A chain shift contains different shifts
+    MismatchedChainShift {
+        /// The shift that starts the chain
+        first: String,
+        /// The shift that differs from the first
+        second: String,
+    },
+    /// A link of a chain shift is empty
+    EmptyChainLink,
+    /// A pattern in an output does not correspond to a pattern in the input or conditions
+    UnmatchedTokenInOutput {
+        /// The pattern
+        pattern: String,
+    },
+    /// A selection scope has fewer options than it was matched with
+    InvalidSelectionAccess {
+        /// The selection scope
+        scope: String,
+        /// The index of the missing option (zero indexed)
+        element: usize,
+    },
+    /// A line took too long to apply
+    ExceededLimit {
+        /// The limit that was exceeded
+        limit: LineApplicationLimit,
+    },
+    /// A pattern in an output cannot be converted to phones
+    PatternCannotBeConvertedToPhones {
+        /// The pattern
+        pattern: String,
+    },
+    /// A feature matrix changes a phone to one that has not been declared
+    NoPhoneWithFeatures {
+        /// The phone being changed
+        phone: String,
+        /// The feature matrix
+        pattern: String,
+    },
+    /// An alpha variable is used in an output before it is set
+    UnsetAlphaVariable {
+        /// The feature specification with the variable
+        spec: String,
+    },
+    /// Fetching input or logging output failed
+    Io {
+        /// The error message from the `IoGetter` or `Runtime`
+        message: String,
+    },
+}
+
+/// An internal error that can be converted to an `ScaErrorKind`
+trait ToErrorKind {
+    /// Gets the kind of the error
+    fn error_kind(&self) -> ScaErrorKind;
+}
+
 /// An error generated by the building or application of sound change rules
 /// or by IO fetched during either process
 #[cfg_attr(test, derive(PartialEq))]
 #[derive(Debug)]
 pub struct ScaError {
     err: String,
+    kind: ScaErrorKind,
     rule: String,
     line_num: NonZero<usize>,
     line_count: NonZero<usize>,
     error_type: ScaErrorType, 
+    span: Option<ErrorSpan>,
 }
 
 impl Error for ScaError {}
@@ -130,6 +345,69 @@ impl ScaError {
     pub const fn error_type(&self) -> ScaErrorType {
         self.error_type
     }
+
+    /// Gets the specific cause of the error
+    #[must_use]
+    #[inline]
+    pub const fn kind(&self) -> &ScaErrorKind {
+        &self.kind
+    }
+
+    /// Gets the location of the token that caused the error,
+    /// if the error was caused by a single token
+    #[must_use]
+    #[inline]
+    pub const fn span(&self) -> Option<&ErrorSpan> {
+        self.span.as_ref()
+    }
+}
+
+/// The location of the token that caused an `ScaError`
+#[cfg_attr(test, derive(PartialEq))]
+#[derive(Debug, Clone)]
+pub struct ErrorSpan {
+    line_num: NonZero<usize>,
+    bytes: Range<usize>,
+    columns: Range<usize>,
+}
+
+impl ErrorSpan {
+    /// Creates an `ErrorSpan` from the span of a token and the line it starts on,
+    /// returns `None` if the span is not within the line
+    fn new(span: Span, line: &str) -> Option<Self> {
+        let start = line.char_indices().nth(span.char()).map_or(line.len(), |(i, _)| i);
+        let len = line.get(start..start + span.len())?.chars().count();
+
+        Some(Self {
+            line_num: NonZero::new(span.line() + 1)?,
+            bytes: span.index()..span.index() + span.len(),
+            columns: span.char()..span.char() + len,
+        })
+    }
+
+    /// Gets the number of the line the token starts on
+    #[must_use]
+    #[inline]
+    pub const fn line_number(&self) -> NonZero<usize> {
+        self.line_num
+    }
+
+    /// Gets the range of bytes the token occupies in the rules
+    /// 
+    /// For rules extended with `AppliableRules::extend`, this is in all of the rules joined with newlines,
+    /// as returned by `AppliableRules::get_rules`
+    #[must_use]
+    #[inline]
+    pub fn bytes(&self) -> Range<usize> {
+        self.bytes.clone()
+    }
+
+    /// Gets the range of characters the token occupies in its line (zero indexed)
+    #[must_use]
+    #[inline]
+    pub fn columns(&self) -> Range<usize> {
+        self.columns.clone()
+    }
 }
 
 impl std::fmt::Display for ScaError {
@@ -154,9 +432,11 @@ impl std::fmt::Display for ScaError {
 #[derive(Debug)]
 struct RulelessScaError {
     err: String,
+    kind: ScaErrorKind,
     line_num: NonZero<usize>,
     line_count: NonZero<usize>,
     error_type: ScaErrorType, 
+    span: Option<Span>,
 }
 
 impl Error for RulelessScaError {}
@@ -164,36 +444,59 @@ impl Error for RulelessScaError {}
 impl RulelessScaError {
     /// Converts into an `ScaError`, getting the rule from an iterator over the lines of the origional rule input
     fn into_sca_error<'a>(self, lines: impl Iterator<Item = &'a str>) -> ScaError {
-        let rule = lines
+        let lines = lines
             .skip(self.line_num.get() - 1)
             .take(self.line_count.get())
-            .collect::<Vec<_>>()
-            .join("\n");
+            .collect::<Vec<_>>();
+
+        // spans of tokens outside of the rule (such as those from `GET` input) are discarded
+        let span = self.span.and_then(|span| {
+            let line = lines.get(span.line().checked_sub(self.line_num.get() - 1)?)?;
+            ErrorSpan::new(span, line)
+        });
 
         ScaError {
             err: self.err,
-            rule,
+            kind: self.kind,
+            rule: lines.join("\n"),
             line_num: self.line_num,
             line_count: self.line_count,
             error_type: self.error_type,
+            span,
         }
     }
 
+    /// Sets the span of the token that caused the error
+    #[inline]
+    const fn with_span(mut self, span: Option<Span>) -> Self {
+        self.span = span;
+        self
+    }
+
     /// Builds a new `RulelessScaError` from any error,
     /// with the line and line number it occurred on
     #[inline]
-    fn from_error<E: Error + ?Sized>(e: &E, error_type: ScaErrorType, line_num: NonZero<usize>, line_count: NonZero<usize>) -> Self {
-        Self::from_error_message(e.to_string(), error_type, line_num, line_count)
+    fn from_error<E: Error + ToErrorKind>(e: &E, error_type: ScaErrorType, line_num: NonZero<usize>, line_count: NonZero<usize>) -> Self {
+        Self {
+            err: e.to_string(),
+            kind: e.error_kind(),
+            line_num,
+            line_count,
+            error_type,
+            span: None,
+        }
     }
 
-    /// Builds a new `RulelessScaError` from an error string,
+    /// Builds a new `RulelessScaError` from an io error string,
     /// with the line and line number it occurred on
     fn from_error_message(msg: String, error_type: ScaErrorType, line_num: NonZero<usize>, line_count: NonZero<usize>) -> Self {
         Self {
+            kind: ScaErrorKind::Io { message: msg.clone() },
             err: msg,
             line_num,
             line_count,
             error_type,
+            span: None,
         }
     }
 }
@@ -5,20 +5,30 @@
 #[cfg(feature = "async_io")]
 compile_error! { "binary cannot be compiled with the feature flag `async_io`" }
 
-use std::{fs, fmt::Write as _};
+use std::{fs, fmt::Write as _, num::NonZero};
 
 mod cli_tools;
 
 use cli_tools::{
-    ansi::{BLUE, BOLD, GREEN, RED, RESET, YELLOW},
+    ansi::{BLUE, BOLD, GREEN, MAGENTA, RED, RESET, YELLOW},
     cli_parser::{MapData, MapType, CliCommand, InputType, OutputData},
     cli_io::{CliGetter, LogAndPrintRuntime},
+    diff::{PhoneDiff, diff_phones},
+    lsp::LanguageServer,
 };
 
 const APPLY_CMD: &str = "sca";
 const CHAR_HELP_CMD: &str = "chars";
+const CHECK_CMD: &str = "check";
+const EXPAND_CMD: &str = "expand";
+const FMT_CMD: &str = "fmt";
 const HELP_CMD: &str = "help";
+const LINT_CMD: &str = "lint";
+const LSP_CMD: &str = "lsp";
 const NEW_CMD: &str = "new";
+const RENDER_CMD: &str = "render";
+const TEST_CMD: &str = "test";
+const VERIFY_CMD: &str = "verify";
 const FILE_EXTENTION: &str = ".sca";
 
 /// Reads the command line arguments and acts upon them
@@ -26,8 +36,8 @@ const FILE_EXTENTION: &str = ".sca";
 /// See `README.md` for more information
 fn main() {
     match CliCommand::from_args() {
-        Ok(CliCommand::Apply { paths, output_data, input }) => {
-            if let Err(e) = run_apply(&paths, &output_data, input) {
+        Ok(CliCommand::Apply { paths, output_data, input, jobs }) => {
+            if let Err(e) = run_apply(&paths, &output_data, input, jobs) {
                 println!("{e}");
             }
         },
@@ -43,6 +53,63 @@ fn main() {
                 println!("{RED}Error: {RESET}An error occured when writing to {BLUE}{path}{RESET}");
             }
         },
+        Ok(CliCommand::Check { path }) => match run_check(&path) {
+            Ok(true) => (),
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                println!("{e}");
+                std::process::exit(1);
+            },
+        },
+        Ok(CliCommand::Expand { path }) => match run_expand(&path) {
+            Ok(true) => (),
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                println!("{e}");
+                std::process::exit(1);
+            },
+        },
+        Ok(CliCommand::Fmt { check, path }) => match run_fmt(&path, check) {
+            Ok(true) => (),
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                println!("{e}");
+                std::process::exit(1);
+            },
+        },
+        Ok(CliCommand::Lint { path }) => if let Err(e) = run_lint(&path) {
+            println!("{e}");
+            std::process::exit(1);
+        },
+        // the exit code tells the client whether the server was shut down before exiting
+        Ok(CliCommand::Lsp) => match LanguageServer::default().run() {
+            Ok(true) => (),
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("{RED}Error: {RESET}{e}");
+                std::process::exit(1);
+            },
+        },
+        Ok(CliCommand::Render { notation, path }) => if let Err(e) = run_render(&path, notation) {
+            println!("{e}");
+            std::process::exit(1);
+        },
+        Ok(CliCommand::Test { path }) => match run_tests(&path) {
+            Ok(true) => (),
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                println!("{e}");
+                std::process::exit(1);
+            },
+        },
+        Ok(CliCommand::Verify { path, lexicon }) => match run_verify(&path, &lexicon) {
+            Ok(true) => (),
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                println!("{e}");
+                std::process::exit(1);
+            },
+        },
         Ok(CliCommand::Help { extra_args }) => {
             if extra_args {
                 println!("{YELLOW} Warning: {RESET}Arguments beyond '{BOLD}{HELP_CMD}{RESET}' do nothing");
@@ -61,6 +128,7 @@ fn main() {
 #[derive(Debug)]
 enum CliError {
     CouldNotWrite(String),
+    InvalidLexiconLine(String, usize),
     NoFile(String),
     NoInput,
 }
@@ -73,6 +141,7 @@ impl std::fmt::Display for CliError {
 
         match self {
             Self::CouldNotWrite(path) => write!(f, "Could not write to file: '{BLUE}{path}{RESET}'"),
+            Self::InvalidLexiconLine(path, line_num) => write!(f, "Line {line_num} of '{BLUE}{path}{RESET}' is not an input and expected output seperated by a tab"),
             Self::NoInput => write!(f, "No input phones or source provided"),
             Self::NoFile(path) => write!(f, "Could not find file: '{BLUE}{path}{RESET}'"),
         }
@@ -80,7 +149,7 @@ impl std::fmt::Display for CliError {
 }
 
 /// Applies changes to every input from CLI data
-fn run_apply(paths: &[String], output_data: &OutputData, input_type: InputType) -> Result<(), CliError> {
+fn run_apply(paths: &[String], output_data: &OutputData, input_type: InputType, jobs: NonZero<usize>) -> Result<(), CliError> {
     // gets the initial input
     let input = match input_type {
         InputType::Raw(raw) if raw.is_empty() => return Err(CliError::NoInput),
@@ -95,7 +164,8 @@ fn run_apply(paths: &[String], output_data: &OutputData, input_type: InputType)
     let mut full_output = String::new();
 
     // determines if rules should be pre-built or line-by-line use interpretation
-    let build = input.contains('\n');
+    // statistics are only collected for pre-built rules
+    let build = input.contains('\n') || output_data.stats();
 
     // gets each rule set in the chain
     let rule_sets = paths.iter()
@@ -114,21 +184,30 @@ fn run_apply(paths: &[String], output_data: &OutputData, input_type: InputType)
                 },
             };
 
-        // applies each rule set in the rules chain to each line of the input
-        for input in input.lines() {
-            let line_output = match apply_rule_sets(paths, output_data, &appliable_rule_sets, input.to_string()) {
-                Ok(out) => {
-                    println!("{out}");
-                    out
-                },
-                Err(e) => {
-                    print_error(&e);
-                    format!("{e}")
-                },
-            };
+        let mut statistics = output_data.stats().then(|| appliable_rule_sets.iter()
+            .map(cscsca::RuleStatistics::new)
+            .collect::<Vec<_>>());
 
-            // records the output
-            _ = writeln!(full_output, "{line_output}");
+        if jobs.get() > 1 {
+            // applies each rule set in the rules chain to the lines of the input on multiple threads
+            let lines = input.lines().collect::<Vec<_>>();
+
+            for (printed, line_output) in apply_rule_sets_in_parallel(paths, output_data, &appliable_rule_sets, statistics.as_deref_mut(), &lines, jobs) {
+                print!("{printed}");
+                record_line_output(line_output, &mut full_output);
+            }
+        } else {
+            // applies each rule set in the rules chain to each line of the input
+            for input in input.lines() {
+                let mut runtime = AppRuntime::new(output_data);
+                let line_output = apply_rule_sets(paths, output_data, &appliable_rule_sets, statistics.as_deref_mut(), &mut runtime, input.to_string());
+
+                record_line_output(line_output, &mut full_output);
+            }
+        }
+
+        if let Some(statistics) = statistics {
+            print_statistics(paths, &statistics);
         }
     } else {
         // applies each rule set in the chain to the input
@@ -157,26 +236,236 @@ fn run_apply(paths: &[String], output_data: &OutputData, input_type: InputType)
     }
 }
 
-/// Applies each pre-built rule set to an input
-fn apply_rule_sets(paths: &[String], output_data: &OutputData, rule_sets: &[cscsca::AppliableRules<'_>], input: String) -> Result<String, cscsca::ScaError> {
-    let mut mapping = new_mapping(output_data.map_data().map(MapData::map_type), &input);
+/// Builds the rules in a file and prints every error
+/// 
+/// Returns if there were no errors
+fn run_check(path: &str) -> Result<bool, CliError> {
+    let rules = fs::read_to_string(path).map_err(|_| CliError::NoFile(path.to_string()))?;
 
-    let mut last_output = input;
+    // variables are empty so that checking does not prompt for input
+    let errors = cscsca::check_rules(&rules);
+
+    if errors.is_empty() {
+        println!("{GREEN}No errors found in {BLUE}{path}{RESET}");
+        return Ok(true);
+    }
+
+    for e in &errors {
+        print_error(e);
+    }
+
+    println!("{RED}{} error(s) found in {BLUE}{path}{RESET}", errors.len());
+    Ok(false)
+}
+
+/// Prints every rule in a file with all definitions and variables substituted
+/// 
+/// Returns if the rules could be built
+fn run_expand(path: &str) -> Result<bool, CliError> {
+    let rules = fs::read_to_string(path).map_err(|_| CliError::NoFile(path.to_string()))?;
+
+    let appliable_rules = match cscsca::build_rules(&rules, &mut CliGetter) {
+        Ok(rules) => rules,
+        Err(e) => {
+            print_error(&e);
+            return Ok(false);
+        },
+    };
+
+    for rule in appliable_rules.expanded_rules() {
+        println!("Line {}: {}", rule.line_number(), rule.rule());
+        println!("{BLUE}{rule}{RESET}");
+    }
+
+    Ok(true)
+}
+
+/// Formats the rules in a file, or only checks if they are formatted if `check` is `true`
+/// 
+/// Returns `false` if `check` is `true` and the rules are not formatted
+fn run_fmt(path: &str, check: bool) -> Result<bool, CliError> {
+    let rules = fs::read_to_string(path).map_err(|_| CliError::NoFile(path.to_string()))?;
+    let formatted = cscsca::format_rules(&rules);
+
+    if formatted == rules {
+        println!("{BLUE}{path}{GREEN} is already formatted{RESET}");
+        Ok(true)
+    } else if check {
+        println!("{BLUE}{path}{RED} is not formatted{RESET}");
+        Ok(false)
+    } else {
+        fs::write(path, formatted).map_err(|_| CliError::CouldNotWrite(path.to_string()))?;
+        println!("{GREEN}Formatted {BLUE}{path}{RESET}");
+        Ok(true)
+    }
+}
+
+/// Prints every lint warning for the rules in a file
+fn run_lint(path: &str) -> Result<(), CliError> {
+    let rules = fs::read_to_string(path).map_err(|_| CliError::NoFile(path.to_string()))?;
+
+    let warnings = cscsca::lint_rules(&rules);
 
-    let mut runtime = if output_data.quiet() {
-        AppRuntime::Quiet(cscsca::LogRuntime::default())
+    for warning in &warnings {
+        println!("{YELLOW}Warning{RESET}: {}", warning.kind());
+        println!("Line {}: {}", warning.line_number(), warning.rule());
+    }
+
+    if warnings.is_empty() {
+        println!("{GREEN}No warnings found in {BLUE}{path}{RESET}");
     } else {
-        AppRuntime::Loud(LogAndPrintRuntime::default())
+        println!("{YELLOW}{} warning(s) found in {BLUE}{path}{RESET}", warnings.len());
+    }
+
+    Ok(())
+}
+
+/// Prints every rule in a file in standard linguistic notation
+fn run_render(path: &str, notation: cscsca::Notation) -> Result<(), CliError> {
+    let rules = fs::read_to_string(path).map_err(|_| CliError::NoFile(path.to_string()))?;
+
+    for rule in cscsca::render_rules(&rules, notation) {
+        println!("{rule}");
+    }
+
+    Ok(())
+}
+
+/// Runs every `TEST` statement in a file
+/// 
+/// Returns if every test passed
+fn run_tests(path: &str) -> Result<bool, CliError> {
+    let rules = fs::read_to_string(path).map_err(|_| CliError::NoFile(path.to_string()))?;
+
+    let appliable_rules = match cscsca::build_rules(&rules, &mut CliGetter) {
+        Ok(rules) => rules,
+        Err(e) => {
+            print_error(&e);
+            return Ok(false);
+        },
+    };
+
+    let results = appliable_rules.run_tests(&mut cscsca::LogRuntime::default());
+    let failed = results.iter().filter(|result| !result.passed()).count();
+
+    for result in &results {
+        let line_num = result.line_number();
+        let (input, expected) = (result.input(), result.expected());
+
+        match result.output() {
+            _ if result.passed() => println!("{GREEN}PASS{RESET} Line {line_num}: '{BLUE}{input}{RESET}' => '{BLUE}{expected}{RESET}'"),
+            Ok(output) => println!("{RED}FAIL{RESET} Line {line_num}: '{BLUE}{input}{RESET}' => '{BLUE}{expected}{RESET}', found '{YELLOW}{output}{RESET}'"),
+            Err(e) => {
+                println!("{RED}FAIL{RESET} Line {line_num}: '{BLUE}{input}{RESET}' => '{BLUE}{expected}{RESET}'");
+                print_error(e);
+            },
+        }
+    }
+
+    println!("{} passed, {failed} failed", results.len() - failed);
+
+    Ok(failed == 0)
+}
+
+/// Applies rules to every input in a lexicon and compares each output to its expected output
+/// 
+/// Returns if every output matched
+fn run_verify(path: &str, lexicon_path: &str) -> Result<bool, CliError> {
+    let rules = fs::read_to_string(path).map_err(|_| CliError::NoFile(path.to_string()))?;
+    let lexicon = fs::read_to_string(lexicon_path).map_err(|_| CliError::NoFile(lexicon_path.to_string()))?;
+
+    // reads each input and expected output pair
+    let entries = lexicon.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| match line.split('\t').collect::<Vec<_>>()[..] {
+            [input, expected] => Ok((i + 1, input.trim(), expected.trim())),
+            _ => Err(CliError::InvalidLexiconLine(lexicon_path.to_string(), i + 1)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let appliable_rules = match cscsca::build_rules(&rules, &mut CliGetter) {
+        Ok(rules) => rules,
+        Err(e) => {
+            print_error(&e);
+            return Ok(false);
+        },
+    };
+
+    let mut failed = 0;
+
+    for (line_num, input, expected) in &entries {
+        let output = match appliable_rules.apply_fallible(input, &mut cscsca::LogRuntime::default()) {
+            Ok(output) => output,
+            Err(e) => {
+                failed += 1;
+                println!("{RED}ERROR{RESET} Line {line_num}: '{BLUE}{input}{RESET}'");
+                print_error(&e);
+                continue;
+            },
+        };
+
+        let expected_phones = cscsca::split_phones(expected);
+        let output_phones = cscsca::split_phones(&output);
+
+        if expected_phones != output_phones {
+            failed += 1;
+            println!("{RED}MISMATCH{RESET} Line {line_num}: '{BLUE}{input}{RESET}' => '{BLUE}{expected}{RESET}', found '{YELLOW}{output}{RESET}'");
+            println!("\t{}", format_diff(&diff_phones(&expected_phones, &output_phones)));
+        }
+    }
+
+    println!("{} of {} outputs matched", entries.len() - failed, entries.len());
+
+    Ok(failed == 0)
+}
+
+/// Formats a phone diff, marking missing phones with `[-...]` and extra phones with `[+...]`
+fn format_diff(diff: &[PhoneDiff]) -> String {
+    diff.iter()
+        .map(|part| match part {
+            PhoneDiff::Same(phone) => (*phone).to_string(),
+            PhoneDiff::Missing(phone) => format!("{RED}[-{phone}]{RESET}"),
+            PhoneDiff::Extra(phone) => format!("{GREEN}[+{phone}]{RESET}"),
+        })
+        .collect()
+}
+
+/// Prints the output of applying rules to a line of input and records it in the full output
+fn record_line_output(line_output: Result<String, cscsca::ScaError>, full_output: &mut String) {
+    let line_output = match line_output {
+        Ok(out) => {
+            println!("{out}");
+            out
+        },
+        Err(e) => {
+            print_error(&e);
+            format!("{e}")
+        },
     };
 
+    _ = writeln!(full_output, "{line_output}");
+}
+
+/// Applies each pre-built rule set to an input,
+/// recording rule statistics for each set if they are given
+fn apply_rule_sets(paths: &[String], output_data: &OutputData, rule_sets: &[cscsca::AppliableRules], mut statistics: Option<&mut [cscsca::RuleStatistics]>, runtime: &mut AppRuntime, input: String) -> Result<String, cscsca::ScaError> {
+    let mut mapping = new_mapping(output_data.map_data().map(MapData::map_type), &input);
+
+    let mut last_output = input;
+
     // applies each rule set
     for (i, rule_set) in rule_sets.iter().enumerate() {
-        println!("{GREEN}Applying changes in {BLUE}{}{GREEN} to '{BLUE}{last_output}{GREEN}'{RESET}", &paths[i]);
+        runtime.print_line(&format!("{GREEN}Applying changes in {BLUE}{}{GREEN} to '{BLUE}{last_output}{GREEN}'{RESET}", &paths[i]));
 
-        let set_output = rule_set.apply_fallible(&last_output, &mut runtime)?;
+        let set_output = if let Some(statistics) = statistics.as_deref_mut() {
+            rule_set.apply_with_statistics(&last_output, runtime, &mut statistics[i])?
+        } else {
+            rule_set.apply_fallible(&last_output, runtime)?
+        };
         
         if let Some(map_data) = output_data.map_data() {
-            extend_mapping(map_data.map_type(), &set_output, &mut mapping, &mut runtime);
+            extend_mapping(map_data.map_type(), &set_output, &mut mapping, runtime);
         }
 
         // records output
@@ -186,17 +475,64 @@ fn apply_rule_sets(paths: &[String], output_data: &OutputData, rule_sets: &[cscs
     Ok(mapped_output(output_data.map_data(), mapping, last_output))
 }
 
+/// Applies each pre-built rule set to every line of an input,
+/// splitting the lines into contiguous chunks applied on up to `jobs` threads
+/// 
+/// Nothing is printed while applying, instead what would have been printed for each line
+/// is returned with its output in the order of the lines
+fn apply_rule_sets_in_parallel(paths: &[String], output_data: &OutputData, rule_sets: &[cscsca::AppliableRules], statistics: Option<&mut [cscsca::RuleStatistics]>, lines: &[&str], jobs: NonZero<usize>) -> Vec<(String, Result<String, cscsca::ScaError>)> {
+    let chunk_size = lines.len().div_ceil(jobs.get()).max(1);
+    let record_statistics = statistics.is_some();
+
+    let chunk_results = std::thread::scope(|scope| {
+        let workers = lines.chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || {
+                let mut runtime = AppRuntime::buffered(output_data);
+                let mut chunk_statistics = record_statistics.then(|| rule_sets.iter()
+                    .map(cscsca::RuleStatistics::new)
+                    .collect::<Vec<_>>());
+
+                let outputs = chunk.iter()
+                    .map(|line| {
+                        let output = apply_rule_sets(paths, output_data, rule_sets, chunk_statistics.as_deref_mut(), &mut runtime, (*line).to_string());
+                        (runtime.take_buffer(), output)
+                    })
+                    .collect::<Vec<_>>();
+
+                (outputs, chunk_statistics)
+            }))
+            .collect::<Vec<_>>();
+
+        workers.into_iter()
+            .map(|worker| worker.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+            .collect::<Vec<_>>()
+    });
+
+    let mut outputs = Vec::with_capacity(lines.len());
+
+    // merges the results of each chunk in order
+    if let Some(statistics) = statistics {
+        for (chunk_outputs, chunk_statistics) in chunk_results {
+            outputs.extend(chunk_outputs);
+
+            for (set_statistics, chunk_set_statistics) in statistics.iter_mut().zip(chunk_statistics.into_iter().flatten()) {
+                set_statistics.merge(chunk_set_statistics);
+            }
+        }
+    } else {
+        outputs.extend(chunk_results.into_iter().flat_map(|(chunk_outputs, _)| chunk_outputs));
+    }
+
+    outputs
+}
+
 /// Applies each rule set to an input
 fn apply_changes(paths: &[String], output_data: &OutputData, rule_sets: &[String], input: String) -> Result<String, cscsca::ScaError> {
     let mut mapping = new_mapping(output_data.map_data().map(MapData::map_type), &input);
 
     let mut last_output = input;
 
-    let runtime = if output_data.quiet() {
-        AppRuntime::Quiet(cscsca::LogRuntime::default())
-    } else {
-        AppRuntime::Loud(LogAndPrintRuntime::default())
-    };
+    let runtime = AppRuntime::new(output_data);
 
     let mut executor = cscsca::LineByLineExecutor::new(runtime, CliGetter);
 
@@ -255,14 +591,57 @@ fn extend_mapping(map_type: MapType, output: &str, mapping: &mut Vec<String>, ru
 fn print_error(e: &cscsca::ScaError) {
     println!("{RED}{} Error{RESET}: {}", e.error_type(), e.error_message());
 
-    if e.line_count().get() == 1 {
-        print!("Line {}", e.line_number());
+    let prefix = if e.line_count().get() == 1 {
+        format!("Line {}: ", e.line_number())
     } else {
-        print!("Lines {}-{}", e.line_number(), e.line_number().get() + e.line_count().get() - 1);
+        format!("Lines {}-{}: ", e.line_number(), e.line_number().get() + e.line_count().get() - 1)
+    };
+
+    // the line of the rule containing the token that caused the error
+    let span_line = e.span().and_then(|span| span.line_number().get().checked_sub(e.line_number().get()));
+
+    for (i, line) in e.rule().lines().enumerate() {
+        if i == 0 {
+            println!("{prefix}{line}");
+        } else {
+            println!("{line}");
+        }
+
+        // underlines the token that caused the error
+        if let Some(span) = e.span() && span_line == Some(i) {
+            let indent = span.columns().start + if i == 0 { prefix.chars().count() } else { 0 };
+            println!("{}{RED}{}{RESET}", " ".repeat(indent), "^".repeat(span.columns().len().max(1)));
+        }
     }
+}
 
-    println!(": {}", e.rule());
-    
+/// Prints how many inputs each rule in each rule set changed,
+/// along with a sample of the inputs it changed
+fn print_statistics(paths: &[String], statistics: &[cscsca::RuleStatistics]) {
+    for (path, statistics) in paths.iter().zip(statistics) {
+        println!("{GREEN}Rule statistics for {BLUE}{path}{GREEN} ({} inputs){RESET}", statistics.inputs());
+
+        for rule in statistics.rules() {
+            let fired = rule.times_fired();
+
+            if fired == 0 {
+                println!("{YELLOW}Line {}{RESET}: {} {RED}never fired{RESET}", rule.line_number(), rule.rule());
+            } else {
+                let percent = fired * 100 / statistics.inputs().max(1);
+                println!("Line {}: {} {BLUE}fired {fired} times ({percent}%){RESET}", rule.line_number(), rule.rule());
+
+                // only a sample of the changed inputs is kept
+                let words = rule.words();
+                let unsampled = fired - words.len();
+
+                if unsampled == 0 {
+                    println!("  changed: {MAGENTA}{}{RESET}", words.join(", "));
+                } else {
+                    println!("  changed: {MAGENTA}{}{RESET} and {unsampled} more", words.join(", "));
+                }
+            }
+        }
+    }
 }
 
 /// prints the characters in a string
@@ -286,18 +665,68 @@ const fn template() -> &'static str {
 
 /// The logging `Runtime` for the cli application
 /// with quiet (does not print PRINT statements)
-/// and loud (prints PRINT statements)
+/// and loud (prints PRINT statements) logging
+/// that may also print every change made by a rule
+/// and which rule set is being applied
+#[derive(Debug)]
+struct AppRuntime {
+    logger: AppLogger,
+    trace: bool,
+    /// Text kept to be printed later instead of being printed
+    buffer: Option<String>,
+}
+
+/// The logger used by an `AppRuntime`
 #[derive(Debug)]
-enum AppRuntime {
+enum AppLogger {
     Quiet(cscsca::LogRuntime),
     Loud(LogAndPrintRuntime),
+    /// Loud logging that adds PRINT statements to the runtime's buffer
+    Buffered(cscsca::LogRuntime),
 }
 
 impl AppRuntime {
+    fn new(output_data: &OutputData) -> Self {
+        let logger = if output_data.quiet() {
+            AppLogger::Quiet(cscsca::LogRuntime::default())
+        } else {
+            AppLogger::Loud(LogAndPrintRuntime::default())
+        };
+
+        Self { logger, trace: output_data.trace(), buffer: None }
+    }
+
+    /// Creates a runtime that keeps what it would print in a buffer,
+    /// so that it can be printed in order after applying on several threads
+    fn buffered(output_data: &OutputData) -> Self {
+        let logger = if output_data.quiet() {
+            AppLogger::Quiet(cscsca::LogRuntime::default())
+        } else {
+            AppLogger::Buffered(cscsca::LogRuntime::default())
+        };
+
+        Self { logger, trace: output_data.trace(), buffer: Some(String::new()) }
+    }
+
+    /// Prints a line of text or adds it to the buffer
+    fn print_line(&mut self, line: &str) {
+        if let Some(buffer) = &mut self.buffer {
+            buffer.push_str(line);
+            buffer.push('\n');
+        } else {
+            println!("{line}");
+        }
+    }
+
+    /// Returns the buffered text and replaces it with an empty buffer
+    fn take_buffer(&mut self) -> String {
+        self.buffer.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
     fn flush_logs(&mut self) -> Vec<(String, String)> {
-        match self {
-            Self::Quiet(logger) => logger.flush_logs(),
-            Self::Loud(logger) => logger.flush_logs(),
+        match &mut self.logger {
+            AppLogger::Quiet(logger) | AppLogger::Buffered(logger) => logger.flush_logs(),
+            AppLogger::Loud(logger) => logger.flush_logs(),
         }
     }
 }
@@ -308,23 +737,41 @@ impl cscsca::Runtime for AppRuntime {
     }
 
     fn put_io(&mut self, msg: &str, phones: String) -> Result<(), String> {
-        match self {
-            Self::Quiet(logger) => logger.put_io(msg, phones),
-            Self::Loud(logger) => logger.put_io(msg, phones),
+        if let AppLogger::Buffered(_) = self.logger {
+            self.print_line(&format!("{msg} '{BLUE}{phones}{RESET}'"));
+        }
+
+        match &mut self.logger {
+            AppLogger::Quiet(logger) | AppLogger::Buffered(logger) => logger.put_io(msg, phones),
+            AppLogger::Loud(logger) => logger.put_io(msg, phones),
         }
     }
 
     fn on_start(&mut self) {
-        match self {
-            Self::Quiet(logger) => logger.on_start(),
-            Self::Loud(logger) => logger.on_start(),
+        match &mut self.logger {
+            AppLogger::Quiet(logger) | AppLogger::Buffered(logger) => logger.on_start(),
+            AppLogger::Loud(logger) => logger.on_start(),
         }
     }
 
     fn on_end(&mut self) {
-        match self {
-            Self::Quiet(logger) => logger.on_end(),
-            Self::Loud(logger) => logger.on_end(),
+        match &mut self.logger {
+            AppLogger::Quiet(logger) | AppLogger::Buffered(logger) => logger.on_end(),
+            AppLogger::Loud(logger) => logger.on_end(),
+        }
+    }
+
+    fn on_rule_applied(&mut self, line_num: std::num::NonZero<usize>, rule_source: &str, before: &str, after: &str, span: std::ops::Range<usize>) {
+        if self.trace {
+            // highlights the changed phones
+            let after = format!("{}{YELLOW}{}{BLUE}{}", &after[..span.start], &after[span.clone()], &after[span.end..]);
+
+            self.print_line(&format!("{GREEN}Line {line_num}{RESET}: {rule_source}"));
+            self.print_line(&format!("\t'{BLUE}{before}{RESET}' -> '{BLUE}{after}{RESET}'"));
         }
     }
-}
\ No newline at end of file
+
+    fn wants_rule_applied(&self) -> bool {
+        self.trace
+    }
+}
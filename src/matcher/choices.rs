@@ -1,37 +1,43 @@
 use std::{borrow::Cow, collections::HashMap};
 
-use crate::{phones::Phone, tokens::ScopeId};
+use crate::{phones::Phone, shared_str::SharedStr, tokens::ScopeId};
 
 /// Choices for how agreement should occur
 #[derive(Debug, Clone, Default)]
-pub struct Choices<'c, 's> {
-    pub(super) selection: Cow<'c, HashMap<ScopeId<'s>, usize>>,
-    pub(super) optional: Cow<'c, HashMap<ScopeId<'s>, bool>>,
-    pub(super) repetition: Cow<'c, HashMap<&'s str, usize>>,
-    pub(super) any: Cow<'c, HashMap<ScopeId<'s>, Phone<'s>>>,
+pub struct Choices<'c> {
+    pub(super) selection: Cow<'c, HashMap<ScopeId, usize>>,
+    pub(super) optional: Cow<'c, HashMap<ScopeId, bool>>,
+    pub(super) repetition: Cow<'c, HashMap<SharedStr, usize>>,
+    pub(super) any: Cow<'c, HashMap<ScopeId, Phone>>,
+    pub(super) alpha: Cow<'c, HashMap<char, bool>>,
 }
 
-impl<'c, 's> Choices<'c, 's> {
+impl<'c> Choices<'c> {
     /// Gets the selection scope choices
-    pub fn selection(&self) -> &HashMap<ScopeId<'s>, usize> {
+    pub fn selection(&self) -> &HashMap<ScopeId, usize> {
         &self.selection
     }
 
     /// Gets the optional scope choices
-    pub fn optional(&self) -> &HashMap<ScopeId<'s>, bool> {
+    pub fn optional(&self) -> &HashMap<ScopeId, bool> {
         &self.optional
     }
 
     /// Gets the repetition choices
-    pub fn repetition(&self) -> &HashMap<&'s str, usize> {
+    pub fn repetition(&self) -> &HashMap<SharedStr, usize> {
         &self.repetition
     }
 
     /// Gets the any phone choices
-    pub fn any(&self) -> &HashMap<ScopeId<'s>, Phone<'s>> {
+    pub fn any(&self) -> &HashMap<ScopeId, Phone> {
         &self.any
     }
 
+    /// Gets the alpha variable choices
+    pub fn alpha(&self) -> &HashMap<char, bool> {
+        &self.alpha
+    }
+
     /// A cheeper way to clone `Choices` with less heap allocation
     pub fn partial_clone(&'c self) -> Self {
         Self {
@@ -39,21 +45,23 @@ impl<'c, 's> Choices<'c, 's> {
             optional: Cow::Borrowed(&*self.optional),
             repetition: Cow::Borrowed(&*self.repetition),
             any: Cow::Borrowed(&*self.any),
+            alpha: Cow::Borrowed(&*self.alpha),
         }
     }
 
     /// Converts a set of copy-on-write choices to only the owned choices
-    pub fn owned_choices(self) -> OwnedChoices<'s> {
+    pub fn owned_choices(self) -> OwnedChoices {
         OwnedChoices {
             selection: take_owned_from_cow(self.selection),
             optional: take_owned_from_cow(self.optional),
             repetition: take_owned_from_cow(self.repetition),
             any: take_owned_from_cow(self.any),
+            alpha: take_owned_from_cow(self.alpha),
         }
     }
 
     /// Takes the choices from `owned`
-    pub fn take_owned(&mut self, owned: OwnedChoices<'s>) {
+    pub fn take_owned(&mut self, owned: OwnedChoices) {
         if let Some(selection) = owned.selection {
             self.selection = Cow::Owned(selection);
         }
@@ -69,6 +77,10 @@ impl<'c, 's> Choices<'c, 's> {
         if let Some(any) = owned.any {
             self.any = Cow::Owned(any);
         }
+
+        if let Some(alpha) = owned.alpha {
+            self.alpha = Cow::Owned(alpha);
+        }
     }
 }
 
@@ -76,11 +88,12 @@ impl<'c, 's> Choices<'c, 's> {
 ///
 /// Used to optimise some clones
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
-pub struct OwnedChoices<'s> {
-    selection: Option<HashMap<ScopeId<'s>, usize>>,
-    optional: Option<HashMap<ScopeId<'s>, bool>>,
-    repetition: Option<HashMap<&'s str, usize>>,
-    any: Option<HashMap<ScopeId<'s>, Phone<'s>>>,
+pub struct OwnedChoices {
+    selection: Option<HashMap<ScopeId, usize>>,
+    optional: Option<HashMap<ScopeId, bool>>,
+    repetition: Option<HashMap<SharedStr, usize>>,
+    any: Option<HashMap<ScopeId, Phone>>,
+    alpha: Option<HashMap<char, bool>>,
 }
 
 /// Returns the owned content of a `Cow` if it exists
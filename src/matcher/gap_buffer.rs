@@ -0,0 +1,207 @@
+use std::ops::Range;
+
+/// A list that can be edited at any index
+/// in time proportional to the size of the edit and the distance from the last edit
+///
+/// The elements are stored with a gap of unused space at the last edited index,
+/// so edits made while moving through the list in one direction never shift the rest of it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GapBuffer<T> {
+    buffer: Vec<T>,
+    gap_start: usize,
+    gap_end: usize,
+    /// The number of elements that have been moved to make an edit
+    #[cfg(test)]
+    moved: usize,
+}
+
+impl<T: Clone + Default> GapBuffer<T> {
+    /// Gets the number of elements in the list
+    pub const fn len(&self) -> usize {
+        self.buffer.len() - (self.gap_end - self.gap_start)
+    }
+
+    /// Gets the element at an index
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.as_split().get(index)
+    }
+
+    /// Views the elements on either side of the gap as one list
+    pub fn as_split(&self) -> SplitSlice<'_, T> {
+        SplitSlice::new(&self.buffer[..self.gap_start], &self.buffer[self.gap_end..])
+    }
+
+    /// Gets a range of the elements as a single slice
+    ///
+    /// Moves the gap to the end of the range
+    pub fn contiguous(&mut self, range: Range<usize>) -> &[T] {
+        self.move_gap(range.end);
+        &self.buffer[range]
+    }
+
+    /// Replaces a range of the elements with new elements
+    ///
+    /// Leaves the gap after the new elements
+    pub fn splice(&mut self, range: Range<usize>, elements: &[T]) {
+        self.move_gap(range.end);
+        self.gap_start = range.start;
+
+        if elements.len() > self.gap_end - self.gap_start {
+            self.grow(elements.len());
+        }
+
+        self.buffer[self.gap_start..self.gap_start + elements.len()].clone_from_slice(elements);
+        self.gap_start += elements.len();
+    }
+
+    /// Moves the gap so that it starts at an index
+    /// 
+    /// Elements are swapped across the gap one at a time,
+    /// so only the elements between the gap and the index are moved
+    fn move_gap(&mut self, index: usize) {
+        #[cfg(test)]
+        { self.moved += index.abs_diff(self.gap_start); }
+
+        while index < self.gap_start {
+            self.gap_start -= 1;
+            self.gap_end -= 1;
+            self.buffer.swap(self.gap_start, self.gap_end);
+        }
+
+        while index > self.gap_start {
+            self.buffer.swap(self.gap_start, self.gap_end);
+            self.gap_start += 1;
+            self.gap_end += 1;
+        }
+    }
+
+    /// Makes the gap at least `min_len` elements long,
+    /// at least doubling the size of the buffer so that growing is amortized
+    fn grow(&mut self, min_len: usize) {
+        let old_len = self.buffer.len();
+        let back_len = old_len - self.gap_end;
+        let new_gap_end = self.gap_start + min_len.max(old_len);
+
+        self.buffer.resize(new_gap_end + back_len, T::default());
+
+        for i in (self.gap_end..old_len).rev() {
+            self.buffer.swap(i, i + new_gap_end - self.gap_end);
+        }
+
+        self.gap_end = new_gap_end;
+
+        #[cfg(test)]
+        { self.moved += back_len; }
+    }
+
+    /// Gets the number of elements that have been moved to make edits
+    #[cfg(test)]
+    pub const fn moved(&self) -> usize {
+        self.moved
+    }
+}
+
+impl<T> From<Vec<T>> for GapBuffer<T> {
+    fn from(buffer: Vec<T>) -> Self {
+        let len = buffer.len();
+
+        Self {
+            buffer,
+            gap_start: len,
+            gap_end: len,
+            #[cfg(test)]
+            moved: 0,
+        }
+    }
+}
+
+impl<T> From<GapBuffer<T>> for Vec<T> {
+    fn from(mut buffer: GapBuffer<T>) -> Self {
+        buffer.buffer.drain(buffer.gap_start..buffer.gap_end);
+        buffer.buffer
+    }
+}
+
+/// A list made of two slices, such as the elements on either side of the gap of a `GapBuffer`
+#[derive(Debug, PartialEq, Eq)]
+pub struct SplitSlice<'b, T> {
+    front: &'b [T],
+    back: &'b [T],
+}
+
+impl<T> Clone for SplitSlice<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SplitSlice<'_, T> {}
+
+impl<T> Default for SplitSlice<'_, T> {
+    fn default() -> Self {
+        Self { front: &[], back: &[] }
+    }
+}
+
+impl<'b, T> From<&'b [T]> for SplitSlice<'b, T> {
+    fn from(slice: &'b [T]) -> Self {
+        Self::new(slice, &[])
+    }
+}
+
+impl<'b, T> SplitSlice<'b, T> {
+    /// Creates a list of the elements of `front` followed by the elements of `back`
+    pub const fn new(front: &'b [T], back: &'b [T]) -> Self {
+        Self { front, back }
+    }
+
+    /// Gets the number of elements in the list
+    pub const fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+
+    /// Gets the element at an index
+    pub fn get(&self, index: usize) -> Option<&'b T> {
+        self.front.get(index).or_else(|| self.back.get(index - self.front.len()))
+    }
+
+    /// Gets a range of the list without copying it
+    ///
+    /// Returns `None` if the range is out of bounds
+    pub fn slice(&self, range: Range<usize>) -> Option<Self> {
+        if range.start > range.end || range.end > self.len() {
+            return None;
+        }
+
+        let split = self.front.len();
+
+        Some(Self {
+            front: &self.front[range.start.min(split)..range.end.min(split)],
+            back: &self.back[range.start.saturating_sub(split)..range.end.saturating_sub(split)],
+        })
+    }
+
+    /// Iterates over the elements of the list
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &'b T> {
+        self.front.iter().chain(self.back)
+    }
+
+    /// Gets the index of the first element that satisfies a predicate
+    pub fn position(&self, predicate: impl FnMut(&'b T) -> bool) -> Option<usize> {
+        self.iter().position(predicate)
+    }
+
+    /// Gets the index of the last element that satisfies a predicate
+    pub fn rposition(&self, predicate: impl FnMut(&'b T) -> bool) -> Option<usize> {
+        self.iter().rev().position(predicate).map(|i| self.len() - 1 - i)
+    }
+}
+
+impl<T: Clone> SplitSlice<'_, T> {
+    /// Copies the list into a `Vec`
+    pub fn to_vec(self) -> Vec<T> {
+        let mut vec = self.front.to_vec();
+        vec.extend_from_slice(self.back);
+        vec
+    }
+}
@@ -1,29 +1,38 @@
 use crate::{matcher::{choices::{Choices, OwnedChoices}, phones::Phones}};
 
-/// A state machine that determines whether or not a rule should be applied
-pub trait MatchState<'s> {
-    /// Determines if a state matches phones
-    fn matches<'p>(&self, phones: &mut Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> Option<OwnedChoices<'p>> where 's: 'p;
+/// A pattern that determines whether or not a rule should be applied
+///
+/// The pattern is never changed by matching,
+/// instead each search for matches keeps its progress in a separate state
+pub trait Matcher {
+    /// The progress of a search for matches of the pattern
+    type State;
 
-    /// Resets to a default state
-    fn reset(&mut self);
+    /// Creates a state in its default position
+    fn new_state(&self) -> Self::State;
 
-    /// Gets the number of phones in the state
-    fn len(&self) -> usize;
+    /// Determines if the pattern in a state matches phones
+    fn matches(&self, state: &Self::State, phones: &mut Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices>;
+
+    /// Resets a state to its default position
+    fn reset(&self, state: &mut Self::State);
+
+    /// Gets the number of phones the pattern represents in a state
+    fn len(&self, state: &Self::State) -> usize;
 
     /// Advances a state to the next valid match and returns the choices made to get there
-    /// 
+    ///
     /// If there is no remaining valid match, `None` is returned
-    fn next_match<'p>(&mut self, phones: &Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> Option<OwnedChoices<'p>> where 's: 'p;
+    fn next_match(&self, state: &mut Self::State, phones: &Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices>;
 
     /// Advances a state by up to one check such that the phones represented are not changed
-    fn advance_once(&mut self);
+    fn advance_once(&self, state: &mut Self::State);
 }
 
-/// A signle-state varient of `MatchState`
-pub trait UnitState<'s> {
+/// A signle-state varient of `Matcher`
+pub trait UnitState {
     /// Determines if a state matches phones
-    fn matches<'p>(&self, phones: &mut Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> Option<OwnedChoices<'p>> where 's: 'p;
+    fn matches(&self, phones: &mut Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices>;
 
     /// Gets the number of phones in the state
     fn len(&self) -> usize;
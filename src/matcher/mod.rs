@@ -1,4 +1,6 @@
 pub mod choices;
+pub mod gap_buffer;
 pub mod match_state;
 pub mod patterns;
-pub mod phones;
\ No newline at end of file
+pub mod phones;
+pub mod syllables;
\ No newline at end of file
@@ -1,57 +1,58 @@
-use std::marker::PhantomData;
+use crate::matcher::{choices::{Choices, OwnedChoices}, match_state::{Matcher, UnitState}, phones::Phones};
 
-use crate::matcher::{choices::{Choices, OwnedChoices}, match_state::{MatchState, UnitState}, phones::Phones};
-
-/// A `MatchState` complient wrapper for a `UnitState`
+/// A `Matcher` complient wrapper for a `UnitState`
+/// 
+/// Its state is whether or not it has been checked
 #[derive(Clone, Copy, PartialEq, Eq, Default)]
-pub struct CheckBox<'s, T: UnitState<'s>> {
-    pub(super) checked: bool,
+pub struct CheckBox<T: UnitState> {
     pub unit_state: T,
-    _phantom_lifetime_s: PhantomData<&'s ()>,
 }
 
-impl<'s, T: UnitState<'s>> CheckBox<'s, T> {
+impl<T: UnitState> CheckBox<T> {
     /// Creates a new `CheckBox` wrapper
     pub const fn new(match_state: T) -> Self {
         Self {
-            checked: false,
             unit_state: match_state,
-            _phantom_lifetime_s: PhantomData,
         }
     }
 }
 
-impl<'s, T: UnitState<'s>> MatchState<'s> for CheckBox<'s, T> {
-    fn matches<'p>(&self, phones: &mut Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> Option<OwnedChoices<'p>> where 's: 'p {
+impl<T: UnitState> Matcher for CheckBox<T> {
+    type State = bool;
+
+    fn new_state(&self) -> bool {
+        false
+    }
+
+    fn matches(&self, _: &bool, phones: &mut Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
         self.unit_state.matches(phones, choices)
     }
 
-    fn next_match<'p>(&mut self, phones: &Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> Option<OwnedChoices<'p>> where 's: 'p {
-        if self.checked {
+    fn next_match(&self, checked: &mut bool, phones: &Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
+        if *checked {
             None
         } else {
-            self.checked = true;
+            *checked = true;
             self.unit_state.matches(&mut phones.clone(), choices)
         }
     }
 
-    fn len(&self) -> usize {
+    fn len(&self, _: &bool) -> usize {
         self.unit_state.len()
     }
 
-    fn reset(&mut self) {
-        self.checked = false;
+    fn reset(&self, checked: &mut bool) {
+        *checked = false;
     }
 
-    fn advance_once(&mut self) {
-        self.checked = true;
+    fn advance_once(&self, checked: &mut bool) {
+        *checked = true;
     }
 }
 
-impl<'s, T: UnitState<'s> + std::fmt::Debug> std::fmt::Debug for CheckBox<'s, T> {
+impl<T: UnitState + std::fmt::Debug> std::fmt::Debug for CheckBox<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("CheckBox")
-            .field("checked", &self.checked)
             .field("unit_state", &self.unit_state)
             .finish()
     }
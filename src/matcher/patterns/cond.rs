@@ -2,8 +2,8 @@ use crate::{
     applier::ApplicationError,
     matcher::{
         choices::{Choices, OwnedChoices},
-        match_state::MatchState,
-        patterns::list::PatternList,
+        match_state::Matcher,
+        patterns::list::{ListState, PatternList},
         phones::Phones,
     },
     tokens::{Direction, AndType, CondType},
@@ -13,22 +13,30 @@ use crate::{
 
 /// Both sides of the input phones to be matched by conditions
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct CondPhoneInput<'p, 's> {
-    pub(super) left: Phones<'p, 's>,
-    pub(super) right: Phones<'p, 's>,
+pub struct CondPhoneInput<'p> {
+    pub(super) left: Phones<'p>,
+    pub(super) right: Phones<'p>,
 }
 
 /// A matchable pattern for a condition or anti-condition
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
-pub struct CondPattern<'s> {
-    left: PatternList<'s>,
-    right: PatternList<'s>,
+pub struct CondPattern {
+    left: PatternList,
+    right: PatternList,
     cond_type: CondType,
     and: Option<(AndType, Box<Self>)>,
 }
 
-impl<'s> CondPattern<'s> {
-    pub fn new(cond_type: CondType, left: PatternList<'s>, right: PatternList<'s>) -> Self {
+/// The state of a `CondPattern`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CondState {
+    left: ListState,
+    right: ListState,
+    and: Option<Box<Self>>,
+}
+
+impl CondPattern {
+    pub fn new(cond_type: CondType, left: PatternList, right: PatternList) -> Self {
         Self {
             left,
             right,
@@ -45,18 +53,43 @@ impl<'s> CondPattern<'s> {
         }
     }
 
-    pub(super) fn next_match<'p>(&mut self, phones: &CondPhoneInput<'_, 'p>, choices: &Choices<'_, 'p>) -> Result<Option<OwnedChoices<'p>>, ApplicationError<'s>> where 's: 'p {
+    /// Gets both sides of the condition and of every condition joined to it
+    pub fn sides(&self) -> Vec<&PatternList> {
+        let mut sides = vec![&self.left, &self.right];
+
+        if let Some((_, and_cond)) = &self.and {
+            sides.extend(and_cond.sides());
+        }
+
+        sides
+    }
+
+    /// Determines if the condition is empty and matches everywhere, like the default condition
+    pub fn is_unconditional(&self) -> bool {
+        self.cond_type == CondType::Pattern && self.left.inner().is_empty() && self.right.inner().is_empty() && self.and.is_none()
+    }
+
+    /// Creates a state in its default position
+    pub(super) fn new_state(&self) -> CondState {
+        CondState {
+            left: self.left.new_state(),
+            right: self.right.new_state(),
+            and: self.and.as_ref().map(|(_, and_cond)| Box::new(and_cond.new_state())),
+        }
+    }
+
+    pub(super) fn next_match(&self, state: &mut CondState, phones: &CondPhoneInput<'_>, choices: &Choices<'_>) -> Result<Option<OwnedChoices>, ApplicationError> {
         let mut new_choices = choices.partial_clone();
 
         // resets the checked flag on the left of the input
         // so the right can be fully checked before it is advanced
         if self.cond_type == CondType::Pattern {
-            self.left.checked_flag_reset();
+            state.left.checked_flag_reset();
         }
         
         'left_check: loop {
             if self.cond_type == CondType::Pattern {
-                let Some(left_choices) = self.left.next_match(&phones.left, &new_choices) else {
+                let Some(left_choices) = self.left.next_match(&mut state.left, &phones.left, &new_choices) else {
                     return Ok(None);
                 };
                 new_choices.take_owned(left_choices);
@@ -65,9 +98,9 @@ impl<'s> CondPattern<'s> {
             'right_check: loop {
                 match self.cond_type {
                     CondType::Pattern => {
-                        let Some(right_choices) = self.right.next_match(&phones.right, &new_choices) else {
+                        let Some(right_choices) = self.right.next_match(&mut state.right, &phones.right, &new_choices) else {
                             // if the right cannot match, resets and looks for another match on the left
-                            self.right.reset();
+                            self.right.reset(&mut state.right);
                             
                             continue 'left_check;
                         };
@@ -79,13 +112,13 @@ impl<'s> CondPattern<'s> {
                         let left_phones = Phones::new(&left_phones, 0, Direction::Ltr);
 
                         // checks if the right matches the left
-                        let Some(right_choices) = self.right.next_match(&left_phones, &new_choices) else {
+                        let Some(right_choices) = self.right.next_match(&mut state.right, &left_phones, &new_choices) else {
                             return Ok(None);
                         };
 
                         // checks that the left and the right not only match
                         // but are also the same length
-                        if self.left.len() != self.right.len() {
+                        if self.left.len(&state.left) != self.right.len(&state.right) {
                             continue 'right_check;
                         }
 
@@ -93,19 +126,16 @@ impl<'s> CondPattern<'s> {
                     }
                 }
 
-                if let Some((and_type, and_cond)) = &mut self.and {
-                    let and_type = *and_type;
-                    let and_cond = and_cond.as_mut();
-
+                if let (Some((and_type, and_cond)), Some(and_state)) = (&self.and, &mut state.and) {
                     // checks the and condition
-                    let and_match = and_cond.next_match(phones, &new_choices)?;
+                    let and_match = and_cond.next_match(and_state, phones, &new_choices)?;
 
                     // ensures the and condition match is correct
-                    match (and_type, and_match) {
+                    match (*and_type, and_match) {
                         (AndType::And, Some(and_choices)) => new_choices.take_owned(and_choices),
                         (AndType::AndNot, None) => (),
                         _ => {
-                            and_cond.reset();
+                            and_cond.reset(and_state);
                             
                             continue 'right_check;
                         },
@@ -117,22 +147,30 @@ impl<'s> CondPattern<'s> {
         }
     }
 
-    pub(super) fn reset(&mut self) {
-        self.left.reset();
-        self.right.reset();
+    pub(super) fn reset(&self, state: &mut CondState) {
+        self.left.reset(&mut state.left);
+        self.right.reset(&mut state.right);
 
-        if let Some((_, and_cond)) = &mut self.and {
-            and_cond.as_mut().reset();
+        if let (Some((_, and_cond)), Some(and_state)) = (&self.and, &mut state.and) {
+            and_cond.reset(and_state);
         }
     }
 }
 
-impl std::fmt::Display for CondPattern<'_> {
+impl std::fmt::Display for CondPattern {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} {} {}", self.left, self.cond_type, self.right)?;
+        // empty sides are skipped so that they do not leave extra spaces
+        let left = self.left.to_string();
+        let right = self.right.to_string();
+
+        write!(f, "{}", [left.as_str(), &self.cond_type.to_string(), right.as_str()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" "))?;
 
         if let Some((and_type, and_cond)) = &self.and {
-            write!(f, "{and_type} {and_cond}")?;
+            write!(f, " {and_type} {and_cond}")?;
         }
 
         Ok(())
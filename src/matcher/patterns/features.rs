@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use crate::{
+    applier::ApplicationError,
+    matcher::{choices::{Choices, OwnedChoices}, match_state::UnitState, patterns::{Pattern, check_box::CheckBox}, phones::Phones},
+    phones::{Phone, features::FeatureTable, suprasegmentals::is_suprasegmental_feature},
+    tokens::{FeatureSpec, FeatureValue, ScopeId, ScopeType},
+};
+
+/// A pattern that represents a phone with a set of distinctive features
+///
+/// Should be used in a `CheckBox`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeatureMatrix {
+    pub specs: Vec<FeatureSpec>,
+    /// The features declared when the pattern was built
+    pub(super) table: Arc<FeatureTable>,
+    pub id: Option<ScopeId>,
+}
+
+impl FeatureMatrix {
+    /// Creates the declared phone that has the features of `phone`
+    /// with the matrix's feature values applied to them
+    pub fn rewrite(&self, phone: &Phone, choices: &Choices<'_>) -> Result<Phone, ApplicationError> {
+        let no_phone_error = || ApplicationError::NoPhoneWithFeatures(phone.to_string(), Pattern::Features(CheckBox::new(self.clone())));
+
+        let symbol = phone.symbol().ok_or_else(no_phone_error)?;
+        let mut supras = phone.suprasegmentals();
+        let mut bundle = None;
+
+        for spec in &self.specs {
+            let value = spec.value.resolve(choices.alpha())
+                .ok_or_else(|| ApplicationError::UnsetAlphaVariable(spec.clone()))?;
+
+            // suprasegmentals are changed without changing the symbol
+            if is_suprasegmental_feature(&spec.name) {
+                supras.set_feature(&spec.name, value);
+                continue;
+            }
+
+            if bundle.is_none() {
+                bundle = Some(self.table.bundle(symbol).ok_or_else(no_phone_error)?.clone());
+            }
+
+            if let Some(bundle) = &mut bundle {
+                bundle.insert(spec.name.clone(), value);
+            }
+        }
+
+        // phones that do not change keep their symbol
+        // even if another phone shares their features
+        let symbol = match bundle {
+            Some(bundle) if Some(&bundle) != self.table.bundle(symbol) => self.table.find_phone(&bundle).ok_or_else(no_phone_error)?,
+            _ => symbol,
+        };
+
+        Ok(Phone::Symbol(symbol.clone()).with_suprasegmentals(supras))
+    }
+}
+
+impl UnitState for FeatureMatrix {
+    fn matches(&self, phones: &mut Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
+        let phone = phones.next();
+
+        // bounds have no features
+        let symbol = phone.symbol()?;
+        let supras = phone.suprasegmentals();
+        let mut new_choices = choices.partial_clone();
+
+        for spec in &self.specs {
+            // undeclared phones and features not specified for a phone never match
+            let value = match supras.feature(&spec.name) {
+                Some(value) => value,
+                None => *self.table.bundle(symbol)?.get(spec.name.as_str())?,
+            };
+
+            if let Some(expected) = spec.value.resolve(&new_choices.alpha) {
+                if value != expected {
+                    return None;
+                }
+            } else if let FeatureValue::Alpha(alpha) | FeatureValue::NegatedAlpha(alpha) = spec.value {
+                // the first use of an alpha variable sets its value
+                let alpha_value = value ^ matches!(spec.value, FeatureValue::NegatedAlpha(_));
+                new_choices.alpha.to_mut().insert(alpha, alpha_value);
+            }
+        }
+
+        if let Some(id) = &self.id {
+            if let Some(choice) = new_choices.any.get(id) {
+                // if the phone matches the choice the pattern matches,
+                // otherwise it doesn't
+                if !phone.matches(choice) {
+                    return None;
+                }
+            } else {
+                new_choices.any.to_mut().insert(id.clone(), phone.clone());
+            }
+        }
+
+        Some(new_choices.owned_choices())
+    }
+
+    fn len(&self) -> usize { 1 }
+}
+
+impl std::fmt::Display for FeatureMatrix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(id) = &self.id {
+            write!(f, "{id}")?;
+        }
+
+        let specs = self.specs.iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        write!(f, "{}{specs}{}", ScopeType::Features.start_char(), ScopeType::Features.end_char())
+    }
+}
@@ -1,9 +1,9 @@
-use std::{cell::RefCell, num::NonZero, rc::Rc};
+use std::{cell::{Cell, RefCell}, num::NonZero, sync::Arc};
 
 use crate::{
-    ONE, executor::io_events::{IoEvent, RuntimeIoEvent}, ir::{IrLine, tokens::{Break, IrToken}}, matcher::patterns::{
-        Pattern, cond::CondPattern, list::PatternList, rule::{RulePattern, SoundChangeRule}
-    }, tokens::{AndType, CondType, LabelType, ScopeId, ScopeType, Shift}
+    ONE, ScaErrorKind, ToErrorKind, keywords::{FEATURES_LINE_START, SYLLABIFY_LINE_START}, executor::io_events::{IoEvent, RuntimeIoEvent}, ir::{IrLine, tokenization_data::TokenizationData, tokens::{Break, IrToken}}, lexer::token_types::Span, matcher::patterns::{
+        Pattern, check_box::CheckBox, cond::CondPattern, list::PatternList, optional::Optional, rule::{RulePattern, SoundChangeRule}, selection::Selection
+    }, matcher::syllables::Syllabifier, phones::{Phone, features::FeatureTable, suprasegmentals::is_suprasegmental_feature}, shared_str::SharedStr, tokens::{AndType, CondType, Direction, FeatureSpec, LabelType, ScopeId, ScopeType, Shift, ShiftType}
 };
 
 #[cfg(test)]
@@ -12,16 +12,16 @@ mod tests;
 /// A rule, executed command, or nothing representing a line of source code
 #[cfg_attr(test, derive(PartialEq))]
 #[derive(Debug, Clone)]
-pub enum RuleLine<'s> {
+pub enum RuleLine {
     Rule {
-        rule: SoundChangeRule<'s>,
+        rule: SoundChangeRule,
         lines: NonZero<usize>,
     },
-    IoEvent(RuntimeIoEvent<'s>),
+    IoEvent(RuntimeIoEvent),
     Empty { lines: NonZero<usize> },
 }
 
-impl RuleLine<'_> {
+impl RuleLine {
     pub const fn lines(&self) -> NonZero<usize> {
         match self {
             Self::Empty { lines } | Self::Rule { lines, .. } => *lines,
@@ -36,50 +36,85 @@ struct DefaultScopeIds {
     optional: usize,
     selection: usize,
     any: usize,
+    features: usize,
 }
 
-/// Builds a sound change rule out of a line of ir tokens along with the number of lines taken up on error
+/// Builds a sound change rule out of a line of ir tokens and their spans,
+/// or produces an error along with the span of the token that caused it and the number of lines taken up
 /// 
 /// # Warning:
 /// Built time commands should be handled before this function is called
-pub fn build_rule(line: IrLine) -> Result<RuleLine, (RuleStructureError, NonZero<usize>)> {
+pub fn build_rule(line: IrLine, spans: &[Span], tokenization_data: &TokenizationData) -> Result<RuleLine, (RuleStructureError, Option<Span>, NonZero<usize>)> {
     let line_count = line.lines();
 
     let line = match line {
-        IrLine::Empty { lines } => return Ok(RuleLine::Empty { lines }),
+        IrLine::Empty { lines } | IrLine::Syllabify { lines, .. } => return Ok(RuleLine::Empty { lines }),
         IrLine::IoEvent(IoEvent::Tokenizer(_)) => return Ok(RuleLine::Empty { lines: ONE }),
         IrLine::IoEvent(IoEvent::Runtime(cmd)) => return Ok(RuleLine::IoEvent(cmd)),
         IrLine::Ir { tokens, .. } if tokens.is_empty() => return Ok(RuleLine::Empty { lines: ONE }),
-        IrLine::Ir { tokens, .. } => tokens
+        IrLine::Ir { tokens, .. } => tokens,
     };
 
-    let (input_region, other_regions) = regionize_ir(&line);
-    let mut other_regions = other_regions.into_iter();
+    let last = LastToken::default();
+    // locates an error at a token, or at the last token read if none is given
+    let locate = |e, token: Option<&IrToken>| (e, span_of(&line, spans, token.or_else(|| last.get())), line_count);
 
-    let input = ir_to_input_output(&input_region)
-        .map_err(|e| (e, line_count))?;
+    let (input_region, other_regions) = regionize_ir(&line);
+    let mut other_regions = other_regions.into_iter().peekable();
 
-    let (shift, output) = match other_regions.next() {
-        Some((Break::Shift(shift), output)) =>
-            (shift, ir_to_input_output(&output).map_err(|e| (e, line_count))?),
-        Some((r#break, _)) => return Err((RuleStructureError::BreakWithoutShift(r#break), line_count)),
-        None => return Err((RuleStructureError::NoShift, line_count)),
+    let (shift, output_region) = match other_regions.next() {
+        Some((Break::Shift(shift), _, output)) => (shift, output),
+        Some((r#break, token, _)) => return Err(locate(RuleStructureError::BreakWithoutShift(r#break), Some(token))),
+        None => return Err((RuleStructureError::NoShift, None, line_count)),
     };
 
+    // collects the links of a chain shift
+    let mut links = vec![input_region, output_region];
+
+    while let Some((Break::Shift(next_shift), token, _)) = other_regions.peek() {
+        if *next_shift != shift {
+            return Err(locate(RuleStructureError::MismatchedChainShift(shift, *next_shift), Some(token)));
+        } else if shift.kind == ShiftType::Stay {
+            return Err(locate(RuleStructureError::SecondShift(*next_shift), Some(token)));
+        }
+
+        if let Some((_, _, link)) = other_regions.next() {
+            links.push(link);
+        }
+    }
+
+    let (input_region, output_region) = chain_to_input_output(links)
+        .map_err(|e| (e, None, line_count))?;
+
+    if let Some(bound) = output_region.iter().find(|token| token == &&&IrToken::SyllableBound) {
+        return Err(locate(RuleStructureError::SyllableBoundInOutput, Some(bound)));
+    }
+
+    let input = ir_to_input_output(&input_region, &last, tokenization_data)
+        .map_err(|e| locate(e, None))?;
+    let output = ir_to_input_output(&output_region, &last, tokenization_data)
+        .map_err(|e| locate(e, None))?;
+
+    let syllabifier = line.contains(&IrToken::SyllableBound)
+        .then(|| tokenization_data.syllabifier().cloned())
+        .flatten();
+
     let mut conds = Vec::new();
     let mut anti_conds = Vec::new();
     let mut to_anti_conds = false;
 
-    for (r#break, tokens) in other_regions {
+    for (r#break, token, tokens) in other_regions {
+        last.set(Some(token));
+
         match r#break {
-            Break::Shift(shift) => return Err((RuleStructureError::SecondShift(shift), line_count)),
-            Break::Cond => conds.push(ir_to_cond(&tokens).map_err(|e| (e, line_count))?),
+            Break::Shift(shift) => return Err(locate(RuleStructureError::SecondShift(shift), None)),
+            Break::Cond => conds.push(ir_to_cond(&tokens, &last, tokenization_data).map_err(|e| locate(e, None))?),
             Break::AntiCond => {
                 to_anti_conds = true;
-                anti_conds.push(ir_to_cond(&tokens).map_err(|e| (e, line_count))?);
+                anti_conds.push(ir_to_cond(&tokens, &last, tokenization_data).map_err(|e| locate(e, None))?);
             },
             Break::And(and_type) => {
-                let cond = ir_to_cond(&tokens).map_err(|e| (e, line_count))?;
+                let cond = ir_to_cond(&tokens, &last, tokenization_data).map_err(|e| locate(e, None))?;
 
                 let last_cond = if to_anti_conds {
                     &mut anti_conds
@@ -88,7 +123,7 @@ pub fn build_rule(line: IrLine) -> Result<RuleLine, (RuleStructureError, NonZero
                 }
                 .last_mut()
                 .ok_or(RuleStructureError::AndDoesNotFollowCond(and_type))
-                .map_err(|e| (e, line_count))?;
+                .map_err(|e| locate(e, Some(token)))?;
 
                 last_cond.add_and(and_type, cond);
             },
@@ -99,28 +134,169 @@ pub fn build_rule(line: IrLine) -> Result<RuleLine, (RuleStructureError, NonZero
         rule: SoundChangeRule {
             kind: shift,
             output,
-            pattern: RefCell::new(
-                RulePattern::new(PatternList::new(input), conds, anti_conds)
-                    .map_err(|e| (e, line_count))?
-            ),
+            first_phones: first_phones(&input, shift.dir),
+            marked_phones: marked_phones(&input),
+            pattern: RulePattern::new(PatternList::new(input), conds, anti_conds)
+                .map_err(|e| (e, None, line_count))?
+                .with_syllabifier(syllabifier),
         },
         lines: line_count,
     })
 }
 
+/// Gets every phone a match of an input can start with in the direction it is matched,
+/// or `None` if a match may start with any phone or be empty
+fn first_phones(input: &[Pattern], dir: Direction) -> Option<Vec<Phone>> {
+    let mut phones = Vec::new();
+    let may_be_empty = extend_first_phones(input, dir, &mut phones)?;
+
+    (!may_be_empty).then_some(phones)
+}
+
+/// Adds every phone a match of a list of patterns can start with in a direction to `phones`
+/// 
+/// Return: if the patterns can match without any phones,
+/// or `None` if a match may start with any phone
+fn extend_first_phones(patterns: &[Pattern], dir: Direction, phones: &mut Vec<Phone>) -> Option<bool> {
+    let mut patterns = patterns.iter().collect::<Vec<_>>();
+
+    // matching RTL starts with the last pattern
+    if dir == Direction::Rtl {
+        patterns.reverse();
+    }
+
+    for pattern in patterns {
+        let may_be_empty = match pattern {
+            Pattern::Phone(CheckBox { unit_state: phone, .. }) => {
+                if !phones.contains(phone) {
+                    phones.push(phone.clone());
+                }
+
+                false
+            },
+            Pattern::Optional(Optional { option, .. }) => {
+                extend_first_phones(option.inner(), dir, phones)?;
+                true
+            },
+            Pattern::Selection(Selection { options, .. }) => {
+                let mut may_be_empty = false;
+
+                for option in options {
+                    may_be_empty |= extend_first_phones(option.inner(), dir, phones)?;
+                }
+
+                may_be_empty
+            },
+            Pattern::List(list) => extend_first_phones(list.inner(), dir, phones)?,
+            Pattern::NonBound(_) | Pattern::Features(_) | Pattern::SyllableBound(_) | Pattern::Repetition(_) => return None,
+        };
+
+        // later patterns can only start a match if every pattern before them may be empty
+        if !may_be_empty {
+            return Some(false);
+        }
+    }
+
+    Some(true)
+}
+
+/// Gets every phone written in an input with suprasegmentals marked on it, in the order they are written
+fn marked_phones(input: &[Pattern]) -> Vec<Phone> {
+    let mut phones = Vec::new();
+    extend_marked_phones(input, &mut phones);
+    phones
+}
+
+/// Adds every phone written in a list of patterns with suprasegmentals marked on it to `phones`
+fn extend_marked_phones(patterns: &[Pattern], phones: &mut Vec<Phone>) {
+    for pattern in patterns {
+        match pattern {
+            Pattern::Phone(CheckBox { unit_state: phone, .. }) => if !phone.suprasegmentals().is_unmarked() {
+                phones.push(phone.clone());
+            },
+            Pattern::Optional(Optional { option, .. }) => extend_marked_phones(option.inner(), phones),
+            Pattern::Selection(Selection { options, .. }) => for option in options {
+                extend_marked_phones(option.inner(), phones);
+            },
+            Pattern::List(list) => extend_marked_phones(list.inner(), phones),
+            Pattern::NonBound(_) | Pattern::Features(_) | Pattern::SyllableBound(_) | Pattern::Repetition(_) => (),
+        }
+    }
+}
+
+/// Builds a syllabifier out of the ir tokens of a syllabification declaration
+/// or returns an error with the span of the token that caused it
+/// 
+/// The declaration should contain onset, nucleus, and coda patterns seperated by `ArgSep` tokens,
+/// an empty declaration results in `None` disabling syllabification
+pub fn build_syllabifier(tokens: &[IrToken], spans: &[Span], tokenization_data: &TokenizationData) -> Result<Option<Syllabifier>, (RuleStructureError, Option<Span>)> {
+    if tokens.is_empty() {
+        return Ok(None);
+    }
+
+    // syllable boundaries depend on the syllabifier so they cannot be used to define it
+    if let Some(bound) = tokens.iter().find(|token| token == &&IrToken::SyllableBound) {
+        return Err((RuleStructureError::UnexpectedToken(IrToken::SyllableBound), span_of(tokens, spans, Some(bound))));
+    }
+
+    let last = LastToken::default();
+
+    // the patterns are parsed as the options of a selection scope
+    let selection_end = IrToken::ScopeEnd(ScopeType::Selection);
+    let ir = &mut tokens.iter()
+        .chain(std::iter::once(&selection_end))
+        .inspect(|&token| last.set(Some(token)));
+    let parts = selection_contents_to_patterns(ir, None, None, &last, tokenization_data)
+        .map_err(|e| (e, span_of(tokens, spans, last.get())))?;
+
+    if let Some(ir_token) = ir.next() {
+        return Err((RuleStructureError::UnexpectedToken(ir_token.clone()), span_of(tokens, spans, Some(ir_token))));
+    }
+
+    match <[_; 3]>::try_from(parts) {
+        Ok([onset, nucleus, coda]) if !nucleus.is_empty() => Ok(Some(Syllabifier::new(onset, nucleus, coda))),
+        _ => Err((RuleStructureError::InvalidSyllabifyFormat, None)),
+    }
+}
+
+/// The last ir token read while building patterns, used to locate errors
+type LastToken<'ir> = Cell<Option<&'ir IrToken>>;
+
+/// Locates errors for unclosed scopes at the token that opened the scope
+fn at_scope_start<'ir, T>(result: Result<T, RuleStructureError>, start: &'ir IrToken, last: &LastToken<'ir>) -> Result<T, RuleStructureError> {
+    if let Err(RuleStructureError::UnclosedScope(_)) = result {
+        last.set(Some(start));
+    }
+
+    result
+}
+
+/// Gets the span of a token in a line of ir tokens,
+/// returns `None` for tokens that are not in the line
+fn span_of(tokens: &[IrToken], spans: &[Span], token: Option<&IrToken>) -> Option<Span> {
+    let token = token?;
+
+    tokens.iter()
+        .position(|t| std::ptr::eq(t, token))
+        .and_then(|i| spans.get(i))
+        .copied()
+}
+
 /// Converts the ir tokens for the input and output of a rule to patterns
 #[inline]
-fn ir_to_input_output<'s>(ir: &[&IrToken<'s>]) -> Result<Vec<Pattern<'s>>, RuleStructureError<'s>> {
+fn ir_to_input_output<'ir>(ir: &[&'ir IrToken], last: &LastToken<'ir>, tokenization_data: &TokenizationData) -> Result<Vec<Pattern>, RuleStructureError> {
     ir_tokens_to_patterns(
-        &mut ir.iter().copied(), 
+        &mut ir.iter().copied().inspect(|&token| last.set(Some(token))), 
         Some(&RefCell::default()),
         None, 
-        None
+        None,
+        last,
+        tokenization_data,
     )
 }
 
 /// Converts lists of ir tokens for the (anti-)conditions of a rule to a list of `CondPattern`s
-fn ir_to_cond<'s>(ir: &[&IrToken<'s>]) -> Result<CondPattern<'s>, RuleStructureError<'s>> {
+fn ir_to_cond<'ir>(ir: &[&'ir IrToken], last: &LastToken<'ir>, tokenization_data: &TokenizationData) -> Result<CondPattern, RuleStructureError> {
         let focus = if ir.contains(&&IrToken::CondType(CondType::Pattern)) {
             CondType::Pattern
         } else if ir.contains(&&IrToken::CondType(CondType::Match)) {
@@ -129,33 +305,38 @@ fn ir_to_cond<'s>(ir: &[&IrToken<'s>]) -> Result<CondPattern<'s>, RuleStructureE
             return Err(RuleStructureError::NoConditionFocus);
         };
 
-        let cond_ir = &mut ir.iter().copied();
+        let cond_ir = &mut ir.iter().copied().inspect(|&token| last.set(Some(token)));
         // takes all of the tokens before the input token and stores them in before
         // and discards the input token leaving cond_ir as the portion after it
         let before = &mut cond_ir.take_while(|&token| token != &IrToken::CondType(focus));
 
         Ok(CondPattern::new(
             focus,
-            PatternList::new(ir_tokens_to_patterns(before, None, None, None)?),
-            PatternList::new(ir_tokens_to_patterns(cond_ir, None, None, None)?),
+            PatternList::new(ir_tokens_to_patterns(before, None, None, None, last, tokenization_data)?),
+            PatternList::new(ir_tokens_to_patterns(cond_ir, None, None, None, last, tokenization_data)?),
         ))
 }
 
 /// Converts ir tokens to patterns
-fn ir_tokens_to_patterns<'ir, 's: 'ir>(ir: &mut impl Iterator<Item = &'ir IrToken<'s>>, default_scope_ids: Option<&RefCell<DefaultScopeIds>>, parent_scope: Option<&ScopeId<'s>>, end_at: Option<ScopeType>) -> Result<Vec<Pattern<'s>>, RuleStructureError<'s>> {
+fn ir_tokens_to_patterns<'ir>(ir: &mut impl Iterator<Item = &'ir IrToken>, default_scope_ids: Option<&RefCell<DefaultScopeIds>>, parent_scope: Option<&ScopeId>, end_at: Option<ScopeType>, last: &LastToken<'ir>, tokenization_data: &TokenizationData) -> Result<Vec<Pattern>, RuleStructureError> {
     let mut patterns = Vec::new();
 
     while let Some(ir_token) = ir.next() {
         let pattern = match ir_token {
-            IrToken::Phone(phone) => Pattern::new_phone(*phone),
+            IrToken::Phone(phone) => Pattern::new_phone(phone.clone()),
             IrToken::Any => Pattern::new_any(any_id(default_scope_ids, parent_scope.cloned())),
+            IrToken::SyllableBound => Pattern::new_syllable_bound(
+                tokenization_data.syllabifier()
+                    .cloned()
+                    .ok_or(RuleStructureError::NoSyllabifier)?
+            ),
             // starts a default labeled option scope
             IrToken::ScopeStart(ScopeType::Optional) => {
                 let id = optional_id(default_scope_ids, parent_scope.cloned());
 
                 let child_ids = default_scope_ids.map(|_| RefCell::default());
 
-                Pattern::new_optional(ir_tokens_to_patterns(ir, child_ids.as_ref(), id.as_ref(), Some(ScopeType::Optional))?, id)
+                Pattern::new_optional(ir_tokens_to_patterns(ir, child_ids.as_ref(), id.as_ref(), Some(ScopeType::Optional), last, tokenization_data)?, id)
             },
             // starts a default labeled selection scope
             IrToken::ScopeStart(ScopeType::Selection) => {
@@ -163,32 +344,47 @@ fn ir_tokens_to_patterns<'ir, 's: 'ir>(ir: &mut impl Iterator<Item = &'ir IrToke
 
                 let child_ids = default_scope_ids.map(|_| RefCell::default());
 
-                Pattern::new_selection(selection_contents_to_patterns(ir, child_ids.as_ref(), id.as_ref())?, id)
+                let options = selection_contents_to_patterns(ir, child_ids.as_ref(), id.as_ref(), last, tokenization_data);
+                Pattern::new_selection(at_scope_start(options, ir_token, last)?, id)
             },
             IrToken::ScopeStart(ScopeType::Repetition) => {
-                let(inclusive, exclusive) = ir_to_repetition(ir)?;
+                let(inclusive, exclusive) = ir_to_repetition(ir, last, tokenization_data)?;
                 Pattern::new_repetition(None, inclusive, exclusive)
             },
+            // starts a default labeled feature matrix
+            IrToken::ScopeStart(ScopeType::Features) => {
+                let id = features_id(default_scope_ids, parent_scope.cloned());
+
+                let specs = ir_to_features(ir, tokenization_data.features());
+                Pattern::new_features(at_scope_start(specs, ir_token, last)?, Arc::clone(tokenization_data.features()), id)
+            },
             // ensures a label is proceeding a labelable token then creates that token with the label
             IrToken::Label(name) => {
                 let next = ir.next();
-                let id = Some(ScopeId::Name(name));
+                let id = Some(ScopeId::Name(name.clone()));
 
-                if let Some(IrToken::ScopeStart(kind)) = next {
+                if let Some(start @ IrToken::ScopeStart(kind)) = next {
                     let child_ids = Some(&RefCell::default());
 
                     match kind {
-                        ScopeType::Optional => Pattern::new_optional(ir_tokens_to_patterns(ir, child_ids, id.as_ref(), Some(ScopeType::Optional))?, id),
-                        ScopeType::Selection => Pattern::new_selection(selection_contents_to_patterns(ir, child_ids, id.as_ref())?, id),
+                        ScopeType::Optional => Pattern::new_optional(ir_tokens_to_patterns(ir, child_ids, id.as_ref(), Some(ScopeType::Optional), last, tokenization_data)?, id),
+                        ScopeType::Selection => {
+                            let options = selection_contents_to_patterns(ir, child_ids, id.as_ref(), last, tokenization_data);
+                            Pattern::new_selection(at_scope_start(options, start, last)?, id)
+                        },
                         ScopeType::Repetition => {
-                            let(inclusive, exclusive) = ir_to_repetition(ir)?;
-                            Pattern::new_repetition(Some(*name), inclusive, exclusive)
+                            let(inclusive, exclusive) = ir_to_repetition(ir, last, tokenization_data)?;
+                            Pattern::new_repetition(Some(name.clone()), inclusive, exclusive)
+                        },
+                        ScopeType::Features => {
+                            let specs = ir_to_features(ir, tokenization_data.features());
+                            Pattern::new_features(at_scope_start(specs, start, last)?, Arc::clone(tokenization_data.features()), id)
                         },
                     }
                 } else if let Some(IrToken::Any) = next {
                     Pattern::new_any(id)
                 } else {
-                    return Err(RuleStructureError::LabelNotFollowedByScope(name));
+                    return Err(RuleStructureError::LabelNotFollowedByScope(name.clone()));
                 }
             },
             // ends a scope returning either its contents or a related error
@@ -206,7 +402,7 @@ fn ir_tokens_to_patterns<'ir, 's: 'ir>(ir: &mut impl Iterator<Item = &'ir IrToke
                 return Ok(patterns);
             }
             // these tokens should be removed in checking
-            _ => return Err(RuleStructureError::UnexpectedToken(*ir_token)),
+            _ => return Err(RuleStructureError::UnexpectedToken(ir_token.clone())),
         };
 
         patterns.push(pattern);
@@ -215,10 +411,10 @@ fn ir_tokens_to_patterns<'ir, 's: 'ir>(ir: &mut impl Iterator<Item = &'ir IrToke
     Ok(patterns)
 }
 
-fn ir_to_repetition<'ir, 's: 'ir>(ir: &mut impl Iterator<Item = &'ir IrToken<'s>>) -> Result<(PatternList<'s>, Option<PatternList<'s>>), RuleStructureError<'s>> {
-    let followed_by_exclusive = |pat: &Pattern<'_>| pat == &Pattern::List(PatternList::default());
+fn ir_to_repetition<'ir>(ir: &mut impl Iterator<Item = &'ir IrToken>, last: &LastToken<'ir>, tokenization_data: &TokenizationData) -> Result<(PatternList, Option<PatternList>), RuleStructureError> {
+    let followed_by_exclusive = |pat: &Pattern| pat == &Pattern::List(PatternList::default());
 
-    let mut inclusive_patterns = ir_tokens_to_patterns(ir, None, None, Some(ScopeType::Repetition))?;
+    let mut inclusive_patterns = ir_tokens_to_patterns(ir, None, None, Some(ScopeType::Repetition), last, tokenization_data)?;
 
     let has_exclusive = inclusive_patterns.pop_if(|pat| followed_by_exclusive(pat)).is_some();
 
@@ -227,7 +423,7 @@ fn ir_to_repetition<'ir, 's: 'ir>(ir: &mut impl Iterator<Item = &'ir IrToken<'s>
     }
 
     let exclusive = if has_exclusive {
-        let exclusive_patterns = ir_tokens_to_patterns(ir, None, None, Some(ScopeType::Repetition))?;
+        let exclusive_patterns = ir_tokens_to_patterns(ir, None, None, Some(ScopeType::Repetition), last, tokenization_data)?;
 
         match exclusive_patterns.last() {
             None => return Err(RuleStructureError::EmptyExclusion),
@@ -245,10 +441,27 @@ fn ir_to_repetition<'ir, 's: 'ir>(ir: &mut impl Iterator<Item = &'ir IrToken<'s>
     Ok((inclusive, exclusive))
 }
 
+/// Converts the ir tokens in a feature matrix to a list of feature specifications,
+/// ensuring each feature has been declared
+fn ir_to_features<'ir>(ir: &mut impl Iterator<Item = &'ir IrToken>, features: &FeatureTable) -> Result<Vec<FeatureSpec>, RuleStructureError> {
+    let mut specs = Vec::new();
+
+    for ir_token in ir {
+        match ir_token {
+            IrToken::Feature(spec) if features.is_declared(&spec.name) || is_suprasegmental_feature(&spec.name) => specs.push(spec.clone()),
+            IrToken::Feature(spec) => return Err(RuleStructureError::UndefinedFeature(spec.name.clone())),
+            IrToken::ScopeEnd(ScopeType::Features) => return Ok(specs),
+            _ => return Err(RuleStructureError::UnexpectedToken(ir_token.clone())),
+        }
+    }
+
+    Err(RuleStructureError::UnclosedScope(ScopeType::Features))
+}
+
 /// Converts the ir tokens in a selection scope to a list of pattern lists
 /// where each is an option to be selected by the scope: 
 /// (options are seperated by the `ArgSep` token)
-fn selection_contents_to_patterns<'ir, 's: 'ir>(ir: &mut impl Iterator<Item = &'ir IrToken<'s>>, default_scope_ids: Option<&RefCell<DefaultScopeIds>>, scope: Option<&ScopeId<'s>>) -> Result<Vec<Vec<Pattern<'s>>>, RuleStructureError<'s>> {
+fn selection_contents_to_patterns<'ir>(ir: &mut impl Iterator<Item = &'ir IrToken>, default_scope_ids: Option<&RefCell<DefaultScopeIds>>, scope: Option<&ScopeId>, last: &LastToken<'ir>, tokenization_data: &TokenizationData) -> Result<Vec<Vec<Pattern>>, RuleStructureError> {
     let mut options = Vec::new();
     // scope_stack tracks which scope the function is analyzing to determine when to seperate options and return
     let mut scope_stack = Vec::new();
@@ -275,7 +488,9 @@ fn selection_contents_to_patterns<'ir, 's: 'ir>(ir: &mut impl Iterator<Item = &'
                         let mut items = Vec::new();
 
                         for item in options {
-                            items.push(ir_tokens_to_patterns(&mut item.into_iter(), default_scope_ids, scope, None)?);
+                            // the iterator is made dynamic so nested selections do not create infinitely many iterator types
+                            let mut item: &mut dyn Iterator<Item = _> = &mut item.into_iter().inspect(|&token| last.set(Some(token)));
+                            items.push(ir_tokens_to_patterns(&mut item, default_scope_ids, scope, None, last, tokenization_data)?);
                         }
 
                         return Ok(items);
@@ -303,20 +518,62 @@ fn selection_contents_to_patterns<'ir, 's: 'ir>(ir: &mut impl Iterator<Item = &'
     }
 }
 
-/// Converts a line of ir into regions, all regions after the first are proceeded by a break
-fn regionize_ir<'s, 'ir>(tokens: &'ir [IrToken<'s>]) -> (Vec<&'ir IrToken<'s>>, Vec<(Break, Vec<&'ir IrToken<'s>>)>) {
+/// Converts the links of a chain shift (`a >> e >> i`) to the input and output of a single rule
+/// 
+/// Each link but the last becomes an option in an input selection scope
+/// and each link but the first becomes an option in an output selection scope,
+/// a shift with only two links is returned unchanged
+fn chain_to_input_output<'ir>(mut links: Vec<Vec<&'ir IrToken>>) -> Result<(Vec<&'ir IrToken>, Vec<&'ir IrToken>), RuleStructureError> {
+    const START: IrToken = IrToken::ScopeStart(ScopeType::Selection);
+    const SEP: IrToken = IrToken::ArgSep;
+    const END: IrToken = IrToken::ScopeEnd(ScopeType::Selection);
+
+    if links.len() == 2 {
+        let output = links.pop().unwrap_or_default();
+        let input = links.pop().unwrap_or_default();
+        return Ok((input, output));
+    }
+
+    // every link that is changed must have some input
+    if links[..links.len() - 1].iter().any(Vec::is_empty) {
+        return Err(RuleStructureError::EmptyChainLink);
+    }
+
+    let to_selection = |links: &[Vec<&'ir IrToken>]| {
+        let mut selection = vec![&START];
+
+        for (i, link) in links.iter().enumerate() {
+            if i > 0 {
+                selection.push(&SEP);
+            }
+
+            selection.extend(link);
+        }
+
+        selection.push(&END);
+        selection
+    };
+
+    Ok((to_selection(&links[..links.len() - 1]), to_selection(&links[1..])))
+}
+
+/// A region of a line of ir tokens
+type Region<'ir> = Vec<&'ir IrToken>;
+
+/// Converts a line of ir into regions, all regions after the first are proceeded by a break and its token
+fn regionize_ir(tokens: &[IrToken]) -> (Region<'_>, Vec<(Break, &IrToken, Region<'_>)>) {
     let mut input_region = Vec::new();
     let mut other_regions = Vec::new();
     let mut after_input = false;
 
     for token in tokens {
         if let IrToken::Break(r#break) = token {
-            other_regions.push((*r#break, Vec::new()));
+            other_regions.push((*r#break, token, Vec::new()));
             after_input = true;
         } else if after_input {
             // for after_input to be true, other_regions must have a length of at least one
             let last_index = other_regions.len() - 1;
-            other_regions[last_index].1.push(token);
+            other_regions[last_index].2.push(token);
         } else {
             input_region.push(token);
         }
@@ -326,36 +583,48 @@ fn regionize_ir<'s, 'ir>(tokens: &'ir [IrToken<'s>]) -> (Vec<&'ir IrToken<'s>>,
 }
 
 /// Creates a default id for an optional scope and mutates the next default
-fn optional_id<'s>(default_scope_ids: Option<&RefCell<DefaultScopeIds>>, parent: Option<ScopeId<'s>>) -> Option<ScopeId<'s>> {
+fn optional_id(default_scope_ids: Option<&RefCell<DefaultScopeIds>>, parent: Option<ScopeId>) -> Option<ScopeId> {
     if let Some(ids) = default_scope_ids {
         let mut ids = ids.borrow_mut();
         let id_num = ids.optional;
         ids.optional += 1;
-        Some(ScopeId::IOUnlabeled { parent: parent.map(Rc::new), id_num, label_type: LabelType::Scope(ScopeType::Optional) })
+        Some(ScopeId::IOUnlabeled { parent: parent.map(Arc::new), id_num, label_type: LabelType::Scope(ScopeType::Optional) })
     } else {
         None
     }
 }
 
 /// Creates a default id for a selection scope and mutates the next default
-fn selection_id<'s>(default_scope_ids: Option<&RefCell<DefaultScopeIds>>, parent: Option<ScopeId<'s>>) -> Option<ScopeId<'s>> {
+fn selection_id(default_scope_ids: Option<&RefCell<DefaultScopeIds>>, parent: Option<ScopeId>) -> Option<ScopeId> {
     if let Some(ids) = default_scope_ids {
         let mut ids = ids.borrow_mut();
         let id_num = ids.selection;
         ids.selection += 1;
-        Some(ScopeId::IOUnlabeled { parent: parent.map(Rc::new), id_num, label_type: LabelType::Scope(ScopeType::Selection) })
+        Some(ScopeId::IOUnlabeled { parent: parent.map(Arc::new), id_num, label_type: LabelType::Scope(ScopeType::Selection) })
     } else {
         None
     }
 }
 
 /// Creates a default id for an an scope and mutates the next default
-fn any_id<'s>(default_scope_ids: Option<&RefCell<DefaultScopeIds>>, parent: Option<ScopeId<'s>>) -> Option<ScopeId<'s>> {
+fn any_id(default_scope_ids: Option<&RefCell<DefaultScopeIds>>, parent: Option<ScopeId>) -> Option<ScopeId> {
     if let Some(ids) = default_scope_ids {
         let mut ids = ids.borrow_mut();
         let id_num = ids.any;
         ids.any += 1;
-        Some(ScopeId::IOUnlabeled { parent: parent.map(Rc::new), id_num, label_type: LabelType::Any })
+        Some(ScopeId::IOUnlabeled { parent: parent.map(Arc::new), id_num, label_type: LabelType::Any })
+    } else {
+        None
+    }
+}
+
+/// Creates a default id for a feature matrix and mutates the next default
+fn features_id(default_scope_ids: Option<&RefCell<DefaultScopeIds>>, parent: Option<ScopeId>) -> Option<ScopeId> {
+    if let Some(ids) = default_scope_ids {
+        let mut ids = ids.borrow_mut();
+        let id_num = ids.features;
+        ids.features += 1;
+        Some(ScopeId::IOUnlabeled { parent: parent.map(Arc::new), id_num, label_type: LabelType::Scope(ScopeType::Features) })
     } else {
         None
     }
@@ -364,15 +633,15 @@ fn any_id<'s>(default_scope_ids: Option<&RefCell<DefaultScopeIds>>, parent: Opti
 /// An error that occurs when converting ir tokens to patterns
 #[cfg_attr(test, derive(PartialEq))]
 #[derive(Debug)]
-pub enum RuleStructureError<'s> {
+pub enum RuleStructureError {
     ArgSepOutOfSelection,
     BreakWithoutShift(Break),
-    LabelNotFollowedByScope(&'s str),
+    LabelNotFollowedByScope(SharedStr),
     NoShift,
     UnclosedScope(ScopeType),
     UnopendScope(ScopeType),
     MismatchedScopeBounds(ScopeType, ScopeType),
-    UnexpectedToken(IrToken<'s>),
+    UnexpectedToken(IrToken),
     NoConditionFocus,
     AndDoesNotFollowCond(AndType),
     SecondShift(Shift),
@@ -380,11 +649,17 @@ pub enum RuleStructureError<'s> {
     RepetitionOutOfCond,
     EmptyRepetition,
     EmptyExclusion,
+    UndefinedFeature(SharedStr),
+    NoSyllabifier,
+    SyllableBoundInOutput,
+    InvalidSyllabifyFormat,
+    MismatchedChainShift(Shift, Shift),
+    EmptyChainLink,
 }
 
-impl std::error::Error for RuleStructureError<'_> {}
+impl std::error::Error for RuleStructureError {}
 
-impl std::fmt::Display for RuleStructureError<'_> {
+impl std::fmt::Display for RuleStructureError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::ArgSepOutOfSelection
@@ -392,7 +667,7 @@ impl std::fmt::Display for RuleStructureError<'_> {
             Self::BreakWithoutShift(r#break)
                 => write!(f, "Found '{break}' without a proceeding shift token"),
             Self::LabelNotFollowedByScope(name)
-                => write!(f, "Label '{}' is not followed by a scope", IrToken::Label(name)),
+                => write!(f, "Label '{}' is not followed by a scope", IrToken::Label(name.clone())),
             Self::NoShift => write!(f, "Rule does not contains a shift token"),
             Self::UnopendScope(kind) => write!(f, "Found unopened '{}'", kind.end_char()),
             Self::UnclosedScope(kind) => write!(f, "Found unclosed '{}'", kind.start_char()),
@@ -408,6 +683,40 @@ impl std::fmt::Display for RuleStructureError<'_> {
             Self::RepetitionOutOfCond => write!(f, "Repetitions ('{}...{}') are not allowed outside of conditions and anti-conditions", ScopeType::Repetition.start_char(), ScopeType::Repetition.end_char()),
             Self::EmptyRepetition => write!(f, "A repetition must contain some inclusive pattern"),
             Self::EmptyExclusion => write!(f, "A repetition exclusion must contain some pattern"),
+            Self::UndefinedFeature(name) => write!(f, "Feature '{name}' has not been declared with '{FEATURES_LINE_START}'"),
+            Self::NoSyllabifier => write!(f, "Found '{}' before syllables were declared with '{SYLLABIFY_LINE_START}'", IrToken::SyllableBound),
+            Self::SyllableBoundInOutput => write!(f, "Syllable boundaries ('{}') are not allowed in outputs", IrToken::SyllableBound),
+            Self::MismatchedChainShift(first, second) => write!(f, "Found '{second}' in a chain shift started with '{first}', every shift in a chain must be the same"),
+            Self::EmptyChainLink => write!(f, "Every link in a chain shift except the last must contain some input"),
+            Self::InvalidSyllabifyFormat => write!(f, "Invalid format after '{SYLLABIFY_LINE_START}', expected onset, nucleus, and coda patterns seperated by '{}' with a non-empty nucleus", IrToken::ArgSep),
         }
     }
-}
\ No newline at end of file
+}
+
+impl ToErrorKind for RuleStructureError {
+    fn error_kind(&self) -> ScaErrorKind {
+        match self {
+            Self::ArgSepOutOfSelection => ScaErrorKind::ArgSepOutOfSelection,
+            Self::BreakWithoutShift(r#break) => ScaErrorKind::BreakWithoutShift { token: r#break.to_string() },
+            Self::LabelNotFollowedByScope(label) => ScaErrorKind::LabelNotFollowedByScope { label: label.to_string() },
+            Self::NoShift => ScaErrorKind::NoShift,
+            Self::UnclosedScope(kind) => ScaErrorKind::UnclosedScope { start: kind.start_char() },
+            Self::UnopendScope(kind) => ScaErrorKind::UnopenedScope { end: kind.end_char() },
+            Self::MismatchedScopeBounds(start, end) => ScaErrorKind::MismatchedScopeBounds { start: start.start_char(), end: end.end_char() },
+            Self::UnexpectedToken(token) => ScaErrorKind::UnexpectedToken { token: token.to_string() },
+            Self::NoConditionFocus => ScaErrorKind::NoConditionFocus,
+            Self::AndDoesNotFollowCond(and_type) => ScaErrorKind::AndDoesNotFollowCond { token: and_type.to_string() },
+            Self::SecondShift(shift) => ScaErrorKind::SecondShift { shift: shift.to_string() },
+            Self::UnexpectedCondType(r#type) => ScaErrorKind::UnexpectedCondType { token: r#type.to_string() },
+            Self::RepetitionOutOfCond => ScaErrorKind::RepetitionOutOfCond,
+            Self::EmptyRepetition => ScaErrorKind::EmptyRepetition,
+            Self::EmptyExclusion => ScaErrorKind::EmptyExclusion,
+            Self::UndefinedFeature(name) => ScaErrorKind::UndefinedFeature { name: name.to_string() },
+            Self::NoSyllabifier => ScaErrorKind::NoSyllabifier,
+            Self::SyllableBoundInOutput => ScaErrorKind::SyllableBoundInOutput,
+            Self::InvalidSyllabifyFormat => ScaErrorKind::InvalidSyllabifyFormat,
+            Self::MismatchedChainShift(first, second) => ScaErrorKind::MismatchedChainShift { first: first.to_string(), second: second.to_string() },
+            Self::EmptyChainLink => ScaErrorKind::EmptyChainLink,
+        }
+    }
+}
@@ -1,14 +1,20 @@
-use std::{num::NonZero, rc::Rc};
+use std::{num::NonZero, sync::Arc};
 
-use crate::{phones::Phone, tokens::{Direction, Shift, ShiftType, AndType}};
+use crate::{phones::{Phone, suprasegmentals::{Length, Stress, Suprasegmentals}}, tokens::{Direction, Shift, ShiftType, AndType}};
 use super::*;
 
 const ONE: NonZero<usize> = NonZero::new(1).expect("1 ought to be nonzero");
 
+/// Builds a sound change rule out of a line of ir tokens without any tokenization data
+fn build_rule(line: IrLine) -> Result<RuleLine, (RuleStructureError, NonZero<usize>)> {
+    super::build_rule(line, &[], &TokenizationData::new())
+        .map_err(|(e, _, lines)| (e, lines))
+}
+
 /// Builds a sound change rules out of lines of ir tokens,
 /// if there is an error it is returned with its line number
 #[cfg(test)]
-fn build_rules<'s>(token_lines: Vec<IrLine<'s>>) -> Result<Vec<RuleLine<'s>>, (RuleStructureError<'s>, usize)> {
+fn build_rules(token_lines: Vec<IrLine>) -> Result<Vec<RuleLine>, (RuleStructureError, usize)> {
     token_lines
         .into_iter()
         .enumerate()
@@ -29,55 +35,59 @@ fn empty_line() {
 
 #[test]
 fn one_to_one() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     assert_eq!(Ok(RuleLine::Rule { rule: SoundChangeRule {
         kind: shift,
-        output: vec![Pattern::new_phone(Phone::Symbol("b"))],
-        pattern: RefCell::new(RulePattern::new(
-            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+        output: vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+        pattern: RulePattern::new(
+            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
             Vec::new(),
             Vec::new(),
-        ).expect("pattern construction should be valid")),
+        ).expect("pattern construction should be valid"),
+        first_phones: Some(vec![Phone::Symbol("a".into())]),
+        marked_phones: Vec::new(),
     }, lines: ONE }), build_rule(IrLine::Ir { tokens: vec![
-        IrToken::Phone(Phone::Symbol("a")),
+        IrToken::Phone(Phone::Symbol("a".into())),
         IrToken::Break(Break::Shift(shift)),
-        IrToken::Phone(Phone::Symbol("b")),
+        IrToken::Phone(Phone::Symbol("b".into())),
     ], lines: ONE }));
 }
 
 #[test]
 fn three_to_three() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     assert_eq!(Ok(RuleLine::Rule { rule: SoundChangeRule {
         kind: shift,
-        output: vec![Pattern::new_phone(Phone::Symbol("d")), Pattern::new_phone(Phone::Symbol("e")), Pattern::new_phone(Phone::Symbol("f"))],
-        pattern: RefCell::new(RulePattern::new(
-            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a")), Pattern::new_phone(Phone::Symbol("b")), Pattern::new_phone(Phone::Symbol("c"))]),
+        output: vec![Pattern::new_phone(Phone::Symbol("d".into())), Pattern::new_phone(Phone::Symbol("e".into())), Pattern::new_phone(Phone::Symbol("f".into()))],
+        pattern: RulePattern::new(
+            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into())), Pattern::new_phone(Phone::Symbol("b".into())), Pattern::new_phone(Phone::Symbol("c".into()))]),
             Vec::new(),
             Vec::new(),
-        ).expect("pattern construction should be valid")),
+        ).expect("pattern construction should be valid"),
+        first_phones: Some(vec![Phone::Symbol("a".into())]),
+        marked_phones: Vec::new(),
     }, lines: ONE }), build_rule(IrLine::Ir { tokens: vec![
-        IrToken::Phone(Phone::Symbol("a")),
-        IrToken::Phone(Phone::Symbol("b")),
-        IrToken::Phone(Phone::Symbol("c")),
+        IrToken::Phone(Phone::Symbol("a".into())),
+        IrToken::Phone(Phone::Symbol("b".into())),
+        IrToken::Phone(Phone::Symbol("c".into())),
         IrToken::Break(Break::Shift(shift)),
-        IrToken::Phone(Phone::Symbol("d")),
-        IrToken::Phone(Phone::Symbol("e")),
-        IrToken::Phone(Phone::Symbol("f")),
+        IrToken::Phone(Phone::Symbol("d".into())),
+        IrToken::Phone(Phone::Symbol("e".into())),
+        IrToken::Phone(Phone::Symbol("f".into())),
     ], lines: ONE }));
 }
 
 #[test]
 fn selected_three_to_selected_three() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     let input = PatternList::new(vec![Pattern::new_selection(
         vec![
-            vec![Pattern::new_phone(Phone::Symbol("a"))],
-            vec![Pattern::new_phone(Phone::Symbol("b"))],
-            vec![Pattern::new_phone(Phone::Symbol("c"))],
+            vec![Pattern::new_phone(Phone::Symbol("a".into()))],
+            vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+            vec![Pattern::new_phone(Phone::Symbol("c".into()))],
         ],
         Some(ScopeId::IOUnlabeled { parent: None, id_num: 0, label_type: LabelType::Scope(ScopeType::Selection) }),
     )]);
@@ -86,98 +96,100 @@ fn selected_three_to_selected_three() {
         kind: shift,
         output: vec![Pattern::new_selection(
             vec![
-                vec![Pattern::new_phone(Phone::Symbol("d"))],
-                vec![Pattern::new_phone(Phone::Symbol("e"))],
-                vec![Pattern::new_phone(Phone::Symbol("f"))],
+                vec![Pattern::new_phone(Phone::Symbol("d".into()))],
+                vec![Pattern::new_phone(Phone::Symbol("e".into()))],
+                vec![Pattern::new_phone(Phone::Symbol("f".into()))],
             ],
             Some(ScopeId::IOUnlabeled { parent: None, id_num: 0, label_type: LabelType::Scope(ScopeType::Selection) }),
         )],
-        pattern: RefCell::new(
-            RulePattern::new(input,
+        pattern: RulePattern::new(input,
             Vec::new(),
             Vec::new(),
-        ).expect("pattern construction should be valid")),
+        ).expect("pattern construction should be valid"),
+        first_phones: Some(vec![Phone::Symbol("a".into()), Phone::Symbol("b".into()), Phone::Symbol("c".into())]),
+        marked_phones: Vec::new(),
 
     }, lines: ONE }), build_rule(IrLine::Ir { tokens: vec![
         IrToken::ScopeStart(ScopeType::Selection),
-        IrToken::Phone(Phone::Symbol("a")),
+        IrToken::Phone(Phone::Symbol("a".into())),
         IrToken::ArgSep,
-        IrToken::Phone(Phone::Symbol("b")),
+        IrToken::Phone(Phone::Symbol("b".into())),
         IrToken::ArgSep,
-        IrToken::Phone(Phone::Symbol("c")),
+        IrToken::Phone(Phone::Symbol("c".into())),
         IrToken::ScopeEnd(ScopeType::Selection),
         IrToken::Break(Break::Shift(shift)),
         IrToken::ScopeStart(ScopeType::Selection),
-        IrToken::Phone(Phone::Symbol("d")),
+        IrToken::Phone(Phone::Symbol("d".into())),
         IrToken::ArgSep,
-        IrToken::Phone(Phone::Symbol("e")),
+        IrToken::Phone(Phone::Symbol("e".into())),
         IrToken::ArgSep,
-        IrToken::Phone(Phone::Symbol("f")),
+        IrToken::Phone(Phone::Symbol("f".into())),
         IrToken::ScopeEnd(ScopeType::Selection),
     ], lines: ONE }));
 }
 
 #[test]
 fn labeled_selected_three_to_selected_three() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     let input = PatternList::new(vec![Pattern::new_selection(
         vec![
-            vec![Pattern::new_phone(Phone::Symbol("a"))],
-            vec![Pattern::new_phone(Phone::Symbol("b"))],
-            vec![Pattern::new_phone(Phone::Symbol("c"))],
+            vec![Pattern::new_phone(Phone::Symbol("a".into()))],
+            vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+            vec![Pattern::new_phone(Phone::Symbol("c".into()))],
         ],
-        Some(ScopeId::Name("label")),
+        Some(ScopeId::Name("label".into())),
     )]);
 
     assert_eq!(Ok(RuleLine::Rule { rule: SoundChangeRule {
         kind: shift,
         output: vec![Pattern::new_selection(
             vec![
-                vec![Pattern::new_phone(Phone::Symbol("d"))],
-                vec![Pattern::new_phone(Phone::Symbol("e"))],
-                vec![Pattern::new_phone(Phone::Symbol("f"))],
+                vec![Pattern::new_phone(Phone::Symbol("d".into()))],
+                vec![Pattern::new_phone(Phone::Symbol("e".into()))],
+                vec![Pattern::new_phone(Phone::Symbol("f".into()))],
             ],
-            Some(ScopeId::Name("label")),
+            Some(ScopeId::Name("label".into())),
         )],
-        pattern: RefCell::new(
-            RulePattern::new(input,
+        pattern: RulePattern::new(input,
             Vec::new(),
             Vec::new(),
-        ).expect("pattern construction should be valid")),
+        ).expect("pattern construction should be valid"),
+        first_phones: Some(vec![Phone::Symbol("a".into()), Phone::Symbol("b".into()), Phone::Symbol("c".into())]),
+        marked_phones: Vec::new(),
 
     }, lines: ONE }), build_rule(IrLine::Ir { tokens: vec![
-        IrToken::Label("label"),
+        IrToken::Label("label".into()),
         IrToken::ScopeStart(ScopeType::Selection),
-        IrToken::Phone(Phone::Symbol("a")),
+        IrToken::Phone(Phone::Symbol("a".into())),
         IrToken::ArgSep,
-        IrToken::Phone(Phone::Symbol("b")),
+        IrToken::Phone(Phone::Symbol("b".into())),
         IrToken::ArgSep,
-        IrToken::Phone(Phone::Symbol("c")),
+        IrToken::Phone(Phone::Symbol("c".into())),
         IrToken::ScopeEnd(ScopeType::Selection),
         IrToken::Break(Break::Shift(shift)),
-        IrToken::Label("label"),
+        IrToken::Label("label".into()),
         IrToken::ScopeStart(ScopeType::Selection),
-        IrToken::Phone(Phone::Symbol("d")),
+        IrToken::Phone(Phone::Symbol("d".into())),
         IrToken::ArgSep,
-        IrToken::Phone(Phone::Symbol("e")),
+        IrToken::Phone(Phone::Symbol("e".into())),
         IrToken::ArgSep,
-        IrToken::Phone(Phone::Symbol("f")),
+        IrToken::Phone(Phone::Symbol("f".into())),
         IrToken::ScopeEnd(ScopeType::Selection),
     ], lines: ONE }));
 }
 
 #[test]
 fn labeled_phone_to_phone() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     assert_eq!(
-        Err((RuleStructureError::LabelNotFollowedByScope("label"), 1)),
+        Err((RuleStructureError::LabelNotFollowedByScope("label".into()), 1)),
         build_rules(vec![IrLine::Ir { tokens: vec![
-            IrToken::Label("label"),
-            IrToken::Phone(Phone::Symbol("a")),
+            IrToken::Label("label".into()),
+            IrToken::Phone(Phone::Symbol("a".into())),
             IrToken::Break(Break::Shift(shift)),
-            IrToken::Phone(Phone::Symbol("b")),
+            IrToken::Phone(Phone::Symbol("b".into())),
         ], lines: ONE }])
     );
 }
@@ -187,48 +199,50 @@ fn no_shift() {
     assert_eq!(
         Err((RuleStructureError::NoShift, 1)),
         build_rules(vec![IrLine::Ir { tokens: vec![
-            IrToken::Phone(Phone::Symbol("a")),
-            IrToken::Phone(Phone::Symbol("b")),
-            IrToken::Phone(Phone::Symbol("c")),
+            IrToken::Phone(Phone::Symbol("a".into())),
+            IrToken::Phone(Phone::Symbol("b".into())),
+            IrToken::Phone(Phone::Symbol("c".into())),
         ], lines: ONE }])
     );
 }
 
 #[test]
 fn no_output() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     assert_eq!(Ok(RuleLine::Rule { rule: SoundChangeRule {
         kind: shift,
         output: Vec::new(),
-        pattern: RefCell::new(
-            RulePattern::new(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+        pattern: RulePattern::new(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
             Vec::new(),
             Vec::new(),
-        ).expect("pattern construction should be valid")),
+        ).expect("pattern construction should be valid"),
+        first_phones: Some(vec![Phone::Symbol("a".into())]),
+        marked_phones: Vec::new(),
     }, lines: ONE }), build_rule(IrLine::Ir { tokens: vec![
-        IrToken::Phone(Phone::Symbol("a")),
+        IrToken::Phone(Phone::Symbol("a".into())),
         IrToken::Break(Break::Shift(shift)),
     ], lines: ONE }));
 }
 
 #[test]
 fn single_option() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
-    let input = PatternList::new(vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("a"))], Some(ScopeId::IOUnlabeled { parent: None, id_num: 0, label_type: LabelType::Scope(ScopeType::Optional) }))]);
+    let input = PatternList::new(vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("a".into()))], Some(ScopeId::IOUnlabeled { parent: None, id_num: 0, label_type: LabelType::Scope(ScopeType::Optional) }))]);
 
     assert_eq!(Ok(RuleLine::Rule { rule: SoundChangeRule {
         kind: shift,
         output: Vec::new(),
-        pattern: RefCell::new(
-            RulePattern::new(input,
+        pattern: RulePattern::new(input,
             Vec::new(),
             Vec::new(),
-        ).expect("pattern construction should be valid")),
+        ).expect("pattern construction should be valid"),
+        first_phones: None,
+        marked_phones: Vec::new(),
     }, lines: ONE }), build_rule(IrLine::Ir { tokens: vec![
         IrToken::ScopeStart(ScopeType::Optional),
-        IrToken::Phone(Phone::Symbol("a")),
+        IrToken::Phone(Phone::Symbol("a".into())),
         IrToken::ScopeEnd(ScopeType::Optional),
         IrToken::Break(Break::Shift(shift)),
     ], lines: ONE }));
@@ -236,18 +250,18 @@ fn single_option() {
 
 #[test]
 fn nested_scopes() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     let label_0 = Some(ScopeId::IOUnlabeled { parent: None, id_num: 0, label_type: LabelType::Scope(ScopeType::Selection) });
-    let label_1 = Some(ScopeId::IOUnlabeled { id_num: 0, label_type: LabelType::Scope(ScopeType::Optional), parent: Some(Rc::new(ScopeId::IOUnlabeled { parent: None, id_num: 0, label_type: LabelType::Scope(ScopeType::Selection) })) });
-    let label_2 = Some(ScopeId::IOUnlabeled { id_num: 1, label_type: LabelType::Scope(ScopeType::Optional), parent: Some(Rc::new(ScopeId::IOUnlabeled { parent: None, id_num: 0, label_type: LabelType::Scope(ScopeType::Selection) })) });
+    let label_1 = Some(ScopeId::IOUnlabeled { id_num: 0, label_type: LabelType::Scope(ScopeType::Optional), parent: Some(Arc::new(ScopeId::IOUnlabeled { parent: None, id_num: 0, label_type: LabelType::Scope(ScopeType::Selection) })) });
+    let label_2 = Some(ScopeId::IOUnlabeled { id_num: 1, label_type: LabelType::Scope(ScopeType::Optional), parent: Some(Arc::new(ScopeId::IOUnlabeled { parent: None, id_num: 0, label_type: LabelType::Scope(ScopeType::Selection) })) });
 
     let input = PatternList::new(vec![Pattern::new_selection(
         vec![
-            vec![Pattern::new_phone(Phone::Symbol("a"))],
-            vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("b"))], label_1.clone())],
-            vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("c"))], label_2.clone())],
-            vec![Pattern::new_phone(Phone::Symbol("d"))],
+            vec![Pattern::new_phone(Phone::Symbol("a".into()))],
+            vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("b".into()))], label_1.clone())],
+            vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("c".into()))], label_2.clone())],
+            vec![Pattern::new_phone(Phone::Symbol("d".into()))],
         ],
         label_0.clone()
     )]);
@@ -257,46 +271,47 @@ fn nested_scopes() {
             kind: shift,
             output: vec![Pattern::new_selection(
                 vec![
-                    vec![Pattern::new_phone(Phone::Symbol("e"))],
-                    vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("f"))], label_1)],
-                    vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("g"))], label_2)],
-                    vec![Pattern::new_phone(Phone::Symbol("h"))],
+                    vec![Pattern::new_phone(Phone::Symbol("e".into()))],
+                    vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("f".into()))], label_1)],
+                    vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("g".into()))], label_2)],
+                    vec![Pattern::new_phone(Phone::Symbol("h".into()))],
                 ],
                 label_0
             )],
-            pattern: RefCell::new(
-            RulePattern::new(    input,
+            pattern: RulePattern::new(    input,
                 Vec::new(),
                 Vec::new(),
-            ).expect("pattern construction should be valid")),
+            ).expect("pattern construction should be valid"),
+            first_phones: None,
+            marked_phones: Vec::new(),
         }, lines: ONE }),
         build_rule(IrLine::Ir { tokens: vec![
             IrToken::ScopeStart(ScopeType::Selection),
-            IrToken::Phone(Phone::Symbol("a")),
+            IrToken::Phone(Phone::Symbol("a".into())),
             IrToken::ArgSep,
             IrToken::ScopeStart(ScopeType::Optional),
-            IrToken::Phone(Phone::Symbol("b")),
+            IrToken::Phone(Phone::Symbol("b".into())),
             IrToken::ScopeEnd(ScopeType::Optional),
             IrToken::ArgSep,
             IrToken::ScopeStart(ScopeType::Optional),
-            IrToken::Phone(Phone::Symbol("c")),
+            IrToken::Phone(Phone::Symbol("c".into())),
             IrToken::ScopeEnd(ScopeType::Optional),
             IrToken::ArgSep,
-            IrToken::Phone(Phone::Symbol("d")),
+            IrToken::Phone(Phone::Symbol("d".into())),
             IrToken::ScopeEnd(ScopeType::Selection),
             IrToken::Break(Break::Shift(shift)),
             IrToken::ScopeStart(ScopeType::Selection),
-            IrToken::Phone(Phone::Symbol("e")),
+            IrToken::Phone(Phone::Symbol("e".into())),
             IrToken::ArgSep,
             IrToken::ScopeStart(ScopeType::Optional),
-            IrToken::Phone(Phone::Symbol("f")),
+            IrToken::Phone(Phone::Symbol("f".into())),
             IrToken::ScopeEnd(ScopeType::Optional),
             IrToken::ArgSep,
             IrToken::ScopeStart(ScopeType::Optional),
-            IrToken::Phone(Phone::Symbol("g")),
+            IrToken::Phone(Phone::Symbol("g".into())),
             IrToken::ScopeEnd(ScopeType::Optional),
             IrToken::ArgSep,
-            IrToken::Phone(Phone::Symbol("h")),
+            IrToken::Phone(Phone::Symbol("h".into())),
             IrToken::ScopeEnd(ScopeType::Selection),
         ], lines: ONE })
     );
@@ -304,263 +319,274 @@ fn nested_scopes() {
 
 #[test]
 fn single_cond() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     assert_eq!(Ok(RuleLine::Rule { rule: SoundChangeRule {
         kind: shift,
-        output: vec![Pattern::new_phone(Phone::Symbol("b"))],
-        pattern: RefCell::new(RulePattern::new(
-            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+        output: vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+        pattern: RulePattern::new(
+            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
             vec![CondPattern::new(
                 CondType::Pattern,
-                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c"))]),
-                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("d"))]),
+                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c".into()))]),
+                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("d".into()))]),
             )],
             Vec::new(),
-        ).expect("pattern construction should be valid")),
+        ).expect("pattern construction should be valid"),
+        first_phones: Some(vec![Phone::Symbol("a".into())]),
+        marked_phones: Vec::new(),
     }, lines: ONE }), build_rule(IrLine::Ir { tokens: vec![
-        IrToken::Phone(Phone::Symbol("a")),
+        IrToken::Phone(Phone::Symbol("a".into())),
         IrToken::Break(Break::Shift(shift)),
-        IrToken::Phone(Phone::Symbol("b")),
+        IrToken::Phone(Phone::Symbol("b".into())),
         IrToken::Break(Break::Cond),
-        IrToken::Phone(Phone::Symbol("c")),
+        IrToken::Phone(Phone::Symbol("c".into())),
         IrToken::CondType(CondType::Pattern),
-        IrToken::Phone(Phone::Symbol("d")),
+        IrToken::Phone(Phone::Symbol("d".into())),
     ], lines: ONE }));
 }
 
 #[test]
 fn three_conds() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     assert_eq!(Ok(RuleLine::Rule { rule: SoundChangeRule {
         kind: shift,
-        output: vec![Pattern::new_phone(Phone::Symbol("b"))],
-        pattern: RefCell::new(RulePattern::new(
-            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+        output: vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+        pattern: RulePattern::new(
+            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
             vec![
                 CondPattern::new(
                     CondType::Pattern,
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c"))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c".into()))]),
                     PatternList::default(),
                 ),
                 CondPattern::new(
                     CondType::Pattern,
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("d"))]),
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("e"))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("d".into()))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("e".into()))]),
                 ),
                 CondPattern::new(
                     CondType::Pattern,
                     PatternList::default(),
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("f"))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("f".into()))]),
                 ),
             ],
             Vec::new(),
-        ).expect("pattern construction should be valid")),
+        ).expect("pattern construction should be valid"),
+        first_phones: Some(vec![Phone::Symbol("a".into())]),
+        marked_phones: Vec::new(),
     }, lines: ONE }), build_rule(IrLine::Ir { tokens: vec![
-        IrToken::Phone(Phone::Symbol("a")),
+        IrToken::Phone(Phone::Symbol("a".into())),
         IrToken::Break(Break::Shift(shift)),
-        IrToken::Phone(Phone::Symbol("b")),
+        IrToken::Phone(Phone::Symbol("b".into())),
         IrToken::Break(Break::Cond),
-        IrToken::Phone(Phone::Symbol("c")),
+        IrToken::Phone(Phone::Symbol("c".into())),
         IrToken::CondType(CondType::Pattern),
         IrToken::Break(Break::Cond),
-        IrToken::Phone(Phone::Symbol("d")),
+        IrToken::Phone(Phone::Symbol("d".into())),
         IrToken::CondType(CondType::Pattern),
-        IrToken::Phone(Phone::Symbol("e")),
+        IrToken::Phone(Phone::Symbol("e".into())),
         IrToken::Break(Break::Cond),
         IrToken::CondType(CondType::Pattern),
-        IrToken::Phone(Phone::Symbol("f")),
+        IrToken::Phone(Phone::Symbol("f".into())),
     ], lines: ONE }));
 }
 
 #[test]
 fn single_anti_cond() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     assert_eq!(Ok(RuleLine::Rule { rule: SoundChangeRule {
         kind: shift,
-        output: vec![Pattern::new_phone(Phone::Symbol("b"))],
-        pattern: RefCell::new(RulePattern::new(
-            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+        output: vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+        pattern: RulePattern::new(
+            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
             Vec::new(),
             vec![CondPattern::new(
                 CondType::Pattern,
-                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c"))]),
-                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("d"))]),
+                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c".into()))]),
+                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("d".into()))]),
             )]
-        ).expect("pattern construction should be valid")),
+        ).expect("pattern construction should be valid"),
+        first_phones: Some(vec![Phone::Symbol("a".into())]),
+        marked_phones: Vec::new(),
     }, lines: ONE }), build_rule(IrLine::Ir { tokens: vec![
-        IrToken::Phone(Phone::Symbol("a")),
+        IrToken::Phone(Phone::Symbol("a".into())),
         IrToken::Break(Break::Shift(shift)),
-        IrToken::Phone(Phone::Symbol("b")),
+        IrToken::Phone(Phone::Symbol("b".into())),
         IrToken::Break(Break::AntiCond),
-        IrToken::Phone(Phone::Symbol("c")),
+        IrToken::Phone(Phone::Symbol("c".into())),
         IrToken::CondType(CondType::Pattern),
-        IrToken::Phone(Phone::Symbol("d")),
+        IrToken::Phone(Phone::Symbol("d".into())),
     ], lines: ONE }));
 }
 
 #[test]
 fn three_anti_conds() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     assert_eq!(Ok(RuleLine::Rule { rule: SoundChangeRule {
         kind: shift,
-        output: vec![Pattern::new_phone(Phone::Symbol("b"))],
-        pattern: RefCell::new(RulePattern::new(
-            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+        output: vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+        pattern: RulePattern::new(
+            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
             Vec::new(),
             vec![
                 CondPattern::new(
                     CondType::Pattern,
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c"))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c".into()))]),
                     PatternList::default(),
                 ),
                 CondPattern::new(
                     CondType::Pattern,
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("d"))]),
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("e"))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("d".into()))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("e".into()))]),
                 ),
                 CondPattern::new(
                     CondType::Pattern,
                     PatternList::default(),
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("f"))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("f".into()))]),
                 ),
             ]
-        ).expect("pattern construction should be valid")),
+        ).expect("pattern construction should be valid"),
+        first_phones: Some(vec![Phone::Symbol("a".into())]),
+        marked_phones: Vec::new(),
     }, lines: ONE }), build_rule(IrLine::Ir { tokens: vec![
-        IrToken::Phone(Phone::Symbol("a")),
+        IrToken::Phone(Phone::Symbol("a".into())),
         IrToken::Break(Break::Shift(shift)),
-        IrToken::Phone(Phone::Symbol("b")),
+        IrToken::Phone(Phone::Symbol("b".into())),
         IrToken::Break(Break::AntiCond),
-        IrToken::Phone(Phone::Symbol("c")),
+        IrToken::Phone(Phone::Symbol("c".into())),
         IrToken::CondType(CondType::Pattern),
         IrToken::Break(Break::AntiCond),
-        IrToken::Phone(Phone::Symbol("d")),
+        IrToken::Phone(Phone::Symbol("d".into())),
         IrToken::CondType(CondType::Pattern),
-        IrToken::Phone(Phone::Symbol("e")),
+        IrToken::Phone(Phone::Symbol("e".into())),
         IrToken::Break(Break::AntiCond),
         IrToken::CondType(CondType::Pattern),
-        IrToken::Phone(Phone::Symbol("f")),
+        IrToken::Phone(Phone::Symbol("f".into())),
     ], lines: ONE }));
 }
 
 #[test]
 fn cond_and_anti_cond() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     assert_eq!(Ok(RuleLine::Rule { rule: SoundChangeRule {
         kind: shift,
-        output: vec![Pattern::new_phone(Phone::Symbol("b"))],
-        pattern: RefCell::new(RulePattern::new(
-            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+        output: vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+        pattern: RulePattern::new(
+            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
             vec![CondPattern::new (
                 CondType::Pattern,
-                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c"))]),
-                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("d"))]),
+                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c".into()))]),
+                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("d".into()))]),
             )],
             vec![CondPattern::new (
                 CondType::Pattern,
-                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("e"))]),
-                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("f"))]),
+                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("e".into()))]),
+                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("f".into()))]),
             )],
-        ).expect("pattern construction should be valid")),
+        ).expect("pattern construction should be valid"),
+        first_phones: Some(vec![Phone::Symbol("a".into())]),
+        marked_phones: Vec::new(),
     }, lines: ONE }), build_rule(IrLine::Ir { tokens: vec![
-        IrToken::Phone(Phone::Symbol("a")),
+        IrToken::Phone(Phone::Symbol("a".into())),
         IrToken::Break(Break::Shift(shift)),
-        IrToken::Phone(Phone::Symbol("b")),
+        IrToken::Phone(Phone::Symbol("b".into())),
         IrToken::Break(Break::Cond),
-        IrToken::Phone(Phone::Symbol("c")),
+        IrToken::Phone(Phone::Symbol("c".into())),
         IrToken::CondType(CondType::Pattern),
-        IrToken::Phone(Phone::Symbol("d")),
+        IrToken::Phone(Phone::Symbol("d".into())),
         IrToken::Break(Break::AntiCond),
-        IrToken::Phone(Phone::Symbol("e")),
+        IrToken::Phone(Phone::Symbol("e".into())),
         IrToken::CondType(CondType::Pattern),
-        IrToken::Phone(Phone::Symbol("f")),
+        IrToken::Phone(Phone::Symbol("f".into())),
     ], lines: ONE }));
 }
 
 #[test]
 fn three_conds_and_anti_conds() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     assert_eq!(Ok(RuleLine::Rule { rule: SoundChangeRule {
         kind: shift,
-        output: vec![Pattern::new_phone(Phone::Symbol("b"))],
-        pattern: RefCell::new(RulePattern::new(
-            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+        output: vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+        pattern: RulePattern::new(
+            PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
             vec![
                 CondPattern::new(
                     CondType::Pattern,
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c"))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c".into()))]),
                     PatternList::default(),
                 ),
                 CondPattern::new(
                     CondType::Pattern,
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("d"))]),
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("e"))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("d".into()))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("e".into()))]),
                 ),
                 CondPattern::new(
                     CondType::Pattern,
                     PatternList::default(),
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("f"))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("f".into()))]),
                 ),
             ],
             vec![
                 CondPattern::new(
                     CondType::Pattern,
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("g"))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("g".into()))]),
                     PatternList::default(),
                 ),
                 CondPattern::new(
                     CondType::Pattern,
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("h"))]),
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("i"))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("h".into()))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("i".into()))]),
                 ),
                 CondPattern::new(
                     CondType::Pattern,
                     PatternList::default(),
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("j"))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("j".into()))]),
                 ),
             ],
-        ).expect("pattern construction should be valid")),
+        ).expect("pattern construction should be valid"),
+        first_phones: Some(vec![Phone::Symbol("a".into())]),
+        marked_phones: Vec::new(),
     }, lines: ONE }), build_rule(IrLine::Ir { tokens: vec![
-        IrToken::Phone(Phone::Symbol("a")),
+        IrToken::Phone(Phone::Symbol("a".into())),
         IrToken::Break(Break::Shift(shift)),
-        IrToken::Phone(Phone::Symbol("b")),
+        IrToken::Phone(Phone::Symbol("b".into())),
         IrToken::Break(Break::Cond),
-        IrToken::Phone(Phone::Symbol("c")),
+        IrToken::Phone(Phone::Symbol("c".into())),
         IrToken::CondType(CondType::Pattern),
         IrToken::Break(Break::Cond),
-        IrToken::Phone(Phone::Symbol("d")),
+        IrToken::Phone(Phone::Symbol("d".into())),
         IrToken::CondType(CondType::Pattern),
-        IrToken::Phone(Phone::Symbol("e")),
+        IrToken::Phone(Phone::Symbol("e".into())),
         IrToken::Break(Break::Cond),
         IrToken::CondType(CondType::Pattern),
-        IrToken::Phone(Phone::Symbol("f")),
+        IrToken::Phone(Phone::Symbol("f".into())),
         IrToken::Break(Break::AntiCond),
-        IrToken::Phone(Phone::Symbol("g")),
+        IrToken::Phone(Phone::Symbol("g".into())),
         IrToken::CondType(CondType::Pattern),
         IrToken::Break(Break::AntiCond),
-        IrToken::Phone(Phone::Symbol("h")),
+        IrToken::Phone(Phone::Symbol("h".into())),
         IrToken::CondType(CondType::Pattern),
-        IrToken::Phone(Phone::Symbol("i")),
+        IrToken::Phone(Phone::Symbol("i".into())),
         IrToken::Break(Break::AntiCond),
         IrToken::CondType(CondType::Pattern),
-        IrToken::Phone(Phone::Symbol("j")),
+        IrToken::Phone(Phone::Symbol("j".into())),
     ], lines: ONE }));
 }
 
 #[test]
 fn shift_cond_repetition_input() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     assert_eq!(Ok(RuleLine::Rule { rule: SoundChangeRule {
         kind: shift,
         output: Vec::new(),
-        pattern: RefCell::new(
-            RulePattern::new(
+        pattern: RulePattern::new(
                 PatternList::default(),
                 vec![CondPattern::new(
                     CondType::Pattern,
@@ -569,7 +595,9 @@ fn shift_cond_repetition_input() {
                 )],
                 Vec::new(),
             ).expect("pattern construction should be valid")
-        ),
+        ,
+        first_phones: None,
+        marked_phones: Vec::new(),
     }, lines: ONE }), build_rule(IrLine::Ir { tokens: vec![
         IrToken::Break(Break::Shift(shift)),
         IrToken::Break(Break::Cond),
@@ -582,13 +610,12 @@ fn shift_cond_repetition_input() {
 
 #[test]
 fn shift_anti_cond_repetition_input() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     assert_eq!(Ok(RuleLine::Rule { rule: SoundChangeRule {
         kind: shift,
         output: Vec::new(),
-        pattern: RefCell::new(
-            RulePattern::new(
+        pattern: RulePattern::new(
                 PatternList::default(),
                 Vec::new(),
                 vec![CondPattern::new(
@@ -597,7 +624,9 @@ fn shift_anti_cond_repetition_input() {
                     PatternList::default(),
                 )],
             ).expect("pattern construction should be valid")
-        ),
+        ,
+        first_phones: None,
+        marked_phones: Vec::new(),
     }, lines: ONE }), build_rule(IrLine::Ir { tokens: vec![
         IrToken::Break(Break::Shift(shift)),
         IrToken::Break(Break::AntiCond),
@@ -610,26 +639,27 @@ fn shift_anti_cond_repetition_input() {
 
 #[test]
 fn shift_cond_label_repetition_input() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     assert_eq!(Ok(RuleLine::Rule { rule: SoundChangeRule {
         kind: shift,
         output: Vec::new(),
-        pattern: RefCell::new(
-            RulePattern::new(
+        pattern: RulePattern::new(
                 PatternList::default(),
                 vec![CondPattern::new(
                     CondType::Pattern,
-                    PatternList::new(vec![Pattern::new_repetition(Some("label"), PatternList::new(vec![Pattern::new_any(None)]), None)]),
+                    PatternList::new(vec![Pattern::new_repetition(Some("label".into()), PatternList::new(vec![Pattern::new_any(None)]), None)]),
                     PatternList::default(),
                 )],
                 Vec::new(),
             ).expect("pattern construction should be valid")
-        ),
+        ,
+        first_phones: None,
+        marked_phones: Vec::new(),
     }, lines: ONE }), build_rule(IrLine::Ir { tokens: vec![
         IrToken::Break(Break::Shift(shift)),
         IrToken::Break(Break::Cond),
-        IrToken::Label("label"),
+        IrToken::Label("label".into()),
         IrToken::ScopeStart(ScopeType::Repetition),
         IrToken::Any,
         IrToken::ScopeEnd(ScopeType::Repetition),
@@ -639,7 +669,7 @@ fn shift_cond_label_repetition_input() {
 
 #[test]
 fn repetition_with_exclusive() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     assert_eq!(Err((RuleStructureError::EmptyRepetition, ONE)), build_rule(IrLine::Ir { tokens: vec![
         IrToken::Break(Break::Shift(shift)),
@@ -654,8 +684,7 @@ fn repetition_with_exclusive() {
     assert_eq!(Ok(RuleLine::Rule { rule: SoundChangeRule {
         kind: shift,
         output: Vec::new(),
-        pattern: RefCell::new(
-            RulePattern::new(
+        pattern: RulePattern::new(
                 PatternList::default(),
                 vec![CondPattern::new(
                     CondType::Pattern,
@@ -664,7 +693,9 @@ fn repetition_with_exclusive() {
                 )],
                 Vec::new(),
             ).expect("pattern construction should be valid")
-        ),
+        ,
+        first_phones: None,
+        marked_phones: Vec::new(),
     }, lines: ONE }), build_rule(IrLine::Ir { tokens: vec![
         IrToken::Break(Break::Shift(shift)),
         IrToken::Break(Break::Cond),
@@ -701,7 +732,7 @@ fn repetition_with_exclusive() {
 
 #[test]
 fn any_to_any() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
     
     let any = vec![Pattern::new_any(Some(ScopeId::IOUnlabeled { id_num: 0, label_type: LabelType::Any, parent: None }))];
 
@@ -709,13 +740,14 @@ fn any_to_any() {
         Ok(RuleLine::Rule { rule: SoundChangeRule {
             kind: shift,
             output: any.clone(),
-            pattern: RefCell::new(
-                RulePattern::new(
+            pattern: RulePattern::new(
                     PatternList::new(any),
                     Vec::new(),
                     Vec::new(),
                 ).expect("pattern construction should be valid")
-            ),
+            ,
+            first_phones: None,
+            marked_phones: Vec::new(),
         }, lines: ONE }),
         build_rule(IrLine::Ir { tokens: vec![
             IrToken::Any,
@@ -727,7 +759,7 @@ fn any_to_any() {
 
 #[test]
 fn any_any_to_any_any() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
     
     let anys = vec![
         Pattern::new_any(Some(ScopeId::IOUnlabeled { id_num: 0, label_type: LabelType::Any, parent: None })),
@@ -738,13 +770,14 @@ fn any_any_to_any_any() {
         Ok(RuleLine::Rule { rule: SoundChangeRule {
             kind: shift,
             output: anys.clone(),
-            pattern: RefCell::new(
-                RulePattern::new(
+            pattern: RulePattern::new(
                     PatternList::new(anys),
                     Vec::new(),
                     Vec::new(),
                 ).expect("pattern construction should be valid")
-            ),
+            ,
+            first_phones: None,
+            marked_phones: Vec::new(),
         }, lines: ONE }),
         build_rule(IrLine::Ir { tokens: vec![
             IrToken::Any,
@@ -758,27 +791,28 @@ fn any_any_to_any_any() {
 
 #[test]
 fn labeled_any_to_any() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
-    let any = vec![Pattern::new_any(Some(ScopeId::Name("label")))];
+    let any = vec![Pattern::new_any(Some(ScopeId::Name("label".into())))];
 
     assert_eq!(
         Ok(RuleLine::Rule { rule: SoundChangeRule {
             kind: shift,
             output: any.clone(),
-            pattern: RefCell::new(
-                RulePattern::new(
+            pattern: RulePattern::new(
                     PatternList::new(any),
                     Vec::new(),
                     Vec::new(),
                 ).expect("pattern construction should be valid")
-            ),
+            ,
+            first_phones: None,
+            marked_phones: Vec::new(),
         }, lines: ONE }),
         build_rule(IrLine::Ir { tokens: vec![
-            IrToken::Label("label"),
+            IrToken::Label("label".into()),
             IrToken::Any,
             IrToken::Break(Break::Shift(shift)),
-            IrToken::Label("label"),
+            IrToken::Label("label".into()),
             IrToken::Any,
         ], lines: ONE })
     )
@@ -786,7 +820,7 @@ fn labeled_any_to_any() {
 
 #[test]
 fn selections_around_any_to_any() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     let selection = |n, sym| Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol(sym))]], Some(ScopeId::IOUnlabeled { id_num: n, label_type: LabelType::Scope(ScopeType::Selection), parent: None }));
     let any = Pattern::new_any(Some(ScopeId::IOUnlabeled { id_num: 0, label_type: LabelType::Any, parent: None }));
@@ -795,37 +829,38 @@ fn selections_around_any_to_any() {
         Ok(RuleLine::Rule { rule: SoundChangeRule {
             kind: shift,
             output: vec![
-                    selection(0, "c"),
+                    selection(0, "c".into()),
                     any.clone(),
-                    selection(1, "d"),
+                    selection(1, "d".into()),
                 ],
-            pattern: RefCell::new(
-                RulePattern::new(
+            pattern: RulePattern::new(
                     PatternList::new(vec![
-                        selection(0, "a"),
+                        selection(0, "a".into()),
                         any,
-                        selection(1, "b"),
+                        selection(1, "b".into()),
                     ]),
                     Vec::new(),
                     Vec::new(),
                 ).expect("pattern construction should be valid")
-            ),
+            ,
+            first_phones: Some(vec![Phone::Symbol("a".into())]),
+            marked_phones: Vec::new(),
         }, lines: ONE }),
         build_rule(IrLine::Ir { tokens: vec![
             IrToken::ScopeStart(ScopeType::Selection),
-            IrToken::Phone(Phone::Symbol("a")),
+            IrToken::Phone(Phone::Symbol("a".into())),
             IrToken::ScopeEnd(ScopeType::Selection),
             IrToken::Any,
             IrToken::ScopeStart(ScopeType::Selection),
-            IrToken::Phone(Phone::Symbol("b")),
+            IrToken::Phone(Phone::Symbol("b".into())),
             IrToken::ScopeEnd(ScopeType::Selection),
             IrToken::Break(Break::Shift(shift)),
             IrToken::ScopeStart(ScopeType::Selection),
-            IrToken::Phone(Phone::Symbol("c")),
+            IrToken::Phone(Phone::Symbol("c".into())),
             IrToken::ScopeEnd(ScopeType::Selection),
             IrToken::Any,
             IrToken::ScopeStart(ScopeType::Selection),
-            IrToken::Phone(Phone::Symbol("d")),
+            IrToken::Phone(Phone::Symbol("d".into())),
             IrToken::ScopeEnd(ScopeType::Selection),
         ], lines: ONE })
     );
@@ -833,40 +868,42 @@ fn selections_around_any_to_any() {
 
 #[test]
 fn cond_with_scope() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     assert_eq!(
         Ok(RuleLine::Rule { rule: SoundChangeRule {
             kind: shift,
-            output: vec![Pattern::new_phone(Phone::Symbol("b"))],
-            pattern: RefCell::new(RulePattern::new(
-                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+            output: vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+            pattern: RulePattern::new(
+                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
                 vec![CondPattern::new (
                     CondType::Pattern,
                     PatternList::new(vec![Pattern::new_selection(
                         vec![
-                            vec![Pattern::new_phone(Phone::Symbol("c"))],
-                            vec![Pattern::new_phone(Phone::Symbol("d"))],
-                            vec![Pattern::new_phone(Phone::Symbol("e"))],
+                            vec![Pattern::new_phone(Phone::Symbol("c".into()))],
+                            vec![Pattern::new_phone(Phone::Symbol("d".into()))],
+                            vec![Pattern::new_phone(Phone::Symbol("e".into()))],
                         ],
                         None
                     )]),
                     PatternList::default(),
                 )],
                 Vec::new(),
-            ).expect("pattern construction should be valid"))
+            ).expect("pattern construction should be valid"),
+            first_phones: Some(vec![Phone::Symbol("a".into())]),
+            marked_phones: Vec::new(),
         }, lines: ONE }),
         build_rule(IrLine::Ir { tokens: vec![
-            IrToken::Phone(Phone::Symbol("a")),
+            IrToken::Phone(Phone::Symbol("a".into())),
             IrToken::Break(Break::Shift(shift)),
-            IrToken::Phone(Phone::Symbol("b")),
+            IrToken::Phone(Phone::Symbol("b".into())),
             IrToken::Break(Break::Cond),
             IrToken::ScopeStart(ScopeType::Selection),
-            IrToken::Phone(Phone::Symbol("c")),
+            IrToken::Phone(Phone::Symbol("c".into())),
             IrToken::ArgSep,
-            IrToken::Phone(Phone::Symbol("d")),
+            IrToken::Phone(Phone::Symbol("d".into())),
             IrToken::ArgSep,
-            IrToken::Phone(Phone::Symbol("e")),
+            IrToken::Phone(Phone::Symbol("e".into())),
             IrToken::ScopeEnd(ScopeType::Selection),
             IrToken::CondType(CondType::Pattern),
         ], lines: ONE })
@@ -875,80 +912,84 @@ fn cond_with_scope() {
 
 #[test]
 fn anti_cond_with_scope() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     assert_eq!(
         Ok(RuleLine::Rule { rule: SoundChangeRule {
             kind: shift,
-            output: vec![Pattern::new_phone(Phone::Symbol("b"))],
-            pattern: RefCell::new(RulePattern::new(
-                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+            output: vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+            pattern: RulePattern::new(
+                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
                 Vec::new(),
                 vec![CondPattern::new (
                     CondType::Pattern,
-                    PatternList::new(vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("c"))], Some(ScopeId::Name("label")))]),
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("d"))]),
+                    PatternList::new(vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("c".into()))], Some(ScopeId::Name("label".into())))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("d".into()))]),
                 )],
-            ).expect("pattern construction should be valid")),
+            ).expect("pattern construction should be valid"),
+            first_phones: Some(vec![Phone::Symbol("a".into())]),
+            marked_phones: Vec::new(),
         }, lines: ONE }),
         build_rule(IrLine::Ir { tokens: vec![
-            IrToken::Phone(Phone::Symbol("a")),
+            IrToken::Phone(Phone::Symbol("a".into())),
             IrToken::Break(Break::Shift(shift)),
-            IrToken::Phone(Phone::Symbol("b")),
+            IrToken::Phone(Phone::Symbol("b".into())),
             IrToken::Break(Break::AntiCond),
-            IrToken::Label("label"),
+            IrToken::Label("label".into()),
             IrToken::ScopeStart(ScopeType::Optional),
-            IrToken::Phone(Phone::Symbol("c")),
+            IrToken::Phone(Phone::Symbol("c".into())),
             IrToken::ScopeEnd(ScopeType::Optional),
             IrToken::CondType(CondType::Pattern),
-            IrToken::Phone(Phone::Symbol("d")),
+            IrToken::Phone(Phone::Symbol("d".into())),
         ], lines: ONE })
     );
 }
 
 #[test]
 fn equality_cond() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     assert_eq!(
         Ok(RuleLine::Rule { rule: SoundChangeRule {
             kind: shift,
-            output: vec![Pattern::new_phone(Phone::Symbol("b"))],
-            pattern: RefCell::new(RulePattern::new(
-                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+            output: vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+            pattern: RulePattern::new(
+                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
                 vec![CondPattern::new (
                     CondType::Match,
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c"))]),
-                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("d"))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c".into()))]),
+                    PatternList::new(vec![Pattern::new_phone(Phone::Symbol("d".into()))]),
                 )],
                 Vec::new(),
-            ).expect("pattern construction should be valid"))
+            ).expect("pattern construction should be valid"),
+            first_phones: Some(vec![Phone::Symbol("a".into())]),
+            marked_phones: Vec::new(),
         }, lines: ONE }),
         build_rule(IrLine::Ir { tokens: vec![
-            IrToken::Phone(Phone::Symbol("a")),
+            IrToken::Phone(Phone::Symbol("a".into())),
             IrToken::Break(Break::Shift(shift)),
-            IrToken::Phone(Phone::Symbol("b")),
+            IrToken::Phone(Phone::Symbol("b".into())),
             IrToken::Break(Break::Cond),
-            IrToken::Phone(Phone::Symbol("c")),
+            IrToken::Phone(Phone::Symbol("c".into())),
             IrToken::CondType(CondType::Match),
-            IrToken::Phone(Phone::Symbol("d")),
+            IrToken::Phone(Phone::Symbol("d".into())),
         ], lines: ONE })
     );
 }
 
 #[test]
 fn and_cond() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     let mut cond = CondPattern::new(
         CondType::Pattern,
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b"))]),
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b".into()))]),
         PatternList::default(),
     );
 
     cond.add_and(AndType::And, CondPattern::new(
         CondType::Pattern,
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c"))]),
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c".into()))]),
         PatternList::default(),
     ));
 
@@ -956,20 +997,22 @@ fn and_cond() {
         Ok(RuleLine::Rule { rule: SoundChangeRule {
             kind: shift,
             output: Vec::new(),
-            pattern: RefCell::new(RulePattern::new(
-                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+            pattern: RulePattern::new(
+                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
                 vec![cond],
                 Vec::new(),
-            ).expect("pattern construction should be valid"))
+            ).expect("pattern construction should be valid"),
+            first_phones: Some(vec![Phone::Symbol("a".into())]),
+            marked_phones: Vec::new(),
         }, lines: ONE }),
         build_rule(IrLine::Ir { tokens: vec![
-            IrToken::Phone(Phone::Symbol("a")),
+            IrToken::Phone(Phone::Symbol("a".into())),
             IrToken::Break(Break::Shift(shift)),
             IrToken::Break(Break::Cond),
-            IrToken::Phone(Phone::Symbol("b")),
+            IrToken::Phone(Phone::Symbol("b".into())),
             IrToken::CondType(CondType::Pattern),
             IrToken::Break(Break::And(AndType::And)),
-            IrToken::Phone(Phone::Symbol("c")),
+            IrToken::Phone(Phone::Symbol("c".into())),
             IrToken::CondType(CondType::Pattern),
         ], lines: ONE })
     )
@@ -977,17 +1020,17 @@ fn and_cond() {
 
 #[test]
 fn and_anticond() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     let mut cond = CondPattern::new(
         CondType::Pattern,
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b"))]),
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b".into()))]),
         PatternList::default(),
     );
 
     cond.add_and(AndType::And, CondPattern::new(
         CondType::Pattern,
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c"))]),
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c".into()))]),
         PatternList::default(),
     ));
 
@@ -995,20 +1038,22 @@ fn and_anticond() {
         Ok(RuleLine::Rule { rule: SoundChangeRule {
             kind: shift,
             output: Vec::new(),
-            pattern: RefCell::new(RulePattern::new(
-                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+            pattern: RulePattern::new(
+                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
                 Vec::new(),
                 vec![cond],
-            ).expect("pattern construction should be valid"))
+            ).expect("pattern construction should be valid"),
+            first_phones: Some(vec![Phone::Symbol("a".into())]),
+            marked_phones: Vec::new(),
         }, lines: ONE }),
         build_rule(IrLine::Ir { tokens: vec![
-            IrToken::Phone(Phone::Symbol("a")),
+            IrToken::Phone(Phone::Symbol("a".into())),
             IrToken::Break(Break::Shift(shift)),
             IrToken::Break(Break::AntiCond),
-            IrToken::Phone(Phone::Symbol("b")),
+            IrToken::Phone(Phone::Symbol("b".into())),
             IrToken::CondType(CondType::Pattern),
             IrToken::Break(Break::And(AndType::And)),
-            IrToken::Phone(Phone::Symbol("c")),
+            IrToken::Phone(Phone::Symbol("c".into())),
             IrToken::CondType(CondType::Pattern),
         ], lines: ONE })
     )
@@ -1016,23 +1061,23 @@ fn and_anticond() {
 
 #[test]
 fn double_and() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     let mut cond = CondPattern::new(
         CondType::Pattern,
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b"))]),
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b".into()))]),
         PatternList::default(),
     );
 
     cond.add_and(AndType::And, CondPattern::new(
         CondType::Pattern,
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c"))]),
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c".into()))]),
         PatternList::default(),
     ));
 
     cond.add_and(AndType::And, CondPattern::new(
         CondType::Pattern,
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("d"))]),
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("d".into()))]),
         PatternList::default(),
     ));
 
@@ -1040,23 +1085,25 @@ fn double_and() {
         Ok(RuleLine::Rule { rule: SoundChangeRule {
             kind: shift,
             output: Vec::new(),
-            pattern: RefCell::new(RulePattern::new(
-                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+            pattern: RulePattern::new(
+                PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
                 vec![cond],
                 Vec::new(),
-            ).expect("pattern construction should be valid"))
+            ).expect("pattern construction should be valid"),
+            first_phones: Some(vec![Phone::Symbol("a".into())]),
+            marked_phones: Vec::new(),
         }, lines: ONE }),
         build_rule(IrLine::Ir { tokens: vec![
-            IrToken::Phone(Phone::Symbol("a")),
+            IrToken::Phone(Phone::Symbol("a".into())),
             IrToken::Break(Break::Shift(shift)),
             IrToken::Break(Break::Cond),
-            IrToken::Phone(Phone::Symbol("b")),
+            IrToken::Phone(Phone::Symbol("b".into())),
             IrToken::CondType(CondType::Pattern),
             IrToken::Break(Break::And(AndType::And)),
-            IrToken::Phone(Phone::Symbol("c")),
+            IrToken::Phone(Phone::Symbol("c".into())),
             IrToken::CondType(CondType::Pattern),
             IrToken::Break(Break::And(AndType::And)),
-            IrToken::Phone(Phone::Symbol("d")),
+            IrToken::Phone(Phone::Symbol("d".into())),
             IrToken::CondType(CondType::Pattern),
         ], lines: ONE })
     )
@@ -1064,66 +1111,68 @@ fn double_and() {
 
 #[test]
 fn selection_sequence() {
-    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move};
+    let shift = Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false };
 
     let outer_scope_1 = ScopeId::IOUnlabeled { id_num: 0, label_type: LabelType::Scope(ScopeType::Selection), parent: None };
-    let outer_scope_2 = ScopeId::Name("label");
+    let outer_scope_2 = ScopeId::Name("label".into());
     let outer_scope_3 = ScopeId::IOUnlabeled { id_num: 1, label_type: LabelType::Scope(ScopeType::Selection), parent: None };
 
     let expected = RuleLine::Rule { rule: SoundChangeRule {
         kind: shift,
         output: Vec::new(),
-        pattern: RefCell::new(RulePattern::new(
+        pattern: RulePattern::new(
             PatternList::new(vec![
                 Pattern::new_selection(vec![
-                    vec![Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("a"))]], Some(ScopeId::IOUnlabeled { id_num: 0, label_type: LabelType::Scope(ScopeType::Selection), parent: Some(Rc::new(outer_scope_1.clone())) }))],
-                    vec![Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("b"))]], Some(ScopeId::IOUnlabeled { id_num: 1, label_type: LabelType::Scope(ScopeType::Selection), parent: Some(Rc::new(outer_scope_1.clone())) }))],
+                    vec![Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("a".into()))]], Some(ScopeId::IOUnlabeled { id_num: 0, label_type: LabelType::Scope(ScopeType::Selection), parent: Some(Arc::new(outer_scope_1.clone())) }))],
+                    vec![Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("b".into()))]], Some(ScopeId::IOUnlabeled { id_num: 1, label_type: LabelType::Scope(ScopeType::Selection), parent: Some(Arc::new(outer_scope_1.clone())) }))],
                 ], Some(outer_scope_1)),
 
                 Pattern::new_selection(vec![
-                    vec![Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("c"))]], Some(ScopeId::IOUnlabeled { id_num: 0, label_type: LabelType::Scope(ScopeType::Selection), parent: Some(Rc::new(outer_scope_2.clone())) }))],
-                    vec![Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("d"))]], Some(ScopeId::IOUnlabeled { id_num: 1, label_type: LabelType::Scope(ScopeType::Selection), parent: Some(Rc::new(outer_scope_2.clone())) }))],
+                    vec![Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("c".into()))]], Some(ScopeId::IOUnlabeled { id_num: 0, label_type: LabelType::Scope(ScopeType::Selection), parent: Some(Arc::new(outer_scope_2.clone())) }))],
+                    vec![Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("d".into()))]], Some(ScopeId::IOUnlabeled { id_num: 1, label_type: LabelType::Scope(ScopeType::Selection), parent: Some(Arc::new(outer_scope_2.clone())) }))],
                 ], Some(outer_scope_2)),
 
                 Pattern::new_selection(vec![
-                    vec![Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("e"))]], Some(ScopeId::IOUnlabeled { id_num: 0, label_type: LabelType::Scope(ScopeType::Selection), parent: Some(Rc::new(outer_scope_3.clone())) }))],
-                    vec![Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("f"))]], Some(ScopeId::IOUnlabeled { id_num: 1, label_type: LabelType::Scope(ScopeType::Selection), parent: Some(Rc::new(outer_scope_3.clone())) }))],
+                    vec![Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("e".into()))]], Some(ScopeId::IOUnlabeled { id_num: 0, label_type: LabelType::Scope(ScopeType::Selection), parent: Some(Arc::new(outer_scope_3.clone())) }))],
+                    vec![Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("f".into()))]], Some(ScopeId::IOUnlabeled { id_num: 1, label_type: LabelType::Scope(ScopeType::Selection), parent: Some(Arc::new(outer_scope_3.clone())) }))],
                 ], Some(outer_scope_3)),
             ]),
             vec![CondPattern::default()],
             Vec::new(),
-        ).expect("pattern construction should be valid"))
+        ).expect("pattern construction should be valid"),
+        first_phones: Some(vec![Phone::Symbol("a".into()), Phone::Symbol("b".into())]),
+        marked_phones: Vec::new(),
     }, lines: ONE };
 
     let actual = build_rule(IrLine::Ir { tokens: vec![
         IrToken::ScopeStart(ScopeType::Selection),
         IrToken::ScopeStart(ScopeType::Selection),
-        IrToken::Phone(Phone::Symbol("a")),
+        IrToken::Phone(Phone::Symbol("a".into())),
         IrToken::ScopeEnd(ScopeType::Selection),
         IrToken::ArgSep,
         IrToken::ScopeStart(ScopeType::Selection),
-        IrToken::Phone(Phone::Symbol("b")),
+        IrToken::Phone(Phone::Symbol("b".into())),
         IrToken::ScopeEnd(ScopeType::Selection),
         IrToken::ScopeEnd(ScopeType::Selection),
 
-        IrToken::Label("label"),
+        IrToken::Label("label".into()),
         IrToken::ScopeStart(ScopeType::Selection),
         IrToken::ScopeStart(ScopeType::Selection),
-        IrToken::Phone(Phone::Symbol("c")),
+        IrToken::Phone(Phone::Symbol("c".into())),
         IrToken::ScopeEnd(ScopeType::Selection),
         IrToken::ArgSep,
         IrToken::ScopeStart(ScopeType::Selection),
-        IrToken::Phone(Phone::Symbol("d")),
+        IrToken::Phone(Phone::Symbol("d".into())),
         IrToken::ScopeEnd(ScopeType::Selection),
         IrToken::ScopeEnd(ScopeType::Selection),
 
         IrToken::ScopeStart(ScopeType::Selection),
         IrToken::ScopeStart(ScopeType::Selection),
-        IrToken::Phone(Phone::Symbol("e")),
+        IrToken::Phone(Phone::Symbol("e".into())),
         IrToken::ScopeEnd(ScopeType::Selection),
         IrToken::ArgSep,
         IrToken::ScopeStart(ScopeType::Selection),
-        IrToken::Phone(Phone::Symbol("f")),
+        IrToken::Phone(Phone::Symbol("f".into())),
         IrToken::ScopeEnd(ScopeType::Selection),
         IrToken::ScopeEnd(ScopeType::Selection),
 
@@ -1134,4 +1183,59 @@ fn selection_sequence() {
         Ok(expected),
         actual
     )
-}
\ No newline at end of file
+}
+
+#[test]
+fn chain_shift() {
+    let shift = IrToken::Break(Break::Shift(Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false }));
+    let (a, e, i) = (IrToken::Phone(Phone::Symbol("a".into())), IrToken::Phone(Phone::Symbol("e".into())), IrToken::Phone(Phone::Symbol("i".into())));
+    let (start, end) = (IrToken::ScopeStart(ScopeType::Selection), IrToken::ScopeEnd(ScopeType::Selection));
+
+    assert_eq!(
+        build_rule(IrLine::Ir { tokens: vec![start.clone(), a.clone(), IrToken::ArgSep, e.clone(), end.clone(), shift.clone(), start, e.clone(), IrToken::ArgSep, i.clone(), end], lines: ONE }),
+        build_rule(IrLine::Ir { tokens: vec![a.clone(), shift.clone(), e.clone(), shift.clone(), i.clone()], lines: ONE }),
+    );
+
+    assert_eq!(
+        Err((RuleStructureError::MismatchedChainShift(Shift { dir: Direction::Ltr, kind: ShiftType::Move, once: false }, Shift { dir: Direction::Rtl, kind: ShiftType::Move, once: false }), ONE)),
+        build_rule(IrLine::Ir { tokens: vec![a, shift, e, IrToken::Break(Break::Shift(Shift { dir: Direction::Rtl, kind: ShiftType::Move, once: false })), i], lines: ONE }),
+    );
+}
+
+#[test]
+fn first_phones_of_input() {
+    let phone = |sym| Pattern::new_phone(Phone::Symbol(sym));
+
+    // a (b) {c, (d)} e
+    let input = vec![
+        phone("a".into()),
+        Pattern::new_optional(vec![phone("b".into())], None),
+        Pattern::new_selection(vec![vec![phone("c".into())], vec![Pattern::new_optional(vec![phone("d".into())], None)]], None),
+        phone("e".into()),
+    ];
+
+    assert_eq!(first_phones(&input, Direction::Ltr), Some(vec![Phone::Symbol("a".into())]));
+    assert_eq!(first_phones(&input, Direction::Rtl), Some(vec![Phone::Symbol("e".into())]));
+    assert_eq!(first_phones(&input[1..], Direction::Ltr), Some(vec![Phone::Symbol("b".into()), Phone::Symbol("c".into()), Phone::Symbol("d".into()), Phone::Symbol("e".into())]));
+    assert_eq!(first_phones(&input[1..3], Direction::Ltr), None);
+    assert_eq!(first_phones(&[], Direction::Ltr), None);
+    assert_eq!(first_phones(&[phone("a".into()), Pattern::new_any(None)], Direction::Ltr), Some(vec![Phone::Symbol("a".into())]));
+    assert_eq!(first_phones(&[phone("a".into()), Pattern::new_any(None)], Direction::Rtl), None);
+}
+
+#[test]
+fn marked_phones_of_input() {
+    let stressed = Phone::Marked("a".into(), Suprasegmentals { stress: Stress::Primary, ..Default::default() });
+    let long = Phone::Marked("c".into(), Suprasegmentals { length: Length::Long, ..Default::default() });
+
+    // ˈa (b) {cː, (d)} e
+    let input = vec![
+        Pattern::new_phone(stressed.clone()),
+        Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("b".into()))], None),
+        Pattern::new_selection(vec![vec![Pattern::new_phone(long.clone())], vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("d".into()))], None)]], None),
+        Pattern::new_phone(Phone::Symbol("e".into())),
+    ];
+
+    assert_eq!(marked_phones(&input), [stressed, long]);
+    assert_eq!(marked_phones(&input[1..2]), []);
+}
@@ -2,8 +2,8 @@ use crate::{
     applier::ApplicationError,
     matcher::{
         choices::{Choices, OwnedChoices},
-        match_state::MatchState,
-        patterns::{check_box::CheckBox, repetition::Repetition, non_bound::NonBound, optional::Optional, selection::Selection, Pattern},
+        match_state::Matcher,
+        patterns::{check_box::CheckBox, features::FeatureMatrix, repetition::Repetition, non_bound::NonBound, optional::Optional, selection::Selection, Pattern, PatternState},
         phones::Phones
     },
     phones::Phone,
@@ -12,49 +12,63 @@ use crate::{
 
 /// A list of matchable `Pattern`s
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
-pub struct PatternList<'s> {
+pub struct PatternList {
+    patterns: Vec<Pattern>,
+}
+
+/// The state of a `PatternList`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ListState {
     checked_at_initial: bool,
-    patterns: Vec<Pattern<'s>>,
+    patterns: Vec<PatternState>,
+}
+
+impl ListState {
+    /// Sets the flag marking the list as checked at its current position to `false`
+    pub const fn checked_flag_reset(&mut self) {
+        self.checked_at_initial = false;
+    }
 }
 
-impl<'s> PatternList<'s> {
+impl PatternList {
     /// Creates a new `PatternList`
-    pub const fn new(patterns: Vec<Pattern<'s>>) -> Self {
-        Self { patterns, checked_at_initial: false }
+    pub const fn new(patterns: Vec<Pattern>) -> Self {
+        Self { patterns }
     }
 
     /// Gets the inner list of `Pattern`s
-    pub fn inner(&self) -> &[Pattern<'s>] {
+    pub fn inner(&self) -> &[Pattern] {
         &self.patterns
     }
 
-    /// Adds a pattern to the end of the list
-    pub fn push(&mut self, pat: Pattern<'s>) {
-        self.patterns.push(pat);
+    /// Views the list as if its patterns were repeated a number of times
+    pub const fn repeated(&self, times: usize) -> Repeated<'_> {
+        Repeated { list: self, times }
     }
 
-    /// Sets the flag marking the list as checked at its current position to `false`
-    pub const fn checked_flag_reset(&mut self) {
-        self.checked_at_initial = false;
+    /// Adds the states of one more repetition of the list to the state of the list repeated
+    pub(super) fn push_repetition_state(&self, state: &mut ListState) {
+        state.patterns.extend(self.patterns.iter().map(Matcher::new_state));
     }
 
     /// Converts a list of patterns to phones
-    pub fn as_phones<'p>(&self, choices: &Choices<'_, 'p>) -> Result<Vec<Phone<'p>>, ApplicationError<'s>> where 's: 'p {
+    pub fn as_phones(&self, choices: &Choices<'_>) -> Result<Vec<Phone>, ApplicationError> {
         let mut phones = Vec::new();
 
         for pattern in &self.patterns {
             match pattern {
-                Pattern::Phone(CheckBox { unit_state: phone, .. }) => phones.push(*phone),
+                Pattern::Phone(CheckBox { unit_state: phone, .. }) => phones.push(phone.clone()),
 
-                Pattern::NonBound(CheckBox { unit_state: NonBound { id: Some(id) }, ..}) =>
+                Pattern::NonBound(CheckBox { unit_state: NonBound { id: Some(id) }, ..})
+                | Pattern::Features(CheckBox { unit_state: FeatureMatrix { id: Some(id), .. }, ..}) =>
                 if let Some(phone) = choices.any.get(id) {
-                    phones.push(*phone);
+                    phones.push(phone.clone());
                 } else {
                     return Err(ApplicationError::PatternCannotBeConvertedToPhones(pattern.clone()));
                 },
 
                 Pattern::Repetition(Repetition { id: Some(id), .. }) =>
-                match choices.repetition.get(id) {
+                match choices.repetition().get(id) {
                     Some(0) => (),
                     _ => return Err(ApplicationError::PatternCannotBeConvertedToPhones(pattern.clone())),
                 }
@@ -82,41 +96,94 @@ impl<'s> PatternList<'s> {
 
         Ok(phones)
     }
+}
+
+impl Matcher for PatternList {
+    type State = ListState;
+
+    fn new_state(&self) -> ListState {
+        self.repeated(1).new_state()
+    }
+
+    fn matches(&self, state: &ListState, phones: &mut Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
+        self.repeated(1).matches(state, phones, choices)
+    }
 
-    // Recursively determines the next match of a sublist of the `PatternList` 
-    fn next_sub_match<'p>(&mut self, index: usize, phones: &Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> Option<OwnedChoices<'p>> where 's: 'p {
-        if index >= self.patterns.len() {
+    fn next_match(&self, state: &mut ListState, phones: &Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
+        self.repeated(1).next_match(state, phones, choices)
+    }
+
+    fn len(&self, state: &ListState) -> usize {
+        self.repeated(1).len(state)
+    }
+
+    fn reset(&self, state: &mut ListState) {
+        self.repeated(1).reset(state);
+    }
+
+    fn advance_once(&self, state: &mut ListState) {
+        self.repeated(1).advance_once(state);
+    }
+}
+
+/// A `PatternList` whose patterns are repeated a number of times, matched as a single list
+#[derive(Debug, Clone, Copy)]
+pub struct Repeated<'l> {
+    list: &'l PatternList,
+    times: usize,
+}
+
+impl<'l> Repeated<'l> {
+    /// Gets the number of patterns in the repeated list
+    const fn count(&self) -> usize {
+        self.list.patterns.len() * self.times
+    }
+
+    /// Gets the pattern at an index of the repeated list
+    fn get(&self, index: usize) -> &'l Pattern {
+        &self.list.patterns[index % self.list.patterns.len()]
+    }
+
+    // Recursively determines the next match of a sublist of the repeated list
+    fn next_sub_match(&self, state: &mut ListState, index: usize, phones: &Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
+        let count = self.count();
+
+        if index >= count {
             return Some(OwnedChoices::default());
         }
 
         // gets the actual index from the input index based on direction
         // (`index` phones from the initial side)
         let real_index = match phones.direction() {
-            Direction::Ltr => Some(index),
-            Direction::Rtl => Some(self.patterns.len() - 1 - index),
-        }?;
+            Direction::Ltr => index,
+            Direction::Rtl => count - 1 - index,
+        };
 
         loop {
             let mut new_choices = choices.partial_clone();
-            let pat = &mut self.patterns[real_index];
+            let pat = self.get(real_index);
 
             // finds the pattern's next match
-            let pat_choices = pat.next_match(phones, &new_choices)?;
+            let pat_choices = pat.next_match(&mut state.patterns[real_index], phones, &new_choices)?;
             new_choices.take_owned(pat_choices);
 
             // creates the phones for the remaining patterns
             let mut next_phones = *phones;
-            next_phones.skip(pat.len());
+            next_phones.skip(pat.len(&state.patterns[real_index]));
 
-            if let Some(next_choices) = self.next_sub_match(index + 1, &next_phones, &new_choices) {
+            if let Some(next_choices) = self.next_sub_match(state, index + 1, &next_phones, &new_choices) {
                 // if the remaining patterns match there is another match
                 new_choices.take_owned(next_choices);
             } else {
                 // resets all the patterns directionally after the real index
-                match phones.direction() {
-                    Direction::Ltr => self.patterns.get_mut(real_index + 1..).unwrap_or_default(),
-                    Direction::Rtl => &mut self.patterns[..real_index]
-                }.iter_mut().for_each(MatchState::reset);
+                let after = match phones.direction() {
+                    Direction::Ltr => real_index + 1..count,
+                    Direction::Rtl => 0..real_index,
+                };
+
+                for i in after {
+                    self.get(i).reset(&mut state.patterns[i]);
+                }
 
                 continue;
             }
@@ -126,19 +193,28 @@ impl<'s> PatternList<'s> {
     }
 }
 
-impl<'s> MatchState<'s> for PatternList<'s> {
-    fn matches<'p>(&self, phones: &mut Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> Option<OwnedChoices<'p>> where 's: 'p {
+impl Matcher for Repeated<'_> {
+    type State = ListState;
+
+    fn new_state(&self) -> ListState {
+        ListState {
+            checked_at_initial: false,
+            patterns: (0..self.count()).map(|i| self.get(i).new_state()).collect(),
+        }
+    }
+
+    fn matches(&self, state: &ListState, phones: &mut Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
         let mut new_choices = choices.partial_clone();
 
         // matches each pattern and saves the choices
         // if a pattern fails to match, the list fails to match
         match phones.direction() {
-            Direction::Ltr => for pat in &self.patterns {
-                let pattern_choices = pat.matches(phones, &new_choices)?;
+            Direction::Ltr => for i in 0..self.count() {
+                let pattern_choices = self.get(i).matches(&state.patterns[i], phones, &new_choices)?;
                 new_choices.take_owned(pattern_choices);
             },
-            Direction::Rtl => for pat in self.patterns.iter().rev() {
-                let pattern_choices = pat.matches(phones, &new_choices)?;
+            Direction::Rtl => for i in (0..self.count()).rev() {
+                let pattern_choices = self.get(i).matches(&state.patterns[i], phones, &new_choices)?;
                 new_choices.take_owned(pattern_choices);
             },
         }
@@ -146,39 +222,44 @@ impl<'s> MatchState<'s> for PatternList<'s> {
         Some(new_choices.owned_choices())
     }
 
-    fn next_match<'p>(&mut self, phones: &Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> Option<OwnedChoices<'p>> where 's: 'p {
-        if !self.checked_at_initial {
-            self.checked_at_initial = true;
-            if let Some(new_choices) = self.matches(&mut phones.clone(), choices) {
-                self.advance_once();
+    fn next_match(&self, state: &mut ListState, phones: &Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
+        if !state.checked_at_initial {
+            state.checked_at_initial = true;
+            if let Some(new_choices) = self.matches(state, &mut phones.clone(), choices) {
+                self.advance_once(state);
                 return Some(new_choices);
             }
-        } else if self.patterns.is_empty() {
+        } else if self.count() == 0 {
             return None;
         }
 
-        self.next_sub_match(0, phones, choices)
+        self.next_sub_match(state, 0, phones, choices)
     }
 
-    fn len(&self) -> usize {
-        self.patterns.iter().fold(0, |len, pat| len + pat.len())
+    fn len(&self, state: &ListState) -> usize {
+        (0..self.count()).fold(0, |len, i| len + self.get(i).len(&state.patterns[i]))
     }
 
-    fn reset(&mut self) {
-        self.checked_at_initial = false;
-        self.patterns.iter_mut().for_each(MatchState::reset);
+    fn reset(&self, state: &mut ListState) {
+        state.checked_at_initial = false;
+
+        for i in 0..self.count() {
+            self.get(i).reset(&mut state.patterns[i]);
+        }
     }
 
-    fn advance_once(&mut self) {
-        if !self.checked_at_initial {
-            self.checked_at_initial = true;
+    fn advance_once(&self, state: &mut ListState) {
+        if !state.checked_at_initial {
+            state.checked_at_initial = true;
+        }
+
+        if let Some(first) = state.patterns.first_mut() {
+            self.get(0).advance_once(first);
         }
-        
-        self.patterns.first_mut().map(MatchState::advance_once);
     }
 }
 
-impl std::fmt::Display for PatternList<'_> {
+impl std::fmt::Display for PatternList {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let content_fmt = self.patterns.iter()
             .map(ToString::to_string)
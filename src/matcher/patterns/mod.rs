@@ -1,20 +1,27 @@
-use std::cell::RefCell;
+use std::sync::Arc;
 
 use crate::{
     matcher::{
         choices::{Choices, OwnedChoices},
-        match_state::MatchState,
-        patterns::{check_box::CheckBox, repetition::Repetition, list::PatternList, non_bound::NonBound, optional::Optional, selection::Selection},
-        phones::Phones
+        match_state::Matcher,
+        patterns::{
+            check_box::CheckBox, features::FeatureMatrix, repetition::{Repetition, RepetitionState}, list::{ListState, PatternList},
+            non_bound::NonBound, optional::{Optional, OptionalState}, selection::{Selection, SelectionState}, syllable_bound::SyllableBound,
+        },
+        phones::Phones,
+        syllables::Syllabifier,
     },
-    phones::Phone,
-    tokens::ScopeId,
+    phones::{Phone, features::FeatureTable},
+    shared_str::SharedStr,
+    tokens::{FeatureSpec, ScopeId},
 };
 
 pub mod list;
 pub mod cond;
 pub mod rule;
 pub mod non_bound;
+pub mod features;
+pub mod syllable_bound;
 pub mod repetition;
 pub mod optional;
 pub mod selection;
@@ -27,44 +34,46 @@ mod tests;
 
 /// A state machine that can be matched to phones of a specific pattern
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum Pattern<'s> {
-    Phone(CheckBox<'s, Phone<'s>>),
-    NonBound(CheckBox<'s, NonBound<'s>>),
-    Repetition(Repetition<'s>),
-    Optional(Optional<'s>),
-    Selection(Selection<'s>),
-    List(PatternList<'s>),
+pub enum Pattern {
+    Phone(CheckBox<Phone>),
+    NonBound(CheckBox<NonBound>),
+    Features(CheckBox<FeatureMatrix>),
+    SyllableBound(CheckBox<SyllableBound>),
+    Repetition(Repetition),
+    Optional(Optional),
+    Selection(Selection),
+    List(PatternList),
 }
 
-impl<'s> Pattern<'s> {
-    pub const fn new_phone(phone: Phone<'s>) -> Self {
+impl Pattern {
+    pub const fn new_phone(phone: Phone) -> Self {
         Self::Phone(CheckBox::new(phone))
     }
 
-    pub const fn new_any(id: Option<ScopeId<'s>>) -> Self {
+    pub const fn new_any(id: Option<ScopeId>) -> Self {
         Self::NonBound(CheckBox::new(NonBound { id }))
     }
 
-    pub fn new_repetition(id: Option<&'s str>, inclusive: PatternList<'s>, exclusive: Option<PatternList<'s>>) -> Self {
-        Self::Repetition(Repetition {
-            checked_at_zero: false,
-            inclusive, exclusive: exclusive.map(RefCell::new),
-            included: PatternList::default(),
-            inclusions: 0,
-            len: 0,
-            id,
-        })
+    pub const fn new_features(specs: Vec<FeatureSpec>, table: Arc<FeatureTable>, id: Option<ScopeId>) -> Self {
+        Self::Features(CheckBox::new(FeatureMatrix { specs, table, id }))
+    }
+
+    pub const fn new_syllable_bound(syllabifier: Arc<Syllabifier>) -> Self {
+        Self::SyllableBound(CheckBox::new(SyllableBound { syllabifier }))
+    }
+
+    pub fn new_repetition(id: Option<SharedStr>, inclusive: PatternList, exclusive: Option<PatternList>) -> Self {
+        Self::Repetition(Repetition { inclusive, exclusive, id })
     }
 
-    pub const fn new_optional(content: Vec<Pattern<'s>>, id: Option<ScopeId<'s>>) -> Self {
+    pub const fn new_optional(content: Vec<Pattern>, id: Option<ScopeId>) -> Self {
         Self::Optional(Optional {
-            selected: true,
             option: PatternList::new(content),
             id
         })
     }
 
-    pub fn new_selection(options: Vec<Vec<Pattern<'s>>>, id: Option<ScopeId<'s>>) -> Self {
+    pub fn new_selection(options: Vec<Vec<Pattern>>, id: Option<ScopeId>) -> Self {
         let options = options.into_iter()
             .map(PatternList::new)
             .collect::<Vec<_>>();
@@ -75,74 +84,117 @@ impl<'s> Pattern<'s> {
             } else {
                 options
             },
-            selected_index: 0,
             id
         })
     }
 }
 
-impl<'s> MatchState<'s> for Pattern<'s> {
-    fn matches<'p>(&self, phones: &mut Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> Option<OwnedChoices<'p>> where 's: 'p {
+/// The state of a `Pattern`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternState {
+    /// Whether or not a single-phone pattern has been checked
+    Checked(bool),
+    Repetition(RepetitionState),
+    Optional(OptionalState),
+    Selection(SelectionState),
+    List(ListState),
+}
+
+impl Matcher for Pattern {
+    type State = PatternState;
+
+    fn new_state(&self) -> PatternState {
         match self {
-            Self::Phone(phone) => phone.matches(phones, choices),
-            Self::NonBound(any) => any.matches(phones, choices),
-            Self::Repetition(repetition) => repetition.matches(phones, choices),
-            Self::Optional(option) => option.matches(phones, choices),
-            Self::Selection(selection) => selection.matches(phones, choices),
-            Self::List(list) => list.matches(phones, choices),
+            Self::Phone(phone) => PatternState::Checked(phone.new_state()),
+            Self::NonBound(any) => PatternState::Checked(any.new_state()),
+            Self::Features(features) => PatternState::Checked(features.new_state()),
+            Self::SyllableBound(bound) => PatternState::Checked(bound.new_state()),
+            Self::Repetition(repetition) => PatternState::Repetition(repetition.new_state()),
+            Self::Optional(option) => PatternState::Optional(option.new_state()),
+            Self::Selection(selection) => PatternState::Selection(selection.new_state()),
+            Self::List(list) => PatternState::List(list.new_state()),
         }
     }
 
-    fn next_match<'p>(&mut self, phones: &Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> Option<OwnedChoices<'p>> where 's: 'p {
-        match self {
-            Self::Phone(phone) => phone.next_match(phones, choices),
-            Self::NonBound(any) => any.next_match(phones, choices),
-            Self::Repetition(repetition) => repetition.next_match(phones, choices),
-            Self::Optional(option) => option.next_match(phones, choices),
-            Self::Selection(selection) => selection.next_match(phones, choices),
-            Self::List(list) => list.next_match(phones, choices),
+    fn matches(&self, state: &PatternState, phones: &mut Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
+        match (self, state) {
+            (Self::Phone(phone), PatternState::Checked(checked)) => phone.matches(checked, phones, choices),
+            (Self::NonBound(any), PatternState::Checked(checked)) => any.matches(checked, phones, choices),
+            (Self::Features(features), PatternState::Checked(checked)) => features.matches(checked, phones, choices),
+            (Self::SyllableBound(bound), PatternState::Checked(checked)) => bound.matches(checked, phones, choices),
+            (Self::Repetition(repetition), PatternState::Repetition(state)) => repetition.matches(state, phones, choices),
+            (Self::Optional(option), PatternState::Optional(state)) => option.matches(state, phones, choices),
+            (Self::Selection(selection), PatternState::Selection(state)) => selection.matches(state, phones, choices),
+            (Self::List(list), PatternState::List(state)) => list.matches(state, phones, choices),
+            _ => unreachable!("a pattern should only be matched with its own state"),
         }
     }
 
-    fn len(&self) -> usize {
-        match self {
-            Self::Phone(phone) => phone.len(),
-            Self::NonBound(any) => any.len(),
-            Self::Repetition(repetition) => repetition.len(),
-            Self::Optional(option) => option.len(),
-            Self::Selection(selection) => selection.len(),
-            Self::List(list) => list.len(),
+    fn next_match(&self, state: &mut PatternState, phones: &Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
+        match (self, state) {
+            (Self::Phone(phone), PatternState::Checked(checked)) => phone.next_match(checked, phones, choices),
+            (Self::NonBound(any), PatternState::Checked(checked)) => any.next_match(checked, phones, choices),
+            (Self::Features(features), PatternState::Checked(checked)) => features.next_match(checked, phones, choices),
+            (Self::SyllableBound(bound), PatternState::Checked(checked)) => bound.next_match(checked, phones, choices),
+            (Self::Repetition(repetition), PatternState::Repetition(state)) => repetition.next_match(state, phones, choices),
+            (Self::Optional(option), PatternState::Optional(state)) => option.next_match(state, phones, choices),
+            (Self::Selection(selection), PatternState::Selection(state)) => selection.next_match(state, phones, choices),
+            (Self::List(list), PatternState::List(state)) => list.next_match(state, phones, choices),
+            _ => unreachable!("a pattern should only be matched with its own state"),
         }
     }
 
-    fn reset(&mut self) {
-        match self {
-            Self::Phone(phone) => phone.reset(),
-            Self::NonBound(any) => any.reset(),
-            Self::Repetition(repetition) => repetition.reset(),
-            Self::Optional(option) => option.reset(),
-            Self::Selection(selection) => selection.reset(),
-            Self::List(list) => list.reset(),
+    fn len(&self, state: &PatternState) -> usize {
+        match (self, state) {
+            (Self::Phone(phone), PatternState::Checked(checked)) => phone.len(checked),
+            (Self::NonBound(any), PatternState::Checked(checked)) => any.len(checked),
+            (Self::Features(features), PatternState::Checked(checked)) => features.len(checked),
+            (Self::SyllableBound(bound), PatternState::Checked(checked)) => bound.len(checked),
+            (Self::Repetition(repetition), PatternState::Repetition(state)) => repetition.len(state),
+            (Self::Optional(option), PatternState::Optional(state)) => option.len(state),
+            (Self::Selection(selection), PatternState::Selection(state)) => selection.len(state),
+            (Self::List(list), PatternState::List(state)) => list.len(state),
+            _ => unreachable!("a pattern should only be matched with its own state"),
         }
     }
 
-    fn advance_once(&mut self) {
-        match self {
-            Self::Phone(phone) => phone.advance_once(),
-            Self::NonBound(any) => any.advance_once(),
-            Self::Repetition(repetition) => repetition.advance_once(),
-            Self::Optional(option) => option.advance_once(),
-            Self::Selection(selection) => selection.advance_once(),
-            Self::List(list) => list.advance_once(),
+    fn reset(&self, state: &mut PatternState) {
+        match (self, state) {
+            (Self::Phone(phone), PatternState::Checked(checked)) => phone.reset(checked),
+            (Self::NonBound(any), PatternState::Checked(checked)) => any.reset(checked),
+            (Self::Features(features), PatternState::Checked(checked)) => features.reset(checked),
+            (Self::SyllableBound(bound), PatternState::Checked(checked)) => bound.reset(checked),
+            (Self::Repetition(repetition), PatternState::Repetition(state)) => repetition.reset(state),
+            (Self::Optional(option), PatternState::Optional(state)) => option.reset(state),
+            (Self::Selection(selection), PatternState::Selection(state)) => selection.reset(state),
+            (Self::List(list), PatternState::List(state)) => list.reset(state),
+            _ => unreachable!("a pattern should only be matched with its own state"),
+        }
+    }
+
+    fn advance_once(&self, state: &mut PatternState) {
+        match (self, state) {
+            (Self::Phone(phone), PatternState::Checked(checked)) => phone.advance_once(checked),
+            (Self::NonBound(any), PatternState::Checked(checked)) => any.advance_once(checked),
+            (Self::Features(features), PatternState::Checked(checked)) => features.advance_once(checked),
+            (Self::SyllableBound(bound), PatternState::Checked(checked)) => bound.advance_once(checked),
+            (Self::Repetition(repetition), PatternState::Repetition(state)) => repetition.advance_once(state),
+            (Self::Optional(option), PatternState::Optional(state)) => option.advance_once(state),
+            (Self::Selection(selection), PatternState::Selection(state)) => selection.advance_once(state),
+            (Self::List(list), PatternState::List(state)) => list.advance_once(state),
+            _ => unreachable!("a pattern should only be matched with its own state"),
         }
     }
 }
 
-impl std::fmt::Display for Pattern<'_> {
+impl std::fmt::Display for Pattern {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Phone(phone) => write!(f, "{}", phone.unit_state.as_symbol()),
+            Self::Phone(CheckBox { unit_state: Phone::Bound, .. }) => write!(f, "{}", Phone::Bound.as_symbol()),
+            Self::Phone(phone) => write!(f, "{}", phone.unit_state),
             Self::NonBound(any) => write!(f, "{}", any.unit_state),
+            Self::Features(features) => write!(f, "{}", features.unit_state),
+            Self::SyllableBound(bound) => write!(f, "{}", bound.unit_state),
             Self::Repetition(repetition) => write!(f, "{repetition}"),
             Self::Optional(option) => write!(f, "{option}"),
             Self::Selection(selection) => write!(f, "{selection}"),
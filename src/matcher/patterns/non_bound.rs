@@ -8,12 +8,12 @@ use crate::{
 /// 
 /// Should be used in a `CheckBox`
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
-pub struct NonBound<'s> {
-    pub id: Option<ScopeId<'s>>,
+pub struct NonBound {
+    pub id: Option<ScopeId>,
 }
 
-impl<'s> UnitState<'s> for NonBound<'s> {
-    fn matches<'p>(&self, phones: &mut Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> Option<OwnedChoices<'p>> where 's: 'p {
+impl UnitState for NonBound {
+    fn matches(&self, phones: &mut Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
         let phone = phones.next();
         let mut new_choices = choices.partial_clone();
 
@@ -28,7 +28,7 @@ impl<'s> UnitState<'s> for NonBound<'s> {
                 }
             } else if !phone.is_bound() {
                 // if the phone isn't a bound the choice is made
-                new_choices.any.to_mut().insert(id.clone(), *phone);
+                new_choices.any.to_mut().insert(id.clone(), phone.clone());
                 Some(new_choices.owned_choices())
             } else {
                 None
@@ -44,7 +44,7 @@ impl<'s> UnitState<'s> for NonBound<'s> {
     fn len(&self) -> usize { 1 }
 }
 
-impl std::fmt::Display for NonBound<'_> {
+impl std::fmt::Display for NonBound {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if let Some(id) = &self.id {
             write!(f, "{id}")?;
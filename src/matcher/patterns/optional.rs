@@ -1,8 +1,8 @@
 use crate::{
     matcher::{
         choices::{Choices, OwnedChoices},
-        match_state::MatchState,
-        patterns::list::PatternList,
+        match_state::Matcher,
+        patterns::list::{ListState, PatternList},
         phones::Phones,
     },
     tokens::{ScopeId, ScopeType}
@@ -10,25 +10,37 @@ use crate::{
 
 /// A pattern the represents the potential of a sub-pattern
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Optional<'s> {
-    pub(super) selected: bool,
-    pub option: PatternList<'s>,
-    pub id: Option<ScopeId<'s>>,
+pub struct Optional {
+    pub option: PatternList,
+    pub id: Option<ScopeId>,
 }
 
-impl<'s> MatchState<'s> for Optional<'s> {
-    fn matches<'p>(&self, phones: &mut Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> Option<OwnedChoices<'p>> where 's: 'p {
+/// The state of an `Optional`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptionalState {
+    selected: bool,
+    option: ListState,
+}
+
+impl Matcher for Optional {
+    type State = OptionalState;
+
+    fn new_state(&self) -> OptionalState {
+        OptionalState { selected: true, option: self.option.new_state() }
+    }
+
+    fn matches(&self, state: &OptionalState, phones: &mut Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
         if let Some(id) = &self.id {
             if let Some(choice) = choices.optional.get(id).copied() {
                 // if choice and selection do not align, the match fails
-                if self.selected != choice {
+                if state.selected != choice {
                     return None;
                 }
 
                 // checks the match if the choice aligns with the selection
                 if choice {
                     // checks if the option matches
-                    self.option.matches(phones, choices)
+                    self.option.matches(&state.option, phones, choices)
                 } else {
                     // if the option is not inserted the pattern matches
                     Some(OwnedChoices::default())
@@ -36,30 +48,30 @@ impl<'s> MatchState<'s> for Optional<'s> {
             } else {
                 // chooses the selection and checks it
                 let mut new_choices = choices.partial_clone();
-                new_choices.optional.to_mut().insert(id.clone(), self.selected);
+                new_choices.optional.to_mut().insert(id.clone(), state.selected);
 
                 // checks if the option matches with the new selection
-                if self.selected {
-                    let internal_choices = self.option.matches(phones, &new_choices)?;
+                if state.selected {
+                    let internal_choices = self.option.matches(&state.option, phones, &new_choices)?;
                     new_choices.take_owned(internal_choices);
                 }
 
                 Some(new_choices.owned_choices())
             }
-        } else if self.selected {
+        } else if state.selected {
             // checks if the option matches
-            self.option.matches(phones, choices)
+            self.option.matches(&state.option, phones, choices)
         } else {
             // if the option is not inserted the pattern matches
             Some(OwnedChoices::default())
         }
     }
 
-    fn next_match<'p>(&mut self, phones: &Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> Option<OwnedChoices<'p>> where 's: 'p {
-        if self.selected {
+    fn next_match(&self, state: &mut OptionalState, phones: &Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
+        if state.selected {
             loop {
-                if self.option.next_match(phones, choices).is_some() {
-                    if let Some(new_choices) = self.matches(&mut phones.clone(), choices) {
+                if self.option.next_match(&mut state.option, phones, choices).is_some() {
+                    if let Some(new_choices) = self.matches(state, &mut phones.clone(), choices) {
                         return Some(new_choices);
                     }
 
@@ -69,34 +81,34 @@ impl<'s> MatchState<'s> for Optional<'s> {
                 break;
             }
 
-            self.selected = false;
-            self.option.reset();
+            state.selected = false;
+            self.option.reset(&mut state.option);
         } else {
             return None
         }
             
-        self.matches(&mut phones.clone(), choices)
+        self.matches(state, &mut phones.clone(), choices)
     }
 
-    fn len(&self) -> usize {
-        if self.selected {
-            self.option.len()
+    fn len(&self, state: &OptionalState) -> usize {
+        if state.selected {
+            self.option.len(&state.option)
         } else{
             0
         }
     }
 
-    fn reset(&mut self) {
-        self.selected = true;
-        self.option.reset();
+    fn reset(&self, state: &mut OptionalState) {
+        state.selected = true;
+        self.option.reset(&mut state.option);
     }
 
-    fn advance_once(&mut self) {
-        self.option.advance_once();
+    fn advance_once(&self, state: &mut OptionalState) {
+        self.option.advance_once(&mut state.option);
     }
 }
 
-impl std::fmt::Display for Optional<'_> {
+impl std::fmt::Display for Optional {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if let Some(id) = &self.id {
             write!(f, "{id}")?;
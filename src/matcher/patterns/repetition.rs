@@ -1,39 +1,45 @@
-use std::cell::RefCell;
-
 use crate::{
     keywords::{REPETITION_END_CHAR, REPETITION_START_CHAR, NOT_CHAR},
-    matcher::{choices::{Choices, OwnedChoices}, match_state::MatchState, patterns::list::PatternList, phones::Phones},
+    matcher::{choices::{Choices, OwnedChoices}, match_state::Matcher, patterns::list::{ListState, PatternList}, phones::Phones},
+    shared_str::SharedStr,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Repetition<'s> {
-    pub(super) checked_at_zero: bool,
-    pub(super) inclusive: PatternList<'s>,
-    pub(super) exclusive: Option<RefCell<PatternList<'s>>>,
-    pub(super) included: PatternList<'s>,
-    pub(super) inclusions: usize,
-    pub(super) len: usize,
-    pub(super) id: Option<&'s str>,
+pub struct Repetition {
+    pub inclusive: PatternList,
+    pub exclusive: Option<PatternList>,
+    pub id: Option<SharedStr>,
+}
+
+/// The state of a `Repetition`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RepetitionState {
+    checked_at_zero: bool,
+    included: ListState,
+    inclusions: usize,
+    len: usize,
 }
 
-impl<'s> Repetition<'s> {
-    fn get_max_len(&self, choices: &Choices<'_, '_>) -> Option<usize> {
-        self.id.and_then(|id| choices.repetition.get(id)).copied()
+impl Repetition {
+    fn get_max_len(&self, choices: &Choices<'_>) -> Option<usize> {
+        self.id.as_ref().and_then(|id| choices.repetition.get(id)).copied()
     }
 
-    fn exclusive_matches<'p>(&self, phones: &mut Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> bool where 's: 'p {
-        let max_ex_len = self.get_max_len(choices).unwrap_or(self.len);
+    fn exclusive_matches(&self, state: &RepetitionState, phones: &mut Phones<'_>, choices: &Choices<'_>) -> bool {
+        let max_ex_len = self.get_max_len(choices).unwrap_or(state.len);
 
-        if let Some(mut exclusive) = self.exclusive.as_ref().map(RefCell::borrow_mut) {
-            for i in 0..self.len {
-                while exclusive.next_match(phones, choices).is_some() {
-                    if i + exclusive.len() <= max_ex_len {
-                        exclusive.reset();
+        // the exclusion is matched with its own state so that the repetition's state is not changed
+        if let Some(exclusive) = &self.exclusive {
+            let mut exclusive_state = exclusive.new_state();
+
+            for i in 0..state.len {
+                while exclusive.next_match(&mut exclusive_state, phones, choices).is_some() {
+                    if i + exclusive.len(&exclusive_state) <= max_ex_len {
                         return true
                     }
                 }
                 
-                exclusive.reset();
+                exclusive.reset(&mut exclusive_state);
                 _ = phones.next();
             }
         }
@@ -41,8 +47,10 @@ impl<'s> Repetition<'s> {
         false
     }
 
-    fn included_matches<'p>(&self, phones: &mut Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> Option<OwnedChoices<'p>> where 's: 'p {
-        if self.included.len() == self.len && let Some(new_choices) = self.included.matches(phones, choices) {
+    fn included_matches(&self, state: &RepetitionState, phones: &mut Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
+        let included = self.inclusive.repeated(state.inclusions);
+
+        if included.len(&state.included) == state.len && let Some(new_choices) = included.matches(&state.included, phones, choices) {
             Some(new_choices)
         } else {
             None
@@ -50,17 +58,23 @@ impl<'s> Repetition<'s> {
     }
 }
 
-impl<'s> MatchState<'s> for Repetition<'s> {
-    fn matches<'p>(&self, phones: &mut Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> Option<OwnedChoices<'p>> where 's: 'p {
-        if self.exclusive_matches(&mut phones.clone(), choices) {
+impl Matcher for Repetition {
+    type State = RepetitionState;
+
+    fn new_state(&self) -> RepetitionState {
+        RepetitionState::default()
+    }
+
+    fn matches(&self, state: &RepetitionState, phones: &mut Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
+        if self.exclusive_matches(state, &mut phones.clone(), choices) {
             None
         } else {
-            self.included_matches(phones, choices)
+            self.included_matches(state, phones, choices)
         }
     }
 
-    fn next_match<'p>(&mut self, phones: &Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> Option<OwnedChoices<'p>> where 's: 'p {
-        if self.checked_at_zero || self.id.as_ref().map(|id| choices.repetition.contains_key(id)).is_some_and(|exists| exists) {
+    fn next_match(&self, state: &mut RepetitionState, phones: &Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
+        if state.checked_at_zero || self.id.as_ref().map(|id| choices.repetition.contains_key(id)).is_some_and(|exists| exists) {
             let mut new_choices = choices.partial_clone();
 
             // gets the maximum length of the repetition
@@ -71,19 +85,21 @@ impl<'s> MatchState<'s> for Repetition<'s> {
 
             // checks each varient up to the maximum length 
             loop {
-                if self.exclusive_matches(&mut phones.clone(), choices) {
+                if self.exclusive_matches(state, &mut phones.clone(), choices) {
                     return None;
                 }
 
-                if let Some(included_choices) = self.included.next_match(phones, &new_choices) {
+                let included = self.inclusive.repeated(state.inclusions);
+
+                if let Some(included_choices) = included.next_match(&mut state.included, phones, &new_choices) {
                     let mut choices = new_choices.partial_clone();
                     choices.take_owned(included_choices);
 
-                    if let Some(match_choices) = self.included_matches(&mut phones.clone(), &choices) {
+                    if let Some(match_choices) = self.included_matches(state, &mut phones.clone(), &choices) {
                         choices.take_owned(match_choices);
 
                         if let Some(id) = &self.id && !choices.repetition.contains_key(id) {
-                            choices.repetition.to_mut().insert(id, self.len);
+                            choices.repetition.to_mut().insert(id.clone(), state.len);
                         }
 
                         new_choices.take_owned(choices.owned_choices());
@@ -91,18 +107,16 @@ impl<'s> MatchState<'s> for Repetition<'s> {
                         return Some(new_choices.owned_choices());
                     }
                 } else {
-                    self.included.reset();
-                    for pat in self.inclusive.inner() {
-                        self.included.push(pat.clone());
-                    }
-                    self.inclusions += 1;
+                    included.reset(&mut state.included);
+                    self.inclusive.push_repetition_state(&mut state.included);
+                    state.inclusions += 1;
 
-                    if self.inclusions > max_len {
-                        self.len += 1;
-                        self.included = PatternList::default();
-                        self.inclusions = 0;
+                    if state.inclusions > max_len {
+                        state.len += 1;
+                        state.included = ListState::default();
+                        state.inclusions = 0;
 
-                        if self.len > max_len {
+                        if state.len > max_len {
                             break;
                         }
                     }
@@ -112,14 +126,14 @@ impl<'s> MatchState<'s> for Repetition<'s> {
             None
         } else {
             // checks with a length of zero
-            self.checked_at_zero = true;
-            self.len = 0;
-            self.inclusions = 0;
-            self.included = PatternList::default();
+            state.checked_at_zero = true;
+            state.len = 0;
+            state.inclusions = 0;
+            state.included = ListState::default();
 
-            if let Some(id) = self.id {
+            if let Some(id) = &self.id {
                 let mut new_choices = choices.partial_clone();
-                new_choices.repetition.to_mut().insert(id, self.len);
+                new_choices.repetition.to_mut().insert(id.clone(), state.len);
                 
                 Some(new_choices.owned_choices())
             } else {
@@ -128,25 +142,25 @@ impl<'s> MatchState<'s> for Repetition<'s> {
         }
     }
 
-    fn len(&self) -> usize {
-        self.len
+    fn len(&self, state: &RepetitionState) -> usize {
+        state.len
     }
 
-    fn reset(&mut self) {
-        self.checked_at_zero = false;
-        self.len = 0;
-        self.included = PatternList::default();
-        self.inclusions = 0;
+    fn reset(&self, state: &mut RepetitionState) {
+        state.checked_at_zero = false;
+        state.len = 0;
+        state.included = ListState::default();
+        state.inclusions = 0;
     }
 
-    fn advance_once(&mut self) {
-        if !self.checked_at_zero {
-            self.checked_at_zero = true;
+    fn advance_once(&self, state: &mut RepetitionState) {
+        if !state.checked_at_zero {
+            state.checked_at_zero = true;
         }
     }
 }
 
-impl std::fmt::Display for Repetition<'_> {
+impl std::fmt::Display for Repetition {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if let Some(id) = &self.id {
             write!(f, "{id}")?;
@@ -155,7 +169,7 @@ impl std::fmt::Display for Repetition<'_> {
         write!(f, "{REPETITION_START_CHAR} {} ", self.inclusive)?;
 
         if let Some(exclusive) = &self.exclusive {
-            write!(f, "{NOT_CHAR} {} ", exclusive.borrow())?;
+            write!(f, "{NOT_CHAR} {exclusive} ")?;
         }
 
         write!(f, "{REPETITION_END_CHAR}")
@@ -1,38 +1,52 @@
-use std::{cell::RefCell, fmt::Write as _};
+use std::{fmt::Write as _, sync::Arc};
 
 use crate::{
     applier::ApplicationError,
     ir::tokens::{Break, IrToken},
     matcher::{
         choices::{Choices, OwnedChoices},
-        match_state::MatchState,
+        match_state::Matcher,
         patterns::{
-            cond::{CondPattern, CondPhoneInput},
+            cond::{CondPattern, CondPhoneInput, CondState},
             ir_to_patterns::RuleStructureError,
-            list::PatternList,
+            list::{ListState, PatternList},
             optional::Optional,
             selection::Selection,
             Pattern,
         },
         phones::Phones,
+        syllables::Syllabifier,
     },
+    phones::Phone,
     tokens::{Direction, Shift}
 };
 
 /// A matchable pattern for a rule
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct RulePattern<'s> {
-    input: PatternList<'s>,
-    conds: Vec<CondPattern<'s>>,
-    anti_conds: Vec<CondPattern<'s>>,
+pub struct RulePattern {
+    input: PatternList,
+    conds: Vec<CondPattern>,
+    anti_conds: Vec<CondPattern>,
+    /// The syllabifier used by the syllable boundaries in the pattern
+    syllabifier: Option<Arc<Syllabifier>>,
 }
 
-fn contains_repetition(tokens: &PatternList<'_>) -> bool {
+/// The progress of a search for matches of a `RulePattern`
+///
+/// A state is created once per application of a rule, so that the rule itself is never changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchState {
+    input: ListState,
+    conds: Vec<CondState>,
+    anti_conds: Vec<CondState>,
+}
+
+fn contains_repetition(tokens: &PatternList) -> bool {
     for token in tokens.inner() {
         match token {
             Pattern::Repetition { .. } => return true,
             Pattern::Optional(Optional { option, ..}) if contains_repetition(option) => return true,
-            Pattern::Selection(Selection { options, .. }) if options.iter().any(|tokens| contains_repetition(tokens)) => return true,
+            Pattern::Selection(Selection { options, .. }) if options.iter().any(contains_repetition) => return true,
             _ => (),
         }
     }
@@ -40,8 +54,8 @@ fn contains_repetition(tokens: &PatternList<'_>) -> bool {
     false
 }
 
-impl<'s> RulePattern<'s> {
-    pub fn new(input: PatternList<'s>, mut conds: Vec<CondPattern<'s>>, anti_conds: Vec<CondPattern<'s>>) -> Result<Self, RuleStructureError<'s>> {
+impl RulePattern {
+    pub fn new(input: PatternList, mut conds: Vec<CondPattern>, anti_conds: Vec<CondPattern>) -> Result<Self, RuleStructureError> {
         if contains_repetition(&input) {
             return Err(RuleStructureError::RepetitionOutOfCond);
         }
@@ -54,37 +68,58 @@ impl<'s> RulePattern<'s> {
             input,
             conds,
             anti_conds,
+            syllabifier: None,
         })
     }
 
-    pub const fn input(&self) -> &PatternList<'s> {
+    /// Sets the syllabifier used by the syllable boundaries in the pattern,
+    /// so that the boundaries are found once per change to the phones instead of once per check
+    #[must_use]
+    pub fn with_syllabifier(mut self, syllabifier: Option<Arc<Syllabifier>>) -> Self {
+        self.syllabifier = syllabifier;
+        self
+    }
+
+    pub const fn syllabifier(&self) -> Option<&Arc<Syllabifier>> {
+        self.syllabifier.as_ref()
+    }
+
+    pub const fn input(&self) -> &PatternList {
         &self.input
     }
 
-    pub fn conds(&self) -> &[CondPattern<'s>] {
+    pub fn conds(&self) -> &[CondPattern] {
         &self.conds
     }
 
-    pub fn anti_conds(&self) -> &[CondPattern<'s>] {
+    pub fn anti_conds(&self) -> &[CondPattern] {
         &self.anti_conds
     }
     
-    pub fn next_match<'p>(&mut self, phones: &Phones<'_, 'p>) -> Result<Option<OwnedChoices<'p>>, ApplicationError<'s>> where 's: 'p {
+    /// Creates a matching state in its default position
+    pub fn new_state(&self) -> MatchState {
+        MatchState {
+            input: self.input.new_state(),
+            conds: self.conds.iter().map(CondPattern::new_state).collect(),
+            anti_conds: self.anti_conds.iter().map(CondPattern::new_state).collect(),
+        }
+    }
+
+    pub fn next_match(&self, state: &mut MatchState, phones: &Phones<'_>) -> Result<Option<OwnedChoices>, ApplicationError> {
         let mut new_choices = Choices::default();
 
         loop {
             // checks the input
-            let Some(input_choices) = self.input.next_match(phones, &new_choices) else {
+            let Some(input_choices) = self.input.next_match(&mut state.input, phones, &new_choices) else {
                 return Ok(None);
             };
-            self.conds.iter_mut().for_each(CondPattern::reset);
-            self.anti_conds.iter_mut().for_each(CondPattern::reset);
+            self.reset_conds(state);
 
             new_choices.take_owned(input_choices);
 
             // prepares to create condition phones
             let mut after_input_phones = *phones;
-            after_input_phones.skip(self.input.len());
+            after_input_phones.skip(self.input.len(&state.input));
 
             // creates the phone iterators for the conditions
             let cond_phones = match phones.direction() {
@@ -99,55 +134,70 @@ impl<'s> RulePattern<'s> {
             };
 
             // checks each condition agains each anti-condition
-            for cond in &mut self.conds {
+            for (cond, cond_state) in self.conds.iter().zip(&mut state.conds) {
                 // checks each match of each condition agains each anti-condition
-                'cond_loop: while let Some(cond_choices) = cond.next_match(&cond_phones, &new_choices)? {
+                'cond_loop: while let Some(cond_choices) = cond.next_match(cond_state, &cond_phones, &new_choices)? {
                     let mut post_cond_choices = new_choices.partial_clone();
                     post_cond_choices.take_owned(cond_choices.clone());
 
                     // checks agains each anti-condition
-                    for anti_cond in &mut self.anti_conds {
+                    for (anti_cond, anti_cond_state) in self.anti_conds.iter().zip(&mut state.anti_conds) {
                         // if an anti-condition matches, checks the next match of the condition
-                        if anti_cond.next_match(&cond_phones, &post_cond_choices)?.is_some() {
-                            anti_cond.reset();
+                        if anti_cond.next_match(anti_cond_state, &cond_phones, &post_cond_choices)?.is_some() {
+                            anti_cond.reset(anti_cond_state);
                             continue 'cond_loop;
                         }
 
-                        anti_cond.reset();
+                        anti_cond.reset(anti_cond_state);
                     }
                     new_choices.take_owned(post_cond_choices.owned_choices());
-                    cond.reset();
+                    cond.reset(cond_state);
                     return Ok(Some(new_choices.owned_choices()));
                 }
 
-                cond.reset();
+                cond.reset(cond_state);
             }
         }
     }
 
-    pub fn len(&self) -> usize {
-        self.input.len()
+    pub fn len(&self, state: &MatchState) -> usize {
+        self.input.len(&state.input)
+    }
+
+    pub fn reset(&self, state: &mut MatchState) {
+        self.input.reset(&mut state.input);
+        self.reset_conds(state);
     }
 
-    pub fn reset(&mut self) {
-        self.input.reset();
-        self.conds.iter_mut().for_each(CondPattern::reset);
-        self.anti_conds.iter_mut().for_each(CondPattern::reset);
+    /// Resets the states of the conditions and anti-conditions
+    fn reset_conds(&self, state: &mut MatchState) {
+        self.conds.iter().zip(&mut state.conds).for_each(|(cond, cond_state)| cond.reset(cond_state));
+        self.anti_conds.iter().zip(&mut state.anti_conds).for_each(|(anti_cond, anti_cond_state)| anti_cond.reset(anti_cond_state));
     }
 }
 
 /// A collection of data that define a sound change rule
+/// 
+/// The rule is never changed by application,
+/// the progress of matching is kept in a separate `MatchState`,
+/// so a rule may be applied on several threads at once
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct SoundChangeRule<'s> {
+pub struct SoundChangeRule {
     pub kind: Shift,
     /// The tokens that represent what should replace the input
-    pub output: Vec<Pattern<'s>>,
-    pub pattern: RefCell<RulePattern<'s>>,
+    pub output: Vec<Pattern>,
+    /// The pattern matched by the rule
+    pub pattern: RulePattern,
+    /// Every phone a match of the input can start with in the direction of the rule,
+    /// `None` if a match may start with any phone
+    pub first_phones: Option<Vec<Phone>>,
+    /// Every phone written in the input with suprasegmentals marked on it, in the order they are written
+    pub marked_phones: Vec<Phone>,
 }
 
-impl std::fmt::Display for SoundChangeRule<'_> {
+impl std::fmt::Display for SoundChangeRule {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let input = self.pattern.borrow()
+        let input = self.pattern
             .input()
             .inner()
             .iter()
@@ -161,16 +211,21 @@ impl std::fmt::Display for SoundChangeRule<'_> {
             .collect::<Vec<_>>()
             .join(" ");
 
-        let mut conds = String::new();
-        for cond in self.pattern.borrow().conds() {
-            _ = write!(conds, " {} {cond}", IrToken::Break(Break::Cond));
+        let mut rule = [input, self.kind.to_string(), output]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        // the default condition matches everywhere, so it is not written
+        for cond in self.pattern.conds().iter().filter(|cond| !cond.is_unconditional()) {
+            _ = write!(rule, " {} {cond}", IrToken::Break(Break::Cond));
         }
 
-        let mut anti_conds = String::new();
-        for anti_cond in self.pattern.borrow().anti_conds() {
-            _ = write!(anti_conds, " {} {anti_cond}", IrToken::Break(Break::AntiCond));
+        for anti_cond in self.pattern.anti_conds() {
+            _ = write!(rule, " {} {anti_cond}", IrToken::Break(Break::AntiCond));
         }
-        
-        write!(f, "{} {} {}{}{}", input, &self.kind, output, conds, anti_conds)
+
+        write!(f, "{rule}")
     }
 }
\ No newline at end of file
@@ -1,51 +1,63 @@
 use crate::{
     keywords::ARG_SEP_CHAR,
     matcher::{choices::{Choices, OwnedChoices},
-    match_state::MatchState,
-    patterns::list::PatternList, phones::Phones},
+    match_state::Matcher,
+    patterns::list::{ListState, PatternList}, phones::Phones},
     tokens::ScopeId,
     tokens::ScopeType,
 };
 
 /// A pattern that repersents one of its sub-patterns
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Selection<'s> {
+pub struct Selection {
     /// should always contain at least one item
-    pub options: Vec<PatternList<'s>>,
-    pub(super) selected_index: usize,
-    pub id: Option<ScopeId<'s>>,
+    pub options: Vec<PatternList>,
+    pub id: Option<ScopeId>,
 }
 
-impl<'s> MatchState<'s>  for Selection<'s> {
-    fn matches<'p>(&self, phones: &mut Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> Option<OwnedChoices<'p>> where 's: 'p {
+/// The state of a `Selection`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectionState {
+    selected_index: usize,
+    options: Vec<ListState>,
+}
+
+impl Matcher  for Selection {
+    type State = SelectionState;
+
+    fn new_state(&self) -> SelectionState {
+        SelectionState { selected_index: 0, options: self.options.iter().map(Matcher::new_state).collect() }
+    }
+
+    fn matches(&self, state: &SelectionState, phones: &mut Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
         let mut new_choices = choices.partial_clone();
 
         if let Some(id) = &self.id {
             if let Some(choice) = choices.selection.get(id).copied() {
-                if self.selected_index != choice {
+                if state.selected_index != choice {
                     // selections cannot be changed
                     return None;
                 }
 
                 // checks if the choice matches
                 let option = self.options.get(choice)?;
-                let internal_choices = option.matches(phones, &new_choices)?;
+                let internal_choices = option.matches(state.options.get(choice)?, phones, &new_choices)?;
 
                 new_choices.take_owned(internal_choices);
             } else {
                 // chooses the current index
-                let option = self.options.get(self.selected_index)?;
+                let option = self.options.get(state.selected_index)?;
 
-                new_choices.selection.to_mut().insert(id.clone(), self.selected_index);
+                new_choices.selection.to_mut().insert(id.clone(), state.selected_index);
 
-                let internal_choices = option.matches(phones, &new_choices)?;
+                let internal_choices = option.matches(state.options.get(state.selected_index)?, phones, &new_choices)?;
 
                 new_choices.take_owned(internal_choices);
             }
         } else {
             // checks if the selection matches
-            let option = self.options.get(self.selected_index)?;
-            let internal_choices = option.matches(phones, &new_choices)?;
+            let option = self.options.get(state.selected_index)?;
+            let internal_choices = option.matches(state.options.get(state.selected_index)?, phones, &new_choices)?;
 
             new_choices.take_owned(internal_choices);
         }
@@ -53,12 +65,12 @@ impl<'s> MatchState<'s>  for Selection<'s> {
         Some(new_choices.owned_choices())
     }
 
-    fn next_match<'p>(&mut self, phones: &Phones<'_, 'p>, choices: &Choices<'_, 'p>) -> Option<OwnedChoices<'p>> where 's: 'p {
+    fn next_match(&self, state: &mut SelectionState, phones: &Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
         loop {
             // checks if the option has a next match form
-            if self.options.get_mut(self.selected_index)?.next_match(phones, choices).is_some() {
+            if self.options.get(state.selected_index)?.next_match(state.options.get_mut(state.selected_index)?, phones, choices).is_some() {
                 // checks if the pattern matches
-                if let Some(new_choices) = self.matches(&mut phones.clone(), choices) {
+                if let Some(new_choices) = self.matches(state, &mut phones.clone(), choices) {
                     return Some(new_choices);
                 }
                 
@@ -66,30 +78,35 @@ impl<'s> MatchState<'s>  for Selection<'s> {
             }
             
             // if there is not another match, moves to the next option
-            self.selected_index += 1;
+            state.selected_index += 1;
 
             // if the next match is invalid, the match fails
-            if self.selected_index >= self.options.len() {
+            if state.selected_index >= self.options.len() {
                 return None;
             }
         }
     }
 
-    fn len(&self) -> usize {
-        self.options.get(self.selected_index).map(MatchState::len).unwrap_or_default()
+    fn len(&self, state: &SelectionState) -> usize {
+        self.options.get(state.selected_index)
+            .zip(state.options.get(state.selected_index))
+            .map(|(option, option_state)| option.len(option_state))
+            .unwrap_or_default()
     }
 
-    fn reset(&mut self) {
-        self.selected_index = 0;
-        self.options.iter_mut().for_each(MatchState::reset);
+    fn reset(&self, state: &mut SelectionState) {
+        state.selected_index = 0;
+        self.options.iter().zip(&mut state.options).for_each(|(option, option_state)| option.reset(option_state));
     }
 
-    fn advance_once(&mut self) {
-        self.options.first_mut().map(MatchState::advance_once);
+    fn advance_once(&self, state: &mut SelectionState) {
+        if let Some((option, option_state)) = self.options.first().zip(state.options.first_mut()) {
+            option.advance_once(option_state);
+        }
     }
 }
 
-impl std::fmt::Display for Selection<'_> {
+impl std::fmt::Display for Selection {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if let Some(id) = &self.id {
             write!(f, "{id}")?;
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use crate::{
+    keywords::SYLLABLE_BOUND_CHAR,
+    matcher::{choices::{Choices, OwnedChoices}, match_state::UnitState, phones::Phones, syllables::Syllabifier},
+};
+
+/// A pattern that represents the boundary between two syllables
+/// 
+/// Matches without consuming any phones
+/// 
+/// Should be used in a `CheckBox`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyllableBound {
+    /// The syllabifier that was active when the pattern was built
+    pub(super) syllabifier: Arc<Syllabifier>,
+}
+
+impl UnitState for SyllableBound {
+    fn matches(&self, phones: &mut Phones<'_>, choices: &Choices<'_>) -> Option<OwnedChoices> {
+        // an exhausted iterator is at the edge of the phones
+        let is_boundary = phones.gap()
+            .is_none_or(|gap| phones.is_syllable_boundary(gap)
+                .unwrap_or_else(|| self.syllabifier.is_boundary(phones.full_list(), gap))
+            );
+
+        if is_boundary {
+            Some(choices.partial_clone().owned_choices())
+        } else {
+            None
+        }
+    }
+
+    fn len(&self) -> usize { 0 }
+}
+
+impl std::fmt::Display for SyllableBound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{SYLLABLE_BOUND_CHAR}")
+    }
+}
@@ -1,18 +1,20 @@
-use crate::{matcher::{choices::Choices, match_state::{MatchState, UnitState}, patterns::{check_box::CheckBox, list::PatternList, Pattern}, phones::Phones}, phones::Phone, tokens::{Direction, ScopeId}};
+use crate::{matcher::{choices::Choices, match_state::{Matcher, UnitState}, patterns::{check_box::CheckBox, list::PatternList, Pattern}, phones::Phones}, phones::Phone, tokens::{Direction, ScopeId}};
 
 #[test]
 fn single_phone() {
     let choices = Choices::default();
 
-    let mut phone = Phone::Symbol("a");
-    let mut match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Ltr);
+    let mut phone = Phone::Symbol("a".into());
+    let phone_list = [Phone::Symbol("a".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
     assert!(UnitState::matches(&mut phone, &mut match_phones, &choices).is_some());
 
     let phone_box = CheckBox::new(phone);
-    let mut match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let phone_list = [Phone::Symbol("a".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
     
-    assert!(phone_box.matches(&mut match_phones, &choices).is_some());
+    assert!(phone_box.matches(&phone_box.new_state(), &mut match_phones, &choices).is_some());
 
     let mut phone = Phone::Bound;
     let mut match_phones = Phones::new(&[Phone::Bound], 0, Direction::Ltr);
@@ -22,9 +24,9 @@ fn single_phone() {
     let phone_box = CheckBox::new(phone);
     let mut match_phones = Phones::new(&[Phone::Bound], 0, Direction::Rtl);
     
-    assert!(phone_box.matches(&mut match_phones, &choices).is_some());
+    assert!(phone_box.matches(&phone_box.new_state(), &mut match_phones, &choices).is_some());
 
-    let mut phone = Phone::Symbol("a");
+    let mut phone = Phone::Symbol("a".into());
     let mut match_phones = Phones::new(&[Phone::Bound], 0, Direction::Ltr);
 
     assert!(UnitState::matches(&mut phone, &mut match_phones, &choices).is_none());
@@ -32,27 +34,31 @@ fn single_phone() {
     let phone_box = CheckBox::new(phone);
     let mut match_phones = Phones::new(&[Phone::Bound], 0, Direction::Rtl);
     
-    assert!(phone_box.matches(&mut match_phones, &choices).is_none());
+    assert!(phone_box.matches(&phone_box.new_state(), &mut match_phones, &choices).is_none());
 
     let mut phone = Phone::Bound;
-    let mut match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Ltr);
+    let phone_list = [Phone::Symbol("a".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
     assert!(UnitState::matches(&mut phone, &mut match_phones, &choices).is_none());
 
     let phone_box = CheckBox::new(phone);
-    let mut match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let phone_list = [Phone::Symbol("a".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
     
-    assert!(phone_box.matches(&mut match_phones, &choices).is_none());
+    assert!(phone_box.matches(&phone_box.new_state(), &mut match_phones, &choices).is_none());
 
-    let mut phone = Phone::Symbol("a");
-    let mut match_phones = Phones::new(&[Phone::Symbol("b")], 0, Direction::Ltr);
+    let mut phone = Phone::Symbol("a".into());
+    let phone_list = [Phone::Symbol("b".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
     assert!(UnitState::matches(&mut phone, &mut match_phones, &choices).is_none());
 
     let phone_box = CheckBox::new(phone);
-    let mut match_phones = Phones::new(&[Phone::Symbol("b")], 0, Direction::Rtl);
+    let phone_list = [Phone::Symbol("b".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
     
-    assert!(phone_box.matches(&mut match_phones, &choices).is_none());
+    assert!(phone_box.matches(&phone_box.new_state(), &mut match_phones, &choices).is_none());
 }
 
 #[test]
@@ -60,33 +66,39 @@ fn multiple_phones() {
     let choices = Choices::default();
 
     let phones = vec![
-        Pattern::new_phone(Phone::Symbol("a")), 
-        Pattern::new_phone(Phone::Symbol("b")), 
-        Pattern::new_phone(Phone::Symbol("c")),
+        Pattern::new_phone(Phone::Symbol("a".into())), 
+        Pattern::new_phone(Phone::Symbol("b".into())), 
+        Pattern::new_phone(Phone::Symbol("c".into())),
     ];
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("b"), Phone::Symbol("c")], 0, Direction::Ltr);
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("b".into()), Phone::Symbol("c".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(PatternList::new(phones).matches(&mut match_phones, &choices).is_some());
+    let phones = PatternList::new(phones);
+    assert!(phones.matches(&phones.new_state(), &mut match_phones, &choices).is_some());
 
     let phones = vec![
-        Pattern::new_phone(Phone::Symbol("a")), 
-        Pattern::new_phone(Phone::Symbol("b")), 
-        Pattern::new_phone(Phone::Symbol("c")),
+        Pattern::new_phone(Phone::Symbol("a".into())), 
+        Pattern::new_phone(Phone::Symbol("b".into())), 
+        Pattern::new_phone(Phone::Symbol("c".into())),
     ];
 
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("b"), Phone::Symbol("d")], 0, Direction::Ltr);
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("b".into()), Phone::Symbol("d".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(PatternList::new(phones).matches(&mut match_phones, &choices).is_none());
+    let phones = PatternList::new(phones);
+    assert!(phones.matches(&phones.new_state(), &mut match_phones, &choices).is_none());
 
     let phones = vec![
-        Pattern::new_phone(Phone::Symbol("a")), 
-        Pattern::new_phone(Phone::Symbol("b")), 
-        Pattern::new_phone(Phone::Symbol("c")),
+        Pattern::new_phone(Phone::Symbol("a".into())), 
+        Pattern::new_phone(Phone::Symbol("b".into())), 
+        Pattern::new_phone(Phone::Symbol("c".into())),
     ];
 
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("b")], 0, Direction::Ltr);
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("b".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(PatternList::new(phones).matches(&mut match_phones, &choices).is_none());
+    let phones = PatternList::new(phones);
+    assert!(phones.matches(&phones.new_state(), &mut match_phones, &choices).is_none());
 }
 
 #[test]
@@ -96,7 +108,7 @@ let choices = Choices::default();
     let patterns = PatternList::new(Vec::new());
     let mut match_phones = Phones::new(&[], 0, Direction::Ltr);
 
-    assert!(patterns.matches(&mut match_phones, &choices).is_some());
+    assert!(patterns.matches(&patterns.new_state(), &mut match_phones, &choices).is_some());
 }
 
 #[test]
@@ -104,46 +116,52 @@ fn single_non_bound() {
     let choices = Choices::default();
 
     let phone = Pattern::new_any(None);
-    let mut match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Ltr);
+    let phone_list = [Phone::Symbol("a".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(phone.matches(&mut match_phones, &choices).is_some());
+    assert!(phone.matches(&phone.new_state(), &mut match_phones, &choices).is_some());
 
     let phone = Pattern::new_any(None);
-    let mut match_phones = Phones::new(&[Phone::Symbol("b")], 0, Direction::Ltr);
+    let phone_list = [Phone::Symbol("b".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(phone.matches(&mut match_phones, &choices).is_some());
+    assert!(phone.matches(&phone.new_state(), &mut match_phones, &choices).is_some());
 
     let phone = Pattern::new_any(None);
     let mut match_phones = Phones::new(&[Phone::Bound], 0, Direction::Ltr);
 
-    assert!(phone.matches(&mut match_phones, &choices).is_none());
+    assert!(phone.matches(&phone.new_state(), &mut match_phones, &choices).is_none());
 }
 
 #[test]
 fn agreeing_non_bounds() {
     let mut choices = Choices::default();
 
-    let label = ScopeId::Name("label");
+    let label = ScopeId::Name("label".into());
 
     let patterns = vec![Pattern::new_any(Some(label.clone())), Pattern::new_any(Some(label.clone()))];
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("a")], 0, Direction::Ltr);
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("a".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    let Some(new_choices) = PatternList::new(patterns).matches(&mut match_phones, &choices) else {
+    let patterns = PatternList::new(patterns);
+    let Some(new_choices) = patterns.matches(&patterns.new_state(), &mut match_phones, &choices) else {
         panic!("agreeing bounds did not patch");
     };
 
     choices.take_owned(new_choices);
 
-    assert_eq!(choices.any.get(&label), Some(&Phone::Symbol("a")));
+    assert_eq!(choices.any.get(&label), Some(&Phone::Symbol("a".into())));
 
     let choices = Choices::default();
 
-    let label = ScopeId::Name("label");
+    let label = ScopeId::Name("label".into());
 
     let patterns = vec![Pattern::new_any(Some(label.clone())), Pattern::new_any(Some(label.clone()))];
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("b")], 0, Direction::Ltr);
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("b".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(PatternList::new(patterns).matches(&mut match_phones, &choices).is_none());
+    let patterns = PatternList::new(patterns);
+    assert!(patterns.matches(&patterns.new_state(), &mut match_phones, &choices).is_none());
 }
 
 #[test]
@@ -153,86 +171,100 @@ fn unbounded_repetition() {
     let pattern = Pattern::new_repetition(None, PatternList::new(vec![Pattern::new_any(None)]), None);
     let mut match_phones = Phones::new(&[], 0, Direction::Ltr);
 
-    assert!(pattern.matches(&mut match_phones, &choices).is_some());
+    assert!(pattern.matches(&pattern.new_state(), &mut match_phones, &choices).is_some());
 }
 
 #[test]
 fn bounded_repetition() {
     let choices = Choices::default();
 
-    let mut patterns = PatternList::new(vec![
-        Pattern::new_phone(Phone::Symbol("a")),
+    let patterns = PatternList::new(vec![
+        Pattern::new_phone(Phone::Symbol("a".into())),
         Pattern::new_repetition(None, PatternList::new(vec![Pattern::new_any(None)]), None),
-        Pattern::new_phone(Phone::Symbol("b")),
+        Pattern::new_phone(Phone::Symbol("b".into())),
     ]);
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("b")], 0, Direction::Ltr);
+    let mut state = patterns.new_state();
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("b".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(patterns.next_match(&mut match_phones, &choices).is_some());
+    assert!(patterns.next_match(&mut state, &mut match_phones, &choices).is_some());
 
-    let mut patterns = PatternList::new(vec![
-        Pattern::new_phone(Phone::Symbol("a")),
+    let patterns = PatternList::new(vec![
+        Pattern::new_phone(Phone::Symbol("a".into())),
         Pattern::new_repetition(None, PatternList::new(vec![Pattern::new_any(None)]), None),
-        Pattern::new_phone(Phone::Symbol("b")),
+        Pattern::new_phone(Phone::Symbol("b".into())),
     ]);
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("-"), Phone::Symbol("b")], 0, Direction::Ltr);
+    let mut state = patterns.new_state();
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("-".into()), Phone::Symbol("b".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(patterns.next_match(&mut match_phones, &choices).is_some());
+    assert!(patterns.next_match(&mut state, &mut match_phones, &choices).is_some());
 
-    let mut patterns = PatternList::new(vec![
-        Pattern::new_phone(Phone::Symbol("a")),
+    let patterns = PatternList::new(vec![
+        Pattern::new_phone(Phone::Symbol("a".into())),
         Pattern::new_repetition(None, PatternList::new(vec![Pattern::new_any(None)]), None),
-        Pattern::new_phone(Phone::Symbol("b")),
+        Pattern::new_phone(Phone::Symbol("b".into())),
     ]);
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("-"), Phone::Symbol("-"), Phone::Symbol("-"), Phone::Symbol("b")], 0, Direction::Ltr);
+    let mut state = patterns.new_state();
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("-".into()), Phone::Symbol("-".into()), Phone::Symbol("-".into()), Phone::Symbol("b".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(patterns.next_match(&mut match_phones, &choices).is_some());
+    assert!(patterns.next_match(&mut state, &mut match_phones, &choices).is_some());
 
-    let mut patterns = PatternList::new(vec![
-        Pattern::new_phone(Phone::Symbol("a")),
+    let patterns = PatternList::new(vec![
+        Pattern::new_phone(Phone::Symbol("a".into())),
         Pattern::new_repetition(None, PatternList::new(vec![Pattern::new_any(None)]), None),
-        Pattern::new_phone(Phone::Symbol("b")),
+        Pattern::new_phone(Phone::Symbol("b".into())),
     ]);
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Bound, Phone::Symbol("b")], 0, Direction::Ltr);
+    let mut state = patterns.new_state();
+    let phone_list = [Phone::Symbol("a".into()), Phone::Bound, Phone::Symbol("b".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(patterns.next_match(&mut match_phones, &choices).is_none());
+    assert!(patterns.next_match(&mut state, &mut match_phones, &choices).is_none());
 }
 
 #[test]
 fn bounded_repetition_with_exclusion() {
     let choices = Choices::default();
 
-    let mut patterns = PatternList::new(vec![
-        Pattern::new_phone(Phone::Symbol("a")),
-        Pattern::new_repetition(None, PatternList::new(vec![Pattern::new_any(None)]), Some(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c"))]))),
-        Pattern::new_phone(Phone::Symbol("b")),
+    let patterns = PatternList::new(vec![
+        Pattern::new_phone(Phone::Symbol("a".into())),
+        Pattern::new_repetition(None, PatternList::new(vec![Pattern::new_any(None)]), Some(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c".into()))]))),
+        Pattern::new_phone(Phone::Symbol("b".into())),
     ]);
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("b")], 0, Direction::Ltr);
+    let mut state = patterns.new_state();
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("b".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(patterns.next_match(&mut match_phones, &choices).is_some());
+    assert!(patterns.next_match(&mut state, &mut match_phones, &choices).is_some());
 
-    patterns.reset();
+    patterns.reset(&mut state);
 
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("c"), Phone::Symbol("b")], 0, Direction::Ltr);
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("c".into()), Phone::Symbol("b".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(patterns.next_match(&mut match_phones, &choices).is_none());
+    assert!(patterns.next_match(&mut state, &mut match_phones, &choices).is_none());
 
-    patterns.reset();
+    patterns.reset(&mut state);
 
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("c"), Phone::Symbol("z"), Phone::Symbol("b")], 0, Direction::Ltr);
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("c".into()), Phone::Symbol("z".into()), Phone::Symbol("b".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(patterns.next_match(&mut match_phones, &choices).is_none());
+    assert!(patterns.next_match(&mut state, &mut match_phones, &choices).is_none());
 
-    patterns.reset();
+    patterns.reset(&mut state);
 
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("z"), Phone::Symbol("c"), Phone::Symbol("b")], 0, Direction::Ltr);
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("z".into()), Phone::Symbol("c".into()), Phone::Symbol("b".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(patterns.next_match(&mut match_phones, &choices).is_none());
+    assert!(patterns.next_match(&mut state, &mut match_phones, &choices).is_none());
 
-    patterns.reset();
+    patterns.reset(&mut state);
 
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("z"), Phone::Symbol("b")], 0, Direction::Ltr);
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("z".into()), Phone::Symbol("b".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(patterns.next_match(&mut match_phones, &choices).is_some());
+    assert!(patterns.next_match(&mut state, &mut match_phones, &choices).is_some());
 }
 
 #[test]
@@ -241,188 +273,214 @@ fn agreeing_repetitions() {
 
     let label = "label";
 
-    let mut patterns = PatternList::new(vec![
-        Pattern::new_phone(Phone::Symbol("a")),
-        Pattern::new_repetition(Some(label), PatternList::new(vec![Pattern::new_any(None)]), None),
-        Pattern::new_phone(Phone::Symbol("b")),
-        Pattern::new_repetition(Some(label), PatternList::new(vec![Pattern::new_any(None)]), None),
-        Pattern::new_phone(Phone::Symbol("c")),
+    let patterns = PatternList::new(vec![
+        Pattern::new_phone(Phone::Symbol("a".into())),
+        Pattern::new_repetition(Some(label.into()), PatternList::new(vec![Pattern::new_any(None)]), None),
+        Pattern::new_phone(Phone::Symbol("b".into())),
+        Pattern::new_repetition(Some(label.into()), PatternList::new(vec![Pattern::new_any(None)]), None),
+        Pattern::new_phone(Phone::Symbol("c".into())),
     ]);
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("-"), Phone::Symbol("b"), Phone::Symbol("c"),], 0, Direction::Ltr);
-
-    assert!(patterns.next_match(&mut match_phones, &choices).is_some());
-    assert_eq!(patterns.len(), 4);
-
-    let mut patterns = PatternList::new(vec![
-        Pattern::new_phone(Phone::Symbol("a")),
-        Pattern::new_repetition(Some(label), PatternList::new(vec![Pattern::new_any(None)]), None),
-        Pattern::new_phone(Phone::Symbol("b")),
-        Pattern::new_repetition(Some(label), PatternList::new(vec![Pattern::new_any(None)]), None),
-        Pattern::new_phone(Phone::Symbol("c")),
+    let mut state = patterns.new_state();
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("-".into()), Phone::Symbol("b".into()), Phone::Symbol("c".into()),];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
+
+    assert!(patterns.next_match(&mut state, &mut match_phones, &choices).is_some());
+    assert_eq!(patterns.len(&state), 4);
+
+    let patterns = PatternList::new(vec![
+        Pattern::new_phone(Phone::Symbol("a".into())),
+        Pattern::new_repetition(Some(label.into()), PatternList::new(vec![Pattern::new_any(None)]), None),
+        Pattern::new_phone(Phone::Symbol("b".into())),
+        Pattern::new_repetition(Some(label.into()), PatternList::new(vec![Pattern::new_any(None)]), None),
+        Pattern::new_phone(Phone::Symbol("c".into())),
     ]);
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("-"), Phone::Symbol("b"), Phone::Symbol("-"), Phone::Symbol("c"),], 0, Direction::Ltr);
-
-    assert!(patterns.next_match(&mut match_phones, &choices).is_some());
-    assert_eq!(patterns.len(), 5);
-
-    let mut patterns = PatternList::new(vec![
-        Pattern::new_phone(Phone::Symbol("a")),
-        Pattern::new_repetition(Some(label), PatternList::new(vec![Pattern::new_any(None)]), None),
-        Pattern::new_phone(Phone::Symbol("b")),
-        Pattern::new_repetition(Some(label), PatternList::new(vec![Pattern::new_any(None)]), None),
-        Pattern::new_phone(Phone::Symbol("c")),
+    let mut state = patterns.new_state();
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("-".into()), Phone::Symbol("b".into()), Phone::Symbol("-".into()), Phone::Symbol("c".into()),];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
+
+    assert!(patterns.next_match(&mut state, &mut match_phones, &choices).is_some());
+    assert_eq!(patterns.len(&state), 5);
+
+    let patterns = PatternList::new(vec![
+        Pattern::new_phone(Phone::Symbol("a".into())),
+        Pattern::new_repetition(Some(label.into()), PatternList::new(vec![Pattern::new_any(None)]), None),
+        Pattern::new_phone(Phone::Symbol("b".into())),
+        Pattern::new_repetition(Some(label.into()), PatternList::new(vec![Pattern::new_any(None)]), None),
+        Pattern::new_phone(Phone::Symbol("c".into())),
     ]);
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("-"), Phone::Symbol("b"), Phone::Symbol("-"), Phone::Symbol("-"), Phone::Symbol("c"),], 0, Direction::Ltr);
+    let mut state = patterns.new_state();
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("-".into()), Phone::Symbol("b".into()), Phone::Symbol("-".into()), Phone::Symbol("-".into()), Phone::Symbol("c".into()),];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(patterns.next_match(&mut match_phones, &choices).is_none());
+    assert!(patterns.next_match(&mut state, &mut match_phones, &choices).is_none());
 }
 
 #[test]
 fn optional() {
     let choices = Choices::default();
 
-    let mut pattern = Pattern::new_optional(vec![
-        Pattern::new_phone(Phone::Symbol("a")),
-        Pattern::new_phone(Phone::Symbol("b")),
-        Pattern::new_phone(Phone::Symbol("c")),
+    let pattern = Pattern::new_optional(vec![
+        Pattern::new_phone(Phone::Symbol("a".into())),
+        Pattern::new_phone(Phone::Symbol("b".into())),
+        Pattern::new_phone(Phone::Symbol("c".into())),
     ], None);
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("b"), Phone::Symbol("c")], 0, Direction::Ltr);
+    let mut state = pattern.new_state();
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("b".into()), Phone::Symbol("c".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(pattern.next_match(&mut match_phones, &choices).is_some());
-    assert_eq!(pattern.len(), 3);
+    assert!(pattern.next_match(&mut state, &mut match_phones, &choices).is_some());
+    assert_eq!(pattern.len(&state), 3);
 
-    let mut pattern = Pattern::new_optional(vec![
-        Pattern::new_phone(Phone::Symbol("a")),
-        Pattern::new_phone(Phone::Symbol("b")),
-        Pattern::new_phone(Phone::Symbol("c")),
+    let pattern = Pattern::new_optional(vec![
+        Pattern::new_phone(Phone::Symbol("a".into())),
+        Pattern::new_phone(Phone::Symbol("b".into())),
+        Pattern::new_phone(Phone::Symbol("c".into())),
     ], None);
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("b"), Phone::Symbol("d")], 0, Direction::Ltr);
+    let mut state = pattern.new_state();
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("b".into()), Phone::Symbol("d".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(pattern.next_match(&mut match_phones, &choices).is_some());
-    assert_eq!(pattern.len(), 0);
+    assert!(pattern.next_match(&mut state, &mut match_phones, &choices).is_some());
+    assert_eq!(pattern.len(&state), 0);
 }
 
 #[test]
 fn agreeing_optionals() {
     let choices = Choices::default();
 
-    let label = ScopeId::Name("label");
+    let label = ScopeId::Name("label".into());
 
-    let mut patterns = PatternList::new(vec![
+    let patterns = PatternList::new(vec![
         Pattern::new_optional(vec![
-            Pattern::new_phone(Phone::Symbol("a")),
+            Pattern::new_phone(Phone::Symbol("a".into())),
         ], Some(label.clone())),
         Pattern::new_optional(vec![
-            Pattern::new_phone(Phone::Symbol("b")),
+            Pattern::new_phone(Phone::Symbol("b".into())),
         ], Some(label.clone())),
     ]);
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("b")], 0, Direction::Ltr);
+    let mut state = patterns.new_state();
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("b".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(patterns.next_match(&mut match_phones, &choices).is_some());
-    assert_eq!(patterns.len(), 2);
+    assert!(patterns.next_match(&mut state, &mut match_phones, &choices).is_some());
+    assert_eq!(patterns.len(&state), 2);
 
-    let mut patterns = PatternList::new(vec![
+    let patterns = PatternList::new(vec![
         Pattern::new_optional(vec![
-            Pattern::new_phone(Phone::Symbol("a")),
+            Pattern::new_phone(Phone::Symbol("a".into())),
         ], Some(label.clone())),
         Pattern::new_optional(vec![
-            Pattern::new_phone(Phone::Symbol("b")),
+            Pattern::new_phone(Phone::Symbol("b".into())),
         ], Some(label.clone())),
     ]);
-    let mut match_phones = Phones::new(&[Phone::Symbol("b"), Phone::Symbol("b")], 0, Direction::Ltr);
+    let mut state = patterns.new_state();
+    let phone_list = [Phone::Symbol("b".into()), Phone::Symbol("b".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(patterns.next_match(&mut match_phones, &choices).is_some());
-    assert_eq!(patterns.len(), 0);
+    assert!(patterns.next_match(&mut state, &mut match_phones, &choices).is_some());
+    assert_eq!(patterns.len(&state), 0);
 }
 
 #[test]
 fn selection() {
     let choices = Choices::default();
 
-    let mut pattern = Pattern::new_selection(vec![
-        vec![Pattern::new_phone(Phone::Symbol("a"))],
-        vec![Pattern::new_phone(Phone::Symbol("b"))],
-        vec![Pattern::new_phone(Phone::Symbol("c"))],
+    let pattern = Pattern::new_selection(vec![
+        vec![Pattern::new_phone(Phone::Symbol("a".into()))],
+        vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+        vec![Pattern::new_phone(Phone::Symbol("c".into()))],
     ], None);
-    let mut match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Ltr);
+    let mut state = pattern.new_state();
+    let phone_list = [Phone::Symbol("a".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(pattern.next_match(&mut match_phones, &choices).is_some());
-    assert_eq!(pattern.len(), 1);
+    assert!(pattern.next_match(&mut state, &mut match_phones, &choices).is_some());
+    assert_eq!(pattern.len(&state), 1);
 
-    let mut pattern = Pattern::new_selection(vec![
-        vec![Pattern::new_phone(Phone::Symbol("a"))],
-        vec![Pattern::new_phone(Phone::Symbol("b"))],
-        vec![Pattern::new_phone(Phone::Symbol("c"))],
+    let pattern = Pattern::new_selection(vec![
+        vec![Pattern::new_phone(Phone::Symbol("a".into()))],
+        vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+        vec![Pattern::new_phone(Phone::Symbol("c".into()))],
     ], None);
-    let mut match_phones = Phones::new(&[Phone::Symbol("b")], 0, Direction::Ltr);
+    let mut state = pattern.new_state();
+    let phone_list = [Phone::Symbol("b".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(pattern.next_match(&mut match_phones, &choices).is_some());
-    assert_eq!(pattern.len(), 1);
+    assert!(pattern.next_match(&mut state, &mut match_phones, &choices).is_some());
+    assert_eq!(pattern.len(&state), 1);
 
-    let mut pattern = Pattern::new_selection(vec![
-        vec![Pattern::new_phone(Phone::Symbol("a"))],
-        vec![Pattern::new_phone(Phone::Symbol("b"))],
-        vec![Pattern::new_phone(Phone::Symbol("c"))],
+    let pattern = Pattern::new_selection(vec![
+        vec![Pattern::new_phone(Phone::Symbol("a".into()))],
+        vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+        vec![Pattern::new_phone(Phone::Symbol("c".into()))],
     ], None);
-    let mut match_phones = Phones::new(&[Phone::Symbol("c")], 0, Direction::Ltr);
+    let mut state = pattern.new_state();
+    let phone_list = [Phone::Symbol("c".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(pattern.next_match(&mut match_phones, &choices).is_some());
-    assert_eq!(pattern.len(), 1);
+    assert!(pattern.next_match(&mut state, &mut match_phones, &choices).is_some());
+    assert_eq!(pattern.len(&state), 1);
 }
 
 #[test]
 fn agreeing_selection() {
     let choices = Choices::default();
 
-    let label = ScopeId::Name("label");
+    let label = ScopeId::Name("label".into());
 
-    let mut patterns = PatternList::new(vec![
+    let patterns = PatternList::new(vec![
         Pattern::new_selection(vec![
-            vec![Pattern::new_phone(Phone::Symbol("a"))],
-            vec![Pattern::new_phone(Phone::Symbol("b"))],
-            vec![Pattern::new_phone(Phone::Symbol("c"))],
+            vec![Pattern::new_phone(Phone::Symbol("a".into()))],
+            vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+            vec![Pattern::new_phone(Phone::Symbol("c".into()))],
         ], Some(label.clone())),
         Pattern::new_selection(vec![
-            vec![Pattern::new_phone(Phone::Symbol("d"))],
-            vec![Pattern::new_phone(Phone::Symbol("e"))],
-            vec![Pattern::new_phone(Phone::Symbol("f"))],
+            vec![Pattern::new_phone(Phone::Symbol("d".into()))],
+            vec![Pattern::new_phone(Phone::Symbol("e".into()))],
+            vec![Pattern::new_phone(Phone::Symbol("f".into()))],
         ], Some(label.clone())),
     ]);
-    let mut match_phones = Phones::new(&[Phone::Symbol("b"), Phone::Symbol("e")], 0, Direction::Ltr);
+    let mut state = patterns.new_state();
+    let phone_list = [Phone::Symbol("b".into()), Phone::Symbol("e".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(patterns.next_match(&mut match_phones, &choices).is_some());
-    assert_eq!(patterns.len(), 2);
+    assert!(patterns.next_match(&mut state, &mut match_phones, &choices).is_some());
+    assert_eq!(patterns.len(&state), 2);
 
-    let mut patterns = PatternList::new(vec![
+    let patterns = PatternList::new(vec![
         Pattern::new_selection(vec![
-            vec![Pattern::new_phone(Phone::Symbol("a"))],
-            vec![Pattern::new_phone(Phone::Symbol("b"))],
-            vec![Pattern::new_phone(Phone::Symbol("c"))],
+            vec![Pattern::new_phone(Phone::Symbol("a".into()))],
+            vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+            vec![Pattern::new_phone(Phone::Symbol("c".into()))],
         ], Some(label.clone())),
         Pattern::new_selection(vec![
-            vec![Pattern::new_phone(Phone::Symbol("d"))],
-            vec![Pattern::new_phone(Phone::Symbol("e"))],
-            vec![Pattern::new_phone(Phone::Symbol("f"))],
+            vec![Pattern::new_phone(Phone::Symbol("d".into()))],
+            vec![Pattern::new_phone(Phone::Symbol("e".into()))],
+            vec![Pattern::new_phone(Phone::Symbol("f".into()))],
         ], Some(label.clone())),
     ]);
-    let mut match_phones = Phones::new(&[Phone::Symbol("b"), Phone::Symbol("d")], 0, Direction::Ltr);
+    let mut state = patterns.new_state();
+    let phone_list = [Phone::Symbol("b".into()), Phone::Symbol("d".into())];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(patterns.next_match(&mut match_phones, &choices).is_none());
+    assert!(patterns.next_match(&mut state, &mut match_phones, &choices).is_none());
 }
 
 #[test]
 fn advancement() {
     let choices = Choices::default();
 
-    let mut patterns = PatternList::new(vec![
-        Pattern::new_phone(Phone::Symbol("a")),
+    let patterns = PatternList::new(vec![
+        Pattern::new_phone(Phone::Symbol("a".into())),
     ]);
+    let mut state = patterns.new_state();
 
-    let mut match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Bound], 0, Direction::Ltr);
+    let phone_list = [Phone::Symbol("a".into()), Phone::Bound];
+    let mut match_phones = Phones::new(&phone_list, 0, Direction::Ltr);
 
-    assert!(patterns.matches(&mut match_phones.clone(), &choices).is_some());
+    assert!(patterns.matches(&state, &mut match_phones.clone(), &choices).is_some());
     
-    patterns.advance_once();
+    patterns.advance_once(&mut state);
 
-    assert!(patterns.matches(&mut match_phones, &choices).is_some());
+    assert!(patterns.matches(&state, &mut match_phones, &choices).is_some());
 }
\ No newline at end of file
@@ -2,241 +2,285 @@ use crate::{matcher::{patterns::{cond::CondPattern, list::PatternList, rule::Rul
 
 #[test]
 fn matches_phones() {
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         Vec::new(),
         Vec::new()
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_some());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_some());
 
-    let mut rule_pattern = RulePattern::new(
+    let rule_pattern = RulePattern::new(
         PatternList::new(vec![
-            Pattern::new_phone(Phone::Symbol("a")),
-            Pattern::new_phone(Phone::Symbol("b")),
-            Pattern::new_phone(Phone::Symbol("c")),
+            Pattern::new_phone(Phone::Symbol("a".into())),
+            Pattern::new_phone(Phone::Symbol("b".into())),
+            Pattern::new_phone(Phone::Symbol("c".into())),
         ]),
         Vec::new(),
         Vec::new()
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("b"), Phone::Symbol("c")], 2, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("b".into()), Phone::Symbol("c".into())];
+    let match_phones = Phones::new(&phone_list, 2, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_some());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_some());
 }
 
 #[test]
 fn match_phone_with_cond() {
-    let before_b = vec![CondPattern::new(CondType::Pattern, PatternList::default(), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b"))]))];
+    let before_b = vec![CondPattern::new(CondType::Pattern, PatternList::default(), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b".into()))]))];
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         before_b.clone(),
         Vec::new(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("b")], 0, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("b".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_some());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_some());
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         before_b.clone(),
         Vec::new(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_none());
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         before_b,
         Vec::new(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("b")], 0, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("b".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_none());
 
-    let after_b = vec![CondPattern::new(CondType::Pattern, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b"))]), PatternList::default())];
+    let after_b = vec![CondPattern::new(CondType::Pattern, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b".into()))]), PatternList::default())];
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         after_b.clone(),
         Vec::new(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("b"), Phone::Symbol("a")], 1, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("b".into()), Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 1, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_some());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_some());
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         after_b.clone(),
         Vec::new(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_none());
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         after_b,
         Vec::new(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("b")], 1, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("b".into())];
+    let match_phones = Phones::new(&phone_list, 1, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_none());
 }
 
 #[test]
 fn match_phone_with_anti_cond() {
-    let before_b = vec![CondPattern::new(CondType::Pattern, PatternList::default(), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b"))]))];
+    let before_b = vec![CondPattern::new(CondType::Pattern, PatternList::default(), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b".into()))]))];
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         Vec::new(),
         before_b.clone(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("b")], 0, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("b".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_none());
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         Vec::new(),
         before_b.clone(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_some());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_some());
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         Vec::new(),
         before_b,
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("b")], 0, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("b".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_none());
 
-    let after_b = vec![CondPattern::new(CondType::Pattern, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b"))]), PatternList::default())];
+    let after_b = vec![CondPattern::new(CondType::Pattern, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b".into()))]), PatternList::default())];
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         Vec::new(),
         after_b.clone(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("b"), Phone::Symbol("a")], 1, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("b".into()), Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 1, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_none());
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         Vec::new(),
         after_b.clone(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_some());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_some());
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         Vec::new(),
         after_b,
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("b")], 0, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("b".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_none());
 }
 
 #[test]
 fn and_cond() {
-    let mut between_b_and_c = CondPattern::new(CondType::Pattern, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b"))]), PatternList::default());
-    let before_c = CondPattern::new(CondType::Pattern, PatternList::default(), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c"))]));
+    let mut between_b_and_c = CondPattern::new(CondType::Pattern, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b".into()))]), PatternList::default());
+    let before_c = CondPattern::new(CondType::Pattern, PatternList::default(), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c".into()))]));
 
     between_b_and_c.add_and(AndType::And, before_c);
 
     let cond = vec![between_b_and_c];
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         cond.clone(),
         Vec::new(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("b"), Phone::Symbol("a"), Phone::Symbol("c")], 1, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("b".into()), Phone::Symbol("a".into()), Phone::Symbol("c".into())];
+    let match_phones = Phones::new(&phone_list, 1, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_some());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_some());
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         cond.clone(),
         Vec::new(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("c")], 0, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("c".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_none());
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         cond.clone(),
         Vec::new(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("b"), Phone::Symbol("a")], 1, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("b".into()), Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 1, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_none());
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         cond,
         Vec::new(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_none());
 }
 
 #[test]
 fn and_not_cond() {
-    let mut between_b_and_c = CondPattern::new(CondType::Pattern, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b"))]), PatternList::default());
-    let before_c = CondPattern::new(CondType::Pattern, PatternList::default(), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c"))]));
+    let mut between_b_and_c = CondPattern::new(CondType::Pattern, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b".into()))]), PatternList::default());
+    let before_c = CondPattern::new(CondType::Pattern, PatternList::default(), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("c".into()))]));
 
     between_b_and_c.add_and(AndType::AndNot, before_c);
 
     let cond = vec![between_b_and_c];
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         cond.clone(),
         Vec::new(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("b"), Phone::Symbol("a"), Phone::Symbol("c")], 1, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("b".into()), Phone::Symbol("a".into()), Phone::Symbol("c".into())];
+    let match_phones = Phones::new(&phone_list, 1, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_none());
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         cond.clone(),
         Vec::new(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("c")], 0, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("c".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_none());
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         cond.clone(),
         Vec::new(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("b"), Phone::Symbol("a")], 1, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("b".into()), Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 1, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_some());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_some());
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         cond,
         Vec::new(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_none());
 }
 
 #[test]
@@ -246,40 +290,44 @@ fn agreement_between_pattern_halves() {
         PatternList::new(vec![
             Pattern::new_selection(
                 vec![
-                    vec![Pattern::new_phone(Phone::Symbol("c"))],
-                    vec![Pattern::new_phone(Phone::Symbol("d"))],
+                    vec![Pattern::new_phone(Phone::Symbol("c".into()))],
+                    vec![Pattern::new_phone(Phone::Symbol("d".into()))],
                 ],
-                Some(ScopeId::Name("label"))
+                Some(ScopeId::Name("label".into()))
             )
         ]),
         PatternList::new(vec![
             Pattern::new_selection(
                 vec![
-                    vec![Pattern::new_phone(Phone::Symbol("c"))],
-                    vec![Pattern::new_phone(Phone::Symbol("d"))],
+                    vec![Pattern::new_phone(Phone::Symbol("c".into()))],
+                    vec![Pattern::new_phone(Phone::Symbol("d".into()))],
                 ],
-                Some(ScopeId::Name("label"))
+                Some(ScopeId::Name("label".into()))
             )
         ])
     )];
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         cond.clone(),
         Vec::new(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("c"), Phone::Symbol("a"), Phone::Symbol("c")], 1, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("c".into()), Phone::Symbol("a".into()), Phone::Symbol("c".into())];
+    let match_phones = Phones::new(&phone_list, 1, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_some());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_some());
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         cond,
         Vec::new(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("c"), Phone::Symbol("a"), Phone::Symbol("d")], 1, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("c".into()), Phone::Symbol("a".into()), Phone::Symbol("d".into())];
+    let match_phones = Phones::new(&phone_list, 1, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_none());
 }
 
 #[test]
@@ -289,10 +337,10 @@ fn agreement_between_and_conds() {
         PatternList::new(vec![
             Pattern::new_selection(
                 vec![
-                    vec![Pattern::new_phone(Phone::Symbol("c"))],
-                    vec![Pattern::new_phone(Phone::Symbol("d"))],
+                    vec![Pattern::new_phone(Phone::Symbol("c".into()))],
+                    vec![Pattern::new_phone(Phone::Symbol("d".into()))],
                 ],
-                Some(ScopeId::Name("label")),
+                Some(ScopeId::Name("label".into())),
             )
         ]),
         PatternList::default()
@@ -304,44 +352,48 @@ fn agreement_between_and_conds() {
         PatternList::new(vec![
             Pattern::new_selection(
                 vec![
-                    vec![Pattern::new_phone(Phone::Symbol("c"))],
-                    vec![Pattern::new_phone(Phone::Symbol("d"))],
+                    vec![Pattern::new_phone(Phone::Symbol("c".into()))],
+                    vec![Pattern::new_phone(Phone::Symbol("d".into()))],
                 ],
-                Some(ScopeId::Name("label")),
+                Some(ScopeId::Name("label".into())),
             )
         ])
     ));
 
     let cond = vec![cond];
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         cond.clone(),
         Vec::new(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("c"), Phone::Symbol("a"), Phone::Symbol("c")], 1, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("c".into()), Phone::Symbol("a".into()), Phone::Symbol("c".into())];
+    let match_phones = Phones::new(&phone_list, 1, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_some());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_some());
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         cond,
         Vec::new(),
     ).expect("pattern construction should be valid");
-    let match_phones = Phones::new(&[Phone::Symbol("c"), Phone::Symbol("a"), Phone::Symbol("d")], 1, Direction::Rtl);
+    let mut state = rule_pattern.new_state();
+    let phone_list = [Phone::Symbol("c".into()), Phone::Symbol("a".into()), Phone::Symbol("d".into())];
+    let match_phones = Phones::new(&phone_list, 1, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_none());
 }
 
 #[test]
 fn complex_argeement() {
-    let scope_id = ScopeId::Name("c");
+    let scope_id = ScopeId::Name("c".into());
 
     let cond = vec![CondPattern::new(CondType::Pattern, PatternList::default(), PatternList::new(vec![
         Pattern::new_selection(
             vec![
-                vec![Pattern::new_phone(Phone::Symbol("b"))],
-                vec![Pattern::new_any(None), Pattern::new_phone(Phone::Symbol("c"))],
+                vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+                vec![Pattern::new_any(None), Pattern::new_phone(Phone::Symbol("c".into()))],
             ],
             Some(scope_id.clone())
         )
@@ -350,139 +402,164 @@ fn complex_argeement() {
     let anti_cond = vec![CondPattern::new(CondType::Pattern, PatternList::default(), PatternList::new(vec![
         Pattern::new_selection(
             vec![
-                vec![Pattern::new_phone(Phone::Symbol("b"))],
-                vec![Pattern::new_phone(Phone::Symbol("d"))],
+                vec![Pattern::new_phone(Phone::Symbol("b".into()))],
+                vec![Pattern::new_phone(Phone::Symbol("d".into()))],
             ],
             Some(scope_id)
         )
     ]))];
 
-    let mut rule_pattern = RulePattern::new(
-        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]),
+    let rule_pattern = RulePattern::new(
+        PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]),
         cond,
         anti_cond,
     ).expect("pattern construction should be valid");
+    let mut state = rule_pattern.new_state();
 
-    let match_phones = Phones::new(&[Phone::Symbol("a"), Phone::Symbol("b"), Phone::Symbol("c")], 0, Direction::Rtl);
+    let phone_list = [Phone::Symbol("a".into()), Phone::Symbol("b".into()), Phone::Symbol("c".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).expect("next match should not error").is_some());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).expect("next match should not error").is_some());
 }
 
 #[test]
 fn phone_match_phone_cond() {
-    let conds = vec![CondPattern::new(CondType::Match, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]))];
+    let conds = vec![CondPattern::new(CondType::Match, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]))];
 
-    let mut rule_pattern = RulePattern::new(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]), conds, Vec::new()).expect("pattern construction should be valid");
+    let rule_pattern = RulePattern::new(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]), conds, Vec::new()).expect("pattern construction should be valid");
+    let mut state = rule_pattern.new_state();
 
-    let match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let phone_list = [Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).is_ok_and(|res| res.is_some()));
+    assert!(rule_pattern.next_match(&mut state, &match_phones).is_ok_and(|res| res.is_some()));
 
 
-    let bad_conds = vec![CondPattern::new(CondType::Match, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b"))]))];
+    let bad_conds = vec![CondPattern::new(CondType::Match, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("b".into()))]))];
 
-    let mut rule_pattern = RulePattern::new(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]), bad_conds, Vec::new()).expect("pattern construction should be valid");
+    let rule_pattern = RulePattern::new(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]), bad_conds, Vec::new()).expect("pattern construction should be valid");
+    let mut state = rule_pattern.new_state();
 
-    let match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let phone_list = [Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).is_ok_and(|res| res.is_none()));
+    assert!(rule_pattern.next_match(&mut state, &match_phones).is_ok_and(|res| res.is_none()));
 }
 
 #[test]
 fn optional_match_conds() {
-    let label = ScopeId::Name("label");
+    let label = ScopeId::Name("label".into());
 
-    let conds = vec![CondPattern::new(CondType::Match, PatternList::new(vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("a"))], Some(label.clone()))]), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]))];
+    let conds = vec![CondPattern::new(CondType::Match, PatternList::new(vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("a".into()))], Some(label.clone()))]), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]))];
 
-    let mut rule_pattern = RulePattern::new(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]), conds.clone(), Vec::new()).expect("pattern construction should be valid");
+    let rule_pattern = RulePattern::new(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]), conds.clone(), Vec::new()).expect("pattern construction should be valid");
+    let mut state = rule_pattern.new_state();
 
-    let match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let phone_list = [Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).is_err());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).is_err());
 
-    let input = PatternList::new(vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("a"))], Some(label.clone()))]);
+    let input = PatternList::new(vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("a".into()))], Some(label.clone()))]);
 
-    let mut rule_pattern = RulePattern::new(input, conds, Vec::new()).expect("pattern construction should be valid");
+    let rule_pattern = RulePattern::new(input, conds, Vec::new()).expect("pattern construction should be valid");
+    let mut state = rule_pattern.new_state();
 
-    let match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let phone_list = [Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).is_ok_and(|res| res.is_some()));
+    assert!(rule_pattern.next_match(&mut state, &match_phones).is_ok_and(|res| res.is_some()));
 
-    let conds = vec![CondPattern::new(CondType::Match, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]), PatternList::new(vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("a"))], None)]))];
+    let conds = vec![CondPattern::new(CondType::Match, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]), PatternList::new(vec![Pattern::new_optional(vec![Pattern::new_phone(Phone::Symbol("a".into()))], None)]))];
 
-    let mut rule_pattern = RulePattern::new(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]), conds, Vec::new()).expect("pattern construction should be valid");
+    let rule_pattern = RulePattern::new(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]), conds, Vec::new()).expect("pattern construction should be valid");
+    let mut state = rule_pattern.new_state();
 
-    let match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let phone_list = [Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).is_ok_and(|res| res.is_some()));
+    assert!(rule_pattern.next_match(&mut state, &match_phones).is_ok_and(|res| res.is_some()));
 }
 
 #[test]
 fn selection_match_conds() {
-    let label = ScopeId::Name("label");
+    let label = ScopeId::Name("label".into());
 
-    let conds = vec![CondPattern::new(CondType::Match, PatternList::new(vec![Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("a"))], vec![Pattern::new_phone(Phone::Symbol("b"))]], Some(label.clone()))]), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]))];
+    let conds = vec![CondPattern::new(CondType::Match, PatternList::new(vec![Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("a".into()))], vec![Pattern::new_phone(Phone::Symbol("b".into()))]], Some(label.clone()))]), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]))];
 
-    let mut rule_pattern = RulePattern::new(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]), conds.clone(), Vec::new()).expect("pattern construction should be valid");
+    let rule_pattern = RulePattern::new(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]), conds.clone(), Vec::new()).expect("pattern construction should be valid");
+    let mut state = rule_pattern.new_state();
 
-    let match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let phone_list = [Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).is_err());
+    assert!(rule_pattern.next_match(&mut state, &match_phones).is_err());
 
-    let input = PatternList::new(vec![Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("a"))], vec![Pattern::new_phone(Phone::Symbol("b"))]], Some(label.clone()))]);
+    let input = PatternList::new(vec![Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("a".into()))], vec![Pattern::new_phone(Phone::Symbol("b".into()))]], Some(label.clone()))]);
 
-    let mut rule_pattern = RulePattern::new(input, conds, Vec::new()).expect("pattern construction should be valid");
+    let rule_pattern = RulePattern::new(input, conds, Vec::new()).expect("pattern construction should be valid");
+    let mut state = rule_pattern.new_state();
 
-    let match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let phone_list = [Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).is_ok_and(|res| res.is_some()));
+    assert!(rule_pattern.next_match(&mut state, &match_phones).is_ok_and(|res| res.is_some()));
 
-    let conds = vec![CondPattern::new(CondType::Match, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]), PatternList::new(vec![Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("a"))], vec![Pattern::new_phone(Phone::Symbol("b"))]], None)]))];
+    let conds = vec![CondPattern::new(CondType::Match, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]), PatternList::new(vec![Pattern::new_selection(vec![vec![Pattern::new_phone(Phone::Symbol("a".into()))], vec![Pattern::new_phone(Phone::Symbol("b".into()))]], None)]))];
 
-    let mut rule_pattern = RulePattern::new(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]), conds, Vec::new()).expect("pattern construction should be valid");
+    let rule_pattern = RulePattern::new(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]), conds, Vec::new()).expect("pattern construction should be valid");
+    let mut state = rule_pattern.new_state();
 
-    let match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let phone_list = [Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).is_ok_and(|res| res.is_some()));
+    assert!(rule_pattern.next_match(&mut state, &match_phones).is_ok_and(|res| res.is_some()));
 }
 
 #[test]
 fn inequal_length_match_conds() {
-    let conds = vec![CondPattern::new(CondType::Match, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a")), Pattern::new_phone(Phone::Symbol("b"))]), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]))];
+    let conds = vec![CondPattern::new(CondType::Match, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into())), Pattern::new_phone(Phone::Symbol("b".into()))]), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]))];
     
-    let mut rule_pattern = RulePattern::new(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]), conds, Vec::new()).expect("pattern construction should be valid");
+    let rule_pattern = RulePattern::new(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]), conds, Vec::new()).expect("pattern construction should be valid");
+    let mut state = rule_pattern.new_state();
 
-    let match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let phone_list = [Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).is_ok_and(|res| res.is_none()));
+    assert!(rule_pattern.next_match(&mut state, &match_phones).is_ok_and(|res| res.is_none()));
 
-    let conds = vec![CondPattern::new(CondType::Match, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a")), Pattern::new_phone(Phone::Symbol("b"))]))];
+    let conds = vec![CondPattern::new(CondType::Match, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]), PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into())), Pattern::new_phone(Phone::Symbol("b".into()))]))];
     
-    let mut rule_pattern = RulePattern::new(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]), conds, Vec::new()).expect("pattern construction should be valid");
+    let rule_pattern = RulePattern::new(PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]), conds, Vec::new()).expect("pattern construction should be valid");
+    let mut state = rule_pattern.new_state();
 
-    let match_phones = Phones::new(&[Phone::Symbol("a")], 0, Direction::Rtl);
+    let phone_list = [Phone::Symbol("a".into())];
+    let match_phones = Phones::new(&phone_list, 0, Direction::Rtl);
 
-    assert!(rule_pattern.next_match(&match_phones).is_ok_and(|res| res.is_none()));
+    assert!(rule_pattern.next_match(&mut state, &match_phones).is_ok_and(|res| res.is_none()));
 }
 
 #[test]
 fn zero_input() {
-    let mut rule_pattern = RulePattern::new(PatternList::default(), Vec::new(), Vec::new()).expect("pattern construction should be valid");
+    let rule_pattern = RulePattern::new(PatternList::default(), Vec::new(), Vec::new()).expect("pattern construction should be valid");
+    let mut state = rule_pattern.new_state();
 
-    assert!(rule_pattern.next_match(&Phones::new(&[], 0, Direction::Rtl)).expect("next match should not error").is_some());
-    assert!(rule_pattern.next_match(&Phones::new(&[], 0, Direction::Rtl)).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &Phones::new(&[], 0, Direction::Rtl)).expect("next match should not error").is_some());
+    assert!(rule_pattern.next_match(&mut state, &Phones::new(&[], 0, Direction::Rtl)).expect("next match should not error").is_none());
 
 
     let cond = vec![CondPattern::new(CondType::Pattern, PatternList::new(vec![Pattern::new_phone(Phone::Bound)]), PatternList::new(vec![Pattern::new_phone(Phone::Bound)]))];
-    let mut rule_pattern = RulePattern::new(PatternList::default(), cond, Vec::new()).expect("pattern construction should be valid");
+    let rule_pattern = RulePattern::new(PatternList::default(), cond, Vec::new()).expect("pattern construction should be valid");
+    let mut state = rule_pattern.new_state();
 
-    assert!(rule_pattern.next_match(&Phones::new(&[], 0, Direction::Rtl)).expect("next match should not error").is_some());
-    assert!(rule_pattern.next_match(&Phones::new(&[], 0, Direction::Rtl)).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &Phones::new(&[], 0, Direction::Rtl)).expect("next match should not error").is_some());
+    assert!(rule_pattern.next_match(&mut state, &Phones::new(&[], 0, Direction::Rtl)).expect("next match should not error").is_none());
     
-    let cond = vec![CondPattern::new(CondType::Pattern, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a"))]), PatternList::default())];
-    let mut rule_pattern = RulePattern::new(PatternList::default(), cond, Vec::new()).expect("pattern construction should be valid");
+    let cond = vec![CondPattern::new(CondType::Pattern, PatternList::new(vec![Pattern::new_phone(Phone::Symbol("a".into()))]), PatternList::default())];
+    let rule_pattern = RulePattern::new(PatternList::default(), cond, Vec::new()).expect("pattern construction should be valid");
+    let mut state = rule_pattern.new_state();
 
-    assert!(rule_pattern.next_match(&Phones::new(&[], 0, Direction::Rtl)).expect("next match should not error").is_none());
+    assert!(rule_pattern.next_match(&mut state, &Phones::new(&[], 0, Direction::Rtl)).expect("next match should not error").is_none());
 }
 
 // todo: conds, anti-conds, &, &!, with repetitions, non phone conds
\ No newline at end of file
@@ -1,9 +1,9 @@
-use crate::{phones::Phone, tokens::Direction};
+use crate::{matcher::gap_buffer::SplitSlice, phones::Phone, tokens::Direction};
 
 /// A directional `Iterator` over a list of phones
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Phones<'b, 'p> {
-    phone_list: &'b [Phone<'p>],
+pub struct Phones<'b> {
+    phone_list: SplitSlice<'b, Phone>,
     /// the index of the next phone LTR,
     /// the index after the next phone RTL,
     /// 
@@ -12,11 +12,23 @@ pub struct Phones<'b, 'p> {
     /// index of 1 points ***between*** p0 and p1
     index: Option<usize>,
     direction: Direction,
+    /// The list of phones that `phone_list` was taken from
+    full_list: SplitSlice<'b, Phone>,
+    /// The index of the start of `phone_list` in `full_list`
+    offset: usize,
+    /// Whether there is a syllable boundary before each phone in `full_list`
+    syllables: Option<SplitSlice<'b, bool>>,
 }
 
-impl<'p, 's> Phones<'p, 's> {
+impl<'p> Phones<'p> {
     /// Creates a new phone iterator where `index` is the index of the first phone to be returned
-    pub const fn new(phones: &'p [Phone<'s>], index: usize, direction: Direction) -> Self {
+    pub const fn new(phones: &'p [Phone], index: usize, direction: Direction) -> Self {
+        Self::new_split(SplitSlice::new(phones, &[]), index, direction)
+    }
+
+    /// Creates a new phone iterator over phones split in two, such as the phones in a `GapBuffer`,
+    /// where `index` is the index of the first phone to be returned
+    pub const fn new_split(phones: SplitSlice<'p, Phone>, index: usize, direction: Direction) -> Self {
         Self {
             phone_list: phones,
             index: if index <= phones.len() {
@@ -28,9 +40,18 @@ impl<'p, 's> Phones<'p, 's> {
                 None
             },
             direction,
+            full_list: phones,
+            offset: 0,
+            syllables: None,
         }
     }
 
+    /// Gives the iterator the syllable boundaries of its phones as found by `Syllabifier::syllabify`
+    pub const fn with_syllables(mut self, syllables: SplitSlice<'p, bool>) -> Self {
+        self.syllables = Some(syllables);
+        self
+    }
+
     // gets the number of phones left in the iterator
     pub fn rem_len(&self) -> usize {
         if let Some(index) = self.index {
@@ -44,7 +65,7 @@ impl<'p, 's> Phones<'p, 's> {
     }
 
     /// Gets the next phone
-    pub fn next(&mut self) -> &'p Phone<'s> {
+    pub fn next(&mut self) -> &'p Phone {
         if let Some(i) = self.index {
             match self.direction {
                 Direction::Ltr => {
@@ -81,22 +102,32 @@ impl<'p, 's> Phones<'p, 's> {
         self.direction
     }
 
+    /// Gets the list of phones that the iterator was originally created from
+    pub const fn full_list(&self) -> SplitSlice<'p, Phone> {
+        self.full_list
+    }
+
+    /// Determines if there is a syllable boundary at a gap in the full list,
+    /// `None` if the syllable boundaries of the phones are not known
+    pub fn is_syllable_boundary(&self, gap: usize) -> Option<bool> {
+        self.syllables.and_then(|syllables| syllables.get(gap).copied())
+    }
+
+    /// Gets the index of the gap between the last and next phones in the full list
+    /// 
+    /// Returns `None` if the iterator has been exhausted
+    pub fn gap(&self) -> Option<usize> {
+        self.index.map(|i| self.offset + i)
+    }
+
     /// Gets the phones to the left of the index
-    fn left(&self) -> &'p [Phone<'s>] {
-        if let Some(i) = self.index {
-            self.phone_list.get(..i).unwrap_or_default()
-        } else {
-            &[]
-        }
+    fn left(&self) -> SplitSlice<'p, Phone> {
+        self.index.and_then(|i| self.phone_list.slice(0..i)).unwrap_or_default()
     }
 
     /// Gets the phones to the right of the index
-    fn right(&self) -> &'p [Phone<'s>] {
-        if let Some(i) = self.index {
-            self.phone_list.get(i..).unwrap_or_default()
-        } else {
-            &[]
-        }
+    fn right(&self) -> SplitSlice<'p, Phone> {
+        self.index.and_then(|i| self.phone_list.slice(i..self.phone_list.len())).unwrap_or_default()
     }
 
     /// Creats an rtl `Phone` `Iterator` from everything left of the current index
@@ -107,6 +138,9 @@ impl<'p, 's> Phones<'p, 's> {
             phone_list: phones,
             index: Some(phones.len()),
             direction: Direction::Rtl,
+            full_list: self.full_list,
+            offset: self.offset,
+            syllables: self.syllables,
         }
     }
 
@@ -118,6 +152,9 @@ impl<'p, 's> Phones<'p, 's> {
             phone_list: phones,
             index: Some(0),
             direction: Direction::Ltr,
+            full_list: self.full_list,
+            offset: self.offset + self.index.unwrap_or_default(),
+            syllables: self.syllables,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,145 @@
+use crate::{
+    matcher::{choices::Choices, gap_buffer::SplitSlice, match_state::Matcher, patterns::{Pattern, list::PatternList}, phones::Phones},
+    phones::Phone,
+    tokens::Direction,
+};
+
+/// Divides words into syllables using onset, nucleus, and coda patterns
+///
+/// Nuclei are the longest matches of the nucleus pattern found from left to right,
+/// the phones between two nuclei are split so that the following onset is as long as possible
+/// while the onset and the preceding coda still match their patterns
+#[derive(Debug, PartialEq, Eq)]
+pub struct Syllabifier {
+    onset: PatternList,
+    nucleus: PatternList,
+    coda: PatternList,
+}
+
+impl Syllabifier {
+    /// Creates a new `Syllabifier`
+    pub fn new(onset: Vec<Pattern>, nucleus: Vec<Pattern>, coda: Vec<Pattern>) -> Self {
+        Self {
+            onset: PatternList::new(onset),
+            nucleus: PatternList::new(nucleus),
+            coda: PatternList::new(coda),
+        }
+    }
+
+    /// Finds the syllable boundaries of every word in a list of phones,
+    /// where `boundaries[i]` is whether there is a syllable boundary before `phones[i]`
+    ///
+    /// The edges of words are always syllable boundaries
+    pub fn syllabify(&self, phones: &[Phone]) -> Vec<bool> {
+        let mut boundaries = vec![false; phones.len() + 1];
+        let mut start = 0;
+
+        for end in phones.iter().enumerate().filter(|(_, phone)| phone.is_bound()).map(|(i, _)| i).chain([phones.len()]) {
+            boundaries[start] = true;
+            boundaries[end] = true;
+
+            for boundary in self.boundaries(&phones[start..end]) {
+                boundaries[start + boundary] = true;
+            }
+
+            start = end + 1;
+        }
+
+        boundaries
+    }
+
+    /// Determines if there is a syllable boundary before `phones[index]`
+    /// without syllabifying any other word,
+    /// used when the boundaries of the phones have not been found with `syllabify`
+    ///
+    /// The edges of words are always syllable boundaries
+    pub fn is_boundary(&self, phones: SplitSlice<Phone>, index: usize) -> bool {
+        let is_bound_at = |i: usize| phones.get(i).is_some_and(Phone::is_bound);
+
+        if index == 0 || index >= phones.len() || is_bound_at(index - 1) || is_bound_at(index) {
+            return true;
+        }
+
+        // only the word containing the index is syllabified
+        let start = phones.slice(0..index)
+            .and_then(|before| before.rposition(Phone::is_bound))
+            .map_or(0, |i| i + 1);
+        let end = phones.slice(index..phones.len())
+            .and_then(|after| after.position(Phone::is_bound))
+            .map_or(phones.len(), |i| index + i);
+        let word = phones.slice(start..end).map(SplitSlice::to_vec).unwrap_or_default();
+
+        self.boundaries(&word).contains(&(index - start))
+    }
+
+    /// Finds the indices of the syllable boundaries within a word
+    fn boundaries(&self, word: &[Phone]) -> Vec<usize> {
+        let nuclei = self.nuclei(word);
+
+        nuclei.windows(2)
+            .map(|pair| {
+                let (coda_start, onset_end) = (pair[0].1, pair[1].0);
+                onset_end - self.onset_len(&word[coda_start..onset_end])
+            })
+            .collect()
+    }
+
+    /// Finds the start and end of each nucleus in a word
+    fn nuclei(&self, word: &[Phone]) -> Vec<(usize, usize)> {
+        let mut nuclei = Vec::new();
+        let mut i = 0;
+
+        while i < word.len() {
+            let len = match_lens(&self.nucleus, &word[i..])
+                .into_iter()
+                .max()
+                .unwrap_or_default();
+
+            if len == 0 {
+                i += 1;
+            } else {
+                nuclei.push((i, i + len));
+                i += len;
+            }
+        }
+
+        nuclei
+    }
+
+    /// Gets the number of phones in a cluster between two nuclei that belong to the following onset
+    fn onset_len(&self, cluster: &[Phone]) -> usize {
+        let split = |onset_len: usize| cluster.split_at(cluster.len() - onset_len);
+
+        // the longest valid onset with a valid coda
+        (0..=cluster.len()).rev()
+            .find(|&onset_len| {
+                let (coda, onset) = split(onset_len);
+                fully_matches(&self.onset, onset) && fully_matches(&self.coda, coda)
+            })
+            // falls back to the longest valid onset
+            .or_else(|| (0..=cluster.len()).rev().find(|&onset_len| fully_matches(&self.onset, split(onset_len).1)))
+            .unwrap_or_default()
+    }
+}
+
+/// Gets the lengths of every match of a pattern that starts at the start of `phones`
+/// and does not extend beyond its end
+fn match_lens(pattern: &PatternList, phones: &[Phone]) -> Vec<usize> {
+    let mut state = pattern.new_state();
+    let match_phones = Phones::new(phones, 0, Direction::Ltr);
+    let choices = Choices::default();
+    let mut lens = Vec::new();
+
+    while pattern.next_match(&mut state, &match_phones, &choices).is_some() {
+        if pattern.len(&state) <= phones.len() {
+            lens.push(pattern.len(&state));
+        }
+    }
+
+    lens
+}
+
+/// Checks if a pattern can match exactly the given phones
+fn fully_matches(pattern: &PatternList, phones: &[Phone]) -> bool {
+    match_lens(pattern, phones).contains(&phones.len())
+}
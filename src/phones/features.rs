@@ -0,0 +1,45 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::shared_str::SharedStr;
+
+/// The distinctive features declared for each phone
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FeatureTable {
+    /// Each declared phone and its feature bundle in declaration order
+    bundles: Vec<(SharedStr, HashMap<SharedStr, bool>)>,
+    /// The index of each phone's bundle in `bundles`
+    indices: HashMap<SharedStr, usize>,
+    /// Every feature that has been declared
+    names: HashSet<SharedStr>,
+}
+
+impl FeatureTable {
+    /// Sets the value of a feature for a phone,
+    /// adding the phone to the table if it is not already in it
+    pub fn declare(&mut self, symbol: &SharedStr, name: &SharedStr, value: bool) {
+        let index = *self.indices.entry(symbol.clone()).or_insert_with(|| {
+            self.bundles.push((symbol.clone(), HashMap::new()));
+            self.bundles.len() - 1
+        });
+
+        self.bundles[index].1.insert(name.clone(), value);
+        self.names.insert(name.clone());
+    }
+
+    /// Determines if a feature has been declared for any phone
+    pub fn is_declared(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    /// Gets the feature bundle of a phone's symbol
+    pub fn bundle(&self, symbol: &str) -> Option<&HashMap<SharedStr, bool>> {
+        self.indices.get(symbol).map(|&index| &self.bundles[index].1)
+    }
+
+    /// Finds the first declared phone with exactly the given feature bundle
+    pub fn find_phone(&self, bundle: &HashMap<SharedStr, bool>) -> Option<&SharedStr> {
+        self.bundles.iter()
+            .find(|(_, phone_bundle)| phone_bundle == bundle)
+            .map(|(symbol, _)| symbol)
+    }
+}
@@ -1,47 +1,95 @@
 use crate::{
-    escaped_strings::EscapedStr,
+    escaped_strings::{EscapedStr, EscapedString},
     keywords::{char_to_str, BOUND_CHAR, ESCAPE_CHAR},
+    phones::suprasegmentals::{is_tone_char, Length, Stress, Suprasegmentals},
     matcher::{
         choices::{Choices, OwnedChoices},
         match_state::UnitState,
         phones::Phones
     },
     lexer::substring::Substring,
+    shared_str::SharedStr,
 };
 
+pub mod features;
+pub mod suprasegmentals;
+
 #[cfg(test)]
 mod tests;
 
 /// A representation of a phoneme or word boundary
 /// 
-/// Stores the phoneme's symbol as a reference to the origional text or rules
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub enum Phone<'s> {
+/// Stores the phoneme's symbol as a slice of the origional text or rules that it shares ownership of
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Phone {
     /// A symbol representing a phoneme
-    Symbol(&'s str),
+    Symbol(SharedStr),
+    /// A symbol representing a phoneme with suprasegmental attributes
+    /// 
+    /// Should be created with `Phone::with_suprasegmentals` so that unmarked phones are always `Symbol`s
+    Marked(SharedStr, Suprasegmentals),
     /// A word boundary
     #[default]
     Bound,
 }
 
-impl<'s> Phone<'s> {
-    /// Returns the phone's symbol.
+impl Phone {
+    /// Creates a phone from a symbol that may contain suprasegmental marks (such as `ˈaː`)
+    #[must_use]
+    pub fn from_marked(s: &SharedStr) -> Self {
+        let (symbol, supras) = Suprasegmentals::split_marks(s);
+        Self::Symbol(symbol).with_suprasegmentals(supras)
+    }
+
+    /// Returns the phone with the given suprasegmental attributes,
+    /// bounds are returned unchanged
+    #[must_use]
+    pub fn with_suprasegmentals(self, supras: Suprasegmentals) -> Self {
+        match self {
+            Self::Symbol(symbol) | Self::Marked(symbol, _) if supras.is_unmarked() => Self::Symbol(symbol),
+            Self::Symbol(symbol) | Self::Marked(symbol, _) => Self::Marked(symbol, supras),
+            Self::Bound => Self::Bound,
+        }
+    }
+
+    /// Returns the phone's suprasegmental attributes,
+    /// unmarked phones and bounds have the default attributes
+    #[must_use]
+    pub fn suprasegmentals(&self) -> Suprasegmentals {
+        match self {
+            Self::Marked(_, supras) => supras.clone(),
+            _ => Suprasegmentals::default(),
+        }
+    }
+
+    /// Returns the phone's symbol without any suprasegmental marks,
+    /// or `None` if the phone is a boundary
+    #[must_use]
+    pub fn symbol(&self) -> Option<&SharedStr> {
+        match self {
+            Self::Symbol(symbol) | Self::Marked(symbol, _) => Some(symbol),
+            Self::Bound => None,
+        }
+    }
+
+    /// Returns the phone's symbol without any suprasegmental marks.
     /// If the phone is a boundary, `" "` (space) is returned
     #[must_use]
-    pub const fn as_str(&self) -> &'s str {
+    pub fn as_str(&self) -> &str {
         match self {
-            Self::Symbol(symbol) => symbol,
+            Self::Symbol(symbol) | Self::Marked(symbol, _) => symbol,
             Self::Bound => " ",
         }
     }
 
     
-    /// Returns the phone's symbol.
+    /// Returns the phone's symbol without any suprasegmental marks.
     /// If the phone is a boundary, `BOUND_CHAR` is returned as a string
     #[must_use]
-    pub const fn as_symbol(&self) -> &'s str {
+    pub fn as_symbol(&self) -> &str {
         match self {
-            Phone::Symbol(symbol) => symbol,
+            Phone::Symbol(symbol) | Phone::Marked(symbol, _) => symbol,
             Phone::Bound => const { char_to_str(&BOUND_CHAR) },
         }
     }
@@ -63,11 +111,23 @@ impl<'s> Phone<'s> {
             _ => self == other,
         }
     }
+
+    /// Determines if a phone matches a phone in a pattern
+    /// 
+    /// Phones with the same symbol match if they have every suprasegmental marked on the pattern
+    #[must_use]
+    pub fn matches_pattern(&self, pattern: &Self) -> bool {
+        match (self.symbol(), pattern.symbol()) {
+            (Some(symbol), Some(pattern_symbol)) if symbol == pattern_symbol
+                => self.suprasegmentals().satisfies(&pattern.suprasegmentals()),
+            _ => self.matches(pattern),
+        }
+    }
 }
 
-impl<'s> UnitState<'s> for Phone<'s> {
-    fn matches<'p>(&self, phones: &mut Phones<'_, 'p>, _: &Choices<'_, 'p>) -> Option<OwnedChoices<'s>> where 's: 'p {
-        let matches = Phone::matches(self, phones.next());
+impl UnitState for Phone {
+    fn matches(&self, phones: &mut Phones<'_>, _: &Choices<'_>) -> Option<OwnedChoices> {
+        let matches = phones.next().matches_pattern(self);
 
         if matches {
             Some(OwnedChoices::default())
@@ -79,13 +139,19 @@ impl<'s> UnitState<'s> for Phone<'s> {
     fn len(&self) -> usize { 1 }
 }
 
-impl std::fmt::Display for Phone<'_> {
+impl std::fmt::Display for Phone {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.as_str())
+        if let Self::Marked(symbol, supras) = self {
+            supras.fmt_prefix(f)?;
+            write!(f, "{symbol}")?;
+            supras.fmt_suffix(f)
+        } else {
+            write!(f, "{}", self.as_str())
+        }
     }
 }
 
-impl Default for &Phone<'_> {
+impl Default for &Phone {
     fn default() -> Self {
         &Phone::Bound
     }
@@ -94,23 +160,45 @@ impl Default for &Phone<'_> {
 /// Builds a list of phones from an input
 /// where each phone is a character or escaped character
 /// and reformats whitespace as word bounderies
+/// 
+/// Stress marks are added to the following phone,
+/// length marks and tones are added to the preceding phone
 #[must_use]
-pub fn build_phone_list(input: EscapedStr<'_>) -> Vec<Phone<'_>> {
-    let input = input.inner();
-    let mut substring = Substring::new(input);
+pub fn build_phone_list(input: EscapedStr<'_>) -> Vec<Phone> {
     let mut phones = Vec::new();
+    rebuild_phone_list(input, &mut phones);
+    phones
+}
+
+/// Builds a list of phones from an input in place of the phones in `phones`,
+/// reusing its allocation
+/// 
+/// See `build_phone_list`
+pub fn rebuild_phone_list(input: EscapedStr<'_>, phones: &mut Vec<Phone>) {
+    let source = SharedStr::from(input.inner());
+    let mut substring = Substring::new(&source);
+    phones.clear();
+    // a stress mark and the phone it represents if it is not followed by a symbol
+    let mut stress: Option<(Stress, Phone)> = None;
 
     while let Some(c) = substring.peek() {
         substring.grow();
 
+        // adds a stress mark that is not followed by a symbol as a phone
+        if c != ESCAPE_CHAR && (c.is_whitespace() || Stress::from_mark(c).is_some())
+            && let Some((_, mark)) = stress.take()
+        {
+            phones.push(mark);
+        }
+
         match c {
             ESCAPE_CHAR => (),
             '\n' => {
-                _ = substring.pass();
+                let newline = source.share(substring.pass());
                 if !phones.last().is_some_and(Phone::is_bound) {
                     phones.push(Phone::Bound);
                 }
-                phones.push(Phone::Symbol("\n"));
+                phones.push(Phone::Symbol(newline));
                 phones.push(Phone::Bound);
             },
             _ if c.is_whitespace() => {
@@ -119,15 +207,76 @@ pub fn build_phone_list(input: EscapedStr<'_>) -> Vec<Phone<'_>> {
                     phones.push(Phone::Bound);
                 }
             },
-            _ => phones.push(Phone::Symbol(substring.pass())),
+            _ if Stress::from_mark(c).is_some() => {
+                stress = Stress::from_mark(c).map(|level| (level, Phone::Symbol(source.share(substring.pass()))));
+            },
+            _ => {
+                if stress.is_none()
+                    && let Some(last) = phones.last_mut()
+                    && let Some(supras) = add_suffix_mark(last, c, &source, &mut substring)
+                {
+                    *last = std::mem::take(last).with_suprasegmentals(supras);
+                    continue;
+                }
+
+                let mut phone = Phone::Symbol(source.share(substring.pass()));
+
+                if let Some((level, _)) = stress.take() {
+                    phone = phone.with_suprasegmentals(Suprasegmentals { stress: level, ..Default::default() });
+                }
+
+                phones.push(phone);
+            },
         }
     }
 
     if !substring.str().is_empty() {
-        phones.push(Phone::Symbol(substring.str()));
+        phones.push(Phone::Symbol(source.share(substring.str())));
+    } else if let Some((_, mark)) = stress {
+        phones.push(mark);
     }
+}
 
-    phones
+/// Gets the suprasegmentals of a phone with a length mark or tone (`c`) added to them,
+/// passing the mark or the whole tone in the substring
+/// 
+/// Returns `None` if `c` is not a suffix mark or the phone cannot take it
+fn add_suffix_mark(phone: &Phone, c: char, source: &SharedStr, substring: &mut Substring<'_>) -> Option<Suprasegmentals> {
+    phone.symbol()?;
+    let mut supras = phone.suprasegmentals();
+
+    if let Some(length) = Length::from_mark(c) && supras.length == Length::Short {
+        _ = substring.pass();
+        supras.length = length;
+        Some(supras)
+    } else if is_tone_char(c) && supras.tone.is_none() {
+        // a tone is made of every tone character in a row
+        let tone_len = substring.rest().chars()
+            .take_while(|c| is_tone_char(*c))
+            .map(char::len_utf8)
+            .sum::<usize>();
+
+        substring.grow_by(tone_len);
+        supras.tone = Some(source.share(substring.pass()));
+        Some(supras)
+    } else {
+        None
+    }
+}
+
+/// Splits text into the phones it would be read as when rules are applied to it,
+/// each word boundary is represented by a space
+/// 
+/// Stress marks are added to the following phone,
+/// length marks and tones are added to the preceding phone
+#[must_use]
+pub fn split_phones(input: &str) -> Vec<String> {
+    let escaped = EscapedString::from(input);
+    let phones = build_phone_list(escaped.as_escaped_str());
+
+    phones.iter()
+        .map(|phone| phone_list_to_string(std::slice::from_ref(phone)))
+        .collect()
 }
 
 /// Converts a list of string slices to a string
@@ -136,7 +285,7 @@ pub fn build_phone_list(input: EscapedStr<'_>) -> Vec<Phone<'_>> {
 pub fn phone_list_to_string(phone_list: &[Phone]) -> String {
     // creates a string
     let s = phone_list.iter()
-        .map(Phone::as_str)
+        .map(ToString::to_string)
         .collect::<String>();
 
     // splits on escape characters
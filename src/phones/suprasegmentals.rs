@@ -0,0 +1,209 @@
+use crate::{keywords::{HALF_LONG_CHAR, LONG_CHAR, PRIMARY_STRESS_CHAR, SECONDARY_STRESS_CHAR, TONE_CHARS}, shared_str::SharedStr};
+
+/// The name of the built-in feature for stress
+pub const STRESS_FEATURE: &str = "stress";
+/// The name of the built-in feature for length
+pub const LONG_FEATURE: &str = "long";
+
+/// Checks if a feature name is reserved for a suprasegmental
+pub(crate) fn is_suprasegmental_feature(name: &str) -> bool {
+    name == STRESS_FEATURE || name == LONG_FEATURE
+}
+
+/// Checks if a char is part of a tone
+pub(crate) fn is_tone_char(c: char) -> bool {
+    TONE_CHARS.contains(&c)
+}
+
+/// Checks if a symbol is made only of suprasegmental marks,
+/// which are part of the phone they are written next to when an input is split into phones
+pub(crate) fn is_detached_mark(symbol: &str) -> bool {
+    !symbol.is_empty() && symbol.chars().all(|c| Stress::from_mark(c).is_some() || Length::from_mark(c).is_some() || is_tone_char(c))
+}
+
+/// The level of stress on a phone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Stress {
+    #[default]
+    Unstressed,
+    Secondary,
+    Primary,
+}
+
+impl Stress {
+    /// Gets the stress level represented by a mark
+    #[must_use]
+    pub const fn from_mark(c: char) -> Option<Self> {
+        match c {
+            PRIMARY_STRESS_CHAR => Some(Self::Primary),
+            SECONDARY_STRESS_CHAR => Some(Self::Secondary),
+            _ => None,
+        }
+    }
+
+    /// Gets the mark for the stress level
+    #[must_use]
+    pub const fn mark(self) -> Option<char> {
+        match self {
+            Self::Unstressed => None,
+            Self::Secondary => Some(SECONDARY_STRESS_CHAR),
+            Self::Primary => Some(PRIMARY_STRESS_CHAR),
+        }
+    }
+}
+
+/// The length of a phone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Length {
+    #[default]
+    Short,
+    HalfLong,
+    Long,
+}
+
+impl Length {
+    /// Gets the length represented by a mark
+    #[must_use]
+    pub const fn from_mark(c: char) -> Option<Self> {
+        match c {
+            LONG_CHAR => Some(Self::Long),
+            HALF_LONG_CHAR => Some(Self::HalfLong),
+            _ => None,
+        }
+    }
+
+    /// Gets the mark for the length
+    #[must_use]
+    pub const fn mark(self) -> Option<char> {
+        match self {
+            Self::Short => None,
+            Self::HalfLong => Some(HALF_LONG_CHAR),
+            Self::Long => Some(LONG_CHAR),
+        }
+    }
+}
+
+/// The stress, length, and tone of a phone
+/// 
+/// The default values (unstressed, short, and no tone) are unmarked
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Suprasegmentals {
+    pub stress: Stress,
+    pub length: Length,
+    pub tone: Option<SharedStr>,
+}
+
+impl Suprasegmentals {
+    /// Checks if no attribute is marked
+    #[must_use]
+    pub fn is_unmarked(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Checks if the attributes have every marked attribute of a pattern's attributes
+    #[must_use]
+    pub fn satisfies(&self, pattern: &Suprasegmentals) -> bool {
+        (pattern.stress == Stress::Unstressed || pattern.stress == self.stress)
+            && (pattern.length == Length::Short || pattern.length == self.length)
+            && (pattern.tone.is_none() || pattern.tone == self.tone)
+    }
+
+    /// Replaces the attributes with every marked attribute of `other`
+    #[must_use]
+    pub fn overlay(self, other: Self) -> Self {
+        Self {
+            stress: if other.stress == Stress::Unstressed { self.stress } else { other.stress },
+            length: if other.length == Length::Short { self.length } else { other.length },
+            tone: other.tone.or(self.tone),
+        }
+    }
+
+    /// Resets every attribute marked in `other` to its unmarked value
+    #[must_use]
+    pub fn clear(self, other: &Suprasegmentals) -> Self {
+        Self {
+            stress: if other.stress == Stress::Unstressed { self.stress } else { Stress::Unstressed },
+            length: if other.length == Length::Short { self.length } else { Length::Short },
+            tone: if other.tone.is_none() { self.tone } else { None },
+        }
+    }
+
+    /// Gets the value of a built-in suprasegmental feature
+    pub(crate) fn feature(&self, name: &str) -> Option<bool> {
+        match name {
+            STRESS_FEATURE => Some(self.stress != Stress::Unstressed),
+            LONG_FEATURE => Some(self.length != Length::Short),
+            _ => None,
+        }
+    }
+
+    /// Sets the value of a built-in suprasegmental feature
+    /// 
+    /// Adding a feature gives primary stress or full length
+    /// only if the phone does not already have the feature
+    pub(crate) fn set_feature(&mut self, name: &str, value: bool) {
+        match (name, value) {
+            (STRESS_FEATURE, false) => self.stress = Stress::Unstressed,
+            (STRESS_FEATURE, true) if self.stress == Stress::Unstressed => self.stress = Stress::Primary,
+            (LONG_FEATURE, false) => self.length = Length::Short,
+            (LONG_FEATURE, true) if self.length == Length::Short => self.length = Length::Long,
+            _ => (),
+        }
+    }
+
+    /// Seperates the suprasegmental marks from a symbol written as a single phone (such as `ˈaː˥˩`)
+    /// 
+    /// Returns the symbol without its marks and the marked attributes,
+    /// if the symbol would be empty, it is returned with no attributes
+    #[must_use]
+    pub fn split_marks(s: &SharedStr) -> (SharedStr, Self) {
+        let mut supras = Self::default();
+        let mut symbol = s.as_str();
+
+        if let Some(c) = symbol.chars().next() && let Some(stress) = Stress::from_mark(c) {
+            supras.stress = stress;
+            symbol = &symbol[c.len_utf8()..];
+        }
+
+        while let Some(c) = symbol.chars().next_back() {
+            if let Some(length) = Length::from_mark(c) && supras.length == Length::Short {
+                supras.length = length;
+                symbol = &symbol[..symbol.len() - c.len_utf8()];
+            } else if is_tone_char(c) && supras.tone.is_none() {
+                let core = symbol.trim_end_matches(is_tone_char);
+                supras.tone = Some(s.share(&symbol[core.len()..]));
+                symbol = core;
+            } else {
+                break;
+            }
+        }
+
+        if symbol.is_empty() {
+            (s.clone(), Self::default())
+        } else {
+            (s.share(symbol), supras)
+        }
+    }
+
+    /// Writes the marks that come before a phone's symbol
+    pub(crate) fn fmt_prefix(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(mark) = self.stress.mark() {
+            write!(f, "{mark}")?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the marks that come after a phone's symbol
+    pub(crate) fn fmt_suffix(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(mark) = self.length.mark() {
+            write!(f, "{mark}")?;
+        }
+
+        if let Some(tone) = &self.tone {
+            write!(f, "{tone}")?;
+        }
+
+        Ok(())
+    }
+}
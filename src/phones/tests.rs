@@ -16,34 +16,34 @@ fn shifting_bounds() {
 fn phone_list() {
     assert_eq!(
         build_phone_list(EscapedString::from("a b c").as_escaped_str()),
-        vec![Phone::Symbol("a"), Phone::Bound, Phone::Symbol("b"), Phone::Bound, Phone::Symbol("c")]
+        vec![Phone::Symbol("a".into()), Phone::Bound, Phone::Symbol("b".into()), Phone::Bound, Phone::Symbol("c".into())]
     );
 
     assert_eq!(
         build_phone_list(EscapedString::from("ab c").as_escaped_str()),
-        vec![Phone::Symbol("a"), Phone::Symbol("b"), Phone::Bound, Phone::Symbol("c")]
+        vec![Phone::Symbol("a".into()), Phone::Symbol("b".into()), Phone::Bound, Phone::Symbol("c".into())]
     );
 
     assert_eq!(
         build_phone_list(EscapedString::from(format!("a{BOUND_CHAR}b c").as_str()).as_escaped_str()),
-        vec![Phone::Symbol("a"), Phone::Symbol(&format!("{ESCAPE_CHAR}{BOUND_CHAR}")), Phone::Symbol("b"), Phone::Bound, Phone::Symbol("c")]
+        vec![Phone::Symbol("a".into()), Phone::Symbol(format!("{ESCAPE_CHAR}{BOUND_CHAR}").into()), Phone::Symbol("b".into()), Phone::Bound, Phone::Symbol("c".into())]
     );
 }
 
 #[test]
 fn phone_list_to_str() {
     assert_eq!(
-        phone_list_to_string(&[Phone::Symbol("a"), Phone::Symbol("b"), Phone::Symbol("c")]),
+        phone_list_to_string(&[Phone::Symbol("a".into()), Phone::Symbol("b".into()), Phone::Symbol("c".into())]),
         "abc"
     );
 
     assert_eq!(
-        phone_list_to_string(&[Phone::Symbol("a"), Phone::Bound, Phone::Symbol("b"), Phone::Bound, Phone::Symbol("c")]),
+        phone_list_to_string(&[Phone::Symbol("a".into()), Phone::Bound, Phone::Symbol("b".into()), Phone::Bound, Phone::Symbol("c".into())]),
         "a b c"
     );
 
     assert_eq!(
-        phone_list_to_string(&[Phone::Symbol(char_to_str(&BOUND_CHAR))]),
+        phone_list_to_string(&[Phone::Symbol(char_to_str(&BOUND_CHAR).into())]),
         char_to_str(&BOUND_CHAR)
     );
 }
@@ -51,41 +51,100 @@ fn phone_list_to_str() {
 #[test]
 fn phone_list_to_str_with_escapes() {
     assert_eq!(
-        phone_list_to_string(&[Phone::Symbol(&format!("{ESCAPE_CHAR}.")), Phone::Symbol("b"), Phone::Symbol("c")]),
+        phone_list_to_string(&[Phone::Symbol(format!("{ESCAPE_CHAR}.").into()), Phone::Symbol("b".into()), Phone::Symbol("c".into())]),
         ".bc"
     );
 
     assert_eq!(
-        phone_list_to_string(&[Phone::Symbol(&format!("a{ESCAPE_CHAR}.")), Phone::Bound, Phone::Symbol("b"), Phone::Bound, Phone::Symbol("c")]),
+        phone_list_to_string(&[Phone::Symbol(format!("a{ESCAPE_CHAR}.").into()), Phone::Bound, Phone::Symbol("b".into()), Phone::Bound, Phone::Symbol("c".into())]),
         "a. b c"
     );
 
     assert_eq!(
-        phone_list_to_string(&[Phone::Symbol("a"), Phone::Bound, Phone::Symbol(&format!("{ESCAPE_CHAR}b")), Phone::Bound, Phone::Symbol("c")]),
+        phone_list_to_string(&[Phone::Symbol("a".into()), Phone::Bound, Phone::Symbol(format!("{ESCAPE_CHAR}b").into()), Phone::Bound, Phone::Symbol("c".into())]),
         "a b c"
     );
 
     assert_eq!(
-        phone_list_to_string(&[Phone::Symbol("a"), Phone::Bound, Phone::Symbol(&format!("{ESCAPE_CHAR}{ESCAPE_CHAR}b")), Phone::Bound, Phone::Symbol("c")]),
+        phone_list_to_string(&[Phone::Symbol("a".into()), Phone::Bound, Phone::Symbol(format!("{ESCAPE_CHAR}{ESCAPE_CHAR}b").into()), Phone::Bound, Phone::Symbol("c".into())]),
         format!("a {ESCAPE_CHAR}b c")
     );
 
     assert_eq!(
-        phone_list_to_string(&[Phone::Symbol(char_to_str(&BOUND_CHAR))]),
+        phone_list_to_string(&[Phone::Symbol(char_to_str(&BOUND_CHAR).into())]),
         char_to_str(&BOUND_CHAR)
     );
 }
 
 #[test]
 fn text_matches_text () {
-    assert!(Phone::Symbol("test").matches(&Phone::Symbol("test")));
-    assert!(!Phone::Symbol("test").matches(&Phone::Symbol("not test")));
+    assert!(Phone::Symbol("test".into()).matches(&Phone::Symbol("test".into())));
+    assert!(!Phone::Symbol("test".into()).matches(&Phone::Symbol("not test".into())));
 }
 
 #[test]
 fn bound_matches_phone() {
-    assert!(Phone::Symbol(" ").matches(&Phone::Bound));
-    assert!(Phone::Symbol("  ").matches(&Phone::Bound));
-    assert!(Phone::Bound.matches(&Phone::Symbol(" ")));
-    assert!(Phone::Symbol(" ").matches(&Phone::Symbol(" ")));
-}
\ No newline at end of file
+    assert!(Phone::Symbol(" ".into()).matches(&Phone::Bound));
+    assert!(Phone::Symbol("  ".into()).matches(&Phone::Bound));
+    assert!(Phone::Bound.matches(&Phone::Symbol(" ".into())));
+    assert!(Phone::Symbol(" ".into()).matches(&Phone::Symbol(" ".into())));
+}
+
+#[test]
+fn phone_list_with_suprasegmentals() {
+    let primary = Suprasegmentals { stress: Stress::Primary, ..Default::default() };
+    let long = Suprasegmentals { length: Length::Long, ..Default::default() };
+    let tone = Suprasegmentals { tone: Some("˥˩".into()), ..Default::default() };
+
+    assert_eq!(
+        build_phone_list(EscapedString::from("pˈaː ma˥˩").as_escaped_str()),
+        vec![
+            Phone::Symbol("p".into()),
+            Phone::Marked("a".into(), Suprasegmentals { length: Length::Long, ..primary }),
+            Phone::Bound,
+            Phone::Symbol("m".into()),
+            Phone::Marked("a".into(), tone),
+        ]
+    );
+
+    assert_eq!(
+        build_phone_list(EscapedString::from("ːa aːː ˈ b").as_escaped_str()),
+        vec![
+            Phone::Symbol("ː".into()),
+            Phone::Symbol("a".into()),
+            Phone::Bound,
+            Phone::Marked("a".into(), long),
+            Phone::Symbol("ː".into()),
+            Phone::Bound,
+            Phone::Symbol("ˈ".into()),
+            Phone::Bound,
+            Phone::Symbol("b".into()),
+        ]
+    );
+}
+
+#[test]
+fn phone_list_to_str_with_suprasegmentals() {
+    for input in ["pˈaː ma˥˩", "ˌaˑ¹²", "ːa aːː ˈ b"] {
+        assert_eq!(
+            phone_list_to_string(&build_phone_list(EscapedString::from(input).as_escaped_str())),
+            input
+        );
+    }
+}
+
+#[test]
+fn marked_phones() {
+    assert_eq!(Phone::from_marked(&"ˈaː˥".into()), Phone::Marked("a".into(), Suprasegmentals { stress: Stress::Primary, length: Length::Long, tone: Some("˥".into()) }));
+    assert_eq!(Phone::from_marked(&"a".into()), Phone::Symbol("a".into()));
+    assert_eq!(Phone::from_marked(&"ˈ".into()), Phone::Symbol("ˈ".into()));
+    assert!(Phone::from_marked(&"ˈaː".into()).matches_pattern(&Phone::Symbol("a".into())));
+    assert!(Phone::from_marked(&"ˈaː".into()).matches_pattern(&Phone::from_marked(&"ˈa".into())));
+    assert!(!Phone::Symbol("a".into()).matches_pattern(&Phone::from_marked(&"ˈa".into())));
+}
+
+#[test]
+fn split_phones_from_text() {
+    assert_eq!(split_phones("ab c"), ["a", "b", " ", "c"]);
+    assert_eq!(split_phones("pˈaːta˥"), ["p", "ˈaː", "t", "a˥"]);
+}
@@ -0,0 +1,131 @@
+use std::{borrow::Borrow, hash::Hash, ops::Deref, sync::Arc};
+
+/// A string slice that shares ownership of the string it was taken from
+///
+/// Cloning only copies a reference to the string,
+/// so phones and tokens can keep their text without borrowing the rules or the input
+#[derive(Clone)]
+pub struct SharedStr {
+    source: Arc<str>,
+    start: usize,
+    end: usize,
+}
+
+impl SharedStr {
+    /// Gets the slice as a `&str`
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.source[self.start..self.end]
+    }
+
+    /// Creates a `SharedStr` for a slice of this string that shares the same source
+    ///
+    /// # Panics
+    /// Panics if `slice` is not a slice of this string
+    #[must_use]
+    pub fn share(&self, slice: &str) -> Self {
+        let start = slice.as_ptr().addr().wrapping_sub(self.as_str().as_ptr().addr());
+
+        assert!(start <= self.len() && start + slice.len() <= self.len(), "`{slice}` should be a slice of `{self}`");
+
+        Self {
+            source: Arc::clone(&self.source),
+            start: self.start + start,
+            end: self.start + start + slice.len(),
+        }
+    }
+}
+
+impl Default for SharedStr {
+    fn default() -> Self {
+        Self::from("")
+    }
+}
+
+impl From<&str> for SharedStr {
+    fn from(value: &str) -> Self {
+        Self::from(Arc::<str>::from(value))
+    }
+}
+
+impl From<String> for SharedStr {
+    fn from(value: String) -> Self {
+        Self::from(Arc::<str>::from(value))
+    }
+}
+
+impl From<Arc<str>> for SharedStr {
+    fn from(source: Arc<str>) -> Self {
+        Self { start: 0, end: source.len(), source }
+    }
+}
+
+impl Deref for SharedStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for SharedStr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for SharedStr {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq for SharedStr {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for SharedStr {}
+
+impl PartialEq<str> for SharedStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for SharedStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialOrd for SharedStr {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SharedStr {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Hash for SharedStr {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl std::fmt::Debug for SharedStr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+impl std::fmt::Display for SharedStr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
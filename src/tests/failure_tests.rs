@@ -1,5 +1,5 @@
-use crate::build_rules;
-use crate::io_macros::{await_io, io_test};
+use crate::{build_rules, LineApplicationLimit, ScaErrorKind};
+use crate::io_macros::{await_io, io_fn, io_test};
 use crate::tests::{apply_fallible, NoGet, NoLog};
 
 #[io_test(pollster::block_on)]
@@ -16,6 +16,38 @@ fn unmatched_output_scope() {
     assert!(await_io! { apply_fallible("a", "a >> *") }.is_err());
 }
 
+#[io_test(pollster::block_on)]
+fn invalid_features() {
+    assert!(await_io! { apply_fallible("a", "[+voice] >> b") }.is_err());
+    assert!(await_io! { apply_fallible("a", "FEATURES a [+voice]\n[+voice -cont] >> b") }.is_err());
+    assert!(await_io! { apply_fallible("a", "FEATURES [+voice]") }.is_err());
+    assert!(await_io! { apply_fallible("a", "FEATURES a [+voice] b") }.is_err());
+    assert!(await_io! { apply_fallible("a", "FEATURES a (b) [+voice]") }.is_err());
+    assert!(await_io! { apply_fallible("a", "FEATURES a [αvoice]") }.is_err());
+    assert!(await_io! { apply_fallible("a", "FEATURES a [+voice]\n[+voice] >> [-voice]") }.is_err());
+    assert!(await_io! { apply_fallible("a", "FEATURES a [+stress]") }.is_err());
+}
+
+#[io_test(pollster::block_on)]
+fn invalid_syllables() {
+    assert!(await_io! { apply_fallible("a", "a >> b / _ .") }.is_err());
+    assert!(await_io! { apply_fallible("a", "SYLLABIFY , a,\na >> b .") }.is_err());
+    assert!(await_io! { apply_fallible("a", "SYLLABIFY , a,\nSYLLABIFY\na >> b / _ .") }.is_err());
+    assert!(await_io! { apply_fallible("a", "SYLLABIFY b, a") }.is_err());
+    assert!(await_io! { apply_fallible("a", "SYLLABIFY b, , c") }.is_err());
+    assert!(await_io! { apply_fallible("a", "SYLLABIFY b, a, c}") }.is_err());
+    assert!(await_io! { apply_fallible("a", "SYLLABIFY , a,\nSYLLABIFY ., a,") }.is_err());
+    assert!(await_io! { apply_fallible("a", "SYLLABIFY , a, >> b") }.is_err());
+}
+
+#[io_test(pollster::block_on)]
+fn invalid_chain_shifts() {
+    assert!(await_io! { apply_fallible("a", "a >> e << i") }.is_err());
+    assert!(await_io! { apply_fallible("a", "a >> e > i") }.is_err());
+    assert!(await_io! { apply_fallible("a", "a > e > i") }.is_err());
+    assert!(await_io! { apply_fallible("a", "a >> >> i") }.is_err());
+}
+
 #[io_test(pollster::block_on)]
 fn comma_not_in_selection() {
     assert!(await_io! { apply_fallible("a", "a, b >> c") }.is_err());
@@ -82,4 +114,22 @@ fn error_on_correct_line_after_escaped_newline_in_definition() {
             .line_number()
             .get()
     );
-}
\ No newline at end of file
+}
+
+/// Applies rules to an input and gets the kind of the error that occurs
+#[io_fn]
+fn error_kind(input: &str, rules: &str) -> ScaErrorKind {
+    await_io! { apply_fallible(input, rules) }
+        .expect_err("rules should fail")
+        .kind()
+        .clone()
+}
+
+#[io_test(pollster::block_on)]
+fn error_kinds() {
+    assert_eq!(await_io! { error_kind("a", "@V >> b") }, ScaErrorKind::UndefinedDefinition { name: "V".to_string() });
+    assert_eq!(await_io! { error_kind("a", "a >> {b, c}") }, ScaErrorKind::UnmatchedTokenInOutput { pattern: "{ b, c }".to_string() });
+    assert_eq!(await_io! { error_kind("a", "a > b a") }, ScaErrorKind::ExceededLimit { limit: LineApplicationLimit::Attempts(10000) });
+    assert_eq!(await_io! { error_kind("a", "a >> b / _ {c") }, ScaErrorKind::UnclosedScope { start: '{' });
+    assert_eq!(await_io! { error_kind("a", "GET x msg:") }, ScaErrorKind::Io { message: "`GET` and `GET_AS_CODE` not implemented".to_string() });
+}
@@ -157,4 +157,119 @@ fn escape_printing() {
 #[io_test(pollster::block_on)]
 fn complex_agreement() {
     assert_eq!("zbc", await_io! { apply("abc", "a >> z / _ $c{b, * c} // _ $c{b, d}") });
-}
\ No newline at end of file
+}
+
+const FEATURE_DECLARATIONS: &str = "FEATURES p t k [-voice -syll]\nFEATURES b d g [+voice -syll]\nFEATURES a i u [+voice +syll]\n";
+
+#[io_test(pollster::block_on)]
+fn feature_matrices() {
+    assert_eq!("ahahu", await_io! { apply("apadu", &format!("{FEATURE_DECLARATIONS}[-syll] >> h")) });
+    assert_eq!("ahadu", await_io! { apply("apadu", &format!("{FEATURE_DECLARATIONS}[-voice] >> h")) });
+    assert_eq!("apbdu", await_io! { apply("apadu", &format!("{FEATURE_DECLARATIONS}a >> b / _ [+voice -syll]")) });
+    assert_eq!("xpadu", await_io! { apply("apadu", &format!("{FEATURE_DECLARATIONS}a >> x / _ [-voice] [+syll]")) });
+    assert_eq!("ipadu", await_io! { apply("apadu", &format!("{FEATURE_DECLARATIONS}[+syll] >> i / # _")) });
+    assert_eq!("appaddu", await_io! { apply("apadu", &format!("{FEATURE_DECLARATIONS}$c[-syll] >> $c* $c* / a _")) });
+}
+
+#[io_test(pollster::block_on)]
+fn feature_matrices_in_definitions_and_equalities() {
+    let rules = format!("{FEATURE_DECLARATIONS}DEFINE C [-syll]\nDEFINE stops {{p, t, k, b, d, g}}\nFEATURES @stops [+stop]\n{{a, u}} >> {{e, o}} / $c@C _ & $c@C = [+stop]");
+
+    assert_eq!("apedo", await_io! { apply("apadu", &rules) });
+}
+
+#[io_test(pollster::block_on)]
+fn feature_changing_outputs() {
+    assert_eq!("abadu", await_io! { apply("apadu", &format!("{FEATURE_DECLARATIONS}[-syll] >> [+voice] / [+syll] _ [+syll]")) });
+    assert_eq!("apatu", await_io! { apply("apadu", "FEATURES p t [-voice]\nFEATURES b d [+voice]\nFEATURES p b [+lab]\nFEATURES t d [-lab]\n$c[+voice] >> $c[-voice]") });
+    assert_eq!("apbdu", await_io! { apply("apadu", &format!("{FEATURE_DECLARATIONS}[+syll] >> [-syll] / [-voice] _ [+voice -syll]")) });
+}
+
+#[io_test(pollster::block_on)]
+fn alpha_agreement() {
+    let rules = "FEATURES m n [+nasal +labial]\nFEATURES n [-labial]\nFEATURES p b [-nasal +labial]\nFEATURES t d [-nasal -labial]\n[+nasal] >> [αlabial] / _ [αlabial]";
+
+    assert_eq!("ampa nta mba nda", await_io! { apply("anpa mta nba mda", rules) });
+    assert_eq!("pd", await_io! { apply("pb", "FEATURES p t [-voice]\nFEATURES b d [+voice]\nFEATURES p b [+labial]\nFEATURES t d [-labial]\n[αlabial] >> [-αlabial] / [αlabial] _") });
+    assert!(await_io! { apply_fallible("pb", "FEATURES p t [+labial]\np >> [αlabial]") }.is_err());
+}
+
+const SYLLABLE_DECLARATIONS: &str = "DEFINE C {p, t, k, r, l, s}\nDEFINE V {a, e, i, o, u}\nSYLLABIFY ({@C, {p, t, k} {r, l}}), @V, (@C)\n";
+
+#[io_test(pollster::block_on)]
+fn syllable_boundaries() {
+    assert_eq!("patko potro pasto", await_io! { apply("patka patra pasta", &format!("{SYLLABLE_DECLARATIONS}a >> o / _ .")) });
+    assert_eq!("patko potro pasto", await_io! { apply("patka patra pasta", &format!("{SYLLABLE_DECLARATIONS}a << o / _ .")) });
+    assert_eq!("opa", await_io! { apply("apa", &format!("{SYLLABLE_DECLARATIONS}a >> o / . _")) });
+    assert_eq!("tra", await_io! { apply("tar", &format!("{SYLLABLE_DECLARATIONS}a r >> r a / _ .")) });
+}
+
+#[io_test(pollster::block_on)]
+fn syllable_boundaries_follow_changes() {
+    // the boundary moves from `pat.ra` to `pa.tra` after the first rule
+    assert_eq!("potra", await_io! { apply("pasra", &format!("{SYLLABLE_DECLARATIONS}s >> t\na >> o / _ . t")) });
+    assert_eq!("potra", await_io! { apply("patra", &format!("{SYLLABLE_DECLARATIONS}SYLLABIFY\nSYLLABIFY ({{@C, {{p, t, k}} {{r, l}}}}), @V, (@C)\na >> o / _ . t")) });
+    // the boundaries move after each change the rule makes
+    assert_eq!("kakaka", await_io! { apply("katkatka", &format!("{SYLLABLE_DECLARATIONS}t >> / _ . k")) });
+}
+
+#[io_test(pollster::block_on)]
+fn chain_shifts() {
+    assert_eq!("ei", await_io! { apply("ae", "a >> e >> i") });
+    assert_eq!("ei", await_io! { apply("ae", "a << e << i") });
+    assert_eq!("ea ae", await_io! { apply("ae ea", "a >> e >> a") });
+    assert_eq!("oi u", await_io! { apply("ae o", "a >> o >> u\ne >> i") });
+    assert_eq!("si hi ka", await_io! { apply("ki si ka", "k >> s >> h / _ i") });
+    // conditions see the phones changed by earlier matches, unless the shift is simultaneous
+    assert_eq!("eee", await_io! { apply("aae", "a << e << i / _ e") });
+    assert_eq!("aee", await_io! { apply("aae", "a <<< e <<< i / _ e") });
+}
+
+#[io_test(pollster::block_on)]
+fn simultaneous_shifts() {
+    assert_eq!("adada", await_io! { apply("atata", "t >>> d / a _ a") });
+    assert_eq!("aba", await_io! { apply("aaa", "a >> b / a _") });
+    assert_eq!("abb", await_io! { apply("aaa", "a >>> b / a _") });
+    assert_eq!("bba", await_io! { apply("aaa", "a <<< b / _ a") });
+    assert_eq!("aaa", await_io! { apply("atata", "t >>> / a _ a") });
+    assert_eq!("ei", await_io! { apply("ae", "a >>> e >>> i") });
+}
+
+#[io_test(pollster::block_on)]
+fn once_shifts() {
+    assert_eq!("ebab", await_io! { apply("abab", "a >>! e") });
+    assert_eq!("abeb", await_io! { apply("abab", "a <<! e") });
+    assert_eq!("ebab eba", await_io! { apply("abab aba", "a >>! e") });
+    assert_eq!("abeb abe", await_io! { apply("abab aba", "a <<! e") });
+    assert_eq!("baa", await_io! { apply("aaa", "a >! b") });
+    assert_eq!("a a", await_io! { apply("ab ab", "b >>! / _ #") });
+    assert_eq!("aba", await_io! { apply("aaa", "a >>>! b / a _") });
+    assert_eq!("aab aab", await_io! { apply("aaa aaa", "a <<<! b / a _") });
+    assert_eq!("ˈpata", await_io! { apply("pata", "{p, t, k} >>! ˈ{p, t, k}") });
+}
+
+#[io_test(pollster::block_on)]
+fn suprasegmentals() {
+    assert_eq!("pˈeteː", await_io! { apply("pˈataː", "a >> e") });
+    assert_eq!("potaː", await_io! { apply("pˈataː", "ˈa >> o") });
+    assert_eq!("pˈota", await_io! { apply("pˈataː", "ˈa >> ˈo\naː >> a") });
+    assert_eq!("pata", await_io! { apply("pˈataː", "ˈa >> a\naː >> a") });
+    assert_eq!("pˈotˈo", await_io! { apply("pˈataː", "a >> o\noː >> ˈo") });
+    assert_eq!("patˈaː", await_io! { apply("pˈataː", "a >> ˈa / _ #\n[+stress] >> [-stress] / _ *") });
+    assert_eq!("ma˩ ma˩ ma", await_io! { apply("ma˥ ma˩ ma", "a˥ >> a˩") });
+    assert_eq!("sˈaːta", await_io! { apply("sˈata", &format!("{FEATURE_DECLARATIONS}{SYLLABLE_DECLARATIONS}[+syll +stress] >> [+long] / _ .")) });
+    assert_eq!("tˈa at", await_io! { apply("ˈat ta", "$a* $b* >> $b* $a* / # _ #") });
+}
+
+#[io_test(pollster::block_on)]
+fn merging_length_marks() {
+    // length marks are already part of the phone before them, so merging them does nothing
+    assert_eq!("paːt", await_io! { apply("paːt", "a ː >> aː") });
+    assert_eq!("peːt", await_io! { apply("paːt", "a ː >> aː\naː >> eː") });
+    assert_eq!("tsaː", await_io! { apply("tsaː", "t s >> ts\na ː >> aː") });
+}
+
+#[io_test(pollster::block_on)]
+fn escaped_syllable_bound_char() {
+    assert_eq!("a-b", await_io! { apply("a.b", "\\. >> -") });
+}
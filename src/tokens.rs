@@ -1,22 +1,33 @@
-use crate::{ir::tokens::IrToken, keywords::{AND_CHAR, ANY_CHAR, REPETITION_END_CHAR, REPETITION_START_CHAR, INPUT_PATTERN_STR, LTR_CHAR, MATCH_CHAR, NOT_CHAR, OPTIONAL_END_CHAR, OPTIONAL_START_CHAR, RTL_CHAR, SELECTION_END_CHAR, SELECTION_START_CHAR}};
+use crate::{ir::tokens::IrToken, shared_str::SharedStr, keywords::{AND_CHAR, ANY_CHAR, FEATURE_MINUS_CHAR, FEATURE_PLUS_CHAR, REPETITION_END_CHAR, REPETITION_START_CHAR, INPUT_PATTERN_STR, LTR_CHAR, MATCH_CHAR, NOT_CHAR, OPTIONAL_END_CHAR, OPTIONAL_START_CHAR, RTL_CHAR, SELECTION_END_CHAR, SELECTION_START_CHAR}};
 
-use std::{fmt::Display, rc::Rc};
+use std::{collections::HashMap, fmt::Display, sync::Arc};
 
 /// A token determining the direction and type of shift
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Shift {
-    /// The shidt direction
+    /// The shift direction
     pub dir: Direction,
     /// The shift type
     pub kind: ShiftType,
+    /// Whether the shift stops after its first successful change
+    pub once: bool,
 }
 
 impl Display for Shift {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self { dir, kind: ShiftType::Stay } => write!(f, "{dir}"),
-            Self { dir, kind: ShiftType::Move } => write!(f, "{dir}{dir}"),
+        let Self { dir, kind, once } = self;
+
+        match kind {
+            ShiftType::Stay => write!(f, "{dir}")?,
+            ShiftType::Move => write!(f, "{dir}{dir}")?,
+            ShiftType::Simultaneous => write!(f, "{dir}{dir}{dir}")?,
+        }
+
+        if *once {
+            write!(f, "{NOT_CHAR}")?;
         }
+
+        Ok(())
     }
 }
 
@@ -43,13 +54,13 @@ impl Direction {
         self.change_by(n, 1)
     }
 
-    /// Returns the first index required for traversing a list according to direction
+    /// Returns the first index required for traversing a list of length `len` according to direction
     /// 
     /// (LTR returns 0, RTL returns list length - 1)
-    pub (crate) const fn start_index<T>(self, list: &[T]) -> usize {
+    pub (crate) const fn start_index(self, len: usize) -> usize {
         match self {
             Self::Ltr => 0,
-            Self::Rtl => list.len().wrapping_sub(1),
+            Self::Rtl => len.wrapping_sub(1),
         }
     }
 }
@@ -72,6 +83,9 @@ pub enum ShiftType {
     Move,
     /// The next phones analyzed are the newly inserted phones
     Stay,
+    /// Every match is found in the phones before the rule is applied,
+    /// then all of the matches are replaced at once
+    Simultaneous,
 }
 
 /// The type of a scope or scope bound
@@ -83,6 +97,8 @@ pub enum ScopeType {
     Selection,
     /// A scope that represents a repetition of phones
     Repetition,
+    /// A scope that represents a phone with a set of distinctive features
+    Features,
 }
 
 impl ScopeType {
@@ -92,7 +108,7 @@ impl ScopeType {
         match self {
             ScopeType::Optional => OPTIONAL_START_CHAR,
             ScopeType::Selection => SELECTION_START_CHAR,
-            ScopeType::Repetition => REPETITION_START_CHAR,
+            ScopeType::Repetition | ScopeType::Features => REPETITION_START_CHAR,
         }
     }
 
@@ -102,7 +118,7 @@ impl ScopeType {
         match self {
             ScopeType::Optional => OPTIONAL_END_CHAR,
             ScopeType::Selection => SELECTION_END_CHAR,
-            ScopeType::Repetition => REPETITION_END_CHAR,
+            ScopeType::Repetition | ScopeType::Features => REPETITION_END_CHAR,
         }
     }
 }
@@ -113,6 +129,87 @@ impl Display for ScopeType {
     }
 }
 
+/// The value of a distinctive feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FeatureValue {
+    /// The phone has the feature
+    Plus,
+    /// The phone does not have the feature
+    Minus,
+    /// The feature agrees with all other uses of the alpha variable
+    Alpha(char),
+    /// The feature is the opposite of all other uses of the alpha variable
+    NegatedAlpha(char),
+}
+
+impl FeatureValue {
+    /// Gets the value as `true` for plus and `false` for minus,
+    /// looking up alpha variables in `alphas`
+    /// 
+    /// Returns `None` if the value is an alpha variable that has not been set
+    pub(crate) fn resolve(self, alphas: &HashMap<char, bool>) -> Option<bool> {
+        match self {
+            Self::Plus => Some(true),
+            Self::Minus => Some(false),
+            Self::Alpha(alpha) => alphas.get(&alpha).copied(),
+            Self::NegatedAlpha(alpha) => alphas.get(&alpha).map(|value| !value),
+        }
+    }
+}
+
+/// Determines if a character may be used as an alpha variable (lowercase greek letters)
+pub(crate) const fn is_alpha_variable(c: char) -> bool {
+    matches!(c, 'α'..='ω')
+}
+
+/// A distinctive feature and the value it should have
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FeatureSpec {
+    /// The feature's value
+    pub value: FeatureValue,
+    /// The feature's name
+    pub name: SharedStr,
+}
+
+impl FeatureSpec {
+    /// Parses a feature specification such as `+voice`, `-cont`, `αplace` or `-αplace`
+    /// 
+    /// Returns `None` if the string is not a feature specification
+    #[must_use]
+    pub fn parse(s: &SharedStr) -> Option<Self> {
+        let (value, name) = if let Some(name) = s.strip_prefix(FEATURE_PLUS_CHAR) {
+            (FeatureValue::Plus, name)
+        } else if let Some(name) = s.strip_prefix(FEATURE_MINUS_CHAR) {
+            match name.chars().next() {
+                Some(alpha) if is_alpha_variable(alpha) => (FeatureValue::NegatedAlpha(alpha), &name[alpha.len_utf8()..]),
+                _ => (FeatureValue::Minus, name),
+            }
+        } else {
+            match s.chars().next() {
+                Some(alpha) if is_alpha_variable(alpha) => (FeatureValue::Alpha(alpha), &s[alpha.len_utf8()..]),
+                _ => return None,
+            }
+        };
+
+        if name.is_empty() {
+            None
+        } else {
+            Some(Self { value, name: s.share(name) })
+        }
+    }
+}
+
+impl Display for FeatureSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.value {
+            FeatureValue::Plus => write!(f, "{FEATURE_PLUS_CHAR}{}", self.name),
+            FeatureValue::Minus => write!(f, "{FEATURE_MINUS_CHAR}{}", self.name),
+            FeatureValue::Alpha(alpha) => write!(f, "{alpha}{}", self.name),
+            FeatureValue::NegatedAlpha(alpha) => write!(f, "{FEATURE_MINUS_CHAR}{alpha}{}", self.name),
+        }
+    }
+}
+
 /// The type of a condition conjunction
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum AndType {
@@ -153,24 +250,24 @@ impl std::fmt::Display for CondType {
 
 /// A scope label
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub (crate) enum ScopeId<'s> {
+pub (crate) enum ScopeId {
     /// Named label
-    Name(&'s str),
+    Name(SharedStr),
     /// The number that a scope is of its type in an input or output
     /// Along with the scope type and the id of the parent scope
     /// (allows for scope matching inference)
     IOUnlabeled {
         id_num: usize,
         label_type: LabelType,
-        parent: Option<Rc<Self>>,
+        parent: Option<Arc<Self>>,
     }
 }
 
-impl std::fmt::Display for ScopeId<'_> {
+impl std::fmt::Display for ScopeId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::IOUnlabeled { id_num: _, label_type: _, parent: _ } => Ok(()),
-            Self::Name(name) => write!(f, "{}", IrToken::Label(name)),
+            Self::Name(name) => write!(f, "{}", IrToken::Label(name.clone())),
         }
     }
 }